@@ -0,0 +1,61 @@
+// For benchmark, run:
+//     RAYON_NUM_THREADS=N cargo bench --features parallel --bench cross_term
+// where N is the number of threads you want to use (N = 1 for single-thread). Add the `simd`
+// feature to compare the lane-batched evaluation with the row-by-row one, e.g. on AVX2 or NEON
+// hosts with `RUSTFLAGS="-C target-cpu=native"`.
+
+use ark_pallas::Fr;
+use ark_std::{test_rng, UniformRand};
+use sangria_impl::cross_term::{
+    compute_cross_term, compute_cross_term_column_by_column, CrossTermConfig, RelaxedTrace, LANES,
+};
+use std::time::Instant;
+
+const NUM_REPETITIONS: usize = 10;
+const NUM_ROWS: usize = 1 << 18;
+const CHUNK_SIZES: [usize; 4] = [256, 1024, 4096, 16384];
+
+fn main() {
+    let rng = &mut test_rng();
+    let mut random_column = || (0..NUM_ROWS).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+    let selectors = (0..5).map(|_| random_column()).collect::<Vec<_>>();
+    let left_columns = [(); 3].map(|_| random_column());
+    let right_columns = [(); 3].map(|_| random_column());
+    let left = RelaxedTrace {
+        columns: [&left_columns[0], &left_columns[1], &left_columns[2]],
+        scaling_factor: Fr::from(3u64),
+    };
+    let right = RelaxedTrace {
+        columns: [&right_columns[0], &right_columns[1], &right_columns[2]],
+        scaling_factor: Fr::from(5u64),
+    };
+
+    let start = Instant::now();
+    for _ in 0..NUM_REPETITIONS {
+        compute_cross_term_column_by_column(&selectors, &left, &right).unwrap();
+    }
+    println!(
+        "cross term, column by column, {} rows: {} ns",
+        NUM_ROWS,
+        start.elapsed().as_nanos() / NUM_REPETITIONS as u128
+    );
+
+    for chunk_size in CHUNK_SIZES {
+        let start = Instant::now();
+        for _ in 0..NUM_REPETITIONS {
+            compute_cross_term(&selectors, &left, &right, CrossTermConfig { chunk_size }).unwrap();
+        }
+        println!(
+            "cross term, chunks of {} rows{}, {} rows: {} ns",
+            chunk_size,
+            if cfg!(feature = "simd") {
+                format!(" in lanes of {}", LANES)
+            } else {
+                String::new()
+            },
+            NUM_ROWS,
+            start.elapsed().as_nanos() / NUM_REPETITIONS as u128
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! A verification service that reads length-prefixed requests from stdin and writes one status
+//! byte per request to stdout, to show how proofs cross process boundaries using the crate's
+//! serialization APIs.
+//!
+//! ```text
+//! cargo run --example verifier_server --features examples-server -- prove 10 \
+//!     | cargo run --example verifier_server --features examples-server -- serve
+//! ```
+//!
+//! A request is a little-endian `u32` length followed by the compressed encoding of
+//! `(number_of_steps: u64, origin_state, current_state, proof)`. The response is `0` if the
+//! proof is accepted, `1` if it is rejected and `2` if the request is malformed.
+//!
+//! Sangria's IVC scheme is not exported yet, so the service is generic over [`IVC`] and this
+//! example instantiates it with `ReplayIVC`, a stand-in whose proof is the list of step
+//! witnesses and whose verifier replays the computation.
+
+use std::{
+    env,
+    io::{self, Read, Write},
+};
+
+use ark_ff::PrimeField;
+use ark_pallas::Fr;
+use ark_serialize::CanonicalDeserialize;
+use ark_std::rand::Rng;
+use sangria_impl::{
+    field_encoding::{from_le_bytes, hash_to_field, Reduction},
+    serialization::{deserialize_with_mode, serialize_with_mode, SerializationMode},
+    SangriaError, StepCircuit, VerificationFailure, IVC,
+};
+
+const ACCEPTED: u8 = 0;
+const REJECTED: u8 = 1;
+const MALFORMED: u8 = 2;
+
+/// The step circuit `z_{i+1} = z_i^2 + w_i`.
+struct SquareAndAdd;
+
+impl StepCircuit<Fr> for SquareAndAdd {
+    type State = Fr;
+    type Witness = Fr;
+    type Outputs = ();
+
+    fn witness_arity(&self) -> usize {
+        1
+    }
+
+    fn witness_elements(witness: &Fr) -> Vec<Fr> {
+        vec![*witness]
+    }
+
+    fn output_elements(_outputs: &()) -> Vec<Fr> {
+        Vec::new()
+    }
+
+    fn state_elements(state: &Fr) -> Vec<Fr> {
+        vec![*state]
+    }
+}
+
+fn step(state: Fr, witness: Fr) -> Fr {
+    state.square() + witness
+}
+
+/// A non-succinct stand-in for an IVC scheme: the proof is the list of step witnesses.
+struct ReplayIVC;
+
+impl IVC<Fr, SquareAndAdd> for ReplayIVC {
+    type PublicParameters = ();
+    type ProverKey = ();
+    type VerifierKey = ();
+    type Proof = Vec<Fr>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<(), SangriaError> {
+        Ok(())
+    }
+
+    fn encode<R: Rng>(
+        _public_parameters: &(),
+        _step_circuit: &SquareAndAdd,
+        _rng: &mut R,
+    ) -> Result<((), ()), SangriaError> {
+        Ok(((), ()))
+    }
+
+    fn prove_step(
+        _prover_key: &(),
+        _origin_state: &Fr,
+        current_state: Fr,
+        current_proof: Option<Vec<Fr>>,
+        current_witness: &Fr,
+    ) -> Result<(Fr, Vec<Fr>), SangriaError> {
+        let mut proof = current_proof.unwrap_or_default();
+        proof.push(*current_witness);
+
+        Ok((step(current_state, *current_witness), proof))
+    }
+
+    fn verify(
+        _verifier_key: &(),
+        number_of_steps: usize,
+        origin_state: &Fr,
+        current_state: Fr,
+        current_proof: Option<Vec<Fr>>,
+    ) -> Result<(), SangriaError> {
+        let witnesses = current_proof.unwrap_or_default();
+        if witnesses.len() != number_of_steps {
+            return Err(SangriaError::VerificationFailed(
+                VerificationFailure::BaseCase,
+            ));
+        }
+
+        let replayed = witnesses
+            .iter()
+            .fold(*origin_state, |state, w| step(state, *w));
+        if replayed != current_state {
+            return Err(SangriaError::VerificationFailed(
+                VerificationFailure::RelaxedRelation,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Handles a single request and returns the status byte to send back.
+fn handle<F, SC, I>(verifier_key: &I::VerifierKey, request: &[u8]) -> u8
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    SC::State: CanonicalDeserialize,
+    I: IVC<F, SC>,
+    I::Proof: CanonicalDeserialize,
+{
+    let mut reader = request;
+    let mut decode = || -> Result<_, SangriaError> {
+        let mode = SerializationMode::Compressed;
+        Ok((
+            deserialize_with_mode::<u64, _>(&mut reader, mode)?,
+            deserialize_with_mode::<SC::State, _>(&mut reader, mode)?,
+            deserialize_with_mode::<SC::State, _>(&mut reader, mode)?,
+            deserialize_with_mode::<I::Proof, _>(&mut reader, mode)?,
+        ))
+    };
+    let (number_of_steps, origin_state, current_state, proof) = match decode() {
+        Ok(decoded) if reader.is_empty() => decoded,
+        _ => return MALFORMED,
+    };
+
+    match I::verify(
+        verifier_key,
+        number_of_steps as usize,
+        &origin_state,
+        current_state,
+        Some(proof),
+    ) {
+        Ok(()) => ACCEPTED,
+        Err(_) => REJECTED,
+    }
+}
+
+fn serve() -> io::Result<()> {
+    let rng = &mut ark_std::test_rng();
+    let public_parameters = ReplayIVC::setup(rng).expect("setup");
+    let (_, verifier_key) =
+        ReplayIVC::encode(&public_parameters, &SquareAndAdd, rng).expect("encode");
+
+    let (mut stdin, mut stdout) = (io::stdin().lock(), io::stdout().lock());
+    let mut length = [0u8; 4];
+    while stdin.read_exact(&mut length).is_ok() {
+        let mut request = vec![0u8; u32::from_le_bytes(length) as usize];
+        stdin.read_exact(&mut request)?;
+
+        let status = handle::<Fr, SquareAndAdd, ReplayIVC>(&verifier_key, &request);
+        stdout.write_all(&[status])?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+fn prove(number_of_steps: usize) -> io::Result<()> {
+    let rng = &mut ark_std::test_rng();
+    let public_parameters = ReplayIVC::setup(rng).expect("setup");
+    let (prover_key, _) =
+        ReplayIVC::encode(&public_parameters, &SquareAndAdd, rng).expect("encode");
+
+    let origin_state = hash_to_field(b"verifier_server", b"origin state");
+    let (mut state, mut proof) = (origin_state, None);
+    for step_index in 0..number_of_steps as u64 {
+        let witness = from_le_bytes(&step_index.to_le_bytes(), Reduction::Reject)
+            .expect("u64s are canonical");
+        let (next_state, next_proof) =
+            ReplayIVC::prove_step(&prover_key, &origin_state, state, proof, &witness)
+                .expect("prove step");
+        state = next_state;
+        proof = Some(next_proof);
+    }
+
+    let mode = SerializationMode::Compressed;
+    let mut bytes = Vec::new();
+    serialize_with_mode(&(number_of_steps as u64), &mut bytes, mode).expect("serialize");
+    serialize_with_mode(&origin_state, &mut bytes, mode).expect("serialize");
+    serialize_with_mode(&state, &mut bytes, mode).expect("serialize");
+    serialize_with_mode(&proof.unwrap_or_default(), &mut bytes, mode).expect("serialize");
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    stdout.write_all(&bytes)
+}
+
+fn main() -> io::Result<()> {
+    let arguments = env::args().skip(1).collect::<Vec<_>>();
+    match arguments.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["serve"] => serve(),
+        ["prove", number_of_steps] => prove(number_of_steps.parse().unwrap_or(1)),
+        _ => {
+            eprintln!("usage: verifier_server serve | verifier_server prove <number of steps>");
+            Ok(())
+        }
+    }
+}
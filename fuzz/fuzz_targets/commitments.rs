@@ -0,0 +1,24 @@
+//! Decodes commitments, tagged artifacts and transcript logs from arbitrary bytes.
+
+#![no_main]
+
+use ark_pallas::{Affine, Fr};
+use libfuzzer_sys::fuzz_target;
+use sangria_impl::{
+    serialization::{deserialize_tagged, from_bytes, ArtifactTag, SerializationMode},
+    transcript::TranscriptRecorder,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let tag = ArtifactTag::new::<Fr>("pallas").unwrap();
+    for mode in [
+        SerializationMode::Compressed,
+        SerializationMode::Uncompressed,
+    ] {
+        let _ = from_bytes::<Vec<Affine>>(data, mode);
+        let _ = deserialize_tagged::<Fr, _>(&tag, data, mode);
+        if let Ok(recorder) = TranscriptRecorder::<Fr>::from_bytes(data, mode) {
+            assert_eq!(recorder.to_bytes(mode).unwrap(), data);
+        }
+    }
+});
@@ -0,0 +1,31 @@
+//! Decodes half cycle proofs and their pairs from arbitrary bytes. Whatever decodes must encode
+//! back to the same bytes.
+
+#![no_main]
+
+use ark_pallas::Fr;
+use libfuzzer_sys::fuzz_target;
+use sangria_impl::{
+    fuzzing::FieldCommitmentConfig, serialization::SerializationMode, FreshInstance,
+    HalfCycleProof, RunningAccumulator,
+};
+
+type Comm = FieldCommitmentConfig<Fr>;
+
+fuzz_target!(|data: &[u8]| {
+    for mode in [
+        SerializationMode::Compressed,
+        SerializationMode::Uncompressed,
+    ] {
+        if let Ok(proof) = HalfCycleProof::<Fr, Comm>::from_bytes(data, mode) {
+            assert_eq!(proof.serialized_size(mode), data.len());
+            assert_eq!(proof.to_bytes(mode).unwrap(), data);
+        }
+        if let Ok(running) = RunningAccumulator::<Fr, Comm>::from_bytes(data, mode) {
+            assert_eq!(running.to_bytes(mode).unwrap(), data);
+        }
+        if let Ok(fresh) = FreshInstance::<Fr, Comm>::from_bytes(data, mode) {
+            assert_eq!(fresh.to_bytes(mode).unwrap(), data);
+        }
+    }
+});
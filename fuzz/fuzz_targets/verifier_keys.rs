@@ -0,0 +1,23 @@
+//! Decodes folding verifier keys from arbitrary bytes. Whatever decodes must encode back to the
+//! same bytes.
+
+#![no_main]
+
+use ark_pallas::Fr;
+use libfuzzer_sys::fuzz_target;
+use sangria_impl::{
+    fuzzing::FieldCommitmentConfig, relations::VerifierKey, serialization::SerializationMode,
+};
+
+fuzz_target!(|data: &[u8]| {
+    for mode in [
+        SerializationMode::Compressed,
+        SerializationMode::Uncompressed,
+    ] {
+        if let Ok(verifier_key) =
+            VerifierKey::<Fr, FieldCommitmentConfig<Fr>>::from_bytes(data, mode)
+        {
+            assert_eq!(verifier_key.to_bytes(mode).unwrap(), data);
+        }
+    }
+});
@@ -25,6 +25,7 @@ pub mod elgamal;
 pub mod errors;
 pub mod hash_to_group;
 pub mod merkle_tree;
+#[cfg(feature = "pasta")]
 pub mod pasta;
 pub mod pcs;
 pub mod prf;
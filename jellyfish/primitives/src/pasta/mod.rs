@@ -16,6 +16,11 @@ impl crate::pcs::CommitmentGroup for PallasGroup {
     type G1Affine = PallasAffine;
     type G1Projective = PallasProjective;
     type Fq = Fq;
+
+    // Pallas has prime order.
+    fn clear_cofactor(point: &PallasAffine) -> PallasAffine {
+        *point
+    }
 }
 
 // this is analogous to the ark_pallas::VestaParameters struct
@@ -28,4 +33,9 @@ impl crate::pcs::CommitmentGroup for VestaGroup {
     type G1Affine = VestaAffine;
     type G1Projective = VestaProjective;
     type Fq = Fr;
+
+    // Vesta has prime order.
+    fn clear_cofactor(point: &VestaAffine) -> VestaAffine {
+        *point
+    }
 }
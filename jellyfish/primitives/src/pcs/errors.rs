@@ -6,6 +6,7 @@
 
 //! Error module.
 
+#[cfg(feature = "pcs-kzg")]
 use super::transcript::TranscriptError;
 use ark_serialize::SerializationError;
 use ark_std::string::String;
@@ -22,11 +23,20 @@ pub enum PCSError {
     InvalidProof(String),
     /// Invalid parameters: {0}
     InvalidParameters(String),
+    /// Unsupported degree {requested}, the parameters support up to {max}
+    UnsupportedDegree {
+        /// The degree that was asked for
+        requested: usize,
+        /// The maximum degree the parameters support
+        max: usize,
+    },
     /// An error during (de)serialization: {0}
     SerializationError(SerializationError),
     /// Transcript error {0}
+    #[cfg(feature = "pcs-kzg")]
     TranscriptError(TranscriptError),
     /// Error from the internal ark_poly_commit primitive
+    #[cfg(feature = "pcs-ipa")]
     ArkPolyCommitError(ark_poly_commit::Error),
 }
 
@@ -36,12 +46,14 @@ impl From<SerializationError> for PCSError {
     }
 }
 
+#[cfg(feature = "pcs-kzg")]
 impl From<TranscriptError> for PCSError {
     fn from(e: TranscriptError) -> Self {
         Self::TranscriptError(e)
     }
 }
 
+#[cfg(feature = "pcs-ipa")]
 impl From<ark_poly_commit::Error> for PCSError {
     fn from(e: ark_poly_commit::Error) -> Self {
         Self::ArkPolyCommitError(e)
@@ -6,19 +6,23 @@
 
 //! Polynomial Commitment Scheme
 pub mod errors;
+#[cfg(feature = "pcs-kzg")]
 mod multilinear_kzg;
 pub mod prelude;
 mod structs;
+#[cfg(feature = "pcs-kzg")]
 mod transcript;
+#[cfg(feature = "pcs-ipa")]
 mod univariate_ipa;
+#[cfg(feature = "pcs-kzg")]
 mod univariate_kzg;
 
 use core::ops::MulAssign;
 
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{Field, PrimeField, SquareRootField};
+use ark_ff::{Field, FpParameters, PrimeField, SquareRootField};
 use ark_poly::univariate::DensePolynomial;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use ark_std::{
     borrow::Borrow,
     fmt::Debug,
@@ -88,6 +92,48 @@ pub trait CommitmentGroup: Sized + 'static + Copy + Debug + Sync + Send + Eq + P
 
     /// The base field
     type Fq: PrimeField + SquareRootField;
+
+    /// Returns the canonical generator of the prime order subgroup.
+    fn generator() -> Self::G1Affine {
+        Self::G1Affine::prime_subgroup_generator()
+    }
+
+    /// Returns the order of the prime order subgroup, i.e. the modulus of `Fr`.
+    fn group_order() -> <Self::Fr as PrimeField>::BigInt {
+        <<Self::Fr as PrimeField>::Params as FpParameters>::MODULUS
+    }
+
+    /// Maps a point of the curve into the prime order subgroup by multiplying it by the
+    /// cofactor.
+    fn clear_cofactor(point: &Self::G1Affine) -> Self::G1Affine {
+        point.mul_by_cofactor()
+    }
+
+    /// Serializes a point, compressed (x-coordinate and a sign bit) or uncompressed.
+    fn serialize_point<W: Write>(
+        point: &Self::G1Affine,
+        writer: W,
+        compressed: bool,
+    ) -> Result<(), SerializationError> {
+        if compressed {
+            point.serialize(writer)
+        } else {
+            point.serialize_uncompressed(writer)
+        }
+    }
+
+    /// Deserializes a point serialized by `serialize_point`. The point is checked to be on the
+    /// curve and in the prime order subgroup.
+    fn deserialize_point<R: Read>(
+        reader: R,
+        compressed: bool,
+    ) -> Result<Self::G1Affine, SerializationError> {
+        if compressed {
+            Self::G1Affine::deserialize(reader)
+        } else {
+            Self::G1Affine::deserialize_uncompressed(reader)
+        }
+    }
 }
 
 impl<E: PairingEngine> CommitmentGroup for E {
@@ -5,17 +5,24 @@
 // along with the Jellyfish library. If not, see <https://mit-license.org/>.
 
 //! Prelude
+#[cfg(feature = "pcs-ipa")]
+pub use crate::pcs::univariate_ipa::{
+    DeferredIPACheck, DeferredIPAChecks, ProverParam as UnivariateIPAProverParam, UnivariateIPA,
+};
+pub use crate::pcs::{
+    errors::PCSError, structs::Commitment, PolynomialCommitmentScheme, StructuredReferenceString,
+    UVPCS,
+};
+#[cfg(feature = "pcs-kzg")]
 pub use crate::pcs::{
-    errors::PCSError,
     multilinear_kzg::{
         srs::{MultilinearProverParam, MultilinearUniversalParams, MultilinearVerifierParam},
         util::{compute_qx_degree, get_batched_nv, merge_polynomials},
         MultilinearKzgBatchProof, MultilinearKzgPCS, MultilinearKzgProof,
     },
-    structs::Commitment,
     univariate_kzg::{
+        ceremony,
         srs::{UnivariateProverParam, UnivariateUniversalParams, UnivariateVerifierParam},
         UnivariateKzgBatchProof, UnivariateKzgPCS, UnivariateKzgProof,
     },
-    PolynomialCommitmentScheme, StructuredReferenceString, UVPCS,
 };
@@ -1,16 +1,19 @@
 use core::borrow::Borrow;
 use core::marker::PhantomData;
 
-use ark_ec::AffineCurve;
-use ark_ff::{Field, One};
-use ark_poly::{univariate::DensePolynomial, Polynomial};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
 use ark_poly_commit::{
-    ipa_pc, LabeledCommitment, LabeledPolynomial, PCCommitment, PCRandomness, PolynomialCommitment,
+    ipa_pc::{self, SuccinctCheckPolynomial},
+    LabeledCommitment, LabeledPolynomial, PCCommitment, PCRandomness, PolynomialCommitment,
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use ark_std::{
+    format,
     rand::{CryptoRng, RngCore},
     string::String,
+    vec,
 };
 use blake2::Blake2s;
 use jf_utils::Vec;
@@ -73,12 +76,19 @@ impl<G: AffineCurve> Into<ipa_pc::CommitterKey<G>> for &ProverParam<G> {
         ipa_pc::CommitterKey {
             comm_key: self.comm_key.clone(),
             h: self.h,
-            s: self.h,
+            s: self.s,
             max_degree: self.max_degree,
         }
     }
 }
 
+impl<G: AffineCurve> ProverParam<G> {
+    /// Returns the maximum degree of polynomials that can be committed to with these parameters.
+    pub fn supported_degree(&self) -> usize {
+        self.comm_key.len() - 1
+    }
+}
+
 /// Like in the arkworks IPA< our verifier key is the same as the prover key.
 pub type VerifierParam<G> = ProverParam<G>;
 
@@ -154,13 +164,24 @@ impl<E: CommitmentGroup> PolynomialCommitmentScheme<E> for UnivariateIPA<E> {
         Ok(arkworks_srs)
     }
 
+    /// Note that the IPA needs a power of two number of generators, so `supported_degree` is
+    /// rounded up to the next power of two minus one. The actual degree is available through
+    /// [`ProverParam::supported_degree`].
     fn trim(
         srs: impl Borrow<Self::SRS>,
         supported_degree: usize,
         _supported_num_vars: Option<usize>,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), super::prelude::PCSError> {
-        let (arkworks_ck, arkworks_vk) =
-            ArkworksIPA::trim(srs.borrow(), supported_degree, 0, None)?;
+        let srs = srs.borrow();
+        let rounded_degree = (supported_degree + 1).next_power_of_two() - 1;
+        if supported_degree == 0 || rounded_degree > srs.max_degree() {
+            return Err(PCSError::UnsupportedDegree {
+                requested: supported_degree,
+                max: srs.max_degree(),
+            });
+        }
+
+        let (arkworks_ck, arkworks_vk) = ArkworksIPA::trim(srs, supported_degree, 0, None)?;
 
         Ok((arkworks_ck.into(), arkworks_vk.into()))
     }
@@ -307,6 +328,179 @@ impl<E: CommitmentGroup> PolynomialCommitmentScheme<E> for UnivariateIPA<E> {
     }
 }
 
+/// The result of the succinct part of IPA verification. The IPA verifier ends with checking that
+/// `final_comm_key = <s, G>`, where `s` are the coefficients of the check polynomial
+/// `h(X) = prod_i (1 + u_i^{-1} X^{2^i})` built from the round challenges `u_i`. This is a
+/// linear-size MSM. In the sub-linear mode it is replaced by checking an opening of
+/// `final_comm_key`, seen as a commitment to `h(X)`, at a random point: `h(z)` can be evaluated
+/// from the challenges in logarithmic time. Openings for many deferred checks sharing the same
+/// point can then be batched so that the linear work is paid once (the Halo endgame).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeferredIPACheck<G: AffineCurve> {
+    /// The folded commitment key claimed by the prover.
+    pub final_comm_key: G,
+
+    /// The round challenges defining the check polynomial `h(X)`.
+    pub challenges: Vec<G::ScalarField>,
+}
+
+impl<G: AffineCurve> DeferredIPACheck<G> {
+    /// Evaluates `h(X)` at `point` in time logarithmic in the degree.
+    pub fn evaluate(&self, point: G::ScalarField) -> G::ScalarField {
+        SuccinctCheckPolynomial(self.challenges.clone()).evaluate(point)
+    }
+
+    /// Returns the coefficients of `h(X)`. This takes linear time and is only needed by the prover.
+    pub fn polynomial(&self) -> DensePolynomial<G::ScalarField> {
+        DensePolynomial::from_coefficients_vec(
+            SuccinctCheckPolynomial(self.challenges.clone()).compute_coeffs(),
+        )
+    }
+
+    fn commitment(&self) -> ipa_pc::Commitment<G> {
+        ipa_pc::Commitment {
+            comm: self.final_comm_key,
+            shifted_comm: None,
+        }
+    }
+}
+
+impl<E: CommitmentGroup> UnivariateIPA<E> {
+    /// Runs the IPA verifier without its final `<s, G>` MSM. Returns `None` if the succinct checks
+    /// fail, otherwise the check that remains to be done on `final_comm_key`.
+    pub fn succinct_verify(
+        verifier_param: &VerifierParam<E::G1Affine>,
+        commitment: &ipa_pc::Commitment<E::G1Affine>,
+        point: &E::Fr,
+        value: &E::Fr,
+        proof: &Proof<E::G1Affine>,
+    ) -> Result<Option<DeferredIPACheck<E::G1Affine>>, PCSError> {
+        let arkworks_proof: ipa_pc::Proof<E::G1Affine> = proof.into();
+        let labeled_commitment = to_labeled_cm(commitment);
+
+        let check_polynomial = ArkworksIPA::succinct_check(
+            &verifier_param.into(),
+            [&labeled_commitment],
+            *point,
+            [*value],
+            &arkworks_proof,
+            &|_| E::Fr::one(),
+        );
+
+        Ok(check_polynomial.map(|check_polynomial| DeferredIPACheck {
+            final_comm_key: proof.final_comm_key,
+            challenges: check_polynomial.0,
+        }))
+    }
+
+    /// Prover side of the sub-linear mode: opens `final_comm_key` as a commitment to `h(X)` at
+    /// `point`.
+    pub fn open_deferred_check(
+        prover_param: impl Borrow<ProverParam<E::G1Affine>>,
+        deferred_check: &DeferredIPACheck<E::G1Affine>,
+        point: &E::Fr,
+    ) -> Result<Proof<E::G1Affine>, PCSError> {
+        let (proof, _evaluation) = Self::open(prover_param, &deferred_check.polynomial(), point)?;
+
+        Ok(proof)
+    }
+
+    /// Verifier side of the sub-linear mode: evaluates `h(point)` from the challenges and runs the
+    /// succinct verifier on the prover's opening of `final_comm_key`. Returns `None` if the
+    /// opening is rejected, otherwise the deferred check of the opening itself, which is not
+    /// done here either but accumulated with the others, see [`DeferredIPAChecks`]. `point` must
+    /// be sampled after `deferred_check` is fixed.
+    pub fn verify_deferred_check(
+        verifier_param: &VerifierParam<E::G1Affine>,
+        deferred_check: &DeferredIPACheck<E::G1Affine>,
+        point: &E::Fr,
+        proof: &Proof<E::G1Affine>,
+    ) -> Result<Option<DeferredIPACheck<E::G1Affine>>, PCSError> {
+        let evaluation = deferred_check.evaluate(*point);
+
+        Self::succinct_verify(
+            verifier_param,
+            &deferred_check.commitment(),
+            point,
+            &evaluation,
+            proof,
+        )
+    }
+}
+
+/// Deferred checks accumulated across IPA proofs, e.g. all the openings of a batch. The checks
+/// `final_comm_key_j = <s_j, G>` are combined with the powers of a random `r` into
+/// `sum_j r^j final_comm_key_j = <sum_j r^j s_j, G>`, so that deciding them costs a single
+/// linear-size MSM instead of one per proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeferredIPAChecks<G: AffineCurve> {
+    checks: Vec<DeferredIPACheck<G>>,
+}
+
+impl<G: AffineCurve> Default for DeferredIPAChecks<G> {
+    fn default() -> Self {
+        Self { checks: Vec::new() }
+    }
+}
+
+impl<G: AffineCurve> DeferredIPAChecks<G> {
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the deferred check of a proof.
+    pub fn push(&mut self, deferred_check: DeferredIPACheck<G>) {
+        self.checks.push(deferred_check);
+    }
+
+    /// Returns the number of accumulated checks.
+    pub fn len(&self) -> usize {
+        self.checks.len()
+    }
+
+    /// Returns true if no check has been accumulated.
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Decides all the accumulated checks at once with the commit key of `verifier_param`.
+    pub fn decide<R: RngCore>(
+        &self,
+        verifier_param: &VerifierParam<G>,
+        rng: &mut R,
+    ) -> Result<bool, PCSError> {
+        let randomizer = G::ScalarField::rand(rng);
+        let mut combined_coefficients = vec![G::ScalarField::zero(); verifier_param.comm_key.len()];
+        let mut combined_final_comm_key = G::Projective::zero();
+        let mut power = G::ScalarField::one();
+        for check in &self.checks {
+            let coefficients = SuccinctCheckPolynomial(check.challenges.clone()).compute_coeffs();
+            if coefficients.len() != combined_coefficients.len() {
+                return Err(PCSError::InvalidParameters(format!(
+                    "deferred check of {} coefficients for a commit key of {} generators",
+                    coefficients.len(),
+                    combined_coefficients.len()
+                )));
+            }
+
+            for (combined, coefficient) in combined_coefficients.iter_mut().zip(coefficients) {
+                *combined += power * coefficient;
+            }
+            combined_final_comm_key += check.final_comm_key.mul(power);
+            power *= randomizer;
+        }
+
+        let scalars = combined_coefficients
+            .iter()
+            .map(|coefficient| coefficient.into_repr())
+            .collect::<Vec<_>>();
+        let expected = VariableBaseMSM::multi_scalar_mul(&verifier_param.comm_key, &scalars);
+
+        Ok(expected == combined_final_comm_key)
+    }
+}
+
 impl<G: AffineCurve> WithMaxDegree for ipa_pc::UniversalParams<G> {
     fn max_degree(&self) -> usize {
         self.comm_key.len() - 1
@@ -525,7 +719,7 @@ mod test_arkworks_comparison {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "pasta"))]
 mod test_pasta_commitments {
 
     use ark_ff::UniformRand;
@@ -534,10 +728,10 @@ mod test_pasta_commitments {
 
     use crate::{
         pasta::PallasGroup,
-        pcs::{CommitmentGroup, PolynomialCommitmentScheme},
+        pcs::{prelude::PCSError, CommitmentGroup, PolynomialCommitmentScheme},
     };
 
-    use super::UnivariateIPA;
+    use super::{DeferredIPAChecks, UnivariateIPA};
 
     type E = PallasGroup;
     type IPA = UnivariateIPA<E>;
@@ -581,4 +775,94 @@ mod test_pasta_commitments {
 
         assert!(batch_res)
     }
+
+    #[test]
+    fn test_trim_unsupported_degree() {
+        let mut rng = test_rng();
+
+        let crs = IPA::gen_srs_for_testing(&mut rng, 15).unwrap();
+
+        let (pk, _vk) = IPA::trim(&crs, 10, None).unwrap();
+        assert_eq!(pk.supported_degree(), 15);
+
+        assert!(matches!(
+            IPA::trim(&crs, 16, None),
+            Err(PCSError::UnsupportedDegree {
+                requested: 16,
+                max: 15
+            })
+        ));
+    }
+
+    #[test]
+    fn test_deferred_check() {
+        let mut rng = test_rng();
+
+        let degree = 15;
+        let crs = IPA::gen_srs_for_testing(&mut rng, degree).unwrap();
+        let (pk, vk) = IPA::trim(crs, degree, None).unwrap();
+
+        let polynomial = DensePolynomial::<<E as CommitmentGroup>::Fr>::rand(degree, &mut rng);
+        let commitment = IPA::commit(&pk, &polynomial).unwrap();
+        let point = <<E as CommitmentGroup>::Fr>::rand(&mut rng);
+        let (proof, evaluation) = IPA::open(&pk, &polynomial, &point).unwrap();
+
+        let deferred_check = IPA::succinct_verify(&vk, &commitment, &point, &evaluation, &proof)
+            .unwrap()
+            .unwrap();
+
+        let challenge = <<E as CommitmentGroup>::Fr>::rand(&mut rng);
+        let deferred_proof = IPA::open_deferred_check(&pk, &deferred_check, &challenge).unwrap();
+        let remaining_check =
+            IPA::verify_deferred_check(&vk, &deferred_check, &challenge, &deferred_proof)
+                .unwrap()
+                .unwrap();
+
+        let mut checks = DeferredIPAChecks::new();
+        checks.push(remaining_check);
+        assert!(checks.decide(&vk, &mut rng).unwrap());
+
+        let wrong_evaluation = evaluation + <<E as CommitmentGroup>::Fr>::from(1u64);
+        assert!(
+            IPA::succinct_verify(&vk, &commitment, &point, &wrong_evaluation, &proof)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_accumulated_deferred_checks() {
+        let mut rng = test_rng();
+
+        let degree = 15;
+        let crs = IPA::gen_srs_for_testing(&mut rng, degree).unwrap();
+        let (pk, vk) = IPA::trim(crs, degree, None).unwrap();
+
+        let mut checks = DeferredIPAChecks::new();
+        assert!(checks.decide(&vk, &mut rng).unwrap());
+        for _ in 0..4 {
+            let polynomial = DensePolynomial::<<E as CommitmentGroup>::Fr>::rand(degree, &mut rng);
+            let commitment = IPA::commit(&pk, &polynomial).unwrap();
+            let point = <<E as CommitmentGroup>::Fr>::rand(&mut rng);
+            let (proof, evaluation) = IPA::open(&pk, &polynomial, &point).unwrap();
+
+            checks.push(
+                IPA::succinct_verify(&vk, &commitment, &point, &evaluation, &proof)
+                    .unwrap()
+                    .unwrap(),
+            );
+        }
+        assert_eq!(checks.len(), 4);
+        assert!(checks.decide(&vk, &mut rng).unwrap());
+
+        let mut tampered = checks.clone();
+        tampered.checks.last_mut().unwrap().final_comm_key = vk.h;
+        assert!(!tampered.decide(&vk, &mut rng).unwrap());
+
+        let mut short = DeferredIPAChecks::new();
+        let mut check = checks.checks[0].clone();
+        check.challenges.pop();
+        short.push(check);
+        assert!(short.decide(&vk, &mut rng).is_err());
+    }
 }
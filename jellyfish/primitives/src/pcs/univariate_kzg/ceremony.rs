@@ -0,0 +1,211 @@
+// Copyright (c) 2022 Espresso Systems (espressosys.com)
+// This file is part of the Jellyfish library.
+
+// You should have received a copy of the MIT License
+// along with the Jellyfish library. If not, see <https://mit-license.org/>.
+
+//! Powers-of-tau ceremony for the univariate KZG structured reference string.
+//!
+//! Each participant takes the latest SRS `{ \beta^i G }, H, \beta H`, samples
+//! a secret `\tau` and publishes the updated SRS `{ (\beta \tau)^i G }, H,
+//! \beta \tau H` together with an [`UpdateProof`]. The final SRS is secure as
+//! long as one participant discarded their `\tau`. SRSs and proofs use the
+//! same canonical serialization as [`UnivariateUniversalParams`], so the
+//! output of a ceremony can be loaded directly by the PCS.
+
+use super::srs::UnivariateUniversalParams;
+use crate::pcs::PCSError;
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::{
+    end_timer,
+    rand::{CryptoRng, RngCore},
+    start_timer,
+    string::ToString,
+    vec::Vec,
+    One, UniformRand,
+};
+use jf_utils::multi_pairing;
+
+/// A proof that an SRS was obtained from the previous one by multiplying its
+/// trapdoor with a secret `\tau`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct UpdateProof<E: PairingEngine> {
+    /// `\tau H`, where `H` is the generator of G2 of the SRS.
+    pub tau_h: E::G2Affine,
+}
+
+/// Updates `srs` with a fresh secret sampled from `rng`. The secret is dropped
+/// before returning.
+pub fn contribute<E, R>(
+    srs: &UnivariateUniversalParams<E>,
+    rng: &mut R,
+) -> Result<(UnivariateUniversalParams<E>, UpdateProof<E>), PCSError>
+where
+    E: PairingEngine,
+    R: RngCore + CryptoRng,
+{
+    if srs.powers_of_g.len() < 2 {
+        return Err(PCSError::InvalidParameters(
+            "the SRS must contain at least two powers".to_string(),
+        ));
+    }
+
+    let contribute_time = start_timer!(|| "Contributing to the SRS");
+    let mut tau = E::Fr::rand(rng);
+    while tau.is_zero() {
+        tau = E::Fr::rand(rng);
+    }
+
+    let mut power_of_tau = E::Fr::one();
+    let powers_of_g = srs
+        .powers_of_g
+        .iter()
+        .map(|g| {
+            let updated = g.mul(power_of_tau);
+            power_of_tau *= tau;
+            updated
+        })
+        .collect::<Vec<_>>();
+
+    let updated_srs = UnivariateUniversalParams {
+        powers_of_g: E::G1Projective::batch_normalization_into_affine(&powers_of_g),
+        h: srs.h,
+        beta_h: srs.beta_h.mul(tau).into_affine(),
+    };
+    let proof = UpdateProof {
+        tau_h: srs.h.mul(tau).into_affine(),
+    };
+    end_timer!(contribute_time);
+
+    Ok((updated_srs, proof))
+}
+
+/// Checks that `next` is a well-formed SRS obtained from `previous` with the
+/// contribution attested by `proof`. `rng` is only used to batch the
+/// well-formedness check.
+pub fn verify_contribution<E, R>(
+    previous: &UnivariateUniversalParams<E>,
+    next: &UnivariateUniversalParams<E>,
+    proof: &UpdateProof<E>,
+    rng: &mut R,
+) -> Result<(), PCSError>
+where
+    E: PairingEngine,
+    R: RngCore + CryptoRng,
+{
+    if previous.powers_of_g.len() < 2
+        || next.powers_of_g.len() != previous.powers_of_g.len()
+        || next.powers_of_g[0] != previous.powers_of_g[0]
+        || next.h != previous.h
+    {
+        return Err(PCSError::InvalidParameters(
+            "the SRS does not extend the previous one".to_string(),
+        ));
+    }
+    if proof.tau_h.is_zero() {
+        return Err(PCSError::InvalidProof(
+            "the contribution is zero".to_string(),
+        ));
+    }
+
+    // e(\beta \tau G, H) = e(\beta G, \tau H)
+    if !multi_pairing::<E>(
+        &[next.powers_of_g[1], -previous.powers_of_g[1]],
+        &[next.h, proof.tau_h],
+    )
+    .is_one()
+    {
+        return Err(PCSError::InvalidProof(
+            "the SRS was not updated with the contribution".to_string(),
+        ));
+    }
+
+    verify_srs(next, rng)
+}
+
+/// Checks that `srs` is of the form `{ \beta^i G }, H, \beta H` for some
+/// `\beta`, by checking `e(\beta^{i+1} G, H) = e(\beta^i G, \beta H)` for a
+/// random linear combination of the powers.
+pub fn verify_srs<E, R>(srs: &UnivariateUniversalParams<E>, rng: &mut R) -> Result<(), PCSError>
+where
+    E: PairingEngine,
+    R: RngCore + CryptoRng,
+{
+    if srs.powers_of_g.len() < 2 || srs.powers_of_g[0].is_zero() || srs.h.is_zero() {
+        return Err(PCSError::InvalidParameters(
+            "the SRS must contain at least two powers of a non-zero generator".to_string(),
+        ));
+    }
+
+    let verify_time = start_timer!(|| "Checking the SRS is well formed");
+    let degree = srs.powers_of_g.len() - 1;
+    let randomizers = (0..degree)
+        .map(|_| E::Fr::rand(rng).into_repr())
+        .collect::<Vec<_>>();
+    let lower = VariableBaseMSM::multi_scalar_mul(&srs.powers_of_g[..degree], &randomizers);
+    let upper = VariableBaseMSM::multi_scalar_mul(&srs.powers_of_g[1..], &randomizers);
+    let combined = E::G1Projective::batch_normalization_into_affine(&[upper, -lower]);
+
+    let well_formed = multi_pairing::<E>(&combined, &[srs.h, srs.beta_h]).is_one();
+    end_timer!(verify_time);
+
+    if !well_formed {
+        return Err(PCSError::InvalidProof(
+            "the SRS is not made of consecutive powers".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcs::StructuredReferenceString;
+    use ark_bls12_381::Bls12_381;
+    use ark_std::test_rng;
+
+    #[test]
+    fn contributions_chain_and_round_trip_through_serialization() -> Result<(), PCSError> {
+        let rng = &mut test_rng();
+        let mut srs = UnivariateUniversalParams::<Bls12_381>::gen_srs_for_testing(rng, 16)?;
+
+        for _ in 0..3 {
+            let (next, proof) = contribute(&srs, rng)?;
+            verify_contribution(&srs, &next, &proof, rng)?;
+
+            let mut bytes = Vec::new();
+            next.serialize(&mut bytes)?;
+            proof.serialize(&mut bytes)?;
+            let mut reader = &bytes[..];
+            assert_eq!(
+                UnivariateUniversalParams::<Bls12_381>::deserialize(&mut reader)?,
+                next
+            );
+            assert_eq!(UpdateProof::<Bls12_381>::deserialize(&mut reader)?, proof);
+
+            srs = next;
+        }
+
+        // A contribution that does not match the proof is rejected.
+        let (next, _) = contribute(&srs, rng)?;
+        let (_, other_proof) = contribute(&srs, rng)?;
+        assert!(verify_contribution(&srs, &next, &other_proof, rng).is_err());
+
+        // An SRS whose powers are not consecutive is rejected.
+        let (mut next, proof) = contribute(&srs, rng)?;
+        next.powers_of_g.swap(2, 3);
+        assert!(verify_contribution(&srs, &next, &proof, rng).is_err());
+
+        Ok(())
+    }
+}
@@ -36,6 +36,7 @@ use jf_utils::par_utils::parallelizable_slice_iter;
 use rayon::prelude::*;
 use srs::{UnivariateProverParam, UnivariateUniversalParams, UnivariateVerifierParam};
 
+pub mod ceremony;
 pub(crate) mod srs;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -6,5 +6,5 @@ export RUSTFLAGS="-C overflow-checks=on"
 
 cargo test --release -p jf-utils -- -Zunstable-options --report-time
 cargo test --release -p jf-plonk -- -Zunstable-options --report-time
-cargo test --release -p jf-primitives -- -Zunstable-options --report-time
+cargo test --release -p jf-primitives --features parallel,compression -- -Zunstable-options --report-time
 cargo test --release -p jf-relation -- -Zunstable-options --report-time
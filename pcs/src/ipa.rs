@@ -0,0 +1,196 @@
+//! Vector commitments with the commit key of the univariate IPA of `jf-primitives`. Committing to
+//! `x` with randomness `r` is `<x, G> + S * r`, where `G` are the IPA generators and `S` is its
+//! hiding generator. This is the IPA commitment to the polynomial with coefficients `x`, so the
+//! folded witness and slack commitments can be opened by the decider with [`UnivariateIPA`]
+//! without a commitment-switch argument.
+//!
+//! [`UnivariateIPA`]: jf_primitives::pcs::prelude::UnivariateIPA
+
+use ark_ec::{msm::VariableBaseMSM, ProjectiveCurve};
+use ark_ff::{PrimeField, ToBytes};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::{
+    io::{Read, Write},
+    marker::PhantomData,
+    rand::Rng,
+};
+use jf_primitives::pcs::prelude::UnivariateIPAProverParam;
+use sangria_impl::{
+    relations::{
+        Commitment, HomomorphicCommitmentScheme, OpenableCommitmentScheme, PedersenCommitment,
+    },
+    SangriaError,
+};
+
+pub struct IPACommitment<C: ProjectiveCurve> {
+    _curve: PhantomData<C>,
+}
+
+/// The parameters of the univariate IPA, used as a vector commit key. The IPA verifier key is the
+/// same as its prover key, so the decider can use [`Self::prover_param`] for both.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitKey<C: ProjectiveCurve> {
+    prover_param: UnivariateIPAProverParam<C::Affine>,
+}
+
+impl<C: ProjectiveCurve> CommitKey<C> {
+    /// Returns the IPA parameters, which open the commitments of this key as commitments to
+    /// polynomials.
+    pub fn prover_param(&self) -> &UnivariateIPAProverParam<C::Affine> {
+        &self.prover_param
+    }
+}
+
+/// Wraps parameters trimmed from an IPA setup, so that the folding layer and the decider share
+/// them.
+impl<C: ProjectiveCurve> From<UnivariateIPAProverParam<C::Affine>> for CommitKey<C> {
+    fn from(prover_param: UnivariateIPAProverParam<C::Affine>) -> Self {
+        Self { prover_param }
+    }
+}
+
+impl<C: ProjectiveCurve> ToBytes for CommitKey<C> {
+    fn write<W: Write>(&self, mut w: W) -> ark_std::io::Result<()> {
+        for g in self.prover_param.comm_key.iter() {
+            g.write(&mut w)?;
+        }
+        self.prover_param.h.write(&mut w)?;
+        self.prover_param.s.write(&mut w)?;
+        (self.prover_param.max_degree as u64).write(&mut w)
+    }
+}
+
+impl<C: ProjectiveCurve> CanonicalSerialize for CommitKey<C> {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.prover_param.serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.prover_param.serialized_size()
+    }
+}
+
+impl<C: ProjectiveCurve> CanonicalDeserialize for CommitKey<C> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        UnivariateIPAProverParam::deserialize(reader).map(Self::from)
+    }
+}
+
+impl<C: ProjectiveCurve> HomomorphicCommitmentScheme<C::ScalarField> for IPACommitment<C> {
+    type CommitKey = CommitKey<C>;
+    type Commitment = Commitment<C>;
+
+    /// Samples a power of two number of generators, as required by the IPA, of at least `len`.
+    fn setup<R: Rng>(public_randomness: &mut R, len: usize) -> CommitKey<C> {
+        let number_of_generators = len.max(2).next_power_of_two();
+        let comm_key = (0..number_of_generators)
+            .map(|_| C::rand(public_randomness).into_affine())
+            .collect();
+        let h = C::rand(public_randomness).into_affine();
+        let s = C::rand(public_randomness).into_affine();
+
+        CommitKey::from(UnivariateIPAProverParam {
+            comm_key,
+            h,
+            s,
+            max_degree: number_of_generators - 1,
+        })
+    }
+
+    fn supported_size(commit_key: &CommitKey<C>) -> usize {
+        commit_key.prover_param.comm_key.len()
+    }
+
+    fn commit(
+        commit_key: &CommitKey<C>,
+        x: &[C::ScalarField],
+        r: C::ScalarField,
+    ) -> Result<Commitment<C>, SangriaError> {
+        let comm_key = &commit_key.prover_param.comm_key;
+        if x.len() > comm_key.len() {
+            return Err(SangriaError::UnsupportedSize {
+                requested: x.len(),
+                max: comm_key.len(),
+            });
+        }
+
+        let scalars = [&[r], x]
+            .concat()
+            .iter()
+            .map(|x| x.into_repr())
+            .collect::<Vec<_>>();
+
+        let bases = [&[commit_key.prover_param.s], &comm_key[..x.len()]].concat();
+
+        Ok(Commitment(
+            VariableBaseMSM::multi_scalar_mul(&bases, &scalars).into_affine(),
+        ))
+    }
+
+    fn canonicalize(commitment: Commitment<C>) -> Commitment<C> {
+        <PedersenCommitment<C> as HomomorphicCommitmentScheme<C::ScalarField>>::canonicalize(
+            commitment,
+        )
+    }
+}
+
+impl<C: ProjectiveCurve> OpenableCommitmentScheme<C::ScalarField> for IPACommitment<C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ark_pallas::{Fr, Projective};
+    use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+    use ark_std::{test_rng, UniformRand};
+    use jf_primitives::{
+        pasta::PallasGroup,
+        pcs::prelude::{PolynomialCommitmentScheme, UnivariateIPA},
+    };
+
+    type IPA = UnivariateIPA<PallasGroup>;
+    type Scheme = IPACommitment<Projective>;
+
+    #[test]
+    fn folded_commitments_open_as_polynomial_commitments() {
+        let rng = &mut test_rng();
+        let srs = IPA::gen_srs_for_testing(rng, 15).unwrap();
+        let (prover_param, verifier_param) = IPA::trim(&srs, 12, None).unwrap();
+        let commit_key = CommitKey::from(prover_param.clone());
+        assert_eq!(Scheme::supported_size(&commit_key), 16);
+
+        let left = (0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let right = (0..10).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let challenge = Fr::rand(rng);
+        let mut folded = DensePolynomial::from_coefficients_vec(left.clone());
+        folded += (
+            challenge,
+            &DensePolynomial::from_coefficients_vec(right.clone()),
+        );
+
+        let folded_commitment = Scheme::commit(&commit_key, &left, Fr::from(0u64)).unwrap()
+            + Scheme::commit(&commit_key, &right, Fr::from(0u64)).unwrap() * challenge;
+        let polynomial_commitment = IPA::commit(&prover_param, &folded).unwrap();
+        assert_eq!(folded_commitment.0, polynomial_commitment.comm);
+
+        let point = Fr::rand(rng);
+        let (proof, evaluation) = IPA::open(&prover_param, &folded, &point).unwrap();
+        assert!(IPA::verify(
+            &verifier_param,
+            &polynomial_commitment,
+            &point,
+            &evaluation,
+            &proof
+        )
+        .unwrap());
+
+        let too_long = vec![Fr::from(1u64); 17];
+        assert_eq!(
+            Scheme::commit(&commit_key, &too_long, Fr::from(0u64)),
+            Err(SangriaError::UnsupportedSize {
+                requested: 17,
+                max: 16
+            })
+        );
+    }
+}
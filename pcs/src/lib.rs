@@ -0,0 +1,5 @@
+//! Vector commitment schemes for the folding layer of `sangria_impl` whose commit keys are the
+//! parameters of a polynomial commitment scheme of `jf-primitives`, so that a decider can open the
+//! folded commitments with that scheme directly.
+
+pub mod ipa;
@@ -0,0 +1,241 @@
+//! An append-only accumulator of per-step data, used to open what happened at a past step from
+//! the final IVC proof without re-running the chain.
+//!
+//! The accumulator is a Merkle mountain range: a list of perfect Merkle trees ("peaks") of
+//! strictly decreasing height, one for every bit set in the number of leaves. Appending a leaf
+//! only hashes along the right edge, and the root is the hash of the number of leaves and of the
+//! peaks. An inclusion proof is the authentication path of the leaf in its peak together with the
+//! other peaks, so it has `O(log n)` elements.
+
+use ark_ff::PrimeField;
+use ark_sponge::poseidon::PoseidonParameters;
+
+use crate::{
+    sponge::{Poseidon, Sponge},
+    SangriaError, VerificationFailure,
+};
+
+fn hash<F: PrimeField>(parameters: &PoseidonParameters<F>, inputs: &[F]) -> F {
+    let mut sponge = Poseidon::new(parameters);
+    sponge.absorb_field_elements(inputs);
+    sponge.squeeze_field_elements(1)[0]
+}
+
+/// Hashes the elements of a leaf, e.g. the public outputs of a step, into a single field element.
+pub fn hash_leaf<F: PrimeField>(parameters: &PoseidonParameters<F>, elements: &[F]) -> F {
+    let mut inputs = vec![F::from(elements.len() as u64)];
+    inputs.extend_from_slice(elements);
+    hash(parameters, &inputs)
+}
+
+fn root_of_peaks<F: PrimeField>(
+    parameters: &PoseidonParameters<F>,
+    number_of_leaves: usize,
+    peaks: &[F],
+) -> F {
+    let mut inputs = vec![F::from(number_of_leaves as u64)];
+    inputs.extend_from_slice(peaks);
+    hash(parameters, &inputs)
+}
+
+/// Returns the heights of the peaks of a range with `number_of_leaves` leaves, tallest first.
+fn peak_heights(number_of_leaves: usize) -> Vec<usize> {
+    (0..usize::BITS as usize)
+        .rev()
+        .filter(|height| (number_of_leaves >> height) & 1 == 1)
+        .collect()
+}
+
+/// A Merkle mountain range over field elements.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerkleMountainRange<F: PrimeField> {
+    /// `levels[h][j]` is the root of the perfect subtree of height `h` over the leaves
+    /// `j * 2^h .. (j + 1) * 2^h`, for every such subtree that is complete.
+    levels: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> MerkleMountainRange<F> {
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self { levels: Vec::new() }
+    }
+
+    /// The number of leaves appended so far.
+    pub fn len(&self) -> usize {
+        self.levels.first().map_or(0, Vec::len)
+    }
+
+    /// Returns true if no leaf has been appended.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends a leaf and merges the peaks of equal height.
+    pub fn append(&mut self, parameters: &PoseidonParameters<F>, leaf: F) {
+        let mut node = leaf;
+        for height in 0.. {
+            if self.levels.len() == height {
+                self.levels.push(Vec::new());
+            }
+            let level = &mut self.levels[height];
+            level.push(node);
+            if level.len() % 2 == 1 {
+                break;
+            }
+            node = hash(parameters, &level[level.len() - 2..]);
+        }
+    }
+
+    /// Returns the peaks, tallest first.
+    pub fn peaks(&self) -> Vec<F> {
+        peak_heights(self.len())
+            .into_iter()
+            .map(|height| {
+                *self.levels[height]
+                    .last()
+                    .expect("a peak exists at this height")
+            })
+            .collect()
+    }
+
+    /// Returns the root, which commits to the number of leaves and to every leaf.
+    pub fn root(&self, parameters: &PoseidonParameters<F>) -> F {
+        root_of_peaks(parameters, self.len(), &self.peaks())
+    }
+
+    /// Proves that the `index`-th leaf is included in the accumulator.
+    pub fn prove(&self, index: usize) -> Result<MountainRangeProof<F>, SangriaError> {
+        if index >= self.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let mut siblings = Vec::new();
+        let mut height = 0;
+        while self.levels.get(height + 1).map_or(false, |parent_level| {
+            index >> (height + 1) < parent_level.len()
+        }) {
+            siblings.push(self.levels[height][(index >> height) ^ 1]);
+            height += 1;
+        }
+
+        let other_peaks = peak_heights(self.len())
+            .into_iter()
+            .filter(|peak_height| *peak_height != height)
+            .map(|peak_height| {
+                *self.levels[peak_height]
+                    .last()
+                    .expect("a peak exists at this height")
+            })
+            .collect();
+
+        Ok(MountainRangeProof {
+            siblings,
+            other_peaks,
+        })
+    }
+}
+
+/// A proof that a leaf is included in a [`MerkleMountainRange`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MountainRangeProof<F: PrimeField> {
+    /// The authentication path of the leaf in its peak, from the bottom.
+    siblings: Vec<F>,
+    /// The peaks that do not contain the leaf, tallest first.
+    other_peaks: Vec<F>,
+}
+
+impl<F: PrimeField> MountainRangeProof<F> {
+    /// Checks that `leaf` is the `index`-th leaf of the accumulator with root `root` and
+    /// `number_of_leaves` leaves.
+    pub fn verify(
+        &self,
+        parameters: &PoseidonParameters<F>,
+        root: F,
+        number_of_leaves: usize,
+        index: usize,
+        leaf: F,
+    ) -> Result<(), SangriaError> {
+        let heights = peak_heights(number_of_leaves);
+
+        // The peaks cover consecutive ranges of leaves, tallest first.
+        let mut first_leaf = 0;
+        let mut peak_position = None;
+        for (position, height) in heights.iter().enumerate() {
+            if index < first_leaf + (1 << height) {
+                peak_position = Some((position, *height));
+                break;
+            }
+            first_leaf += 1 << height;
+        }
+        let (position, height) = peak_position.ok_or(SangriaError::IndexOutOfBounds)?;
+
+        let reject = || {
+            VerificationFailure::AccumulatorInclusion.reject(|| {
+                format!(
+                    "leaf {} of {} is not in the accumulator",
+                    index, number_of_leaves
+                )
+            })
+        };
+        if self.siblings.len() != height || self.other_peaks.len() + 1 != heights.len() {
+            return Err(reject());
+        }
+
+        let mut node = leaf;
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            node = if (index >> level) & 1 == 0 {
+                hash(parameters, &[node, *sibling])
+            } else {
+                hash(parameters, &[*sibling, node])
+            };
+        }
+
+        let mut peaks = self.other_peaks.clone();
+        peaks.insert(position, node);
+        if root_of_peaks(parameters, number_of_leaves, &peaks) != root {
+            return Err(reject());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::seeded_rng, test_vectors::poseidon_parameters_for_test};
+    use ark_pallas::Fr;
+
+    #[test]
+    fn every_leaf_opens_against_the_root() {
+        let rng = &mut seeded_rng("accumulator::every_leaf_opens_against_the_root");
+        let parameters = poseidon_parameters_for_test::<Fr, _>(rng);
+        let mut accumulator = MerkleMountainRange::new();
+
+        for number_of_leaves in 1..=13usize {
+            accumulator.append(&parameters, Fr::from(number_of_leaves as u64));
+            let root = accumulator.root(&parameters);
+
+            for index in 0..number_of_leaves {
+                let leaf = Fr::from(index as u64 + 1);
+                let proof = accumulator.prove(index).unwrap();
+                proof
+                    .verify(&parameters, root, number_of_leaves, index, leaf)
+                    .unwrap();
+                assert!(proof
+                    .verify(
+                        &parameters,
+                        root,
+                        number_of_leaves,
+                        index,
+                        leaf + Fr::from(1u64)
+                    )
+                    .is_err());
+            }
+            assert_eq!(
+                accumulator.prove(number_of_leaves),
+                Err(SangriaError::IndexOutOfBounds)
+            );
+        }
+    }
+}
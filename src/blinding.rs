@@ -0,0 +1,78 @@
+//! Sources of hiding randomness for commitments. In the deterministic mode the randomness is
+//! derived from a secret seed and the step index with a Poseidon PRF, so that a prover restarted
+//! from a checkpoint recomputes exactly the same commitments as before the restart.
+
+use ark_ff::PrimeField;
+use ark_sponge::poseidon::PoseidonParameters;
+use ark_std::rand::Rng;
+
+use crate::sponge::{Poseidon, Sponge};
+
+const PRF_DOMAIN_SEPARATOR: &[u8] = b"sangria-blinding-prf";
+
+/// A PRF built from Poseidon: `PRF_seed(step, index) = H(domain, seed, step, index)`.
+#[derive(Clone)]
+pub struct PoseidonPRF<F: PrimeField> {
+    poseidon_constants: PoseidonParameters<F>,
+    seed: F,
+}
+
+impl<F: PrimeField> PoseidonPRF<F> {
+    /// Creates a PRF keyed with `seed`. The seed must be kept secret, anyone knowing it can
+    /// recompute the hiding randomness and break zero-knowledge.
+    pub fn new(poseidon_constants: PoseidonParameters<F>, seed: F) -> Self {
+        Self {
+            poseidon_constants,
+            seed,
+        }
+    }
+
+    /// Returns the hiding randomness of the `index`-th commitment made at step `step`.
+    pub fn evaluate(&self, step: u64, index: u64) -> F {
+        let mut sponge = Poseidon::new(&self.poseidon_constants);
+        sponge.absorb_bytes(PRF_DOMAIN_SEPARATOR);
+        sponge.absorb_field_elements(&[self.seed, F::from(step), F::from(index)]);
+
+        sponge.squeeze_field_elements(1)[0]
+    }
+}
+
+/// How the prover samples the hiding randomness of its commitments.
+#[derive(Clone)]
+pub enum BlindingMode<F: PrimeField> {
+    /// Sample from the prover's random number generator.
+    Random,
+    /// Derive from a secret seed and the step index.
+    Deterministic(PoseidonPRF<F>),
+}
+
+impl<F: PrimeField> BlindingMode<F> {
+    /// Returns the hiding randomness of the `index`-th commitment made at step `step`. `rng` is
+    /// only used in the random mode.
+    pub fn hiding<R: Rng>(&self, rng: &mut R, step: u64, index: u64) -> F {
+        match self {
+            BlindingMode::Random => F::rand(rng),
+            BlindingMode::Deterministic(prf) => prf.evaluate(step, index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::seeded_rng, test_vectors::poseidon_parameters_for_test};
+    use ark_pallas::Fr;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn deterministic_blinding_is_reproducible() {
+        let rng = &mut seeded_rng("blinding::deterministic_blinding_is_reproducible");
+        let prf = PoseidonPRF::new(poseidon_parameters_for_test(rng), Fr::rand(rng));
+        let mode = BlindingMode::Deterministic(prf.clone());
+
+        assert_eq!(mode.hiding(rng, 3, 1), prf.evaluate(3, 1));
+        assert_eq!(prf.evaluate(3, 1), prf.evaluate(3, 1));
+        assert_ne!(prf.evaluate(3, 1), prf.evaluate(3, 2));
+        assert_ne!(prf.evaluate(3, 1), prf.evaluate(4, 1));
+    }
+}
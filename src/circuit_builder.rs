@@ -0,0 +1,221 @@
+//! Incremental construction of [`PLONKCircuit`]s. Gates are appended one at a time and copy
+//! constraints are declared between pairs of cells; they are turned into the permutation encoding
+//! of [`crate::preprocessing`] when the circuit is built. Gates and wires can be labeled within
+//! nested namespaces, so that failures are reported as e.g. `poseidon_round_3/after_sbox` instead
+//! of a raw row index.
+
+use ark_ff::FftField;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    preprocessing::{CopyConstraints, NUMBER_OF_WIRES},
+    CircuitLabels, PLONKCircuit, SangriaError, CONSTANT_SELECTOR_INDEX,
+};
+
+/// The number of selectors of a gate, in the order of the `*_SELECTOR_INDEX` constants.
+pub const NUMBER_OF_SELECTORS: usize = CONSTANT_SELECTOR_INDEX + 1;
+
+/// The cell in a given wire (witness column) and row (gate) of a circuit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cell {
+    /// The wire of the cell, below [`NUMBER_OF_WIRES`].
+    pub wire: usize,
+    /// The row of the cell.
+    pub row: usize,
+}
+
+/// Builds a [`PLONKCircuit`] gate by gate, see the module documentation.
+#[derive(Clone, Debug, Default)]
+pub struct PLONKCircuitBuilder<F: FftField> {
+    gates: Vec<[F; NUMBER_OF_SELECTORS]>,
+    copies: Vec<(Cell, Cell)>,
+    gate_labels: BTreeMap<usize, String>,
+    wire_labels: BTreeMap<Cell, String>,
+    namespace: Vec<String>,
+    static_columns: BTreeSet<usize>,
+}
+
+impl<F: FftField> PLONKCircuitBuilder<F> {
+    /// Creates a builder without gates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of gates added so far.
+    pub fn number_of_gates(&self) -> usize {
+        self.gates.len()
+    }
+
+    /// Enters a namespace: the labels added until the matching [`Self::pop_namespace`] are
+    /// prefixed with `name/`.
+    pub fn push_namespace(&mut self, name: impl Into<String>) {
+        self.namespace.push(name.into());
+    }
+
+    /// Leaves the innermost namespace, if any.
+    pub fn pop_namespace(&mut self) {
+        self.namespace.pop();
+    }
+
+    /// Appends a gate and returns its row.
+    pub fn add_gate(&mut self, selectors: [F; NUMBER_OF_SELECTORS]) -> usize {
+        self.gates.push(selectors);
+        self.gates.len() - 1
+    }
+
+    /// Appends a gate labeled `label` within the current namespace and returns its row.
+    pub fn add_labeled_gate(&mut self, selectors: [F; NUMBER_OF_SELECTORS], label: &str) -> usize {
+        let row = self.add_gate(selectors);
+        self.gate_labels.insert(row, self.qualified(label));
+        row
+    }
+
+    /// Labels a cell within the current namespace, or returns an error if it is out of bounds.
+    pub fn label_wire(&mut self, cell: Cell, label: &str) -> Result<(), SangriaError> {
+        self.check_cell(cell)?;
+        self.wire_labels.insert(cell, self.qualified(label));
+        Ok(())
+    }
+
+    /// Constrains two cells to hold the same value, or returns an error if either is out of bounds.
+    pub fn copy(&mut self, left: Cell, right: Cell) -> Result<(), SangriaError> {
+        self.check_cell(left)?;
+        self.check_cell(right)?;
+        self.copies.push((left, right));
+        Ok(())
+    }
+
+    /// Marks a wire column as static, i.e. holding the same values at every step, or returns an
+    /// error if there is no such wire. See [`PLONKCircuit::with_static_columns`].
+    pub fn mark_static(&mut self, wire: usize) -> Result<(), SangriaError> {
+        if wire >= NUMBER_OF_WIRES {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        self.static_columns.insert(wire);
+        Ok(())
+    }
+
+    /// Builds the circuit. Cells that are copied to each other, directly or through other cells,
+    /// form one cycle of the permutation.
+    pub fn build(self) -> Result<PLONKCircuit<F>, SangriaError> {
+        let number_of_gates = self.gates.len();
+        let index = |cell: Cell| cell.wire * number_of_gates + cell.row;
+
+        let mut parents = (0..NUMBER_OF_WIRES * number_of_gates).collect::<Vec<_>>();
+        let root = |parents: &mut Vec<usize>, mut cell: usize| {
+            while parents[cell] != cell {
+                parents[cell] = parents[parents[cell]];
+                cell = parents[cell];
+            }
+            cell
+        };
+        for (left, right) in &self.copies {
+            let left = root(&mut parents, index(*left));
+            let right = root(&mut parents, index(*right));
+            parents[left.max(right)] = left.min(right);
+        }
+
+        let mut cycles = BTreeMap::<usize, Vec<usize>>::new();
+        for cell in 0..parents.len() {
+            let cycle_root = root(&mut parents, cell);
+            cycles.entry(cycle_root).or_default().push(cell);
+        }
+        let cycles = cycles.into_values().collect::<Vec<_>>();
+        let copy_constraint =
+            CopyConstraints::from_cycles(number_of_gates, &cycles)?.to_sigma::<F>()?;
+
+        let selectors = (0..NUMBER_OF_SELECTORS)
+            .map(|selector_index| self.gates.iter().map(|gate| gate[selector_index]).collect())
+            .collect();
+
+        let mut labels = CircuitLabels::default();
+        for (row, label) in self.gate_labels {
+            labels.label_gate(row, label);
+        }
+        for (cell, label) in self.wire_labels {
+            labels.label_cell(index(cell), label);
+        }
+
+        Ok(PLONKCircuit::new(selectors, copy_constraint)
+            .with_labels(labels)
+            .with_static_columns(self.static_columns.into_iter().collect()))
+    }
+
+    fn qualified(&self, label: &str) -> String {
+        self.namespace
+            .iter()
+            .map(String::as_str)
+            .chain(ark_std::iter::once(label))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn check_cell(&self, cell: Cell) -> Result<(), SangriaError> {
+        if cell.wire >= NUMBER_OF_WIRES || cell.row >= self.gates.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessing::PreprocessedCircuit;
+    use ark_ff::{One, Zero};
+    use ark_pallas::Fr;
+
+    #[test]
+    fn copies_become_cycles_and_labels_are_namespaced() {
+        let (zero, one) = (Fr::zero(), Fr::one());
+        let addition = [one, one, -one, zero, zero];
+        let mut builder = PLONKCircuitBuilder::<Fr>::new();
+
+        builder.push_namespace("poseidon_round_3");
+        let first = builder.add_labeled_gate(addition, "add_round_constant");
+        let second = builder.add_gate(addition);
+        builder.pop_namespace();
+        let third = builder.add_labeled_gate(addition, "output");
+
+        let left = |row| Cell { wire: 0, row };
+        let output = |row| Cell { wire: 2, row };
+        builder.copy(output(first), left(second)).unwrap();
+        builder.copy(left(third), output(first)).unwrap();
+        builder.label_wire(output(first), "after_sbox").unwrap();
+        assert_eq!(
+            builder.copy(output(first), Cell { wire: 3, row: 0 }),
+            Err(SangriaError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            builder.label_wire(left(3), "missing"),
+            Err(SangriaError::IndexOutOfBounds)
+        );
+
+        let circuit = builder.build().unwrap();
+        let labels = circuit.labels();
+        assert_eq!(
+            labels.gate_label(first),
+            Some("poseidon_round_3/add_round_constant")
+        );
+        assert_eq!(labels.gate_label(second), None);
+        assert_eq!(labels.describe_gate(third), "gate 2 (output)");
+        assert_eq!(labels.cell_label(6), Some("after_sbox"));
+
+        // The cells a[1], a[2] and c[0] form one cycle, every other cell is a fixed point.
+        let permutation = PreprocessedCircuit::new(&circuit)
+            .unwrap()
+            .permutation()
+            .to_vec();
+        let mut cycle = vec![1];
+        while permutation[cycle[cycle.len() - 1]] != cycle[0] {
+            cycle.push(permutation[cycle[cycle.len() - 1]]);
+        }
+        cycle.sort_unstable();
+        assert_eq!(cycle, vec![1, 2, 6]);
+        for cell in [0, 3, 4, 5, 7, 8] {
+            assert_eq!(permutation[cell], cell);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Combinators that build a step circuit from smaller ones over the same state:
+//! - [`Sequence`] runs one circuit and then another within a step,
+//! - [`Repeat`] runs a circuit a fixed number of times within a step,
+//! - [`Select`] runs one of two circuits, chosen by the witness.
+//!
+//! The witness and outputs of a combined circuit are those of its parts, concatenated in the
+//! order the parts run, so that [`StepCircuit::witness_arity`] and the element encodings compose.
+//! So do the witness integers of [`StepCircuit::witness_reprs`], so that a part that decodes its
+//! witness from integers is checked for canonicity inside a combined circuit too.
+
+use ark_ff::PrimeField;
+
+use crate::StepCircuit;
+
+/// Runs `first` and then `second` on the state within a single step.
+#[derive(Clone, Debug)]
+pub struct Sequence<A, B> {
+    /// The circuit that runs first.
+    pub first: A,
+    /// The circuit that runs on the state output by `first`.
+    pub second: B,
+}
+
+impl<F, A, B> StepCircuit<F> for Sequence<A, B>
+where
+    F: PrimeField,
+    A: StepCircuit<F>,
+    B: StepCircuit<F, State = A::State>,
+{
+    type State = A::State;
+    type Witness = (A::Witness, B::Witness);
+    type Outputs = (A::Outputs, B::Outputs);
+
+    fn witness_arity(&self) -> usize {
+        self.first.witness_arity() + self.second.witness_arity()
+    }
+
+    fn witness_elements((first, second): &Self::Witness) -> Vec<F> {
+        let mut elements = A::witness_elements(first);
+        elements.extend(B::witness_elements(second));
+        elements
+    }
+
+    fn witness_reprs((first, second): &Self::Witness) -> Vec<F::BigInt> {
+        let mut reprs = A::witness_reprs(first);
+        reprs.extend(B::witness_reprs(second));
+        reprs
+    }
+
+    fn output_elements((first, second): &Self::Outputs) -> Vec<F> {
+        let mut elements = A::output_elements(first);
+        elements.extend(B::output_elements(second));
+        elements
+    }
+
+    fn state_elements(state: &Self::State) -> Vec<F> {
+        A::state_elements(state)
+    }
+}
+
+/// Runs `circuit` `N` times on the state within a single step.
+#[derive(Clone, Debug)]
+pub struct Repeat<A, const N: usize> {
+    /// The repeated circuit.
+    pub circuit: A,
+}
+
+impl<F, A, const N: usize> StepCircuit<F> for Repeat<A, N>
+where
+    F: PrimeField,
+    A: StepCircuit<F>,
+{
+    type State = A::State;
+    type Witness = [A::Witness; N];
+    type Outputs = [A::Outputs; N];
+
+    fn witness_arity(&self) -> usize {
+        N * self.circuit.witness_arity()
+    }
+
+    fn witness_elements(witness: &Self::Witness) -> Vec<F> {
+        witness.iter().flat_map(A::witness_elements).collect()
+    }
+
+    fn witness_reprs(witness: &Self::Witness) -> Vec<F::BigInt> {
+        witness.iter().flat_map(A::witness_reprs).collect()
+    }
+
+    fn output_elements(outputs: &Self::Outputs) -> Vec<F> {
+        outputs.iter().flat_map(A::output_elements).collect()
+    }
+
+    fn state_elements(state: &Self::State) -> Vec<F> {
+        A::state_elements(state)
+    }
+}
+
+/// Runs either `left` or `right` on the state, as chosen by the witness. The witness of both
+/// branches takes the same number of elements, see [`Select::left_witness`].
+#[derive(Clone, Debug)]
+pub struct Select<A, B> {
+    /// The circuit run when the selector is zero.
+    pub left: A,
+    /// The circuit run when the selector is one.
+    pub right: B,
+}
+
+/// A branch of a [`Select`]: the witness or outputs of one of its circuits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Branch<L, R> {
+    /// The left circuit was run.
+    Left(L),
+    /// The right circuit was run.
+    Right(R),
+}
+
+/// The witness of a [`Select`]: the witness of the branch that runs, and the number of zero
+/// elements that pad it to the arity of the longer branch.
+#[derive(Clone, Debug)]
+pub struct SelectWitness<L, R> {
+    branch: Branch<L, R>,
+    padding: usize,
+}
+
+impl<A, B> Select<A, B> {
+    /// Returns the witness that runs `left` with `witness`.
+    pub fn left_witness<F>(&self, witness: A::Witness) -> SelectWitness<A::Witness, B::Witness>
+    where
+        F: PrimeField,
+        A: StepCircuit<F>,
+        B: StepCircuit<F, State = A::State>,
+    {
+        let arity = self.left.witness_arity();
+        SelectWitness {
+            branch: Branch::Left(witness),
+            padding: self.right.witness_arity().saturating_sub(arity),
+        }
+    }
+
+    /// Returns the witness that runs `right` with `witness`.
+    pub fn right_witness<F>(&self, witness: B::Witness) -> SelectWitness<A::Witness, B::Witness>
+    where
+        F: PrimeField,
+        A: StepCircuit<F>,
+        B: StepCircuit<F, State = A::State>,
+    {
+        let arity = self.right.witness_arity();
+        SelectWitness {
+            branch: Branch::Right(witness),
+            padding: self.left.witness_arity().saturating_sub(arity),
+        }
+    }
+}
+
+impl<F, A, B> StepCircuit<F> for Select<A, B>
+where
+    F: PrimeField,
+    A: StepCircuit<F>,
+    B: StepCircuit<F, State = A::State>,
+{
+    type State = A::State;
+    type Witness = SelectWitness<A::Witness, B::Witness>;
+    type Outputs = Branch<A::Outputs, B::Outputs>;
+
+    /// The selector and the witness of the longer branch.
+    fn witness_arity(&self) -> usize {
+        1 + self.left.witness_arity().max(self.right.witness_arity())
+    }
+
+    fn witness_elements(witness: &Self::Witness) -> Vec<F> {
+        let mut elements = match &witness.branch {
+            Branch::Left(witness) => [vec![F::zero()], A::witness_elements(witness)].concat(),
+            Branch::Right(witness) => [vec![F::one()], B::witness_elements(witness)].concat(),
+        };
+        elements.resize(elements.len() + witness.padding, F::zero());
+        elements
+    }
+
+    fn witness_reprs(witness: &Self::Witness) -> Vec<F::BigInt> {
+        let (zero, one) = (F::zero().into_repr(), F::one().into_repr());
+        let mut reprs = match &witness.branch {
+            Branch::Left(witness) => [vec![zero], A::witness_reprs(witness)].concat(),
+            Branch::Right(witness) => [vec![one], B::witness_reprs(witness)].concat(),
+        };
+        reprs.resize(reprs.len() + witness.padding, zero);
+        reprs
+    }
+
+    fn output_elements(outputs: &Self::Outputs) -> Vec<F> {
+        match outputs {
+            Branch::Left(outputs) => [vec![F::zero()], A::output_elements(outputs)].concat(),
+            Branch::Right(outputs) => [vec![F::one()], B::output_elements(outputs)].concat(),
+        }
+    }
+
+    fn state_elements(state: &Self::State) -> Vec<F> {
+        A::state_elements(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ivc::check_step_witness;
+    use ark_pallas::Fr;
+
+    /// The step circuit `z_{i+1} = z_i + w`.
+    #[derive(Clone)]
+    struct Add;
+
+    type Circuit = Sequence<Add, Select<Add, Repeat<Add, 2>>>;
+
+    impl StepCircuit<Fr> for Add {
+        type State = Fr;
+        type Witness = Fr;
+        type Outputs = ();
+
+        fn witness_arity(&self) -> usize {
+            1
+        }
+
+        fn witness_elements(witness: &Fr) -> Vec<Fr> {
+            vec![*witness]
+        }
+
+        fn output_elements(_outputs: &()) -> Vec<Fr> {
+            Vec::new()
+        }
+
+        fn state_elements(state: &Fr) -> Vec<Fr> {
+            vec![*state]
+        }
+    }
+
+    #[test]
+    fn combined_witnesses_have_the_combined_arity() {
+        let (one, two, three) = (Fr::from(1u64), Fr::from(2u64), Fr::from(3u64));
+        let select = Select {
+            left: Add,
+            right: Repeat::<_, 2> { circuit: Add },
+        };
+        let circuit = Sequence {
+            first: Add,
+            second: select.clone(),
+        };
+        assert_eq!(StepCircuit::<Fr>::witness_arity(&circuit), 4);
+
+        let right = (one, select.right_witness::<Fr>([two, three]));
+        assert_eq!(
+            <Circuit as StepCircuit<Fr>>::witness_elements(&right),
+            vec![one, Fr::from(1u64), two, three]
+        );
+        assert_eq!(check_step_witness::<Fr, _>(&circuit, &right), Ok(()));
+
+        // The shorter branch is padded to the arity of the longer one.
+        let left = (one, select.left_witness::<Fr>(two));
+        assert_eq!(
+            <Circuit as StepCircuit<Fr>>::witness_elements(&left),
+            vec![one, Fr::from(0u64), two, Fr::from(0u64)]
+        );
+        assert_eq!(check_step_witness::<Fr, _>(&circuit, &left), Ok(()));
+    }
+}
@@ -0,0 +1,281 @@
+//! Arguments that two commitments of different schemes, e.g. the Pedersen commitment of the
+//! folding layer and the KZG commitment of a decider, commit to the same vector. This allows mixed
+//! configurations without changing the folding hot path: the folded witness stays committed with
+//! the homomorphic scheme and is only switched once, when it is decided.
+//!
+//! The argument is evaluation based. The vector `x` is read as the coefficients of a polynomial
+//! `p`, and a point `z` is derived from both commitments. The prover claims `y = p(z)`, opens the
+//! homomorphic commitment to the linear form `<(1, z, z^2, ...), x> = y` (see
+//! [`OpenableCommitmentScheme::open_linear_form`]) and the polynomial commitment to `p(z) = y`. If
+//! the commitments were to different vectors, the polynomials would agree at `z` with probability
+//! at most `len(x) / |F|`.
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_sponge::{Absorb, CryptographicSponge};
+use ark_std::rand::Rng;
+
+use crate::{
+    relations::{LinearFormOpening, OpenableCommitmentScheme},
+    transcript::{Blake2bSponge, LabeledChallenges},
+    SangriaError, VerificationFailure,
+};
+
+/// Label of the evaluation point of a commitment switch.
+pub const SWITCH_POINT: &[u8] = b"switch_point";
+
+/// A polynomial commitment scheme with evaluation proofs, e.g. KZG, as seen by a commitment
+/// switch. Polynomials are given by their coefficients, lowest degree first.
+pub trait EvaluationCommitmentScheme<F: PrimeField> {
+    /// The key used to commit and to prove evaluations.
+    type CommitKey;
+
+    /// The key used to verify evaluations.
+    type VerifierKey;
+
+    /// A commitment to a polynomial.
+    type Commitment: Absorb;
+
+    /// A proof of the evaluation of a committed polynomial.
+    type EvaluationProof;
+
+    /// Commits to the polynomial with the given coefficients.
+    fn commit(
+        commit_key: &Self::CommitKey,
+        coefficients: &[F],
+    ) -> Result<Self::Commitment, SangriaError>;
+
+    /// Proves the evaluation at `point` of the polynomial with the given coefficients.
+    fn prove_evaluation(
+        commit_key: &Self::CommitKey,
+        coefficients: &[F],
+        point: F,
+    ) -> Result<Self::EvaluationProof, SangriaError>;
+
+    /// Verifies that the committed polynomial evaluates to `value` at `point`.
+    fn verify_evaluation(
+        verifier_key: &Self::VerifierKey,
+        commitment: &Self::Commitment,
+        point: F,
+        value: F,
+        proof: &Self::EvaluationProof,
+    ) -> Result<(), SangriaError>;
+}
+
+/// A proof that a homomorphic commitment and a polynomial commitment are to the same vector, see
+/// the module documentation.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CommitmentSwitchProof<F, C, P>
+where
+    F: PrimeField,
+    C: CanonicalSerialize + CanonicalDeserialize,
+    P: CanonicalSerialize + CanonicalDeserialize,
+{
+    /// The evaluation `y = p(z)`.
+    evaluation: F,
+    /// The opening of the homomorphic commitment to the linear form of the evaluation.
+    linear_form_opening: LinearFormOpening<F, C>,
+    /// The evaluation proof of the polynomial commitment.
+    evaluation_proof: P,
+}
+
+/// Proves that `homomorphic_commitment`, the commitment to `x` with hiding `randomness`, and
+/// `polynomial_commitment`, the commitment to the polynomial with coefficients `x`, are to the
+/// same vector.
+#[allow(clippy::type_complexity)]
+pub fn prove_commitment_switch<F, H, P, R>(
+    homomorphic_commit_key: &H::CommitKey,
+    polynomial_commit_key: &P::CommitKey,
+    x: &[F],
+    randomness: F,
+    homomorphic_commitment: &H::Commitment,
+    polynomial_commitment: &P::Commitment,
+    rng: &mut R,
+) -> Result<CommitmentSwitchProof<F, H::Commitment, P::EvaluationProof>, SangriaError>
+where
+    F: PrimeField,
+    H: OpenableCommitmentScheme<F>,
+    P: EvaluationCommitmentScheme<F>,
+    P::EvaluationProof: CanonicalSerialize + CanonicalDeserialize,
+    R: Rng,
+{
+    let point = switch_point::<F, H, P>(x.len(), homomorphic_commitment, polynomial_commitment);
+    let powers = powers_of(point, x.len());
+
+    Ok(CommitmentSwitchProof {
+        evaluation: powers.iter().zip(x).map(|(power, x_i)| *power * x_i).sum(),
+        linear_form_opening: H::open_linear_form(
+            homomorphic_commit_key,
+            x,
+            randomness,
+            &powers,
+            rng,
+        )?,
+        evaluation_proof: P::prove_evaluation(polynomial_commit_key, x, point)?,
+    })
+}
+
+/// Verifies that `homomorphic_commitment` and `polynomial_commitment` are to the same vector of
+/// length `length`.
+pub fn verify_commitment_switch<F, H, P>(
+    homomorphic_commit_key: &H::CommitKey,
+    polynomial_verifier_key: &P::VerifierKey,
+    length: usize,
+    homomorphic_commitment: &H::Commitment,
+    polynomial_commitment: &P::Commitment,
+    proof: &CommitmentSwitchProof<F, H::Commitment, P::EvaluationProof>,
+) -> Result<(), SangriaError>
+where
+    F: PrimeField,
+    H: OpenableCommitmentScheme<F>,
+    P: EvaluationCommitmentScheme<F>,
+    P::EvaluationProof: CanonicalSerialize + CanonicalDeserialize,
+{
+    let point = switch_point::<F, H, P>(length, homomorphic_commitment, polynomial_commitment);
+
+    H::verify_linear_form(
+        homomorphic_commit_key,
+        homomorphic_commitment,
+        &powers_of(point, length),
+        proof.evaluation,
+        &proof.linear_form_opening,
+    )?;
+    P::verify_evaluation(
+        polynomial_verifier_key,
+        polynomial_commitment,
+        point,
+        proof.evaluation,
+        &proof.evaluation_proof,
+    )
+    .map_err(|_| {
+        VerificationFailure::CommitmentOpening
+            .reject(|| "polynomial commitment does not open to the evaluation".to_string())
+    })
+}
+
+/// Derives the evaluation point from both commitments.
+fn switch_point<F, H, P>(
+    length: usize,
+    homomorphic_commitment: &H::Commitment,
+    polynomial_commitment: &P::Commitment,
+) -> F
+where
+    F: PrimeField,
+    H: OpenableCommitmentScheme<F>,
+    P: EvaluationCommitmentScheme<F>,
+{
+    let mut sponge = Blake2bSponge::new(&());
+    sponge.absorb(&(length as u64));
+    sponge.absorb(&H::canonicalize(*homomorphic_commitment));
+    sponge.absorb(polynomial_commitment);
+
+    LabeledChallenges::<F>::challenge(&mut sponge, SWITCH_POINT)
+}
+
+/// Returns `(1, z, z^2, ..., z^(length - 1))`.
+fn powers_of<F: PrimeField>(point: F, length: usize) -> Vec<F> {
+    ark_std::iter::successors(Some(F::one()), |power| Some(*power * point))
+        .take(length)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        relations::HomomorphicCommitmentScheme, test_utils::seeded_rng,
+        vector_commitment::mock::FieldCommitment,
+    };
+    use ark_pallas::Fr;
+    use ark_std::UniformRand;
+
+    /// A transparent polynomial commitment: the commitment is the polynomial itself.
+    struct TransparentPolynomial;
+
+    impl EvaluationCommitmentScheme<Fr> for TransparentPolynomial {
+        type CommitKey = ();
+        type VerifierKey = ();
+        type Commitment = Vec<Fr>;
+        type EvaluationProof = ();
+
+        fn commit(_commit_key: &(), coefficients: &[Fr]) -> Result<Vec<Fr>, SangriaError> {
+            Ok(coefficients.to_vec())
+        }
+
+        fn prove_evaluation(
+            _commit_key: &(),
+            _coefficients: &[Fr],
+            _point: Fr,
+        ) -> Result<(), SangriaError> {
+            Ok(())
+        }
+
+        fn verify_evaluation(
+            _verifier_key: &(),
+            commitment: &Vec<Fr>,
+            point: Fr,
+            value: Fr,
+            _proof: &(),
+        ) -> Result<(), SangriaError> {
+            let evaluation = commitment
+                .iter()
+                .rev()
+                .fold(Fr::from(0u64), |acc, coefficient| acc * point + coefficient);
+            if evaluation != value {
+                return Err(SangriaError::CommitmentError);
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn switches_are_accepted_for_the_same_vector_only() {
+        let rng =
+            &mut seeded_rng("commitment_switch::switches_are_accepted_for_the_same_vector_only");
+        let commit_key = FieldCommitment::<Fr>::setup(rng, 8);
+        let x = (0..8).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let randomness = Fr::rand(rng);
+        let commitment = FieldCommitment::commit(&commit_key, &x, randomness).unwrap();
+        let polynomial_commitment = TransparentPolynomial::commit(&(), &x).unwrap();
+
+        let proof = prove_commitment_switch::<_, FieldCommitment<Fr>, TransparentPolynomial, _>(
+            &commit_key,
+            &(),
+            &x,
+            randomness,
+            &commitment,
+            &polynomial_commitment,
+            rng,
+        )
+        .unwrap();
+        assert_eq!(
+            verify_commitment_switch::<_, FieldCommitment<Fr>, TransparentPolynomial>(
+                &commit_key,
+                &(),
+                x.len(),
+                &commitment,
+                &polynomial_commitment,
+                &proof,
+            ),
+            Ok(())
+        );
+
+        let mut other = x.clone();
+        other[5] += Fr::from(1u64);
+        let other_commitment = TransparentPolynomial::commit(&(), &other).unwrap();
+        assert_eq!(
+            verify_commitment_switch::<_, FieldCommitment<Fr>, TransparentPolynomial>(
+                &commit_key,
+                &(),
+                x.len(),
+                &commitment,
+                &other_commitment,
+                &proof,
+            ),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::CommitmentOpening
+            ))
+        );
+    }
+}
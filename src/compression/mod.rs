@@ -0,0 +1,281 @@
+//! Proof compression. Folding leaves a single relaxed instance-witness pair that vouches for
+//! every statement folded into it, see [`crate::relations::decide`]. Deciding it by revealing the
+//! witness is linear in the circuit size and not zero-knowledge; a [`Decider`] instead proves that
+//! the pair satisfies the relation, so that only the instance and the proof are sent.
+//!
+//! Backends:
+//! - [`spartan::SpartanDecider`]: sumchecks over the R1CS bridge of the circuit (see
+//!   [`crate::r1cs::R1CSShape::from_plonk`]), with no trusted setup and no pairing.
+//!
+//! [`compare`] measures the backends on a circuit of a given size, to pick one empirically.
+
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use ark_sponge::Absorb;
+use ark_std::{rand::Rng, UniformRand};
+use std::time::{Duration, Instant};
+
+use crate::{
+    preprocessing::cell_identities,
+    relations::{
+        relax, CircuitShape, FoldingCommitmentConfig, OpenableCommitmentScheme, PLONKInstance,
+        PLONKWitness, ProverKey, PublicParameters,
+    },
+    transcript::FoldingRandomOracle,
+    NonInteractiveFoldingScheme, PLONKCircuit, PLONKFoldingScheme, RelaxedPLONKInstance,
+    RelaxedPLONKWitness, SangriaError,
+};
+
+pub mod spartan;
+
+/// A proof system for the relaxed PLONK relation of a circuit, used to compress the final pair of
+/// a folding.
+pub trait Decider<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    /// The name of the backend, e.g. to report it in comparisons.
+    const NAME: &'static str;
+
+    /// The key used to prove that a pair satisfies the relation.
+    type ProverKey;
+
+    /// The key used to verify proofs.
+    type VerifierKey;
+
+    /// A proof that an instance has a satisfying witness.
+    type Proof: CanonicalSerialize;
+
+    /// Derives the keys of the decider for the circuit of `prover_key`.
+    fn setup<R: Rng>(
+        public_parameters: &PublicParameters<F, Comm>,
+        prover_key: &ProverKey<F, Comm>,
+        rng: &mut R,
+    ) -> Result<(Self::ProverKey, Self::VerifierKey), SangriaError>;
+
+    /// Proves that `witness` satisfies the relation for `instance`.
+    fn prove<R: Rng>(
+        prover_key: &Self::ProverKey,
+        instance: &RelaxedPLONKInstance<F, Comm>,
+        witness: &RelaxedPLONKWitness<F>,
+        rng: &mut R,
+    ) -> Result<Self::Proof, SangriaError>;
+
+    /// Verifies that `instance` has a satisfying witness.
+    fn verify(
+        verifier_key: &Self::VerifierKey,
+        instance: &RelaxedPLONKInstance<F, Comm>,
+        proof: &Self::Proof,
+    ) -> Result<(), SangriaError>;
+}
+
+/// A decider backend, see the module documentation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Backend {
+    /// A HyperPlonk proof of the relaxed PLONK relation. Not available yet.
+    HyperPlonk,
+    /// A Groth16 proof of the R1CS bridge, with a per-circuit trusted setup. Not available yet.
+    Groth16,
+    /// [`spartan::SpartanDecider`].
+    Spartan,
+}
+
+impl Backend {
+    /// Every backend, available or not.
+    pub const ALL: [Backend; 3] = [Backend::HyperPlonk, Backend::Groth16, Backend::Spartan];
+}
+
+/// The cost of deciding a folded pair with a backend.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Measurement {
+    /// The size of the compressed proof, in bytes.
+    pub proof_size: usize,
+    /// The time taken to prove.
+    pub prove_time: Duration,
+    /// The time taken to verify.
+    pub verify_time: Duration,
+}
+
+/// The outcome of [`compare`] for a backend.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Comparison {
+    /// The backend measured.
+    pub backend: Backend,
+    /// Its measurement, or [`SangriaError::NotImplemented`] if the backend is not available.
+    pub measurement: Result<Measurement, SangriaError>,
+}
+
+/// Measures every backend of `backends` on the same folded pair of a circuit with the number of
+/// gates of `circuit_shape`, and returns the results in the order of `backends`. The circuit
+/// chains multiplication gates; its public inputs do not enter the decided relation. Returns an
+/// error if `public_parameters` do not support the shape.
+pub fn compare<F, Comm, RO, R>(
+    backends: &[Backend],
+    public_parameters: &PublicParameters<F, Comm>,
+    circuit_shape: CircuitShape,
+    rng: &mut R,
+) -> Result<Vec<Comparison>, SangriaError>
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+    Comm::CommitmentWitness: OpenableCommitmentScheme<F>,
+    Comm::CommitmentSlack: OpenableCommitmentScheme<F>,
+    RO: FoldingRandomOracle<F>,
+    R: Rng,
+{
+    let public_parameters = public_parameters.trim_for_circuit(circuit_shape)?;
+    let number_of_gates = circuit_shape.number_of_gates;
+
+    // c = a * b + a at every gate, and the output of each gate is the left input of the next.
+    let (zero, one) = (F::zero(), F::one());
+    let constant = |value: F| vec![value; number_of_gates];
+    let selectors = vec![
+        constant(one),
+        constant(zero),
+        constant(-one),
+        constant(one),
+        constant(zero),
+    ];
+    let mut copy_constraint = cell_identities::<F>(number_of_gates)?;
+    for row in 0..number_of_gates.saturating_sub(1) {
+        copy_constraint.swap(2 * number_of_gates + row, row + 1);
+    }
+    let circuit = PLONKCircuit::new(selectors, copy_constraint);
+    let (prover_key, verifier_key) =
+        PLONKFoldingScheme::<F, Comm, RO>::encode(&public_parameters, &circuit, rng)?;
+
+    let statement = |rng: &mut R| {
+        let b = (0..number_of_gates)
+            .map(|_| F::rand(rng))
+            .collect::<Vec<_>>();
+        let (mut a, mut c) = (vec![F::rand(rng)], Vec::new());
+        for b_i in &b {
+            let c_i = a[a.len() - 1] * b_i + a[a.len() - 1];
+            c.push(c_i);
+            a.push(c_i);
+        }
+        a.truncate(number_of_gates);
+        relax(
+            &public_parameters,
+            &verifier_key,
+            PLONKInstance::new(vec![]),
+            PLONKWitness::new(vec![a, b, c]),
+            rng,
+        )
+    };
+    let (left, left_witness) = statement(rng)?;
+    let (right, right_witness) = statement(rng)?;
+    let (instance, witness, _) = PLONKFoldingScheme::<F, Comm, RO>::prover(
+        &public_parameters,
+        &prover_key,
+        &left,
+        &left_witness,
+        &right,
+        &right_witness,
+    )?;
+
+    Ok(backends
+        .iter()
+        .map(|backend| Comparison {
+            backend: *backend,
+            measurement: match backend {
+                Backend::HyperPlonk => Err(SangriaError::NotImplemented("HyperPlonk decider")),
+                Backend::Groth16 => Err(SangriaError::NotImplemented("Groth16 decider")),
+                Backend::Spartan => measure::<F, Comm, spartan::SpartanDecider, R>(
+                    &public_parameters,
+                    &prover_key,
+                    &instance,
+                    &witness,
+                    rng,
+                ),
+            },
+        })
+        .collect())
+}
+
+/// Sets up `D`, then proves and verifies the pair with it.
+fn measure<F, Comm, D, R>(
+    public_parameters: &PublicParameters<F, Comm>,
+    prover_key: &ProverKey<F, Comm>,
+    instance: &RelaxedPLONKInstance<F, Comm>,
+    witness: &RelaxedPLONKWitness<F>,
+    rng: &mut R,
+) -> Result<Measurement, SangriaError>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+    D: Decider<F, Comm>,
+    R: Rng,
+{
+    let (decider_prover_key, decider_verifier_key) = D::setup(public_parameters, prover_key, rng)?;
+
+    let start = Instant::now();
+    let proof = D::prove(&decider_prover_key, instance, witness, rng)?;
+    let prove_time = start.elapsed();
+
+    let start = Instant::now();
+    D::verify(&decider_verifier_key, instance, &proof)?;
+    let verify_time = start.elapsed();
+
+    Ok(Measurement {
+        proof_size: proof.serialized_size(),
+        prove_time,
+        verify_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        relations::{SetupInfo, SlackRandomization},
+        test_utils::seeded_rng,
+        test_vectors::{poseidon_parameters_for_test, sponge_config_for_test},
+        vector_commitment::mock::FieldCommitmentConfig,
+    };
+    use ark_pallas::Fr;
+    use ark_sponge::poseidon::PoseidonSponge;
+
+    #[test]
+    fn available_backends_are_measured_in_order() {
+        let rng = &mut seeded_rng("compression::available_backends_are_measured_in_order");
+        let info = SetupInfo {
+            number_of_public_inputs: 0,
+            number_of_gates: 8,
+            domain_separator: b"sangria-compression-test".to_vec(),
+            poseidon_constants: poseidon_parameters_for_test(rng),
+            poseidon2_constants: None,
+            sponge_config: sponge_config_for_test(),
+            slack_randomization: SlackRandomization::EveryFold,
+        };
+        let pp = PLONKFoldingScheme::<Fr, FieldCommitmentConfig<Fr>, PoseidonSponge<Fr>>::setup(
+            &info, rng,
+        )
+        .unwrap();
+        let shape = CircuitShape {
+            number_of_public_inputs: 0,
+            number_of_gates: 4,
+        };
+
+        let comparisons =
+            compare::<_, _, PoseidonSponge<Fr>, _>(&Backend::ALL, &pp, shape, rng).unwrap();
+        assert_eq!(
+            comparisons
+                .iter()
+                .map(|comparison| comparison.backend)
+                .collect::<Vec<_>>(),
+            Backend::ALL
+        );
+        assert_eq!(
+            comparisons[1].measurement,
+            Err(SangriaError::NotImplemented("Groth16 decider"))
+        );
+        assert!(comparisons[2].measurement.as_ref().unwrap().proof_size > 0);
+
+        let too_large = CircuitShape {
+            number_of_public_inputs: 0,
+            number_of_gates: 16,
+        };
+        assert_eq!(
+            compare::<_, _, PoseidonSponge<Fr>, _>(&[Backend::Spartan], &pp, too_large, rng),
+            Err(SangriaError::CircuitTooLarge)
+        );
+    }
+}
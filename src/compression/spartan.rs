@@ -0,0 +1,706 @@
+//! A transparent decider in the style of Spartan. It only relies on the discrete logarithm
+//! assumption of the folding commitments, so over the Pasta cycle the whole stack needs neither a
+//! pairing-friendly curve nor a trusted setup.
+//!
+//! The circuit is bridged to relaxed R1CS with [`R1CSShape::from_plonk`], whose witness is `z =
+//! (a, b, c, u)`. Multilinear extensions are taken over the hypercube with the lowest bit of an
+//! index as the first variable, and `eq(x, y)` is the extension of equality. The prover shows
+//! 1. with a sumcheck of degree 3 over the constraints, for a random point `tau`, that
+//!    `sum_x eq(tau, x) * ((A * z)(x) * (B * z)(x) - u * (C * z)(x) - E(x)) = 0`, which reduces
+//!    to the evaluations of `A * z`, `B * z`, `C * z` and `E` at a random point `r_x`,
+//! 2. with a sumcheck of degree 2 over the columns, that a random combination of the first three
+//!    evaluations is `sum_y (r_A * A + r_B * B + r_C * C)(r_x, y) * z(y)`, which reduces to the
+//!    evaluation of `z` at a random point `r_y`.
+//!
+//! The verifier evaluates the matrices at `(r_x, r_y)` itself. The evaluations of `E` and of the
+//! wire columns are inner products with the vector of `eq(r, .)`, proven against the commitments
+//! of the folded instance with linear form openings (see
+//! [`OpenableCommitmentScheme::open_linear_form`]). These are linear in the number of gates; an
+//! inner product argument would make them logarithmic.
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_sponge::CryptographicSponge;
+use ark_std::rand::Rng;
+use std::sync::Arc;
+
+use super::Decider;
+#[cfg(feature = "json")]
+use crate::{json, r1cs::SparseMatrix};
+use crate::{
+    preprocessing::NUMBER_OF_WIRES,
+    r1cs::R1CSShape,
+    relations::{
+        FoldingCommitmentConfig, HomomorphicCommitmentScheme, LinearFormOpening,
+        OpenableCommitmentScheme, ProverKey, PublicParameters,
+    },
+    transcript::{Blake2bSponge, LabeledChallenges},
+    RelaxedPLONKInstance, RelaxedPLONKWitness, SangriaError, VerificationFailure,
+};
+
+/// Domain separator of the transcript of the decider.
+pub const SPARTAN_DOMAIN: &[u8] = b"sangria_spartan";
+
+/// Label of the coordinates of the random point `tau` of the outer sumcheck.
+pub const OUTER_POINT: &[u8] = b"spartan_tau";
+
+/// Label of the challenges that combine the matrix evaluations into the inner sumcheck.
+pub const MATRIX_COMBINATION: &[u8] = b"spartan_matrices";
+
+/// Label of the challenge of a sumcheck round.
+pub const SUMCHECK_CHALLENGE: &[u8] = b"sumcheck_round";
+
+/// The degree of the round polynomials of the outer sumcheck.
+const OUTER_DEGREE: usize = 3;
+
+/// The degree of the round polynomials of the inner sumcheck.
+const INNER_DEGREE: usize = 2;
+
+/// The Spartan decider, see the module documentation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpartanDecider;
+
+/// The key of the Spartan decider, used both to prove and to verify: the R1CS bridge of the
+/// circuit and the folding commit keys.
+pub struct SpartanKey<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    shape: R1CSShape<F>,
+    circuit_digest: F,
+    commit_key_witness: Arc<<Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::CommitKey>,
+    commit_key_slack: Arc<<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::CommitKey>,
+}
+
+impl<F, Comm> Clone for SpartanKey<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shape: self.shape.clone(),
+            circuit_digest: self.circuit_digest,
+            commit_key_witness: self.commit_key_witness.clone(),
+            commit_key_slack: self.commit_key_slack.clone(),
+        }
+    }
+}
+
+impl<F, Comm> SpartanKey<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    /// Returns the R1CS bridge of the circuit.
+    pub fn shape(&self) -> &R1CSShape<F> {
+        &self.shape
+    }
+
+    /// Exports the key as JSON, for tooling that does not link the crate. The layout is
+    /// `{"curve", "circuit_digest", "number_of_constraints", "number_of_witnesses",
+    /// "number_of_public_inputs", "a": [..], "b": [..], "c": [..]}`, where the matrices list their
+    /// non-zero entries as `[row, column, value]`. Field elements are hex strings of their
+    /// compressed serialization. The commit keys are part of the public parameters and are not
+    /// exported.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, SangriaError> {
+        let matrix = |matrix: &SparseMatrix<F>| {
+            matrix
+                .iter()
+                .map(|(row, column, value)| {
+                    Ok(serde_json::json!([row, column, json::to_hex(value)?]))
+                })
+                .collect::<Result<Vec<_>, SangriaError>>()
+        };
+        let [a, b, c] = self.shape.matrices();
+
+        Ok(serde_json::json!({
+            "curve": Comm::CURVE_ID,
+            "circuit_digest": json::to_hex(&self.circuit_digest)?,
+            "number_of_constraints": self.shape.number_of_constraints(),
+            "number_of_witnesses": self.shape.number_of_witnesses(),
+            "number_of_public_inputs": self.shape.number_of_public_inputs(),
+            "a": matrix(a)?,
+            "b": matrix(b)?,
+            "c": matrix(c)?,
+        })
+        .to_string())
+    }
+
+    /// Imports a key exported with [`Self::to_json`], with the commit keys of
+    /// `public_parameters`. Returns [`SangriaError::CurveMismatch`] if it was exported for
+    /// another curve.
+    #[cfg(feature = "json")]
+    pub fn from_json(
+        json: &str,
+        public_parameters: &PublicParameters<F, Comm>,
+    ) -> Result<Self, SangriaError> {
+        let object = json::parse(json, Comm::CURVE_ID)?;
+        let matrix = |name: &str| {
+            json::array(&object, name)?
+                .iter()
+                .map(|entry| match entry.as_array().map(Vec::as_slice) {
+                    Some([row, column, value]) => Ok((
+                        json::as_index(row)?,
+                        json::as_index(column)?,
+                        json::from_hex(value)?,
+                    )),
+                    _ => Err(SangriaError::SerializationError),
+                })
+                .collect::<Result<SparseMatrix<F>, _>>()
+        };
+
+        Ok(Self {
+            shape: R1CSShape::new(
+                json::index(&object, "number_of_constraints")?,
+                json::index(&object, "number_of_witnesses")?,
+                json::index(&object, "number_of_public_inputs")?,
+                matrix("a")?,
+                matrix("b")?,
+                matrix("c")?,
+            )?,
+            circuit_digest: json::from_hex(json::member(&object, "circuit_digest")?)?,
+            commit_key_witness: public_parameters.commit_key_witness.clone(),
+            commit_key_slack: public_parameters.commit_key_selectors_and_slack.clone(),
+        })
+    }
+
+    fn number_of_gates(&self) -> usize {
+        self.shape.number_of_witnesses() / NUMBER_OF_WIRES
+    }
+
+    fn number_of_columns(&self) -> usize {
+        self.shape.number_of_witnesses() + 1 + self.shape.number_of_public_inputs()
+    }
+
+    /// Starts the transcript of a proof for `instance`.
+    fn transcript(
+        &self,
+        instance: &RelaxedPLONKInstance<F, Comm>,
+    ) -> Result<Blake2bSponge, SangriaError> {
+        let mut sponge = Blake2bSponge::new(&());
+        sponge.absorb(&SPARTAN_DOMAIN.to_vec());
+        absorb_serialized(
+            &mut sponge,
+            &[self.circuit_digest, instance.scaling_factor()].to_vec(),
+        )?;
+        for commitment in instance.witness_commitments() {
+            sponge.absorb(&Comm::CommitmentWitness::canonicalize(commitment));
+        }
+        sponge.absorb(&Comm::CommitmentSlack::canonicalize(
+            instance.slack_commitment(),
+        ));
+
+        Ok(sponge)
+    }
+}
+
+/// A Spartan proof, see the module documentation.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SpartanProof<F, W, S>
+where
+    F: PrimeField,
+    W: CanonicalSerialize + CanonicalDeserialize,
+    S: CanonicalSerialize + CanonicalDeserialize,
+{
+    /// The round polynomials of the outer sumcheck, by their evaluations at `0, ..., 3`.
+    outer_rounds: Vec<Vec<F>>,
+    /// The evaluations of `A * z`, `B * z` and `C * z` at `r_x`.
+    matrix_evaluations: Vec<F>,
+    /// The evaluation of the slack vector at `r_x`.
+    slack_evaluation: F,
+    /// The opening of the slack commitment to its evaluation.
+    slack_opening: LinearFormOpening<F, S>,
+    /// The round polynomials of the inner sumcheck, by their evaluations at `0, 1, 2`.
+    inner_rounds: Vec<Vec<F>>,
+    /// The contribution of every wire column to the evaluation of `z` at `r_y`.
+    wire_evaluations: Vec<F>,
+    /// The openings of the wire commitments to their contributions.
+    wire_openings: Vec<LinearFormOpening<F, W>>,
+}
+
+impl<F, Comm> Decider<F, Comm> for SpartanDecider
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+    Comm::CommitmentWitness: OpenableCommitmentScheme<F>,
+    Comm::CommitmentSlack: OpenableCommitmentScheme<F>,
+{
+    const NAME: &'static str = "spartan";
+
+    type ProverKey = SpartanKey<F, Comm>;
+    type VerifierKey = SpartanKey<F, Comm>;
+    type Proof = SpartanProof<
+        F,
+        <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::Commitment,
+        <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+    >;
+
+    /// There is no setup beyond bridging the circuit to R1CS.
+    fn setup<R: Rng>(
+        public_parameters: &PublicParameters<F, Comm>,
+        prover_key: &ProverKey<F, Comm>,
+        _rng: &mut R,
+    ) -> Result<(Self::ProverKey, Self::VerifierKey), SangriaError> {
+        let key = SpartanKey {
+            shape: R1CSShape::from_plonk(&prover_key.circuit)?,
+            circuit_digest: prover_key.verifier_key.digest(),
+            commit_key_witness: public_parameters.commit_key_witness.clone(),
+            commit_key_slack: public_parameters.commit_key_selectors_and_slack.clone(),
+        };
+
+        Ok((key.clone(), key))
+    }
+
+    fn prove<R: Rng>(
+        prover_key: &Self::ProverKey,
+        instance: &RelaxedPLONKInstance<F, Comm>,
+        witness: &RelaxedPLONKWitness<F>,
+        rng: &mut R,
+    ) -> Result<Self::Proof, SangriaError> {
+        let shape = &prover_key.shape;
+        let number_of_gates = prover_key.number_of_gates();
+        let u = instance.scaling_factor();
+        let columns = (0..NUMBER_OF_WIRES)
+            .map(|column_index| witness.witness_column_with_rand(column_index))
+            .collect::<Result<Vec<_>, _>>()?;
+        let slack_vector = witness.slack_vector();
+        if columns
+            .iter()
+            .any(|(column, _)| column.len() != number_of_gates)
+            || slack_vector.len() != number_of_gates
+        {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+        let mut sponge = prover_key.transcript(instance)?;
+
+        // Outer sumcheck, over the constraints.
+        let number_of_constraints = shape.number_of_constraints().next_power_of_two();
+        let tau = (0..number_of_constraints.trailing_zeros())
+            .map(|_| LabeledChallenges::<F>::challenge(&mut sponge, OUTER_POINT))
+            .collect::<Vec<_>>();
+        let z_witness = columns
+            .iter()
+            .flat_map(|(column, _)| column.iter().copied())
+            .collect::<Vec<_>>();
+        let [mut az, mut bz, mut cz] = shape.products(&z_witness, u, &[])?;
+        let mut slack = slack_vector.clone();
+        for vector in [&mut az, &mut bz, &mut cz, &mut slack] {
+            vector.resize(number_of_constraints, F::zero());
+        }
+        let (outer_rounds, r_x, values) = prove_sumcheck(
+            &mut sponge,
+            vec![eq_evaluations(&tau), az, bz, cz, slack],
+            OUTER_DEGREE,
+            |v| v[0] * (v[1] * v[2] - u * v[3] - v[4]),
+        )?;
+        let matrix_evaluations = values[1..4].to_vec();
+
+        let eq_x = eq_evaluations(&r_x);
+        let slack_evaluation = inner_product(&eq_x[..number_of_gates], &slack_vector);
+        let slack_opening = Comm::CommitmentSlack::open_linear_form(
+            &prover_key.commit_key_slack,
+            &slack_vector,
+            witness.slack_hiding(),
+            &eq_x[..number_of_gates],
+            rng,
+        )?;
+        absorb_serialized(
+            &mut sponge,
+            &[matrix_evaluations.as_slice(), &[slack_evaluation]].concat(),
+        )?;
+
+        // Inner sumcheck, over the columns.
+        let weights = (0..3)
+            .map(|_| LabeledChallenges::<F>::challenge(&mut sponge, MATRIX_COMBINATION))
+            .collect::<Vec<_>>();
+        let number_of_columns = prover_key.number_of_columns().next_power_of_two();
+        let mut z = z_witness;
+        z.push(u);
+        z.resize(number_of_columns, F::zero());
+        let (inner_rounds, r_y, _) = prove_sumcheck(
+            &mut sponge,
+            vec![combined_row(shape, &eq_x, &weights, number_of_columns), z],
+            INNER_DEGREE,
+            |v| v[0] * v[1],
+        )?;
+
+        let eq_y = eq_evaluations(&r_y);
+        let mut wire_evaluations = Vec::new();
+        let mut wire_openings = Vec::new();
+        for (wire, (column, hiding)) in columns.iter().enumerate() {
+            let coefficients = &eq_y[wire * number_of_gates..(wire + 1) * number_of_gates];
+            wire_evaluations.push(inner_product(coefficients, column));
+            wire_openings.push(Comm::CommitmentWitness::open_linear_form(
+                &prover_key.commit_key_witness,
+                column,
+                *hiding,
+                coefficients,
+                rng,
+            )?);
+        }
+
+        Ok(SpartanProof {
+            outer_rounds,
+            matrix_evaluations,
+            slack_evaluation,
+            slack_opening,
+            inner_rounds,
+            wire_evaluations,
+            wire_openings,
+        })
+    }
+
+    fn verify(
+        verifier_key: &Self::VerifierKey,
+        instance: &RelaxedPLONKInstance<F, Comm>,
+        proof: &Self::Proof,
+    ) -> Result<(), SangriaError> {
+        instance.check_circuit(verifier_key.circuit_digest)?;
+        instance.check_padding()?;
+        let shape = &verifier_key.shape;
+        let number_of_gates = verifier_key.number_of_gates();
+        let u = instance.scaling_factor();
+        let witness_commitments = instance.witness_commitments();
+        if proof.matrix_evaluations.len() != 3
+            || [
+                witness_commitments.len(),
+                proof.wire_evaluations.len(),
+                proof.wire_openings.len(),
+            ]
+            .iter()
+            .any(|length| *length != NUMBER_OF_WIRES)
+        {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+        let mut sponge = verifier_key.transcript(instance)?;
+
+        let number_of_constraints = shape.number_of_constraints().next_power_of_two();
+        let tau = (0..number_of_constraints.trailing_zeros())
+            .map(|_| LabeledChallenges::<F>::challenge(&mut sponge, OUTER_POINT))
+            .collect::<Vec<_>>();
+        let (outer_claim, r_x) = verify_sumcheck(
+            &mut sponge,
+            &proof.outer_rounds,
+            tau.len(),
+            OUTER_DEGREE,
+            F::zero(),
+        )?;
+        let [a, b, c] = [0, 1, 2].map(|index| proof.matrix_evaluations[index]);
+        if outer_claim != eq_at(&tau, &r_x) * (a * b - u * c - proof.slack_evaluation) {
+            return Err(VerificationFailure::RelaxedRelation
+                .reject(|| String::from("outer sumcheck does not reduce to the constraints")));
+        }
+
+        let eq_x = eq_evaluations(&r_x);
+        Comm::CommitmentSlack::verify_linear_form(
+            &verifier_key.commit_key_slack,
+            &instance.slack_commitment(),
+            &eq_x[..number_of_gates],
+            proof.slack_evaluation,
+            &proof.slack_opening,
+        )?;
+        absorb_serialized(
+            &mut sponge,
+            &[
+                proof.matrix_evaluations.as_slice(),
+                &[proof.slack_evaluation],
+            ]
+            .concat(),
+        )?;
+
+        let weights = (0..3)
+            .map(|_| LabeledChallenges::<F>::challenge(&mut sponge, MATRIX_COMBINATION))
+            .collect::<Vec<_>>();
+        let number_of_columns = verifier_key.number_of_columns().next_power_of_two();
+        let (inner_claim, r_y) = verify_sumcheck(
+            &mut sponge,
+            &proof.inner_rounds,
+            number_of_columns.trailing_zeros() as usize,
+            INNER_DEGREE,
+            inner_product(&weights, &proof.matrix_evaluations),
+        )?;
+
+        let eq_y = eq_evaluations(&r_y);
+        for (wire, (commitment, (evaluation, opening))) in witness_commitments
+            .iter()
+            .zip(proof.wire_evaluations.iter().zip(&proof.wire_openings))
+            .enumerate()
+        {
+            Comm::CommitmentWitness::verify_linear_form(
+                &verifier_key.commit_key_witness,
+                commitment,
+                &eq_y[wire * number_of_gates..(wire + 1) * number_of_gates],
+                *evaluation,
+                opening,
+            )?;
+        }
+        let z_evaluation = proof.wire_evaluations.iter().copied().sum::<F>()
+            + eq_y[NUMBER_OF_WIRES * number_of_gates] * u;
+        let matrix_evaluation = inner_product(
+            &combined_row(shape, &eq_x, &weights, number_of_columns),
+            &eq_y,
+        );
+        if inner_claim != matrix_evaluation * z_evaluation {
+            return Err(VerificationFailure::RelaxedRelation
+                .reject(|| String::from("inner sumcheck does not reduce to the witness")));
+        }
+
+        Ok(())
+    }
+}
+
+/// Proves that the sum of `combine` over the hypercube of `tables` is the claimed sum. Returns
+/// the round polynomials, the random point and the values of the tables at the point.
+#[allow(clippy::type_complexity)]
+fn prove_sumcheck<F: PrimeField>(
+    sponge: &mut Blake2bSponge,
+    mut tables: Vec<Vec<F>>,
+    degree: usize,
+    combine: impl Fn(&[F]) -> F,
+) -> Result<(Vec<Vec<F>>, Vec<F>, Vec<F>), SangriaError> {
+    let mut rounds = Vec::new();
+    let mut point = Vec::new();
+    let mut values = vec![F::zero(); tables.len()];
+    while tables[0].len() > 1 {
+        let half = tables[0].len() / 2;
+        let round = (0..=degree as u64)
+            .map(|t| {
+                let t = F::from(t);
+                (0..half)
+                    .map(|k| {
+                        for (value, table) in values.iter_mut().zip(&tables) {
+                            *value = table[2 * k] + t * (table[2 * k + 1] - table[2 * k]);
+                        }
+                        combine(&values)
+                    })
+                    .sum()
+            })
+            .collect::<Vec<F>>();
+
+        let challenge = round_challenge(sponge, &round)?;
+        for table in &mut tables {
+            *table = (0..half)
+                .map(|k| table[2 * k] + challenge * (table[2 * k + 1] - table[2 * k]))
+                .collect();
+        }
+        rounds.push(round);
+        point.push(challenge);
+    }
+
+    Ok((rounds, point, tables.iter().map(|table| table[0]).collect()))
+}
+
+/// Checks the rounds of a sumcheck of `claim` over `number_of_variables` variables. Returns the
+/// claimed value at the random point, which the caller must check, and the point.
+fn verify_sumcheck<F: PrimeField>(
+    sponge: &mut Blake2bSponge,
+    rounds: &[Vec<F>],
+    number_of_variables: usize,
+    degree: usize,
+    mut claim: F,
+) -> Result<(F, Vec<F>), SangriaError> {
+    if rounds.len() != number_of_variables || rounds.iter().any(|round| round.len() != degree + 1) {
+        return Err(SangriaError::IndexOutOfBounds);
+    }
+
+    let mut point = Vec::new();
+    for (index, round) in rounds.iter().enumerate() {
+        if round[0] + round[1] != claim {
+            return Err(VerificationFailure::Sumcheck
+                .reject(|| format!("round {} does not add up to the claimed sum", index)));
+        }
+        let challenge = round_challenge(sponge, round)?;
+        claim = interpolate(round, challenge);
+        point.push(challenge);
+    }
+
+    Ok((claim, point))
+}
+
+fn round_challenge<F: PrimeField>(
+    sponge: &mut Blake2bSponge,
+    round: &[F],
+) -> Result<F, SangriaError> {
+    absorb_serialized(sponge, &round.to_vec())?;
+    Ok(LabeledChallenges::<F>::challenge(
+        sponge,
+        SUMCHECK_CHALLENGE,
+    ))
+}
+
+fn absorb_serialized<T: CanonicalSerialize>(
+    sponge: &mut Blake2bSponge,
+    value: &T,
+) -> Result<(), SangriaError> {
+    let mut bytes = Vec::new();
+    value
+        .serialize(&mut bytes)
+        .map_err(|_| SangriaError::SerializationError)?;
+    sponge.absorb(&bytes);
+    Ok(())
+}
+
+/// Evaluates at `point` the polynomial with the given evaluations at `0, 1, ...`.
+fn interpolate<F: PrimeField>(evaluations: &[F], point: F) -> F {
+    let node = |index: usize| F::from(index as u64);
+    (0..evaluations.len())
+        .map(|i| {
+            let (numerator, denominator) = (0..evaluations.len()).filter(|j| *j != i).fold(
+                (F::one(), F::one()),
+                |(numerator, denominator), j| {
+                    (
+                        numerator * (point - node(j)),
+                        denominator * (node(i) - node(j)),
+                    )
+                },
+            );
+            evaluations[i]
+                * numerator
+                * denominator
+                    .inverse()
+                    .expect("interpolation nodes are distinct")
+        })
+        .sum()
+}
+
+/// Returns `eq(point, x)` for every `x` of the hypercube.
+fn eq_evaluations<F: PrimeField>(point: &[F]) -> Vec<F> {
+    let mut evaluations = vec![F::one()];
+    for coordinate in point {
+        let high = evaluations
+            .iter()
+            .map(|evaluation| *evaluation * coordinate)
+            .collect::<Vec<_>>();
+        for (evaluation, high) in evaluations.iter_mut().zip(&high) {
+            *evaluation -= high;
+        }
+        evaluations.extend(high);
+    }
+    evaluations
+}
+
+/// Returns `eq(left, right)`.
+fn eq_at<F: PrimeField>(left: &[F], right: &[F]) -> F {
+    left.iter()
+        .zip(right)
+        .map(|(l, r)| *l * r + (F::one() - l) * (F::one() - r))
+        .product()
+}
+
+/// Returns `(r_A * A + r_B * B + r_C * C)(r_x, y)` for every column `y`, given `eq(r_x, .)`.
+fn combined_row<F: PrimeField>(
+    shape: &R1CSShape<F>,
+    eq_x: &[F],
+    weights: &[F],
+    number_of_columns: usize,
+) -> Vec<F> {
+    let mut row = vec![F::zero(); number_of_columns];
+    for (matrix, weight) in shape.matrices().iter().zip(weights) {
+        for (i, j, value) in matrix.iter() {
+            row[*j] += *weight * value * eq_x[*i];
+        }
+    }
+    row
+}
+
+fn inner_product<F: PrimeField>(left: &[F], right: &[F]) -> F {
+    left.iter().zip(right).map(|(l, r)| *l * r).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        preprocessing::cell_identities,
+        relations::{relax, PLONKInstance, PLONKWitness, SetupInfo, SlackRandomization},
+        test_utils::seeded_rng,
+        test_vectors::{poseidon_parameters_for_test, sponge_config_for_test},
+        vector_commitment::mock::FieldCommitmentConfig,
+        NonInteractiveFoldingScheme, PLONKCircuit, PLONKFoldingScheme,
+    };
+    use ark_pallas::Fr;
+    use ark_sponge::poseidon::PoseidonSponge;
+    use ark_std::UniformRand;
+
+    type Comm = FieldCommitmentConfig<Fr>;
+    type FoldingScheme = PLONKFoldingScheme<Fr, Comm, PoseidonSponge<Fr>>;
+
+    #[test]
+    fn folded_pairs_are_decided_without_their_witness() {
+        let rng =
+            &mut seeded_rng("compression::spartan::folded_pairs_are_decided_without_their_witness");
+        let number_of_gates = 4;
+        let info = SetupInfo {
+            number_of_public_inputs: 0,
+            number_of_gates,
+            domain_separator: b"sangria-spartan-test".to_vec(),
+            poseidon_constants: poseidon_parameters_for_test(rng),
+            poseidon2_constants: None,
+            sponge_config: sponge_config_for_test(),
+            slack_randomization: SlackRandomization::EveryFold,
+        };
+        let pp = FoldingScheme::setup(&info, rng).unwrap();
+
+        // c = a * b + a at every gate, and the output of each gate is the left input of the next.
+        let (zero, one) = (Fr::from(0u64), Fr::from(1u64));
+        let constant = |value: Fr| vec![value; number_of_gates];
+        let selectors = vec![
+            constant(one),
+            constant(zero),
+            constant(-one),
+            constant(one),
+            constant(zero),
+        ];
+        let mut copy_constraint = cell_identities::<Fr>(number_of_gates).unwrap();
+        for row in 0..number_of_gates - 1 {
+            copy_constraint.swap(2 * number_of_gates + row, row + 1);
+        }
+        let circuit = PLONKCircuit::new(selectors, copy_constraint);
+        let (pk, vk) = FoldingScheme::encode(&pp, &circuit, rng).unwrap();
+        let (decider_pk, decider_vk) = SpartanDecider::setup(&pp, &pk, rng).unwrap();
+
+        let statement = |rng: &mut _| {
+            let b = (0..number_of_gates)
+                .map(|_| Fr::rand(rng))
+                .collect::<Vec<_>>();
+            let (mut a, mut c) = (vec![Fr::rand(rng)], Vec::new());
+            for b_i in &b {
+                let c_i = a[a.len() - 1] * b_i + a[a.len() - 1];
+                c.push(c_i);
+                a.push(c_i);
+            }
+            a.truncate(number_of_gates);
+            relax(
+                &pp,
+                &vk,
+                PLONKInstance::new(vec![]),
+                PLONKWitness::new(vec![a, b, c]),
+                rng,
+            )
+            .unwrap()
+        };
+        let (left, left_witness) = statement(rng);
+        let (right, right_witness) = statement(rng);
+        let (instance, witness, _) =
+            FoldingScheme::prover(&pp, &pk, &left, &left_witness, &right, &right_witness).unwrap();
+        assert!(!instance.is_strict());
+
+        let proof = SpartanDecider::prove(&decider_pk, &instance, &witness, rng).unwrap();
+        assert_eq!(
+            SpartanDecider::verify(&decider_vk, &instance, &proof),
+            Ok(())
+        );
+        assert!(SpartanDecider::verify(&decider_vk, &left, &proof).is_err());
+
+        #[cfg(feature = "json")]
+        {
+            let json = decider_vk.to_json().unwrap();
+            let imported = SpartanKey::<Fr, Comm>::from_json(&json, &pp).unwrap();
+            assert_eq!(imported.shape(), decider_vk.shape());
+            assert_eq!(SpartanDecider::verify(&imported, &instance, &proof), Ok(()));
+        }
+
+        // A witness of another statement does not satisfy the folded relation.
+        let proof = SpartanDecider::prove(&decider_pk, &instance, &left_witness, rng).unwrap();
+        assert!(SpartanDecider::verify(&decider_vk, &instance, &proof).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! Computation of the cross term `T` of two relaxed PLONK traces. For the relaxed gate equation
+//! `u * (q_L * a + q_R * b + q_O * c) + q_M * a * b + u^2 * q_C`, folding two traces with
+//! challenge `r` yields a polynomial in `r` whose linear coefficient is, for every row,
+//!
+//! `T = u_1 * (q_L * a_2 + q_R * b_2 + q_O * c_2) + u_2 * (q_L * a_1 + q_R * b_1 + q_O * c_1)
+//!     + q_M * (a_1 * b_2 + a_2 * b_1) + 2 * u_1 * u_2 * q_C`.
+//!
+//! Every row is independent, so rows are processed in chunks that fit in cache, in parallel
+//! under the `parallel` feature. Under the `simd` feature, the rows of a chunk are evaluated
+//! [`LANES`] at a time, one operation across all lanes before the next. The lanes do not depend on
+//! each other, so their Montgomery multiplications can be pipelined by the CPU and vectorized by
+//! the compiler where the field backend allows it.
+
+use ark_ff::PrimeField;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    SangriaError, CONSTANT_SELECTOR_INDEX, LEFT_SELECTOR_INDEX, MULTIPLICATION_SELECTOR_INDEX,
+    OUTPUT_SELECTOR_INDEX, RIGHT_SELECTOR_INDEX,
+};
+
+/// Tuning knobs for the cross-term computation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrossTermConfig {
+    /// The number of rows processed together. Each chunk touches `5 + 2 * 3` columns, so the
+    /// default keeps a chunk of 256-bit field elements well within a typical L2 cache.
+    pub chunk_size: usize,
+}
+
+/// The number of rows evaluated together under the `simd` feature.
+pub const LANES: usize = 4;
+
+impl Default for CrossTermConfig {
+    fn default() -> Self {
+        Self { chunk_size: 1024 }
+    }
+}
+
+/// The wire columns `a`, `b` and `c` of a relaxed PLONK trace and its scaling factor `u`.
+#[derive(Clone, Copy, Debug)]
+pub struct RelaxedTrace<'a, F: PrimeField> {
+    /// The columns `a`, `b` and `c`.
+    pub columns: [&'a [F]; 3],
+    /// The scaling factor `u`.
+    pub scaling_factor: F,
+}
+
+fn check_lengths<F: PrimeField>(
+    selectors: &[Vec<F>],
+    left: &RelaxedTrace<F>,
+    right: &RelaxedTrace<F>,
+) -> Result<usize, SangriaError> {
+    let number_of_rows = left.columns[0].len();
+    let selectors = selectors
+        .get(..=CONSTANT_SELECTOR_INDEX)
+        .ok_or(SangriaError::IndexOutOfBounds)?;
+
+    let all_columns = selectors
+        .iter()
+        .map(Vec::as_slice)
+        .chain(left.columns)
+        .chain(right.columns);
+    for column in all_columns {
+        if column.len() != number_of_rows {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+    }
+
+    Ok(number_of_rows)
+}
+
+/// Computes the cross term row by row, in chunks of `config.chunk_size` rows.
+pub fn compute_cross_term<F: PrimeField>(
+    selectors: &[Vec<F>],
+    left: &RelaxedTrace<F>,
+    right: &RelaxedTrace<F>,
+    config: CrossTermConfig,
+) -> Result<Vec<F>, SangriaError> {
+    let number_of_rows = check_lengths(selectors, left, right)?;
+    let chunk_size = config.chunk_size.max(1);
+
+    let q_l = &selectors[LEFT_SELECTOR_INDEX];
+    let q_r = &selectors[RIGHT_SELECTOR_INDEX];
+    let q_o = &selectors[OUTPUT_SELECTOR_INDEX];
+    let q_m = &selectors[MULTIPLICATION_SELECTOR_INDEX];
+    let q_c = &selectors[CONSTANT_SELECTOR_INDEX];
+    let [a_1, b_1, c_1] = left.columns;
+    let [a_2, b_2, c_2] = right.columns;
+    let (u_1, u_2) = (left.scaling_factor, right.scaling_factor);
+    let two_u_1_u_2 = (u_1 * u_2).double();
+
+    let mut cross_term = vec![F::zero(); number_of_rows];
+
+    #[cfg(feature = "parallel")]
+    let chunks = cross_term.par_chunks_mut(chunk_size);
+    #[cfg(not(feature = "parallel"))]
+    let chunks = cross_term.chunks_mut(chunk_size);
+
+    let row = |i: usize| {
+        let linear_1 = q_l[i] * a_1[i] + q_r[i] * b_1[i] + q_o[i] * c_1[i];
+        let linear_2 = q_l[i] * a_2[i] + q_r[i] * b_2[i] + q_o[i] * c_2[i];
+
+        u_1 * linear_2
+            + u_2 * linear_1
+            + q_m[i] * (a_1[i] * b_2[i] + a_2[i] * b_1[i])
+            + two_u_1_u_2 * q_c[i]
+    };
+
+    chunks.enumerate().for_each(|(chunk_index, chunk)| {
+        let start = chunk_index * chunk_size;
+
+        #[cfg(not(feature = "simd"))]
+        let evaluated = 0;
+        #[cfg(feature = "simd")]
+        let evaluated = {
+            for (batch_index, batch) in chunk.chunks_exact_mut(LANES).enumerate() {
+                let i = start + batch_index * LANES;
+                let lanes =
+                    |column: &[F]| -> [F; LANES] { column[i..i + LANES].try_into().unwrap() };
+                let linear = |a: &[F], b: &[F], c: &[F]| {
+                    add(
+                        add(mul(lanes(q_l), lanes(a)), mul(lanes(q_r), lanes(b))),
+                        mul(lanes(q_o), lanes(c)),
+                    )
+                };
+
+                let linear_terms = add(
+                    scale(u_1, linear(a_2, b_2, c_2)),
+                    scale(u_2, linear(a_1, b_1, c_1)),
+                );
+                let multiplication_term = mul(
+                    lanes(q_m),
+                    add(mul(lanes(a_1), lanes(b_2)), mul(lanes(a_2), lanes(b_1))),
+                );
+                let constant_term = scale(two_u_1_u_2, lanes(q_c));
+                batch.copy_from_slice(&add(add(linear_terms, multiplication_term), constant_term));
+            }
+
+            // The rows that do not fill a batch are evaluated one by one below.
+            chunk.len() - chunk.len() % LANES
+        };
+
+        for (offset, t) in chunk.iter_mut().enumerate().skip(evaluated) {
+            *t = row(start + offset);
+        }
+    });
+
+    Ok(cross_term)
+}
+
+#[cfg(feature = "simd")]
+fn add<F: PrimeField>(x: [F; LANES], y: [F; LANES]) -> [F; LANES] {
+    core::array::from_fn(|lane| x[lane] + y[lane])
+}
+
+#[cfg(feature = "simd")]
+fn mul<F: PrimeField>(x: [F; LANES], y: [F; LANES]) -> [F; LANES] {
+    core::array::from_fn(|lane| x[lane] * y[lane])
+}
+
+#[cfg(feature = "simd")]
+fn scale<F: PrimeField>(scalar: F, x: [F; LANES]) -> [F; LANES] {
+    x.map(|x_i| scalar * x_i)
+}
+
+/// Computes the cross term one column operation at a time. Each pass streams full columns
+/// through the cache; this is the baseline [`compute_cross_term`] is benchmarked against.
+pub fn compute_cross_term_column_by_column<F: PrimeField>(
+    selectors: &[Vec<F>],
+    left: &RelaxedTrace<F>,
+    right: &RelaxedTrace<F>,
+) -> Result<Vec<F>, SangriaError> {
+    check_lengths(selectors, left, right)?;
+
+    compute_cross_term_streaming(
+        |selector_index| Ok(selectors[selector_index].as_slice()),
+        left,
+        right,
+    )
+}
+
+/// Computes the cross term column by column, reading each of the five gate selectors through
+/// `read_selector` right before its pass. Only one selector is held at a time, so the selectors
+/// can be read from disk, e.g. with [`crate::relations::ProverKeyHandle::read_selector`].
+pub fn compute_cross_term_streaming<F, S>(
+    mut read_selector: impl FnMut(usize) -> Result<S, SangriaError>,
+    left: &RelaxedTrace<F>,
+    right: &RelaxedTrace<F>,
+) -> Result<Vec<F>, SangriaError>
+where
+    F: PrimeField,
+    S: AsRef<[F]>,
+{
+    let number_of_rows = left.columns[0].len();
+    if left
+        .columns
+        .iter()
+        .chain(&right.columns)
+        .any(|column| column.len() != number_of_rows)
+    {
+        return Err(SangriaError::IndexOutOfBounds);
+    }
+    let mut read_selector = |selector_index| {
+        let selector = read_selector(selector_index)?;
+        if selector.as_ref().len() != number_of_rows {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+        Ok(selector)
+    };
+    let (u_1, u_2) = (left.scaling_factor, right.scaling_factor);
+
+    let mut cross_term = vec![F::zero(); number_of_rows];
+    let linear_selectors = [
+        LEFT_SELECTOR_INDEX,
+        RIGHT_SELECTOR_INDEX,
+        OUTPUT_SELECTOR_INDEX,
+    ];
+    for (selector_index, column_index) in linear_selectors.into_iter().zip(0..3) {
+        let selector = read_selector(selector_index)?;
+        for ((t, q), w) in cross_term
+            .iter_mut()
+            .zip(selector.as_ref())
+            .zip(right.columns[column_index])
+        {
+            *t += u_1 * q * w;
+        }
+        for ((t, q), w) in cross_term
+            .iter_mut()
+            .zip(selector.as_ref())
+            .zip(left.columns[column_index])
+        {
+            *t += u_2 * q * w;
+        }
+    }
+
+    let q_m = read_selector(MULTIPLICATION_SELECTOR_INDEX)?;
+    for (((t, q), a), b) in cross_term
+        .iter_mut()
+        .zip(q_m.as_ref())
+        .zip(left.columns[0])
+        .zip(right.columns[1])
+    {
+        *t += *q * a * b;
+    }
+    for (((t, q), a), b) in cross_term
+        .iter_mut()
+        .zip(q_m.as_ref())
+        .zip(right.columns[0])
+        .zip(left.columns[1])
+    {
+        *t += *q * a * b;
+    }
+
+    let two_u_1_u_2 = (u_1 * u_2).double();
+    let q_c = read_selector(CONSTANT_SELECTOR_INDEX)?;
+    for (t, q) in cross_term.iter_mut().zip(q_c.as_ref()) {
+        *t += two_u_1_u_2 * q;
+    }
+
+    Ok(cross_term)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seeded_rng;
+    use ark_pallas::Fr;
+    use ark_std::UniformRand;
+
+    fn random_column(rng: &mut impl ark_std::rand::Rng, length: usize) -> Vec<Fr> {
+        (0..length).map(|_| Fr::rand(rng)).collect()
+    }
+
+    // The relaxed gate evaluated on a trace.
+    fn gate(selectors: &[Vec<Fr>], columns: &[Vec<Fr>; 3], u: Fr, i: usize) -> Fr {
+        u * (selectors[0][i] * columns[0][i]
+            + selectors[1][i] * columns[1][i]
+            + selectors[2][i] * columns[2][i])
+            + selectors[3][i] * columns[0][i] * columns[1][i]
+            + u.square() * selectors[4][i]
+    }
+
+    #[test]
+    fn cross_term_is_the_linear_coefficient_of_the_folded_gate() {
+        let rng =
+            &mut seeded_rng("cross_term::cross_term_is_the_linear_coefficient_of_the_folded_gate");
+        let number_of_rows = 37;
+        let selectors = (0..5)
+            .map(|_| random_column(rng, number_of_rows))
+            .collect::<Vec<_>>();
+        let left_columns = [(); 3].map(|_| random_column(rng, number_of_rows));
+        let right_columns = [(); 3].map(|_| random_column(rng, number_of_rows));
+        let (u_1, u_2, r) = (Fr::rand(rng), Fr::rand(rng), Fr::rand(rng));
+
+        let left = RelaxedTrace {
+            columns: [&left_columns[0], &left_columns[1], &left_columns[2]],
+            scaling_factor: u_1,
+        };
+        let right = RelaxedTrace {
+            columns: [&right_columns[0], &right_columns[1], &right_columns[2]],
+            scaling_factor: u_2,
+        };
+
+        let naive = compute_cross_term_column_by_column(&selectors, &left, &right).unwrap();
+        let mut read_selectors = Vec::new();
+        let streamed = compute_cross_term_streaming(
+            |selector_index| {
+                read_selectors.push(selector_index);
+                Ok(selectors[selector_index].clone())
+            },
+            &left,
+            &right,
+        )
+        .unwrap();
+        assert_eq!(streamed, naive);
+        assert_eq!(read_selectors, [0, 1, 2, 3, 4]);
+        for chunk_size in [1, 4, 36, 37, 1024] {
+            let chunked =
+                compute_cross_term(&selectors, &left, &right, CrossTermConfig { chunk_size })
+                    .unwrap();
+            assert_eq!(chunked, naive);
+        }
+
+        let folded_columns = [0, 1, 2].map(|j| {
+            left_columns[j]
+                .iter()
+                .zip(&right_columns[j])
+                .map(|(l, r_j)| *l + r * r_j)
+                .collect::<Vec<_>>()
+        });
+        for (i, t) in naive.iter().enumerate() {
+            assert_eq!(
+                gate(&selectors, &folded_columns, u_1 + r * u_2, i),
+                gate(&selectors, &left_columns, u_1, i)
+                    + r * t
+                    + r.square() * gate(&selectors, &right_columns, u_2, i)
+            );
+        }
+    }
+}
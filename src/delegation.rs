@@ -0,0 +1,191 @@
+//! Delegated proving. The owner of a computation encrypts every step witness under a fresh data
+//! key and wraps the data key for the party allowed to use it, e.g. seals it to a TEE the
+//! delegated prover runs in, or secret-shares it among threshold key holders. The delegated
+//! prover only sees ciphertexts: a [`DelegatedWitnessProvider`] fetches them, has the key
+//! unwrapped and decrypts the witness right before the step is proven by the
+//! [`crate::driver`].
+//!
+//! The ciphers and key services are deployment specific and are plugged in through
+//! [`EncryptedWitnessSource`], [`KeyUnwrapper`] and [`WitnessCipher`].
+
+use ark_ff::PrimeField;
+use std::{future::Future, marker::PhantomData};
+
+use crate::{driver::WitnessProvider, SangriaError, StepCircuit};
+
+/// A data key wrapped for the party that may unwrap it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WrappedKey {
+    /// Names the key that wrapped the data key, e.g. a TEE measurement or a threshold key id.
+    pub wrapping_key_id: String,
+    /// The wrapped data key.
+    pub bytes: Vec<u8>,
+}
+
+/// A step witness encrypted under a data key, together with the wrapped data key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedWitness {
+    /// The encrypted witness, including any nonce the cipher needs.
+    pub ciphertext: Vec<u8>,
+    /// The data key the witness is encrypted under.
+    pub wrapped_key: WrappedKey,
+}
+
+/// Where a delegated prover fetches the encrypted witnesses from, e.g. a queue or the owner's
+/// service.
+pub trait EncryptedWitnessSource {
+    /// Fetches the encrypted witness of the `step_index`-th step.
+    fn fetch_encrypted(
+        &mut self,
+        step_index: usize,
+    ) -> impl Future<Output = Result<EncryptedWitness, SangriaError>> + Send;
+}
+
+/// Unwraps data keys, e.g. inside a TEE or by combining threshold shares.
+pub trait KeyUnwrapper {
+    /// Unwraps a data key. Returns [`SangriaError::WitnessUnavailable`] if this party may not
+    /// unwrap it.
+    fn unwrap_key(&self, wrapped_key: &WrappedKey) -> Result<Vec<u8>, SangriaError>;
+}
+
+/// Decrypts step witnesses.
+pub trait WitnessCipher<F: PrimeField, SC: StepCircuit<F>> {
+    /// Decrypts a witness with a data key. Returns [`SangriaError::WitnessUnavailable`] if the
+    /// ciphertext does not decrypt under the key.
+    fn decrypt(&self, data_key: &[u8], ciphertext: &[u8]) -> Result<SC::Witness, SangriaError>;
+}
+
+/// A [`WitnessProvider`] for delegated proving, see the module documentation.
+pub struct DelegatedWitnessProvider<F, SC, S, U, C> {
+    source: S,
+    unwrapper: U,
+    cipher: C,
+    _step_circuit: PhantomData<fn() -> (F, SC)>,
+}
+
+impl<F, SC, S, U, C> DelegatedWitnessProvider<F, SC, S, U, C>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    S: EncryptedWitnessSource,
+    U: KeyUnwrapper,
+    C: WitnessCipher<F, SC>,
+{
+    /// Creates a provider that fetches from `source`, unwraps with `unwrapper` and decrypts with
+    /// `cipher`.
+    pub fn new(source: S, unwrapper: U, cipher: C) -> Self {
+        Self {
+            source,
+            unwrapper,
+            cipher,
+            _step_circuit: PhantomData,
+        }
+    }
+}
+
+impl<F, SC, S, U, C> WitnessProvider<F, SC> for DelegatedWitnessProvider<F, SC, S, U, C>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    S: EncryptedWitnessSource + Send,
+    U: KeyUnwrapper + Send,
+    C: WitnessCipher<F, SC> + Send,
+{
+    async fn fetch(&mut self, step_index: usize) -> Result<SC::Witness, SangriaError> {
+        let encrypted = self.source.fetch_encrypted(step_index).await?;
+        let data_key = self.unwrapper.unwrap_key(&encrypted.wrapped_key)?;
+        self.cipher.decrypt(&data_key, &encrypted.ciphertext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::block_on;
+    use ark_pallas::Fr;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    /// The step circuit `z_{i+1} = z_i + w`.
+    struct Add;
+
+    impl StepCircuit<Fr> for Add {
+        type State = Fr;
+        type Witness = Fr;
+        type Outputs = ();
+
+        fn witness_arity(&self) -> usize {
+            1
+        }
+
+        fn witness_elements(witness: &Fr) -> Vec<Fr> {
+            vec![*witness]
+        }
+
+        fn output_elements(_outputs: &()) -> Vec<Fr> {
+            Vec::new()
+        }
+
+        fn state_elements(state: &Fr) -> Vec<Fr> {
+            vec![*state]
+        }
+    }
+
+    /// Witnesses `1, 2, ...`, encrypted by xoring with the data key `[step; 32]`, itself wrapped
+    /// by xoring with the key of the enclave.
+    struct Queue;
+    struct Enclave(u8);
+    struct Xor;
+
+    fn xor(bytes: &[u8], key: &[u8]) -> Vec<u8> {
+        bytes
+            .iter()
+            .zip(key.iter().cycle())
+            .map(|(b, k)| b ^ k)
+            .collect()
+    }
+
+    impl EncryptedWitnessSource for Queue {
+        async fn fetch_encrypted(
+            &mut self,
+            step_index: usize,
+        ) -> Result<EncryptedWitness, SangriaError> {
+            let data_key = [step_index as u8; 32];
+            let mut plaintext = Vec::new();
+            Fr::from(step_index as u64 + 1)
+                .serialize(&mut plaintext)
+                .unwrap();
+            Ok(EncryptedWitness {
+                ciphertext: xor(&plaintext, &data_key),
+                wrapped_key: WrappedKey {
+                    wrapping_key_id: String::from("enclave"),
+                    bytes: xor(&data_key, &[7]),
+                },
+            })
+        }
+    }
+
+    impl KeyUnwrapper for Enclave {
+        fn unwrap_key(&self, wrapped_key: &WrappedKey) -> Result<Vec<u8>, SangriaError> {
+            Ok(xor(&wrapped_key.bytes, &[self.0]))
+        }
+    }
+
+    impl WitnessCipher<Fr, Add> for Xor {
+        fn decrypt(&self, data_key: &[u8], ciphertext: &[u8]) -> Result<Fr, SangriaError> {
+            Fr::deserialize(xor(ciphertext, data_key).as_slice())
+                .map_err(|_| SangriaError::WitnessUnavailable)
+        }
+    }
+
+    #[test]
+    fn delegated_witnesses_are_decrypted_with_the_unwrapped_key() {
+        let mut provider =
+            DelegatedWitnessProvider::<Fr, Add, _, _, _>::new(Queue, Enclave(7), Xor);
+        assert_eq!(block_on(provider.fetch(2)), Ok(Fr::from(3u64)));
+
+        // Another enclave unwraps another data key, which does not decrypt the witness.
+        let mut provider =
+            DelegatedWitnessProvider::<Fr, Add, _, _, _>::new(Queue, Enclave(8), Xor);
+        assert_ne!(block_on(provider.fetch(2)), Ok(Fr::from(3u64)));
+    }
+}
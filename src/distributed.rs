@@ -0,0 +1,325 @@
+//! Folding of a long sequence of statements of one circuit by several provers. Each worker folds
+//! a contiguous segment of the sequence into a [`SegmentAccumulator`] with [`fold_segment`], and a
+//! coordinator merges the accumulators of consecutive segments with [`merge_segments`]. The result
+//! is the pair a single prover would have folded, up to the order of the folds, and is decided
+//! once with [`crate::relations::decide`].
+//!
+//! A verifier replays every fold with [`fold_instances`]: first the statements of each segment
+//! with the prover messages of the segment, then the segment instances with the prover messages
+//! of the coordinator.
+//!
+//! Workers send their accumulators to the coordinator with [`SegmentAccumulator::to_bytes`]: the
+//! index of the first statement and the number of statements as `u64`, the relaxed instance, the
+//! relaxed witness and the prover messages of the folds of the segment, each encoded as described
+//! in [`crate::serialization`].
+
+use ark_ff::PrimeField;
+use ark_sponge::Absorb;
+
+use crate::{
+    folding_scheme::{FoldingCommitmentConfig, ProverKey, PublicParameters, VerifierKey},
+    serialization::{deserialize_with_mode, serialize_with_mode, SerializationMode},
+    transcript::FoldingRandomOracle,
+    vector_commitment::HomomorphicCommitmentScheme,
+    NonInteractiveFoldingScheme, PLONKFoldingScheme, RelaxedPLONKInstance, RelaxedPLONKWitness,
+    SangriaError,
+};
+
+/// The prover message of a fold: the commitment to the cross term.
+pub type ProverMessage<F, Comm> =
+    <<Comm as FoldingCommitmentConfig<F>>::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment;
+
+/// The running pair of a contiguous segment of statements, with the prover messages of the folds
+/// that produced it.
+pub struct SegmentAccumulator<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    pub(crate) first_statement: u64,
+    pub(crate) number_of_statements: u64,
+    pub(crate) instance: RelaxedPLONKInstance<F, Comm>,
+    pub(crate) witness: RelaxedPLONKWitness<F>,
+    pub(crate) prover_messages: Vec<ProverMessage<F, Comm>>,
+}
+
+impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> SegmentAccumulator<F, Comm> {
+    /// Returns the index of the first statement of the segment.
+    pub fn first_statement(&self) -> u64 {
+        self.first_statement
+    }
+
+    /// Returns the number of statements of the segment.
+    pub fn number_of_statements(&self) -> u64 {
+        self.number_of_statements
+    }
+
+    /// Returns the running instance of the segment.
+    pub fn instance(&self) -> &RelaxedPLONKInstance<F, Comm> {
+        &self.instance
+    }
+
+    /// Returns the running witness of the segment.
+    pub fn witness(&self) -> &RelaxedPLONKWitness<F> {
+        &self.witness
+    }
+
+    /// Returns the prover messages of the folds of the segment, in order.
+    pub fn prover_messages(&self) -> &[ProverMessage<F, Comm>] {
+        &self.prover_messages
+    }
+
+    /// Serializes the segment, see the module documentation.
+    pub fn to_bytes(&self, mode: SerializationMode) -> Result<Vec<u8>, SangriaError> {
+        let mut bytes = Vec::new();
+        serialize_with_mode(&self.first_statement, &mut bytes, mode)?;
+        serialize_with_mode(&self.number_of_statements, &mut bytes, mode)?;
+        self.instance.write(&mut bytes, mode)?;
+        self.witness.write(&mut bytes, mode)?;
+        serialize_with_mode(&self.prover_messages, &mut bytes, mode)?;
+
+        Ok(bytes)
+    }
+
+    /// Deserializes a segment serialized with the same `mode`.
+    pub fn from_bytes(bytes: &[u8], mode: SerializationMode) -> Result<Self, SangriaError> {
+        let mut reader = bytes;
+        let first_statement = deserialize_with_mode(&mut reader, mode)?;
+        let number_of_statements: u64 = deserialize_with_mode(&mut reader, mode)?;
+        let instance = RelaxedPLONKInstance::read(&mut reader, mode)?;
+        let witness = RelaxedPLONKWitness::read(&mut reader, mode)?;
+        let prover_messages: Vec<_> = deserialize_with_mode(&mut reader, mode)?;
+        if !reader.is_empty() || number_of_statements == 0 {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(Self {
+            first_statement,
+            number_of_statements,
+            instance,
+            witness,
+            prover_messages,
+        })
+    }
+}
+
+/// Folds the relaxed pairs of the statements `first_statement, first_statement + 1, ...` from
+/// left to right. Returns an error if there are none.
+pub fn fold_segment<F, Comm, RO>(
+    public_parameters: &PublicParameters<F, Comm>,
+    prover_key: &ProverKey<F, Comm>,
+    first_statement: u64,
+    pairs: Vec<(RelaxedPLONKInstance<F, Comm>, RelaxedPLONKWitness<F>)>,
+) -> Result<SegmentAccumulator<F, Comm>, SangriaError>
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+    RO: FoldingRandomOracle<F>,
+{
+    let number_of_statements = pairs.len() as u64;
+    let mut pairs = pairs.into_iter();
+    let (mut instance, mut witness) = pairs.next().ok_or(SangriaError::IndexOutOfBounds)?;
+    let mut prover_messages = Vec::with_capacity(pairs.len());
+    for (right_instance, right_witness) in pairs {
+        let (folded_instance, folded_witness, prover_message) =
+            PLONKFoldingScheme::<F, Comm, RO>::prover(
+                public_parameters,
+                prover_key,
+                &instance,
+                &witness,
+                &right_instance,
+                &right_witness,
+            )?;
+        instance = folded_instance;
+        witness = folded_witness;
+        prover_messages.push(prover_message);
+    }
+
+    Ok(SegmentAccumulator {
+        first_statement,
+        number_of_statements,
+        instance,
+        witness,
+        prover_messages,
+    })
+}
+
+/// Merges the accumulators of consecutive segments, in any order, into the accumulator of the
+/// segment they cover. Returns [`SangriaError::NonContiguousSegments`] if they leave a gap or
+/// overlap. The prover messages of the result are those of the merge, one per segment but the
+/// first; the messages of the segments are needed as well to replay every fold.
+pub fn merge_segments<F, Comm, RO>(
+    public_parameters: &PublicParameters<F, Comm>,
+    prover_key: &ProverKey<F, Comm>,
+    mut segments: Vec<SegmentAccumulator<F, Comm>>,
+) -> Result<SegmentAccumulator<F, Comm>, SangriaError>
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+    RO: FoldingRandomOracle<F>,
+{
+    segments.sort_by_key(|segment| segment.first_statement);
+    let first_statement = segments
+        .first()
+        .ok_or(SangriaError::IndexOutOfBounds)?
+        .first_statement;
+
+    let mut next_statement = first_statement;
+    for segment in &segments {
+        if segment.first_statement != next_statement {
+            return Err(SangriaError::NonContiguousSegments);
+        }
+        next_statement = next_statement
+            .checked_add(segment.number_of_statements)
+            .ok_or(SangriaError::NonContiguousSegments)?;
+    }
+
+    let pairs = segments
+        .into_iter()
+        .map(|segment| (segment.instance, segment.witness))
+        .collect();
+    let merged =
+        fold_segment::<F, Comm, RO>(public_parameters, prover_key, first_statement, pairs)?;
+
+    Ok(SegmentAccumulator {
+        number_of_statements: next_statement - first_statement,
+        ..merged
+    })
+}
+
+/// Folds `instances` from left to right with the folding verifier, the `i`-th fold using the
+/// `i`-th prover message. This is the verifier side of [`fold_segment`] and [`merge_segments`].
+pub fn fold_instances<F, Comm, RO>(
+    public_parameters: &PublicParameters<F, Comm>,
+    verifier_key: &VerifierKey<F, Comm>,
+    instances: &[RelaxedPLONKInstance<F, Comm>],
+    prover_messages: &[ProverMessage<F, Comm>],
+) -> Result<RelaxedPLONKInstance<F, Comm>, SangriaError>
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+    RO: FoldingRandomOracle<F>,
+{
+    let (first, rest) = instances
+        .split_first()
+        .ok_or(SangriaError::IndexOutOfBounds)?;
+    if rest.len() != prover_messages.len() {
+        return Err(SangriaError::IndexOutOfBounds);
+    }
+
+    rest.iter().zip(prover_messages).try_fold(
+        first.clone(),
+        |instance, (right_instance, prover_message)| {
+            PLONKFoldingScheme::<F, Comm, RO>::verifier(
+                public_parameters,
+                verifier_key,
+                &instance,
+                right_instance,
+                prover_message,
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        preprocessing::cell_identities,
+        relations::{decide, relax, PLONKInstance, PLONKWitness, SetupInfo, SlackRandomization},
+        test_utils::seeded_rng,
+        test_vectors::{poseidon_parameters_for_test, sponge_config_for_test},
+        vector_commitment::mock::FieldCommitmentConfig,
+        PLONKCircuit,
+    };
+    use ark_pallas::Fr;
+    use ark_sponge::poseidon::PoseidonSponge;
+    use ark_std::{One, UniformRand, Zero};
+
+    type Comm = FieldCommitmentConfig<Fr>;
+    type RO = PoseidonSponge<Fr>;
+    type FoldingScheme = PLONKFoldingScheme<Fr, Comm, RO>;
+
+    #[test]
+    fn merged_segments_are_decided_and_replayed() {
+        let rng = &mut seeded_rng("distributed::merged_segments_are_decided_and_replayed");
+        let number_of_gates = 4;
+        let info = SetupInfo {
+            number_of_public_inputs: 0,
+            number_of_gates,
+            domain_separator: b"sangria-distributed-test".to_vec(),
+            poseidon_constants: poseidon_parameters_for_test(rng),
+            poseidon2_constants: None,
+            sponge_config: sponge_config_for_test(),
+            slack_randomization: SlackRandomization::EveryFold,
+        };
+        let pp = FoldingScheme::setup(&info, rng).unwrap();
+
+        // c = a + b at every gate.
+        let (zero, one) = (Fr::zero(), Fr::one());
+        let selectors = [one, one, -one, zero, zero]
+            .map(|value| vec![value; number_of_gates])
+            .to_vec();
+        let circuit = PLONKCircuit::new(selectors, cell_identities(number_of_gates).unwrap());
+        let (pk, vk) = FoldingScheme::encode(&pp, &circuit, rng).unwrap();
+
+        let statement = |rng: &mut _| {
+            let a = (0..number_of_gates)
+                .map(|_| Fr::rand(rng))
+                .collect::<Vec<_>>();
+            let b = (0..number_of_gates)
+                .map(|_| Fr::rand(rng))
+                .collect::<Vec<_>>();
+            let c = a.iter().zip(&b).map(|(a_i, b_i)| *a_i + b_i).collect();
+            let witness = PLONKWitness::new(vec![a, b, c]);
+            relax(&pp, &vk, PLONKInstance::new(vec![]), witness, rng).unwrap()
+        };
+
+        let mode = SerializationMode::Compressed;
+        let segments = [(0, 2), (2, 3), (5, 1)]
+            .into_iter()
+            .map(|(first_statement, length)| {
+                let (instances, witnesses): (Vec<_>, Vec<_>) =
+                    (0..length).map(|_| statement(rng)).unzip();
+                let pairs = instances.iter().cloned().zip(witnesses).collect();
+                let segment = fold_segment::<_, _, RO>(&pp, &pk, first_statement, pairs).unwrap();
+                assert!(
+                    fold_instances::<_, _, RO>(&pp, &vk, &instances, segment.prover_messages())
+                        .unwrap()
+                        == *segment.instance()
+                );
+
+                let bytes = segment.to_bytes(mode).unwrap();
+                SegmentAccumulator::from_bytes(&bytes, mode).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let segment_instances = segments
+            .iter()
+            .map(|segment| segment.instance().clone())
+            .collect::<Vec<_>>();
+
+        let merged =
+            merge_segments::<_, _, RO>(&pp, &pk, segments.into_iter().rev().collect()).unwrap();
+        assert_eq!(
+            (merged.first_statement(), merged.number_of_statements()),
+            (0, 6)
+        );
+        assert_eq!(
+            decide(&pp, &pk, merged.instance(), merged.witness()),
+            Ok(())
+        );
+        assert!(
+            fold_instances::<_, _, RO>(&pp, &vk, &segment_instances, merged.prover_messages())
+                .unwrap()
+                == *merged.instance()
+        );
+
+        let gap = [(0, 2), (3, 3)]
+            .into_iter()
+            .map(|(first_statement, length)| {
+                let pairs = (0..length).map(|_| statement(rng)).collect();
+                fold_segment::<_, _, RO>(&pp, &pk, first_statement, pairs).unwrap()
+            })
+            .collect();
+        assert!(matches!(
+            merge_segments::<_, _, RO>(&pp, &pk, gap),
+            Err(SangriaError::NonContiguousSegments)
+        ));
+    }
+}
@@ -0,0 +1,96 @@
+//! Evaluation domain helpers. Circuit sizes are rounded up to the next power of two so that every
+//! column can be interpolated over a radix-2 domain.
+
+use ark_ff::FftField;
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain};
+
+use crate::SangriaError;
+
+/// Returns the smallest radix-2 domain with at least `size` elements, or an error if the field
+/// does not have a large enough two-adic subgroup.
+pub fn domain_for_size<F: FftField>(
+    size: usize,
+) -> Result<Radix2EvaluationDomain<F>, SangriaError> {
+    Radix2EvaluationDomain::new(size).ok_or(SangriaError::UnsupportedDomainSize(size))
+}
+
+/// Returns the evaluations of `polynomial` over the coset `g * domain`, where `g` is the
+/// multiplicative generator of the field.
+pub fn coset_evaluations<F: FftField>(
+    domain: &Radix2EvaluationDomain<F>,
+    polynomial: &DensePolynomial<F>,
+) -> Result<Vec<F>, SangriaError> {
+    if polynomial.coeffs.len() > domain.size() {
+        return Err(SangriaError::DomainTooSmall);
+    }
+
+    Ok(domain.coset_fft(&polynomial.coeffs))
+}
+
+/// Interpolates the polynomial whose evaluations over the coset `g * domain` are `evaluations`.
+pub fn coset_interpolate<F: FftField>(
+    domain: &Radix2EvaluationDomain<F>,
+    evaluations: &[F],
+) -> Result<DensePolynomial<F>, SangriaError> {
+    if evaluations.len() > domain.size() {
+        return Err(SangriaError::DomainTooSmall);
+    }
+
+    Ok(DensePolynomial {
+        coeffs: domain.coset_ifft(evaluations),
+    })
+}
+
+/// Evaluates the vanishing polynomial `X^n - 1` of `domain` at `point`.
+pub fn vanishing_polynomial_evaluation<F: FftField>(
+    domain: &Radix2EvaluationDomain<F>,
+    point: F,
+) -> F {
+    domain.evaluate_vanishing_polynomial(point)
+}
+
+/// Returns the value of the vanishing polynomial of `domain` on its coset `g * domain`. It is the
+/// same constant `g^n - 1` at every point of the coset.
+pub fn coset_vanishing_evaluation<F: FftField>(domain: &Radix2EvaluationDomain<F>) -> F {
+    domain.evaluate_vanishing_polynomial(F::multiplicative_generator())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seeded_rng;
+    use ark_ff::Zero;
+    use ark_pallas::Fr;
+    use ark_poly::UVPolynomial;
+
+    #[test]
+    fn rounds_up_to_power_of_two() {
+        let domain = domain_for_size::<Fr>(5).unwrap();
+        assert_eq!(domain.size(), 8);
+
+        let domain = domain_for_size::<Fr>(8).unwrap();
+        assert_eq!(domain.size(), 8);
+    }
+
+    #[test]
+    fn coset_round_trip() {
+        let rng = &mut seeded_rng("domain::coset_round_trip");
+        let domain = domain_for_size::<Fr>(16).unwrap();
+        let polynomial = DensePolynomial::<Fr>::rand(15, rng);
+
+        let evaluations = coset_evaluations(&domain, &polynomial).unwrap();
+        let interpolated = coset_interpolate(&domain, &evaluations).unwrap();
+
+        assert_eq!(polynomial, interpolated);
+    }
+
+    #[test]
+    fn vanishing_polynomial() {
+        let domain = domain_for_size::<Fr>(4).unwrap();
+
+        for element in domain.elements() {
+            assert!(vanishing_polynomial_evaluation(&domain, element).is_zero());
+        }
+        assert_ne!(coset_vanishing_evaluation(&domain), Fr::zero());
+    }
+}
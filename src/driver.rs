@@ -0,0 +1,160 @@
+//! A driver that proves consecutive steps of an [`IVC`] computation, fetching the witness of every
+//! step from a [`WitnessProvider`], e.g. a [`crate::delegation::DelegatedWitnessProvider`] when
+//! proving is delegated.
+//!
+//! [`prove_steps`] blocks the calling thread. Under the `async` feature, `prove_steps_async`
+//! awaits the witnesses and yields to the executor between the phases of every step, so that it
+//! can run inside an async service without a blocking wrapper.
+
+use ark_ff::PrimeField;
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+#[cfg(feature = "async")]
+use std::{
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "async")]
+use crate::{IVCWithDeadline, StepProgress};
+use crate::{SangriaError, StepCircuit, IVC};
+
+/// A source of step witnesses. Fetching is asynchronous, so that witnesses can come from the
+/// network or from a key service without blocking the prover.
+pub trait WitnessProvider<F: PrimeField, SC: StepCircuit<F>> {
+    /// Fetches the witness of the `step_index`-th step.
+    fn fetch(
+        &mut self,
+        step_index: usize,
+    ) -> impl Future<Output = Result<SC::Witness, SangriaError>> + Send;
+}
+
+/// Proves the steps `first_step..first_step + number_of_steps` from `current_state` and
+/// `current_proof`, see [`IVC::prove_step`], and returns the last state and proof. The proof is
+/// `None` only if no step was proven from a computation that had none. Fetching a witness
+/// blocks the calling thread.
+#[allow(clippy::type_complexity)]
+pub fn prove_steps<F, SC, I, P>(
+    prover_key: &I::ProverKey,
+    origin_state: &SC::State,
+    current_state: SC::State,
+    current_proof: Option<I::Proof>,
+    first_step: usize,
+    number_of_steps: usize,
+    provider: &mut P,
+) -> Result<(SC::State, Option<I::Proof>), SangriaError>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    I: IVC<F, SC>,
+    P: WitnessProvider<F, SC>,
+{
+    let (mut state, mut proof) = (current_state, current_proof);
+    for step_index in first_step..first_step + number_of_steps {
+        let witness = block_on(provider.fetch(step_index))?;
+        let (next_state, next_proof) =
+            I::prove_step(prover_key, origin_state, state, proof, &witness)?;
+        state = next_state;
+        proof = Some(next_proof);
+    }
+
+    Ok((state, proof))
+}
+
+/// Proves steps like [`prove_steps`], but awaits the witnesses and yields to the executor after
+/// every phase boundary of [`IVCWithDeadline`] reached after `time_slice`, and after every step.
+#[cfg(feature = "async")]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub async fn prove_steps_async<F, SC, I, P>(
+    prover_key: &I::ProverKey,
+    origin_state: &SC::State,
+    current_state: SC::State,
+    current_proof: Option<I::Proof>,
+    first_step: usize,
+    number_of_steps: usize,
+    time_slice: Duration,
+    provider: &mut P,
+) -> Result<(SC::State, Option<I::Proof>), SangriaError>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    I: IVCWithDeadline<F, SC>,
+    P: WitnessProvider<F, SC>,
+{
+    let (mut state, mut proof) = (current_state, current_proof);
+    for step_index in first_step..first_step + number_of_steps {
+        let witness = provider.fetch(step_index).await?;
+        let mut progress = I::prove_step_with_deadline(
+            prover_key,
+            origin_state,
+            state,
+            proof,
+            &witness,
+            Instant::now() + time_slice,
+        )?;
+        let (next_state, next_proof) = loop {
+            match progress {
+                StepProgress::Done(next_state, next_proof) => break (next_state, next_proof),
+                StepProgress::Interrupted(partial_fold) => {
+                    YieldNow(false).await;
+                    progress = I::resume_step(
+                        prover_key,
+                        origin_state,
+                        &witness,
+                        partial_fold,
+                        Instant::now() + time_slice,
+                    )?;
+                }
+            }
+        };
+        state = next_state;
+        proof = Some(next_proof);
+        YieldNow(false).await;
+    }
+
+    Ok((state, proof))
+}
+
+/// A future that is pending once, so that the executor can run other tasks.
+#[cfg(feature = "async")]
+struct YieldNow(bool);
+
+#[cfg(feature = "async")]
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        context.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Runs a future to completion on the calling thread, parking it while the future is pending.
+pub(crate) fn block_on<T>(future: impl Future<Output = T>) -> T {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
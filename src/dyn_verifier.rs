@@ -0,0 +1,104 @@
+//! Object-safe IVC verification. [`IVC`] is generic over the field, the step circuit and the
+//! configuration, which makes it impossible to keep verifiers for several configurations in one
+//! collection. [`DynIVCVerifier`] erases these parameters by taking serialized states and proofs,
+//! so applications can hold `Box<dyn DynIVCVerifier>` in a registry.
+
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalDeserialize;
+use ark_std::marker::PhantomData;
+
+use crate::{
+    serialization::{deserialize_tagged, from_bytes, ArtifactTag, SerializationMode},
+    telemetry, SangriaError, StepCircuit, IVC,
+};
+
+/// An IVC verifier for a fixed verifier key that operates on serialized artifacts.
+pub trait DynIVCVerifier: Send + Sync {
+    /// Deserializes the states and the proof and verifies them, see [`IVC::verify`]. A `proof`
+    /// of length zero stands for the absence of a proof, i.e. zero steps.
+    fn verify(
+        &self,
+        number_of_steps: usize,
+        origin_state: &[u8],
+        current_state: &[u8],
+        proof: &[u8],
+    ) -> Result<(), SangriaError>;
+}
+
+/// Adapts an [`IVC`] scheme and one of its verifier keys to [`DynIVCVerifier`].
+pub struct ErasedIVCVerifier<F, SC, I>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    I: IVC<F, SC>,
+{
+    verifier_key: I::VerifierKey,
+    mode: SerializationMode,
+    tag: Option<ArtifactTag>,
+    _marker: PhantomData<fn() -> (F, SC)>,
+}
+
+impl<F, SC, I> ErasedIVCVerifier<F, SC, I>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    I: IVC<F, SC>,
+{
+    /// Wraps `verifier_key`. States and proofs are expected to be serialized with `mode`.
+    pub fn new(verifier_key: I::VerifierKey, mode: SerializationMode) -> Self {
+        Self {
+            verifier_key,
+            mode,
+            tag: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Expects proofs to be prefixed with `tag`, see [`crate::serialization::serialize_tagged`].
+    /// Proofs from another configuration are then rejected with [`SangriaError::CurveMismatch`].
+    pub fn with_tag(mut self, tag: ArtifactTag) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Returns the wrapped verifier key.
+    pub fn verifier_key(&self) -> &I::VerifierKey {
+        &self.verifier_key
+    }
+}
+
+impl<F, SC, I> DynIVCVerifier for ErasedIVCVerifier<F, SC, I>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    SC::State: CanonicalDeserialize,
+    I: IVC<F, SC>,
+    I::VerifierKey: Send + Sync,
+    I::Proof: CanonicalDeserialize,
+{
+    fn verify(
+        &self,
+        number_of_steps: usize,
+        origin_state: &[u8],
+        current_state: &[u8],
+        proof: &[u8],
+    ) -> Result<(), SangriaError> {
+        telemetry::proof_bytes(proof.len());
+
+        let origin_state: SC::State = from_bytes(origin_state, self.mode)?;
+        let current_state: SC::State = from_bytes(current_state, self.mode)?;
+        let proof = match &self.tag {
+            _ if proof.is_empty() => None,
+            Some(tag) => Some(deserialize_tagged(tag, proof, self.mode)?),
+            None => Some(from_bytes(proof, self.mode)?),
+        };
+
+        I::verify(
+            &self.verifier_key,
+            number_of_steps,
+            &origin_state,
+            current_state,
+            proof,
+        )
+    }
+}
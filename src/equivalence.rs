@@ -0,0 +1,645 @@
+//! Proofs that two IVC computations, possibly of different step circuits, ended at the same
+//! state, e.g. to bridge a computation to a new version of its circuit. Each side commits to the
+//! [`StepCircuit::state_elements`] of its final state with its own commit key, and the proof is a
+//! Schnorr-style proof of knowledge of openings of both commitments to the same vector: the
+//! prover masks the vector once and opens both announcements with the same responses. It only
+//! uses the homomorphism of the scheme, and reveals nothing about the state but its length if the
+//! scheme is hiding, e.g. Pedersen.
+//!
+//! The challenge absorbs the final instances of both IVC proofs (see
+//! [`IVCProof::final_instances`]), so a proof is bound to the two computations it was made for and
+//! cannot be replayed for commitments presented with other proofs.
+//!
+//! A [`CircuitMigration`] uses such a proof to upgrade a long-lived computation to a new version
+//! of its step circuit (see [`crate::PLONKCircuit::with_version`]): the final state of the
+//! computation under the old version becomes the origin state of a fresh computation under the
+//! new one.
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_sponge::{Absorb, CryptographicSponge};
+use ark_std::{rand::Rng, UniformRand};
+
+use crate::{
+    relations::HomomorphicCommitmentScheme,
+    transcript::{Blake2bSponge, LabeledChallenges},
+    IVCProof, SangriaError, SangriaIVCConfig, StepCircuit, VerificationFailure, VerifierKeyDigest,
+};
+
+/// Label of the challenge of a state equivalence proof.
+pub const STATE_EQUIVALENCE_CHALLENGE: &[u8] = b"state_equivalence";
+
+/// A proof that two commitments, under possibly different commit keys, are to the same state.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct StateEquivalenceProof<F: PrimeField, C: CanonicalSerialize + CanonicalDeserialize> {
+    /// The commitment to the masks under the left commit key.
+    left_announcement: C,
+    /// The commitment to the masks under the right commit key.
+    right_announcement: C,
+    /// The responses for every state element, shared by both commitments.
+    responses: Vec<F>,
+    /// The response for the hiding randomness of the left commitment.
+    left_hiding_response: F,
+    /// The response for the hiding randomness of the right commitment.
+    right_hiding_response: F,
+}
+
+/// A computation under one version of its step circuit, as seen by the verifier of a migration.
+pub struct VersionedComputation<'a, F: PrimeField, H: PrimeField, C: SangriaIVCConfig<F, H>> {
+    /// The version of the step circuit, see [`crate::PLONKCircuit::with_version`].
+    pub version: u32,
+    /// The digest of the verifier key of that version.
+    pub verifier_key_digest: VerifierKeyDigest<F, H>,
+    /// The IVC proof of the computation.
+    pub proof: &'a IVCProof<F, H, C>,
+}
+
+/// A migration of a computation from a version of its step circuit to a later one, see the
+/// module documentation.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CircuitMigration<F: PrimeField, C: CanonicalSerialize + CanonicalDeserialize> {
+    /// The commitment to the final state of the computation under the old version.
+    pub final_state_commitment: C,
+    /// The commitment to the origin state of the computation under the new version.
+    pub origin_state_commitment: C,
+    /// The proof that both commitments are to the same state.
+    proof: StateEquivalenceProof<F, C>,
+}
+
+/// Commits to the state elements of `state` with hiding `randomness`.
+pub fn commit_state<F, SC, S>(
+    commit_key: &S::CommitKey,
+    state: &SC::State,
+    randomness: F,
+) -> Result<S::Commitment, SangriaError>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    S: HomomorphicCommitmentScheme<F>,
+{
+    S::commit(commit_key, &SC::state_elements(state), randomness)
+}
+
+/// Proves that the commitment to `state_elements` with hiding `left_randomness` under
+/// `left_commit_key` and the one with hiding `right_randomness` under `right_commit_key` are to
+/// the same state, namely the final state of the computations of `left_proof` and `right_proof`.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_state_equivalence<F, S, LH, LC, RH, RC, R>(
+    left_commit_key: &S::CommitKey,
+    right_commit_key: &S::CommitKey,
+    left_proof: &IVCProof<F, LH, LC>,
+    right_proof: &IVCProof<F, RH, RC>,
+    state_elements: &[F],
+    left_randomness: F,
+    right_randomness: F,
+    rng: &mut R,
+) -> Result<StateEquivalenceProof<F, S::Commitment>, SangriaError>
+where
+    F: PrimeField + Absorb,
+    S: HomomorphicCommitmentScheme<F>,
+    LH: PrimeField,
+    LC: SangriaIVCConfig<F, LH>,
+    RH: PrimeField,
+    RC: SangriaIVCConfig<F, RH>,
+    R: Rng,
+{
+    let masks = (0..state_elements.len())
+        .map(|_| F::rand(rng))
+        .collect::<Vec<_>>();
+    let (left_hiding_mask, right_hiding_mask) = (F::rand(rng), F::rand(rng));
+    let left_announcement = S::commit(left_commit_key, &masks, left_hiding_mask)?;
+    let right_announcement = S::commit(right_commit_key, &masks, right_hiding_mask)?;
+
+    let challenge = equivalence_challenge::<F, S, LH, LC, RH, RC>(
+        left_commit_key,
+        right_commit_key,
+        left_proof,
+        right_proof,
+        state_elements.len(),
+        &S::commit(left_commit_key, state_elements, left_randomness)?,
+        &S::commit(right_commit_key, state_elements, right_randomness)?,
+        &left_announcement,
+        &right_announcement,
+    )?;
+
+    Ok(StateEquivalenceProof {
+        left_announcement,
+        right_announcement,
+        responses: masks
+            .iter()
+            .zip(state_elements)
+            .map(|(mask, element)| *mask + challenge * element)
+            .collect(),
+        left_hiding_response: left_hiding_mask + challenge * left_randomness,
+        right_hiding_response: right_hiding_mask + challenge * right_randomness,
+    })
+}
+
+/// Verifies that `left_commitment` under `left_commit_key` and `right_commitment` under
+/// `right_commit_key` are to the same state of `length` elements, for the computations of
+/// `left_proof` and `right_proof`. The IVC proofs themselves must be verified separately.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_state_equivalence<F, S, LH, LC, RH, RC>(
+    left_commit_key: &S::CommitKey,
+    right_commit_key: &S::CommitKey,
+    left_proof: &IVCProof<F, LH, LC>,
+    right_proof: &IVCProof<F, RH, RC>,
+    length: usize,
+    left_commitment: &S::Commitment,
+    right_commitment: &S::Commitment,
+    proof: &StateEquivalenceProof<F, S::Commitment>,
+) -> Result<(), SangriaError>
+where
+    F: PrimeField + Absorb,
+    S: HomomorphicCommitmentScheme<F>,
+    LH: PrimeField,
+    LC: SangriaIVCConfig<F, LH>,
+    RH: PrimeField,
+    RC: SangriaIVCConfig<F, RH>,
+{
+    if proof.responses.len() != length {
+        return Err(SangriaError::IndexOutOfBounds);
+    }
+
+    let challenge = equivalence_challenge::<F, S, LH, LC, RH, RC>(
+        left_commit_key,
+        right_commit_key,
+        left_proof,
+        right_proof,
+        length,
+        left_commitment,
+        right_commitment,
+        &proof.left_announcement,
+        &proof.right_announcement,
+    )?;
+
+    for (commit_key, commitment, announcement, hiding_response, side) in [
+        (
+            left_commit_key,
+            left_commitment,
+            &proof.left_announcement,
+            proof.left_hiding_response,
+            "left",
+        ),
+        (
+            right_commit_key,
+            right_commitment,
+            &proof.right_announcement,
+            proof.right_hiding_response,
+            "right",
+        ),
+    ] {
+        let opened = S::commit(commit_key, &proof.responses, hiding_response)?;
+        let expected = [*announcement, *commitment * challenge].into_iter().sum();
+        if S::canonicalize(opened) != S::canonicalize(expected) {
+            return Err(VerificationFailure::CommitmentOpening
+                .reject(|| format!("the {} commitment does not open to the shared state", side)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Migrates the computation `from`, which ended at `state_elements`, to the fresh computation
+/// `to` under a later version of its step circuit. Each version commits to the state with its own
+/// commit key and hiding randomness. See [`verify_migration`] for the errors.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_migration<F, S, LH, LC, RH, RC, R>(
+    from: &VersionedComputation<F, LH, LC>,
+    to: &VersionedComputation<F, RH, RC>,
+    from_commit_key: &S::CommitKey,
+    to_commit_key: &S::CommitKey,
+    state_elements: &[F],
+    from_randomness: F,
+    to_randomness: F,
+    rng: &mut R,
+) -> Result<CircuitMigration<F, S::Commitment>, SangriaError>
+where
+    F: PrimeField + Absorb,
+    S: HomomorphicCommitmentScheme<F>,
+    LH: PrimeField,
+    LC: SangriaIVCConfig<F, LH>,
+    RH: PrimeField,
+    RC: SangriaIVCConfig<F, RH>,
+    R: Rng,
+{
+    check_migration(from, to)?;
+
+    Ok(CircuitMigration {
+        final_state_commitment: S::commit(from_commit_key, state_elements, from_randomness)?,
+        origin_state_commitment: S::commit(to_commit_key, state_elements, to_randomness)?,
+        proof: prove_state_equivalence::<F, S, LH, LC, RH, RC, R>(
+            from_commit_key,
+            to_commit_key,
+            from.proof,
+            to.proof,
+            state_elements,
+            from_randomness,
+            to_randomness,
+            rng,
+        )?,
+    })
+}
+
+/// Verifies that `migration` moves the computation `from`, whose state has `state_length`
+/// elements, to the fresh computation `to` without changing its state. Returns
+/// [`SangriaError::InvalidMigration`] if `to` is not under a later version than `from`,
+/// [`SangriaError::MismatchedCircuit`] if a proof does not belong to the verifier key of its
+/// version, and fails with [`VerificationFailure::BaseCase`] if `to` is not at its base case.
+/// The IVC proof of `from` must be verified separately.
+pub fn verify_migration<F, S, LH, LC, RH, RC>(
+    from: &VersionedComputation<F, LH, LC>,
+    to: &VersionedComputation<F, RH, RC>,
+    from_commit_key: &S::CommitKey,
+    to_commit_key: &S::CommitKey,
+    state_length: usize,
+    migration: &CircuitMigration<F, S::Commitment>,
+) -> Result<(), SangriaError>
+where
+    F: PrimeField + Absorb,
+    S: HomomorphicCommitmentScheme<F>,
+    LH: PrimeField,
+    LC: SangriaIVCConfig<F, LH>,
+    RH: PrimeField,
+    RC: SangriaIVCConfig<F, RH>,
+{
+    check_migration(from, to)?;
+
+    verify_state_equivalence::<F, S, LH, LC, RH, RC>(
+        from_commit_key,
+        to_commit_key,
+        from.proof,
+        to.proof,
+        state_length,
+        &migration.final_state_commitment,
+        &migration.origin_state_commitment,
+        &migration.proof,
+    )
+}
+
+/// Checks the versions of both computations, that their proofs belong to the verifier keys of
+/// their versions and that the computation migrated to is fresh.
+fn check_migration<F, LH, LC, RH, RC>(
+    from: &VersionedComputation<F, LH, LC>,
+    to: &VersionedComputation<F, RH, RC>,
+) -> Result<(), SangriaError>
+where
+    F: PrimeField + Absorb,
+    LH: PrimeField,
+    LC: SangriaIVCConfig<F, LH>,
+    RH: PrimeField,
+    RC: SangriaIVCConfig<F, RH>,
+{
+    if to.version <= from.version {
+        return Err(SangriaError::InvalidMigration {
+            from: from.version,
+            to: to.version,
+        });
+    }
+    from.proof.check_circuits(&from.verifier_key_digest)?;
+    to.proof.check_circuits(&to.verifier_key_digest)?;
+    if !to.proof.is_base() {
+        return Err(VerificationFailure::BaseCase
+            .reject(|| String::from("the computation migrated to is not fresh")));
+    }
+
+    Ok(())
+}
+
+/// Derives the challenge of a state equivalence proof from both commit keys, the final instances
+/// of both IVC proofs and the public data.
+#[allow(clippy::too_many_arguments)]
+fn equivalence_challenge<F, S, LH, LC, RH, RC>(
+    left_commit_key: &S::CommitKey,
+    right_commit_key: &S::CommitKey,
+    left_proof: &IVCProof<F, LH, LC>,
+    right_proof: &IVCProof<F, RH, RC>,
+    length: usize,
+    left_commitment: &S::Commitment,
+    right_commitment: &S::Commitment,
+    left_announcement: &S::Commitment,
+    right_announcement: &S::Commitment,
+) -> Result<F, SangriaError>
+where
+    F: PrimeField + Absorb,
+    S: HomomorphicCommitmentScheme<F>,
+    LH: PrimeField,
+    LC: SangriaIVCConfig<F, LH>,
+    RH: PrimeField,
+    RC: SangriaIVCConfig<F, RH>,
+{
+    let mut public_data = Vec::new();
+    left_commit_key
+        .serialize(&mut public_data)
+        .and_then(|_| right_commit_key.serialize(&mut public_data))
+        .and_then(|_| (length as u64).serialize(&mut public_data))
+        .map_err(|_| SangriaError::SerializationError)?;
+
+    let mut sponge = Blake2bSponge::new(&());
+    sponge.absorb(&public_data);
+    left_proof.absorb_final_instances(&mut sponge);
+    right_proof.absorb_final_instances(&mut sponge);
+    sponge.absorb(&S::canonicalize(*left_commitment));
+    sponge.absorb(&S::canonicalize(*right_commitment));
+    sponge.absorb(&S::canonicalize(*left_announcement));
+    sponge.absorb(&S::canonicalize(*right_announcement));
+
+    Ok(LabeledChallenges::<F>::challenge(
+        &mut sponge,
+        STATE_EQUIVALENCE_CHALLENGE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        accumulator::MerkleMountainRange,
+        circuit_builder::PLONKCircuitBuilder,
+        folding_scheme::{ProverKey, SlackRandomization},
+        relations::{NonInteractiveFoldingScheme, PLONKFoldingScheme, SetupInfo},
+        test_utils::seeded_rng,
+        test_vectors::{poseidon_parameters_for_test, sponge_config_for_test},
+        vector_commitment::mock::{FieldCommitment, FieldCommitmentConfig},
+        HalfCycleProof, AUGMENTED_CIRCUIT_PUBLIC_INPUTS,
+    };
+    use ark_pallas::{Fq, Fr};
+    use ark_sponge::poseidon::PoseidonSponge;
+
+    struct MockConfig;
+
+    impl SangriaIVCConfig<Fr, Fq> for MockConfig {
+        type MainCommitmentSchemes = FieldCommitmentConfig<Fr>;
+        type HelperCommitmentSchemes = FieldCommitmentConfig<Fq>;
+        type MainRandomOracle = PoseidonSponge<Fr>;
+        type HelperRandomOracle = PoseidonSponge<Fq>;
+    }
+
+    fn nifs_prover_key<F: PrimeField + Absorb, R: Rng>(
+        version: u32,
+        rng: &mut R,
+    ) -> ProverKey<F, FieldCommitmentConfig<F>> {
+        type FoldingScheme<F> = PLONKFoldingScheme<F, FieldCommitmentConfig<F>, PoseidonSponge<F>>;
+
+        let number_of_gates = 4;
+        let info = SetupInfo {
+            number_of_public_inputs: AUGMENTED_CIRCUIT_PUBLIC_INPUTS,
+            number_of_gates,
+            domain_separator: b"sangria-equivalence-test".to_vec(),
+            poseidon_constants: poseidon_parameters_for_test(rng),
+            poseidon2_constants: None,
+            sponge_config: sponge_config_for_test(),
+            slack_randomization: SlackRandomization::EveryFold,
+        };
+        let pp = FoldingScheme::<F>::setup(&info, rng).unwrap();
+
+        let mut builder = PLONKCircuitBuilder::new();
+        for _ in 0..number_of_gates {
+            builder.add_gate([F::one(), F::zero(), -F::one(), F::zero(), F::zero()]);
+        }
+        let circuit = builder.build().unwrap().with_version(version);
+        let (pk, _) = FoldingScheme::<F>::encode(&pp, &circuit, rng).unwrap();
+
+        pk
+    }
+
+    /// Returns the proof of the base case of a computation with fresh keys, whose final
+    /// instances differ from those of any other such computation.
+    fn base_proof<R: Rng>(rng: &mut R) -> IVCProof<Fr, Fq, MockConfig> {
+        versioned_base_proof(0, rng).1
+    }
+
+    /// Returns the digest of fresh keys for `version` of a circuit and the proof of the base
+    /// case of a computation under them.
+    fn versioned_base_proof<R: Rng>(
+        version: u32,
+        rng: &mut R,
+    ) -> (VerifierKeyDigest<Fr, Fq>, IVCProof<Fr, Fq, MockConfig>) {
+        let (main_pk, helper_pk) = (nifs_prover_key(version, rng), nifs_prover_key(version, rng));
+        let digest = VerifierKeyDigest {
+            main: main_pk.verifier_key.digest(),
+            helper: helper_pk.verifier_key.digest(),
+        };
+        let proof = IVCProof {
+            _main_half_proof: HalfCycleProof::base(&main_pk),
+            _helper_half_proof: HalfCycleProof::base(&helper_pk),
+            _number_of_steps: 0,
+            _outputs_accumulator: MerkleMountainRange::new(),
+            _step_accumulator: MerkleMountainRange::new(),
+        };
+
+        (digest, proof)
+    }
+
+    #[test]
+    fn only_commitments_to_the_same_state_are_equivalent() {
+        let rng = &mut seeded_rng("equivalence::only_commitments_to_the_same_state_are_equivalent");
+        let left_commit_key = FieldCommitment::<Fr>::setup(rng, 3);
+        let right_commit_key = FieldCommitment::<Fr>::setup(rng, 5);
+        let (left_proof, right_proof) = (base_proof(rng), base_proof(rng));
+        let state = (0..3).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let (left_randomness, right_randomness) = (Fr::rand(rng), Fr::rand(rng));
+        let left_commitment =
+            FieldCommitment::commit(&left_commit_key, &state, left_randomness).unwrap();
+        let right_commitment =
+            FieldCommitment::commit(&right_commit_key, &state, right_randomness).unwrap();
+
+        let proof = prove_state_equivalence::<_, FieldCommitment<Fr>, _, _, _, _, _>(
+            &left_commit_key,
+            &right_commit_key,
+            &left_proof,
+            &right_proof,
+            &state,
+            left_randomness,
+            right_randomness,
+            rng,
+        )
+        .unwrap();
+        assert_eq!(
+            verify_state_equivalence::<_, FieldCommitment<Fr>, _, _, _, _>(
+                &left_commit_key,
+                &right_commit_key,
+                &left_proof,
+                &right_proof,
+                state.len(),
+                &left_commitment,
+                &right_commitment,
+                &proof,
+            ),
+            Ok(())
+        );
+
+        let other_state = (0..3).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let other_commitment =
+            FieldCommitment::commit(&right_commit_key, &other_state, right_randomness).unwrap();
+        assert_eq!(
+            verify_state_equivalence::<_, FieldCommitment<Fr>, _, _, _, _>(
+                &left_commit_key,
+                &right_commit_key,
+                &left_proof,
+                &right_proof,
+                state.len(),
+                &left_commitment,
+                &other_commitment,
+                &proof,
+            ),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::CommitmentOpening
+            ))
+        );
+    }
+
+    #[test]
+    fn proofs_are_bound_to_the_final_instances() {
+        let rng = &mut seeded_rng("equivalence::proofs_are_bound_to_the_final_instances");
+        let commit_key = FieldCommitment::<Fr>::setup(rng, 2);
+        let (left_proof, right_proof, other_proof) =
+            (base_proof(rng), base_proof(rng), base_proof(rng));
+
+        // A proof made for the right computation does not vouch for the final state of another
+        // computation, even with the same commitments.
+        let state = vec![Fr::rand(rng), Fr::rand(rng)];
+        let randomness = Fr::rand(rng);
+        let commitment = FieldCommitment::commit(&commit_key, &state, randomness).unwrap();
+
+        let proof = prove_state_equivalence::<_, FieldCommitment<Fr>, _, _, _, _, _>(
+            &commit_key,
+            &commit_key,
+            &left_proof,
+            &right_proof,
+            &state,
+            randomness,
+            randomness,
+            rng,
+        )
+        .unwrap();
+        let verify = |right_proof: &IVCProof<Fr, Fq, MockConfig>| {
+            verify_state_equivalence::<_, FieldCommitment<Fr>, _, _, _, _>(
+                &commit_key,
+                &commit_key,
+                &left_proof,
+                right_proof,
+                state.len(),
+                &commitment,
+                &commitment,
+                &proof,
+            )
+        };
+        assert_eq!(verify(&right_proof), Ok(()));
+        assert_eq!(
+            verify(&other_proof),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::CommitmentOpening
+            ))
+        );
+    }
+
+    #[test]
+    fn migrations_move_to_fresh_computations_of_later_versions() {
+        let rng =
+            &mut seeded_rng("equivalence::migrations_move_to_fresh_computations_of_later_versions");
+        let (v1_digest, v1_proof) = versioned_base_proof(1, rng);
+        let (v2_digest, mut v2_proof) = versioned_base_proof(2, rng);
+        let (v1_commit_key, v2_commit_key) = (
+            FieldCommitment::<Fr>::setup(rng, 2),
+            FieldCommitment::<Fr>::setup(rng, 2),
+        );
+        let state = vec![Fr::rand(rng), Fr::rand(rng)];
+
+        let v1 = VersionedComputation {
+            version: 1,
+            verifier_key_digest: v1_digest,
+            proof: &v1_proof,
+        };
+        let v2 = VersionedComputation {
+            version: 2,
+            verifier_key_digest: v2_digest,
+            proof: &v2_proof,
+        };
+        let migration = prove_migration::<_, FieldCommitment<Fr>, _, _, _, _, _>(
+            &v1,
+            &v2,
+            &v1_commit_key,
+            &v2_commit_key,
+            &state,
+            Fr::rand(rng),
+            Fr::rand(rng),
+            rng,
+        )
+        .unwrap();
+        let verify = |from: &VersionedComputation<Fr, Fq, MockConfig>,
+                      to: &VersionedComputation<Fr, Fq, MockConfig>| {
+            verify_migration::<_, FieldCommitment<Fr>, _, _, _, _>(
+                from,
+                to,
+                &v1_commit_key,
+                &v2_commit_key,
+                state.len(),
+                &migration,
+            )
+        };
+        assert_eq!(verify(&v1, &v2), Ok(()));
+        assert_eq!(
+            verify(&v2, &v1),
+            Err(SangriaError::InvalidMigration { from: 2, to: 1 })
+        );
+        assert_eq!(
+            verify(&v1, &VersionedComputation { version: 1, ..v2 }),
+            Err(SangriaError::InvalidMigration { from: 1, to: 1 })
+        );
+
+        // Each proof must belong to the keys of its version.
+        let v1_with_v2_keys = VersionedComputation {
+            verifier_key_digest: v2_digest,
+            ..v1
+        };
+        assert_eq!(
+            verify(&v1_with_v2_keys, &v2),
+            Err(SangriaError::MismatchedCircuit)
+        );
+
+        // The migration was proven for another state.
+        let other_migration = prove_migration::<_, FieldCommitment<Fr>, _, _, _, _, _>(
+            &v1,
+            &v2,
+            &v1_commit_key,
+            &v2_commit_key,
+            &[Fr::rand(rng), Fr::rand(rng)],
+            Fr::rand(rng),
+            Fr::rand(rng),
+            rng,
+        )
+        .unwrap();
+        let mixed_migration = CircuitMigration {
+            final_state_commitment: migration.final_state_commitment,
+            ..other_migration
+        };
+        assert_eq!(
+            verify_migration::<_, FieldCommitment<Fr>, _, _, _, _>(
+                &v1,
+                &v2,
+                &v1_commit_key,
+                &v2_commit_key,
+                state.len(),
+                &mixed_migration,
+            ),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::CommitmentOpening
+            ))
+        );
+
+        // Only fresh computations can be migrated to.
+        v2_proof._number_of_steps = 1;
+        let advanced_v2 = VersionedComputation {
+            version: 2,
+            verifier_key_digest: v2_digest,
+            proof: &v2_proof,
+        };
+        assert_eq!(
+            verify(&v1, &advanced_v2),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::BaseCase
+            ))
+        );
+    }
+}
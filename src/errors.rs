@@ -10,4 +10,172 @@ pub enum SangriaError {
     /// returned if the commitment scheme returns an error
     #[error("An error occurred with the commitment scheme")]
     CommitmentError,
+
+    /// returned if key material could not be written to or read from disk
+    #[error("An error occurred while storing or loading key material")]
+    KeyStorageError,
+
+    /// returned if a step witness does not have the declared arity or contains a non-canonical field element
+    #[error("Invalid step witness: expected {expected_arity} elements, got {actual_arity} (non-canonical element: {non_canonical_element:?})")]
+    InvalidWitness {
+        /// the number of elements declared by the step circuit
+        expected_arity: usize,
+        /// the number of elements in the supplied witness
+        actual_arity: usize,
+        /// the index of the first non-canonical element, if any
+        non_canonical_element: Option<usize>,
+    },
+
+    /// returned if a vector has more entries than the evaluation domain it is interpolated over
+    #[error("Evaluation domain is too small")]
+    DomainTooSmall,
+
+    /// returned if the field has no radix-2 evaluation domain of the requested size
+    #[error("No evaluation domain of size {0}")]
+    UnsupportedDomainSize(usize),
+
+    /// returned if a commit key is too short for the vector being committed to
+    #[error("Unsupported size {requested}, the commit key supports up to {max}")]
+    UnsupportedSize {
+        /// the requested length
+        requested: usize,
+        /// the maximum length supported by the commit key
+        max: usize,
+    },
+
+    /// returned if instances of different circuits are folded together
+    #[error("Instance does not belong to the circuit of the key")]
+    MismatchedCircuit,
+
+    /// returned if the parameters of the random oracle are missing or malformed
+    #[error("Invalid random oracle parameters")]
+    InvalidOracleParameters,
+
+    /// returned if the copy constraints of a circuit are not a permutation of its cells
+    #[error("Copy constraints are not a permutation of the circuit cells")]
+    InvalidPermutation,
+
+    /// returned if the challenge field is too small for the soundness required over the maximum number of steps
+    #[error("Challenge field provides {bits} bits of soundness, {required} are required")]
+    InsufficientSoundness {
+        /// the bits of soundness provided by the field
+        bits: u32,
+        /// the bits of soundness required
+        required: u32,
+    },
+
+    /// returned if a circuit is larger than the public parameters support
+    #[error("Circuit is too large for the public parameters")]
+    CircuitTooLarge,
+
+    /// returned if the self test of deployed parameters fails
+    #[error("Self test failed: {0}")]
+    SelfTestFailed(&'static str),
+
+    /// returned if a value cannot be serialized or the bytes do not encode a valid value
+    #[error("Serialization error")]
+    SerializationError,
+
+    /// returned if bytes or a field element do not canonically encode the requested value
+    #[error("Non-canonical encoding")]
+    NonCanonicalEncoding,
+
+    /// returned if a serialized artifact was produced over another curve or field than the one it is loaded as
+    #[error("Artifact was produced over another curve or field")]
+    CurveMismatch,
+
+    /// returned if an instance-witness pair that must be fresh, e.g. the latest step of an IVC proof, is not strict
+    #[error(
+        "Instance is not strict: its scaling factor is not one or its slack vector is not zero"
+    )]
+    NonStrictInstance,
+
+    /// returned if no verifier key with the requested digest is registered
+    #[error("No verifier key with this digest is registered")]
+    UnknownVerifierKey,
+
+    /// returned if a looked up value is not an entry of its table
+    #[error("Value is not an entry of the lookup table")]
+    LookupFailed,
+
+    /// returned if the segments merged by a distributed folding coordinator leave a gap or overlap
+    #[error("Segments do not cover consecutive statements")]
+    NonContiguousSegments,
+
+    /// returned if a delegated step witness cannot be fetched, its key unwrapped or its ciphertext decrypted
+    #[error("Step witness is unavailable to this prover")]
+    WitnessUnavailable,
+
+    /// returned if a computation would exceed, or claims more than, the maximum number of steps of its keys
+    #[error("Step counter exceeds the maximum number of steps")]
+    TooManySteps,
+
+    /// returned if a computation is migrated to a circuit version that is not later than its current one
+    #[error("Cannot migrate from circuit version {from} to version {to}")]
+    InvalidMigration {
+        /// the version of the circuit the computation is migrated from
+        from: u32,
+        /// the version of the circuit the computation is migrated to
+        to: u32,
+    },
+
+    /// returned if a part of the scheme that is not implemented yet is called
+    #[error("Not implemented: {0}")]
+    NotImplemented(&'static str),
+
+    /// returned if a proof is rejected by the verifier
+    #[error("Verification failed: {0}")]
+    VerificationFailed(VerificationFailure),
+}
+
+/// The check that caused the verifier to reject a proof
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Error)]
+pub enum VerificationFailure {
+    /// the hash of the running instance does not match the public input of the latest step
+    #[error("instance hash mismatch")]
+    InstanceHash,
+
+    /// the folded instance does not match the instance computed by the folding verifier
+    #[error("folding equation does not hold")]
+    FoldingEquation,
+
+    /// a step-0 proof does not have the shape of the base case
+    #[error("invalid base case")]
+    BaseCase,
+
+    /// an instance-witness pair does not satisfy the relaxed PLONK relation
+    #[error("relaxed PLONK relation is not satisfied")]
+    RelaxedRelation,
+
+    /// a witness does not open the commitments of its instance
+    #[error("commitment does not match witness")]
+    CommitmentOpening,
+
+    /// an opening of a past step does not match the accumulator of the proof
+    #[error("step is not included in the accumulator")]
+    AccumulatorInclusion,
+
+    /// a proof claims another number of steps than the one fixed by the verifier key
+    #[error("unexpected number of steps")]
+    NumberOfSteps,
+
+    /// the step counter of a proof does not match the number of steps it is verified for
+    #[error("step counter mismatch")]
+    StepCount,
+
+    /// a round of a sumcheck does not add up to the sum claimed by the previous round
+    #[error("sumcheck round does not match the claimed sum")]
+    Sumcheck,
+}
+
+impl VerificationFailure {
+    /// Converts the failure into an error. Under the `diagnostics` feature, `details` is logged
+    /// at debug level. Callers must only include public data (instances, commitments, step
+    /// counters) in `details`, never witness values.
+    pub(crate) fn reject<D: FnOnce() -> String>(self, _details: D) -> SangriaError {
+        #[cfg(feature = "diagnostics")]
+        log::debug!("verification failed ({}): {}", self, _details());
+
+        SangriaError::VerificationFailed(self)
+    }
 }
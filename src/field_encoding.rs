@@ -0,0 +1,150 @@
+//! Encodings of application data (integers, byte strings, labels) as field elements, e.g. to build
+//! the state of a step circuit. Using these instead of ad-hoc conversions keeps the encodings of
+//! provers and verifiers consistent, and makes reductions modulo the field order explicit.
+
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use blake2::{Blake2b512, Digest};
+
+use crate::SangriaError;
+
+/// What to do with byte strings that encode an integer larger than the field modulus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reduction {
+    /// Reject them with [`SangriaError::NonCanonicalEncoding`]. Use this for values that must
+    /// round-trip, e.g. state read back from storage.
+    Reject,
+    /// Reduce them modulo the field order.
+    Reduce,
+}
+
+/// Decodes a little-endian integer into a field element.
+pub fn from_le_bytes<F: PrimeField>(bytes: &[u8], reduction: Reduction) -> Result<F, SangriaError> {
+    let value = F::from_le_bytes_mod_order(bytes);
+
+    if reduction == Reduction::Reject {
+        let canonical = to_le_bytes(&value);
+        let significant = |bytes: &[u8]| {
+            let length = bytes
+                .iter()
+                .rposition(|byte| *byte != 0)
+                .map_or(0, |i| i + 1);
+            bytes[..length].to_vec()
+        };
+        if significant(bytes) != significant(&canonical) {
+            return Err(SangriaError::NonCanonicalEncoding);
+        }
+    }
+
+    Ok(value)
+}
+
+/// Encodes a field element as a little-endian integer of `F::BigInt` size.
+pub fn to_le_bytes<F: PrimeField>(value: &F) -> Vec<u8> {
+    value.into_repr().to_bytes_le()
+}
+
+/// The number of `u128`s packed into each field element by [`pack_u128`].
+pub fn u128s_per_element<F: PrimeField>() -> usize {
+    F::Params::CAPACITY as usize / 128
+}
+
+/// Packs `u128`s into as few field elements as possible, least significant first. Fails if the
+/// field cannot hold a single `u128`.
+pub fn pack_u128<F: PrimeField>(values: &[u128]) -> Result<Vec<F>, SangriaError> {
+    let per_element = u128s_per_element::<F>();
+    if per_element == 0 {
+        return Err(SangriaError::NonCanonicalEncoding);
+    }
+
+    Ok(values
+        .chunks(per_element)
+        .map(|chunk| {
+            let bytes = chunk
+                .iter()
+                .flat_map(|value| value.to_le_bytes())
+                .collect::<Vec<_>>();
+            F::from_le_bytes_mod_order(&bytes)
+        })
+        .collect())
+}
+
+/// Unpacks `number_of_values` `u128`s packed by [`pack_u128`]. Fails if an element does not
+/// encode the expected number of `u128`s.
+pub fn unpack_u128<F: PrimeField>(
+    elements: &[F],
+    number_of_values: usize,
+) -> Result<Vec<u128>, SangriaError> {
+    let per_element = u128s_per_element::<F>();
+    if per_element == 0 || elements.len() != number_of_values.div_ceil(per_element) {
+        return Err(SangriaError::NonCanonicalEncoding);
+    }
+
+    let mut values = Vec::with_capacity(number_of_values);
+    for element in elements {
+        let count = (number_of_values - values.len()).min(per_element);
+        let mut bytes = to_le_bytes(element);
+        bytes.resize(bytes.len().max(16 * count), 0);
+        if bytes[16 * count..].iter().any(|byte| *byte != 0) {
+            return Err(SangriaError::NonCanonicalEncoding);
+        }
+
+        values.extend(
+            bytes[..16 * count]
+                .chunks_exact(16)
+                .map(|chunk| u128::from_le_bytes(chunk.try_into().expect("chunks have 16 bytes"))),
+        );
+    }
+
+    Ok(values)
+}
+
+/// Hashes an arbitrary byte string, e.g. a name or a document, to a field element. The 512-bit
+/// digest is reduced modulo the field order, so the output is statistically close to uniform.
+/// `domain` separates the hashes of different applications.
+pub fn hash_to_field<F: PrimeField>(domain: &[u8], message: &[u8]) -> F {
+    let digest = Blake2b512::new()
+        .chain_update((domain.len() as u64).to_le_bytes())
+        .chain_update(domain)
+        .chain_update(message)
+        .finalize();
+
+    F::from_le_bytes_mod_order(&digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::Zero;
+    use ark_pallas::Fr;
+
+    #[test]
+    fn encodings_round_trip_and_reject_non_canonical_inputs() {
+        let value = hash_to_field::<Fr>(b"sangria-test", b"label");
+        assert_ne!(value, hash_to_field(b"sangria-test", b"other label"));
+        assert_ne!(value, hash_to_field(b"sangria-tes", b"tlabel"));
+
+        let mut bytes = to_le_bytes(&value);
+        assert_eq!(from_le_bytes::<Fr>(&bytes, Reduction::Reject), Ok(value));
+        bytes.extend([0, 0]);
+        assert_eq!(from_le_bytes::<Fr>(&bytes, Reduction::Reject), Ok(value));
+
+        let modulus = <Fr as PrimeField>::Params::MODULUS.to_bytes_le();
+        assert_eq!(
+            from_le_bytes::<Fr>(&modulus, Reduction::Reject),
+            Err(SangriaError::NonCanonicalEncoding)
+        );
+        assert_eq!(
+            from_le_bytes::<Fr>(&modulus, Reduction::Reduce),
+            Ok(Fr::zero())
+        );
+
+        let values = [0, 1, u128::MAX, 1 << 100, 7];
+        let packed = pack_u128::<Fr>(&values).unwrap();
+        assert_eq!(packed.len(), values.len() / u128s_per_element::<Fr>());
+        assert_eq!(unpack_u128(&packed, values.len()), Ok(values.to_vec()));
+        assert_eq!(
+            unpack_u128(&[value], 1),
+            Err(SangriaError::NonCanonicalEncoding)
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Verifier keys for computations whose number of steps is fixed and public, e.g. a VDF of `N`
+//! iterations. The number of steps is part of the key, so that a proof of a shortened
+//! computation is rejected even if the caller passes the count the prover claims.
+
+use ark_ff::PrimeField;
+use ark_std::marker::PhantomData;
+
+use crate::{SangriaError, StepCircuit, VerificationFailure, IVC};
+
+/// A verifier key of an IVC scheme together with the number of steps every accepted proof must
+/// attest.
+pub struct FixedStepsVerifierKey<F, SC, I>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    I: IVC<F, SC>,
+{
+    verifier_key: I::VerifierKey,
+    number_of_steps: usize,
+    _marker: PhantomData<fn() -> (F, SC)>,
+}
+
+impl<F, SC, I> FixedStepsVerifierKey<F, SC, I>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    I: IVC<F, SC>,
+{
+    /// Binds `verifier_key` to computations of exactly `number_of_steps` steps.
+    pub fn new(verifier_key: I::VerifierKey, number_of_steps: usize) -> Self {
+        Self {
+            verifier_key,
+            number_of_steps,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped verifier key.
+    pub fn verifier_key(&self) -> &I::VerifierKey {
+        &self.verifier_key
+    }
+
+    /// The number of steps every accepted proof attests.
+    pub fn number_of_steps(&self) -> usize {
+        self.number_of_steps
+    }
+
+    /// Verifies that `current_state` is the result of the fixed number of steps from
+    /// `origin_state`, see [`IVC::verify`].
+    pub fn verify(
+        &self,
+        origin_state: &SC::State,
+        current_state: SC::State,
+        current_proof: Option<I::Proof>,
+    ) -> Result<(), SangriaError> {
+        I::verify(
+            &self.verifier_key,
+            self.number_of_steps,
+            origin_state,
+            current_state,
+            current_proof,
+        )
+    }
+
+    /// Verifies a proof that claims to attest `claimed_number_of_steps` steps, rejecting it with
+    /// [`VerificationFailure::NumberOfSteps`] if the claim is not the fixed number of steps.
+    pub fn verify_claimed(
+        &self,
+        claimed_number_of_steps: usize,
+        origin_state: &SC::State,
+        current_state: SC::State,
+        current_proof: Option<I::Proof>,
+    ) -> Result<(), SangriaError> {
+        if claimed_number_of_steps != self.number_of_steps {
+            return Err(VerificationFailure::NumberOfSteps.reject(|| {
+                format!(
+                    "proof claims {} steps, the key fixes {}",
+                    claimed_number_of_steps, self.number_of_steps
+                )
+            }));
+        }
+
+        self.verify(origin_state, current_state, current_proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::One;
+    use ark_pallas::Fr;
+    use ark_std::rand::Rng;
+
+    /// The step circuit `z_{i+1} = z_i + 1`.
+    struct Increment;
+
+    impl StepCircuit<Fr> for Increment {
+        type State = Fr;
+        type Witness = ();
+        type Outputs = ();
+
+        fn witness_arity(&self) -> usize {
+            0
+        }
+
+        fn witness_elements(_witness: &()) -> Vec<Fr> {
+            Vec::new()
+        }
+
+        fn output_elements(_outputs: &()) -> Vec<Fr> {
+            Vec::new()
+        }
+
+        fn state_elements(state: &Fr) -> Vec<Fr> {
+            vec![*state]
+        }
+    }
+
+    /// A stand-in IVC scheme without proofs whose verifier recomputes the state.
+    struct IncrementIVC;
+
+    impl IVC<Fr, Increment> for IncrementIVC {
+        type PublicParameters = ();
+        type ProverKey = ();
+        type VerifierKey = ();
+        type Proof = ();
+
+        fn setup<R: Rng>(_rng: &mut R) -> Result<(), SangriaError> {
+            Ok(())
+        }
+
+        fn encode<R: Rng>(
+            _public_parameters: &(),
+            _step_circuit: &Increment,
+            _rng: &mut R,
+        ) -> Result<((), ()), SangriaError> {
+            Ok(((), ()))
+        }
+
+        fn prove_step(
+            _prover_key: &(),
+            _origin_state: &Fr,
+            current_state: Fr,
+            _current_proof: Option<()>,
+            _current_witness: &(),
+        ) -> Result<(Fr, ()), SangriaError> {
+            Ok((current_state + Fr::one(), ()))
+        }
+
+        fn verify(
+            _verifier_key: &(),
+            number_of_steps: usize,
+            origin_state: &Fr,
+            current_state: Fr,
+            _current_proof: Option<()>,
+        ) -> Result<(), SangriaError> {
+            if *origin_state + Fr::from(number_of_steps as u64) != current_state {
+                return Err(SangriaError::VerificationFailed(
+                    VerificationFailure::RelaxedRelation,
+                ));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn shortened_computations_are_rejected() {
+        let key = FixedStepsVerifierKey::<Fr, Increment, IncrementIVC>::new((), 3);
+        let origin_state = Fr::from(7u64);
+        let mut state = origin_state;
+        for _ in 0..2 {
+            state = IncrementIVC::prove_step(&(), &origin_state, state, None, &())
+                .unwrap()
+                .0;
+        }
+
+        // Two steps verify against the generic verifier, but not against the fixed key.
+        assert_eq!(
+            IncrementIVC::verify(&(), 2, &origin_state, state, None),
+            Ok(())
+        );
+        assert!(key.verify(&origin_state, state, None).is_err());
+        assert_eq!(
+            key.verify_claimed(2, &origin_state, state, None),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::NumberOfSteps
+            ))
+        );
+
+        let (state, proof) =
+            IncrementIVC::prove_step(&(), &origin_state, state, None, &()).unwrap();
+        assert_eq!(key.verify(&origin_state, state, Some(proof)), Ok(()));
+        assert_eq!(key.verify_claimed(3, &origin_state, state, None), Ok(()));
+    }
+}
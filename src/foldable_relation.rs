@@ -0,0 +1,260 @@
+//! The relation a folding scheme folds, abstracted so that the same transcript and folding
+//! machinery can host more than one arithmetization. A relation fixes the relaxed instances and
+//! witnesses, how two pairs are folded with a challenge `r` and the commitment to their cross term
+//! `T`, and what it means for a pair to satisfy it.
+//!
+//! [`PLONKRelation`] is the relaxed PLONK relation of [`crate::relations`], the one the IVC
+//! scheme is built on. [`crate::r1cs::R1CSRelation`] is the relaxed R1CS relation of Nova, for
+//! comparisons and for migrating R1CS circuits.
+
+use ark_ff::PrimeField;
+use ark_sponge::Absorb;
+
+use crate::{
+    cross_term::{compute_cross_term, CrossTermConfig, RelaxedTrace},
+    relations::{
+        check_constraints, FoldingCommitmentConfig, HomomorphicCommitmentScheme, ProverKey,
+    },
+    RelaxedPLONKInstance, RelaxedPLONKWitness, SangriaError,
+};
+
+/// A relaxed relation that can be folded, see the module documentation.
+pub trait FoldableRelation<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    /// The fixed part of the relation, e.g. a circuit, with everything needed to check it.
+    type Structure;
+
+    /// A committed relaxed instance. It is absorbed into the folding transcript, see
+    /// [`crate::transcript::derive_folding_challenge`].
+    type Instance: Clone + PartialEq + Absorb;
+
+    /// A relaxed witness.
+    type Witness;
+
+    /// Returns true if the pair is strict, i.e. it is the relaxation of an unrelaxed pair: its
+    /// scaling factor is one and its slack vector is zero.
+    fn is_strict(instance: &Self::Instance, witness: &Self::Witness) -> bool;
+
+    /// Computes the cross term `T` of two pairs, the coefficient of `r` in the folded slack.
+    fn cross_term(
+        structure: &Self::Structure,
+        left_instance: &Self::Instance,
+        left_witness: &Self::Witness,
+        right_instance: &Self::Instance,
+        right_witness: &Self::Witness,
+    ) -> Result<Vec<F>, SangriaError>;
+
+    /// Folds two instances with the commitment to their cross term. This is all the folding
+    /// verifier computes.
+    fn fold_instances(
+        left: &Self::Instance,
+        right: &Self::Instance,
+        cross_term_commitment: &<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        challenge: F,
+    ) -> Result<Self::Instance, SangriaError>;
+
+    /// Folds two witnesses with their cross term and the hiding of its commitment, so that the
+    /// folded witness opens the instance folded by [`Self::fold_instances`].
+    fn fold_witnesses(
+        left: &Self::Witness,
+        right: &Self::Witness,
+        cross_term: &[F],
+        cross_term_hiding: F,
+        challenge: F,
+    ) -> Result<Self::Witness, SangriaError>;
+
+    /// Checks that the witness satisfies the constraints of `structure` with the scaling factor
+    /// and public inputs of the instance. It does not check that the witness opens the
+    /// commitments of the instance.
+    fn check(
+        structure: &Self::Structure,
+        instance: &Self::Instance,
+        witness: &Self::Witness,
+    ) -> Result<(), SangriaError>;
+}
+
+/// The relaxed PLONK relation, see [`crate::relations`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PLONKRelation;
+
+impl<F, Comm> FoldableRelation<F, Comm> for PLONKRelation
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    type Structure = ProverKey<F, Comm>;
+    type Instance = RelaxedPLONKInstance<F, Comm>;
+    type Witness = RelaxedPLONKWitness<F>;
+
+    fn is_strict(instance: &Self::Instance, witness: &Self::Witness) -> bool {
+        instance.is_strict() && witness.is_strict()
+    }
+
+    fn cross_term(
+        structure: &Self::Structure,
+        left_instance: &Self::Instance,
+        left_witness: &Self::Witness,
+        right_instance: &Self::Instance,
+        right_witness: &Self::Witness,
+    ) -> Result<Vec<F>, SangriaError> {
+        compute_cross_term(
+            &structure.circuit.selectors(),
+            &plonk_trace(left_instance, left_witness)?,
+            &plonk_trace(right_instance, right_witness)?,
+            CrossTermConfig::default(),
+        )
+    }
+
+    fn fold_instances(
+        left: &Self::Instance,
+        right: &Self::Instance,
+        cross_term_commitment: &<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        challenge: F,
+    ) -> Result<Self::Instance, SangriaError> {
+        left.fold(right, cross_term_commitment, challenge)
+    }
+
+    fn fold_witnesses(
+        left: &Self::Witness,
+        right: &Self::Witness,
+        cross_term: &[F],
+        cross_term_hiding: F,
+        challenge: F,
+    ) -> Result<Self::Witness, SangriaError> {
+        left.fold_with(right, cross_term, cross_term_hiding, challenge)
+    }
+
+    fn check(
+        structure: &Self::Structure,
+        instance: &Self::Instance,
+        witness: &Self::Witness,
+    ) -> Result<(), SangriaError> {
+        instance.check_circuit(structure.verifier_key.digest())?;
+        instance.check_padding()?;
+
+        check_constraints(structure, instance, witness)
+    }
+}
+
+/// Returns the wire columns and the scaling factor of a relaxed PLONK pair.
+pub(crate) fn plonk_trace<'a, F, Comm>(
+    instance: &RelaxedPLONKInstance<F, Comm>,
+    witness: &'a RelaxedPLONKWitness<F>,
+) -> Result<RelaxedTrace<'a, F>, SangriaError>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    Ok(RelaxedTrace {
+        columns: [
+            witness.witness_column(0)?,
+            witness.witness_column(1)?,
+            witness.witness_column(2)?,
+        ],
+        scaling_factor: instance.scaling_factor(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_builder::PLONKCircuitBuilder,
+        relations::{
+            decide, relax, NonInteractiveFoldingScheme, PLONKFoldingScheme, PLONKInstance,
+            PLONKWitness, SetupInfo, SlackRandomization,
+        },
+        test_utils::seeded_rng,
+        test_vectors::{poseidon_parameters_for_test, sponge_config_for_test},
+        vector_commitment::mock::{FieldCommitment, FieldCommitmentConfig},
+    };
+    use ark_ff::{One, Zero};
+    use ark_pallas::Fr;
+    use ark_sponge::poseidon::PoseidonSponge;
+    use ark_std::UniformRand;
+
+    type Comm = FieldCommitmentConfig<Fr>;
+    type FoldingScheme = PLONKFoldingScheme<Fr, Comm, PoseidonSponge<Fr>>;
+
+    #[test]
+    fn folded_plonk_pairs_satisfy_the_relation() {
+        let rng = &mut seeded_rng("foldable_relation::folded_plonk_pairs_satisfy_the_relation");
+        let number_of_gates = 4;
+        let info = SetupInfo {
+            number_of_public_inputs: 0,
+            number_of_gates,
+            domain_separator: b"sangria-foldable-relation-test".to_vec(),
+            poseidon_constants: poseidon_parameters_for_test(rng),
+            poseidon2_constants: None,
+            sponge_config: sponge_config_for_test(),
+            slack_randomization: SlackRandomization::EveryFold,
+        };
+        let pp = FoldingScheme::setup(&info, rng).unwrap();
+
+        // c = a * b + 1 at every gate, so that folding produces a non-zero cross term.
+        let mut builder = PLONKCircuitBuilder::new();
+        for _ in 0..number_of_gates {
+            builder.add_gate([Fr::zero(), Fr::zero(), -Fr::one(), Fr::one(), Fr::one()]);
+        }
+        let (pk, vk) = FoldingScheme::encode(&pp, &builder.build().unwrap(), rng).unwrap();
+
+        let relaxed = |rng: &mut _| {
+            let a = (0..number_of_gates)
+                .map(|_| Fr::rand(rng))
+                .collect::<Vec<_>>();
+            let b = (0..number_of_gates)
+                .map(|_| Fr::rand(rng))
+                .collect::<Vec<_>>();
+            let c = a.iter().zip(&b).map(|(a, b)| *a * b + Fr::one()).collect();
+            let witness = PLONKWitness::new(vec![a, b, c]);
+            relax(&pp, &vk, PLONKInstance::new(vec![]), witness, rng).unwrap()
+        };
+        let (left_instance, left_witness) = relaxed(rng);
+        let (right_instance, right_witness) = relaxed(rng);
+        assert!(<PLONKRelation as FoldableRelation<Fr, Comm>>::is_strict(
+            &left_instance,
+            &left_witness
+        ));
+
+        let cross_term = <PLONKRelation as FoldableRelation<Fr, Comm>>::cross_term(
+            &pk,
+            &left_instance,
+            &left_witness,
+            &right_instance,
+            &right_witness,
+        )
+        .unwrap();
+        let cross_term_hiding = Fr::rand(rng);
+        let cross_term_commitment = FieldCommitment::commit(
+            &pp.commit_key_selectors_and_slack,
+            &cross_term,
+            cross_term_hiding,
+        )
+        .unwrap();
+
+        let challenge = Fr::rand(rng);
+        let instance = <PLONKRelation as FoldableRelation<Fr, Comm>>::fold_instances(
+            &left_instance,
+            &right_instance,
+            &cross_term_commitment,
+            challenge,
+        )
+        .unwrap();
+        let witness = <PLONKRelation as FoldableRelation<Fr, Comm>>::fold_witnesses(
+            &left_witness,
+            &right_witness,
+            &cross_term,
+            cross_term_hiding,
+            challenge,
+        )
+        .unwrap();
+
+        assert!(!<PLONKRelation as FoldableRelation<Fr, Comm>>::is_strict(
+            &instance, &witness
+        ));
+        assert_eq!(
+            <PLONKRelation as FoldableRelation<Fr, Comm>>::check(&pk, &instance, &witness),
+            Ok(())
+        );
+        assert_eq!(decide(&pp, &pk, &instance, &witness), Ok(()));
+    }
+}
@@ -1,14 +1,39 @@
 use crate::vector_commitment::HomomorphicCommitmentScheme;
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_sponge::{
     poseidon::{PoseidonParameters, PoseidonSponge},
     Absorb, CryptographicSponge, FieldBasedCryptographicSponge,
 };
 use ark_std::{marker::PhantomData, rand::Rng};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+#[cfg(feature = "json")]
+use crate::json;
 use crate::{
+    circuit_builder::PLONKCircuitBuilder,
+    cross_term::{compute_cross_term, compute_cross_term_streaming, CrossTermConfig},
+    foldable_relation::{plonk_trace, FoldableRelation, PLONKRelation},
+    preprocessing::PreprocessedCircuit,
+    relations::{decide, relax},
+    relaxed_plonk::{PLONKInstance, PLONKWitness},
+    serialization::{
+        deserialize_tagged, deserialize_with_mode, serialize_tagged, serialize_with_mode,
+        ArtifactTag, SerializationMode,
+    },
+    sponge::{Poseidon, Sponge},
+    telemetry,
+    transcript::{
+        derive_folding_challenge, FoldingRandomOracle, LabeledChallenges, Poseidon2Parameters,
+        SpongeConfig, TranscriptRecorder, TRANSCRIPT_SEED,
+    },
     NonInteractiveFoldingScheme, PLONKCircuit, RelaxedPLONKInstance, RelaxedPLONKWitness,
-    SangriaError, CONSTANT_SELECTOR_INDEX,
+    SangriaError, VerificationFailure, CONSTANT_SELECTOR_INDEX,
 };
 
 /// A folding scheme for relaxed PLONK
@@ -21,6 +46,22 @@ pub struct PLONKFoldingScheme<
 pub trait FoldingCommitmentConfig<F: PrimeField> {
     type CommitmentSlack: HomomorphicCommitmentScheme<F>;
     type CommitmentWitness: HomomorphicCommitmentScheme<F>;
+
+    /// The name of the curve the commitments live on, e.g. `"pallas"`. It is embedded in stored
+    /// keys so that they are not loaded in another configuration.
+    const CURVE_ID: &'static str;
+}
+
+/// How the commitment to the cross term, and hence the folded slack commitment, is randomized.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SlackRandomization {
+    /// Hide the cross term with fresh randomness at every fold. Needed for zero-knowledge. The
+    /// prover takes no rng, so the hiding is derived deterministically with Poseidon from the
+    /// transcript seed of the fold and the hidings of both witnesses, which only the prover knows.
+    EveryFold,
+    /// Commit to the cross term with zero randomness. Cheaper, but the folded slack commitment
+    /// is not hiding.
+    Disabled,
 }
 
 pub struct SetupInfo<F: PrimeField> {
@@ -28,19 +69,249 @@ pub struct SetupInfo<F: PrimeField> {
     pub number_of_gates: usize,
     pub domain_separator: Vec<u8>,
     pub poseidon_constants: PoseidonParameters<F>,
+    /// Required if the folding scheme uses [`crate::transcript::Poseidon2Sponge`] as its random
+    /// oracle.
+    pub poseidon2_constants: Option<Poseidon2Parameters<F>>,
+    /// The configuration of the sponge of the random oracle, which the augmented circuit is
+    /// built for. The hash constants of the oracle are checked against it.
+    pub sponge_config: SpongeConfig,
+    pub slack_randomization: SlackRandomization,
+}
+
+/// The size of a PLONK circuit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CircuitShape {
+    pub number_of_public_inputs: usize,
+    pub number_of_gates: usize,
 }
 
 /// Public parameters for the folding scheme. Contains size parameters for the PLONK circuits
 /// and commitment parameters for vectors of sizes `number_of_gates` and `number_of_public_inputs + number_of_gates + 1`
+///
+/// The commit keys are reference counted so that parameters produced by `trim_for_circuit` for
+/// many circuits share the generators of a single, maximum-size setup.
 pub struct PublicParameters<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
     pub number_of_public_inputs: usize,
     pub number_of_gates: usize,
-    pub commit_key_witness: <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::CommitKey,
+    pub commit_key_witness:
+        Arc<<Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::CommitKey>,
     pub commit_key_selectors_and_slack:
-        <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::CommitKey,
+        Arc<<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::CommitKey>,
     pub poseidon_constants: PoseidonParameters<F>,
+    pub poseidon2_constants: Option<Poseidon2Parameters<F>>,
+    pub sponge_config: SpongeConfig,
+    pub slack_randomization: SlackRandomization,
 
     pub domain_separator: Vec<u8>,
+
+    /// A digest of the commit keys, computed once at setup and shared by trimmed parameters.
+    pub commit_keys_digest: F,
+
+    /// A digest of all the fields above. It is what gets absorbed into the transcript so that
+    /// absorbing the parameters does not scale with the commit keys.
+    pub digest: F,
+}
+
+impl<F, Comm> PublicParameters<F, Comm>
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    /// Creates a fresh random oracle from the hash parameters. Fails if the parameters of the
+    /// oracle do not have the sponge configuration declared at setup.
+    pub fn new_oracle<RO: FoldingRandomOracle<F>>(&self) -> Result<RO, SangriaError> {
+        let poseidon2_constants = self.poseidon2_constants.as_ref();
+        if let Some(config) = RO::sponge_config(&self.poseidon_constants, poseidon2_constants)? {
+            if config != self.sponge_config {
+                return Err(SangriaError::InvalidOracleParameters);
+            }
+        }
+
+        RO::new_oracle(&self.poseidon_constants, poseidon2_constants)
+    }
+
+    /// Hashes the commit keys into a single field element. This scales with the size of the keys
+    /// and is only done once per setup. Fails if a commit key cannot be serialized.
+    pub fn compute_commit_keys_digest(&self) -> Result<F, SangriaError> {
+        let mut bytes = Vec::new();
+        self.commit_key_witness
+            .serialize(&mut bytes)
+            .map_err(|_| SangriaError::SerializationError)?;
+        self.commit_key_selectors_and_slack
+            .serialize(&mut bytes)
+            .map_err(|_| SangriaError::SerializationError)?;
+
+        let mut sponge = PoseidonSponge::new(&self.poseidon_constants);
+        sponge.absorb(&bytes);
+        Ok(sponge.squeeze_native_field_elements(1)[0])
+    }
+
+    /// Hashes the parameters into a single field element. Must be called again if any of the
+    /// fields is modified after setup.
+    pub fn compute_digest(&self) -> F {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&self.domain_separator);
+        bytes.extend_from_slice(&(self.number_of_public_inputs as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.number_of_gates as u64).to_le_bytes());
+        bytes.push(self.slack_randomization as u8);
+        for value in [
+            self.sponge_config.rate,
+            self.sponge_config.capacity,
+            self.sponge_config.full_rounds,
+            self.sponge_config.partial_rounds,
+        ] {
+            bytes.extend_from_slice(&(value as u64).to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.sponge_config.alpha.to_le_bytes());
+
+        let mut sponge = PoseidonSponge::new(&self.poseidon_constants);
+        sponge.absorb(&bytes);
+        sponge.absorb(&self.commit_keys_digest);
+        sponge.squeeze_native_field_elements(1)[0]
+    }
+
+    /// Returns the shape of the largest circuit these parameters support.
+    pub fn shape(&self) -> CircuitShape {
+        CircuitShape {
+            number_of_public_inputs: self.number_of_public_inputs,
+            number_of_gates: self.number_of_gates,
+        }
+    }
+
+    /// Specializes the parameters to a smaller circuit. The returned parameters reference the
+    /// same commit keys instead of copying them, so a service hosting many circuits only keeps
+    /// one set of generators in memory.
+    pub fn trim_for_circuit(&self, shape: CircuitShape) -> Result<Self, SangriaError> {
+        if shape.number_of_gates > self.number_of_gates
+            || shape.number_of_gates + shape.number_of_public_inputs
+                > self.number_of_gates + self.number_of_public_inputs
+        {
+            return Err(SangriaError::CircuitTooLarge);
+        }
+
+        let mut trimmed = self.clone();
+        trimmed.number_of_public_inputs = shape.number_of_public_inputs;
+        trimmed.number_of_gates = shape.number_of_gates;
+        trimmed.digest = trimmed.compute_digest();
+
+        Ok(trimmed)
+    }
+
+    /// A quick randomized check that the parameters are usable, meant to be run by operators at
+    /// startup. It checks that the cached digests match the keys and constants, and that both
+    /// commitment schemes are additively homomorphic on random vectors, which exercises the
+    /// commit keys and the MSM backend. It then encodes a tiny circuit, folds two satisfied pairs
+    /// of it with [`PLONKFoldingScheme::prove_and_check`] and the random oracle `RO` of the
+    /// deployment, and decides the folded pair.
+    pub fn self_test<RO: FoldingRandomOracle<F>, R: Rng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(), SangriaError> {
+        if self.compute_commit_keys_digest()? != self.commit_keys_digest
+            || self.compute_digest() != self.digest
+        {
+            return Err(SangriaError::SelfTestFailed("parameter digest mismatch"));
+        }
+
+        if !homomorphism_holds::<F, Comm::CommitmentWitness, R>(
+            &self.commit_key_witness,
+            self.number_of_gates,
+            rng,
+        )? {
+            return Err(SangriaError::SelfTestFailed(
+                "witness commitment is not homomorphic",
+            ));
+        }
+
+        if !homomorphism_holds::<F, Comm::CommitmentSlack, R>(
+            &self.commit_key_selectors_and_slack,
+            self.number_of_gates + self.number_of_public_inputs + 1,
+            rng,
+        )? {
+            return Err(SangriaError::SelfTestFailed(
+                "slack commitment is not homomorphic",
+            ));
+        }
+
+        self.self_test_fold::<RO, R>(rng)
+    }
+
+    /// Folds two satisfied pairs of a circuit with `a - c = 0` at every gate and decides the
+    /// folded pair.
+    fn self_test_fold<RO: FoldingRandomOracle<F>, R: Rng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(), SangriaError> {
+        let mut builder = PLONKCircuitBuilder::new();
+        for _ in 0..self.number_of_gates {
+            builder.add_gate([F::one(), F::zero(), -F::one(), F::zero(), F::zero()]);
+        }
+        let (prover_key, verifier_key) =
+            PLONKFoldingScheme::<F, Comm, RO>::encode(self, &builder.build()?, rng)?;
+
+        let mut relaxed = || {
+            let a = (0..self.number_of_gates)
+                .map(|_| F::rand(rng))
+                .collect::<Vec<_>>();
+            let b = (0..self.number_of_gates).map(|_| F::rand(rng)).collect();
+            let witness = PLONKWitness::new(vec![a.clone(), b, a]);
+            relax(
+                self,
+                &verifier_key,
+                PLONKInstance::new(vec![]),
+                witness,
+                rng,
+            )
+        };
+        let (left_instance, left_witness) = relaxed()?;
+        let (right_instance, right_witness) = relaxed()?;
+
+        let (instance, witness, _) = PLONKFoldingScheme::<F, Comm, RO>::prove_and_check(
+            self,
+            &prover_key,
+            &left_instance,
+            &left_witness,
+            &right_instance,
+            &right_witness,
+        )?;
+        if decide(self, &prover_key, &instance, &witness).is_err() {
+            return Err(SangriaError::SelfTestFailed("folded pair is not satisfied"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that `commit(a, r_a) + commit(b, r_b) * c == commit(a + c * b, r_a + c * r_b)` for
+/// random vectors of length `len`.
+fn homomorphism_holds<F, CS, R>(
+    commit_key: &CS::CommitKey,
+    len: usize,
+    rng: &mut R,
+) -> Result<bool, SangriaError>
+where
+    F: PrimeField,
+    CS: HomomorphicCommitmentScheme<F>,
+    R: Rng,
+{
+    let a = (0..len).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let b = (0..len).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let (r_a, r_b, c) = (F::rand(rng), F::rand(rng), F::rand(rng));
+
+    let combined = a
+        .iter()
+        .zip(&b)
+        .map(|(a_i, b_i)| *a_i + c * b_i)
+        .collect::<Vec<_>>();
+
+    let commitment_a = CS::commit(commit_key, &a, r_a)?;
+    let commitment_b = CS::commit(commit_key, &b, r_b)?;
+    let commitment_combined = CS::commit(commit_key, &combined, r_a + c * r_b)?;
+
+    Ok([commitment_a, commitment_b * c]
+        .into_iter()
+        .sum::<CS::Commitment>()
+        == commitment_combined)
 }
 
 impl<F, Comm> Clone for PublicParameters<F, Comm>
@@ -55,29 +326,37 @@ where
             commit_key_witness: self.commit_key_witness.clone(),
             commit_key_selectors_and_slack: self.commit_key_selectors_and_slack.clone(),
             poseidon_constants: self.poseidon_constants.clone(),
+            poseidon2_constants: self.poseidon2_constants.clone(),
+            sponge_config: self.sponge_config,
+            slack_randomization: self.slack_randomization,
             domain_separator: self.domain_separator.clone(),
+            commit_keys_digest: self.commit_keys_digest,
+            digest: self.digest,
         }
     }
 }
 
 impl<F, Comm> Absorb for PublicParameters<F, Comm>
 where
-    F: PrimeField,
+    F: PrimeField + Absorb,
     Comm: FoldingCommitmentConfig<F>,
 {
-    fn to_sponge_bytes(&self, _dest: &mut Vec<u8>) {
-        todo!()
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        self.digest.to_sponge_bytes(dest)
     }
 
-    fn to_sponge_field_elements<SpongeF: PrimeField>(&self, _dest: &mut Vec<SpongeF>) {
-        todo!()
+    fn to_sponge_field_elements<SpongeF: PrimeField>(&self, dest: &mut Vec<SpongeF>) {
+        self.digest.to_sponge_field_elements(dest)
     }
 }
 
 /// The verifier key for the PLONK folding scheme. Contains a commitment to the q_C selector (constant)
+/// and commitments to the global lookup tables of the circuit.
 pub struct VerifierKey<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
     pub selector_c_commitment:
         <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+    pub lookup_table_commitments:
+        Vec<<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment>,
     pub transcript_seed: F,
 }
 
@@ -89,40 +368,403 @@ where
     fn clone(&self) -> Self {
         Self {
             selector_c_commitment: self.selector_c_commitment,
+            lookup_table_commitments: self.lookup_table_commitments.clone(),
             transcript_seed: self.transcript_seed,
         }
     }
 }
 
-impl<F, Comm> Absorb for VerifierKey<F, Comm>
+impl<F, Comm> VerifierKey<F, Comm>
 where
     F: PrimeField,
     Comm: FoldingCommitmentConfig<F>,
 {
-    fn to_sponge_bytes(&self, _dest: &mut Vec<u8>) {
-        todo!()
+    /// Returns a digest of the verifier key. This is the transcript seed, which binds the
+    /// circuit, the lookup table commitments and the public parameters, and is all the folding
+    /// verifier needs to derive its challenges.
+    pub fn digest(&self) -> F {
+        self.transcript_seed
+    }
+
+    /// Serializes the verifier key.
+    pub fn to_bytes(&self, mode: SerializationMode) -> Result<Vec<u8>, SangriaError> {
+        let mut bytes = Vec::new();
+        serialize_with_mode(&self.selector_c_commitment, &mut bytes, mode)?;
+        serialize_with_mode(&self.lookup_table_commitments, &mut bytes, mode)?;
+        serialize_with_mode(&self.transcript_seed, &mut bytes, mode)?;
+
+        Ok(bytes)
+    }
+
+    /// Deserializes a verifier key serialized with the same `mode`.
+    pub fn from_bytes(bytes: &[u8], mode: SerializationMode) -> Result<Self, SangriaError> {
+        let mut reader = bytes;
+        let verifier_key = Self {
+            selector_c_commitment: deserialize_with_mode(&mut reader, mode)?,
+            lookup_table_commitments: deserialize_with_mode(&mut reader, mode)?,
+            transcript_seed: deserialize_with_mode(&mut reader, mode)?,
+        };
+        if !reader.is_empty() {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(verifier_key)
+    }
+
+    /// Exports the verifier key as JSON, for tooling that does not link the crate. The layout is
+    /// `{"curve", "selector_c_commitment", "lookup_table_commitments": [..], "transcript_seed"}`,
+    /// where `curve` is [`FoldingCommitmentConfig::CURVE_ID`] and every other value is the hex
+    /// string of its compressed serialization.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, SangriaError> {
+        let lookup_table_commitments = self
+            .lookup_table_commitments
+            .iter()
+            .map(json::to_hex)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(serde_json::json!({
+            "curve": Comm::CURVE_ID,
+            "selector_c_commitment": json::to_hex(&self.selector_c_commitment)?,
+            "lookup_table_commitments": lookup_table_commitments,
+            "transcript_seed": json::to_hex(&self.transcript_seed)?,
+        })
+        .to_string())
+    }
+
+    /// Imports a verifier key exported with [`Self::to_json`]. Returns
+    /// [`SangriaError::CurveMismatch`] if it was exported for another curve.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, SangriaError> {
+        let object = json::parse(json, Comm::CURVE_ID)?;
+
+        Ok(Self {
+            selector_c_commitment: json::from_hex(json::member(&object, "selector_c_commitment")?)?,
+            lookup_table_commitments: json::array(&object, "lookup_table_commitments")?
+                .iter()
+                .map(json::from_hex)
+                .collect::<Result<_, _>>()?,
+            transcript_seed: json::from_hex(json::member(&object, "transcript_seed")?)?,
+        })
+    }
+}
+
+/// Only the transcript seed is absorbed: it already binds the circuit, the lookup table
+/// commitments and the public parameters, which therefore do not re-enter the transcript at
+/// every fold.
+impl<F, Comm> Absorb for VerifierKey<F, Comm>
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        self.transcript_seed.to_sponge_bytes(dest)
     }
 
-    fn to_sponge_field_elements<SpongeF: PrimeField>(&self, _dest: &mut Vec<SpongeF>) {
-        todo!()
+    fn to_sponge_field_elements<SpongeF: PrimeField>(&self, dest: &mut Vec<SpongeF>) {
+        self.transcript_seed.to_sponge_field_elements(dest)
     }
 }
 
+/// Resource limits of the folding prover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProverConfig {
+    /// The memory the prover may use for the vectors it folds. Defaults to no limit.
+    pub max_memory_bytes: usize,
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        Self {
+            max_memory_bytes: usize::MAX,
+        }
+    }
+}
+
+/// How the folding prover holds the circuit and the witnesses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverStrategy {
+    /// Every vector is held in memory, which is the fastest.
+    InMemory,
+    /// The cross term is computed one selector at a time, so that at most one selector is held
+    /// at once when the prover key is a [`ProverKeyHandle`] whose circuit is not loaded.
+    Streaming,
+}
+
+impl ProverConfig {
+    /// Returns the strategy to use for a working set of `working_set_bytes`.
+    pub fn strategy(&self, working_set_bytes: usize) -> ProverStrategy {
+        if working_set_bytes > self.max_memory_bytes {
+            ProverStrategy::Streaming
+        } else {
+            ProverStrategy::InMemory
+        }
+    }
+}
+
+/// Estimates the memory needed to fold with a circuit of `number_of_gates` rows: its selectors
+/// and copy constraints, the witness columns and slack vectors of both witnesses, and the cross
+/// term.
+fn estimated_working_set_bytes<F: PrimeField>(
+    number_of_selectors: usize,
+    number_of_gates: usize,
+) -> usize {
+    let number_of_vectors = number_of_selectors + 3 + 2 * (3 + 1) + 1;
+
+    number_of_vectors
+        .saturating_mul(number_of_gates)
+        .saturating_mul(std::mem::size_of::<F>())
+}
+
 /// Prover key for the PLONK folding scheme. Contains:
 /// - a commitment to the q_C selector (as the verifier key)
 /// - a description of the circuit (needed to compute cross terms)
+/// - the circuit in evaluation-ready form, computed once at encode time
 /// - commitment parameters (as the public parameters)
 /// - the randomness that was used to commit to q_C
+///
+/// The circuit is reference counted so that cloning the key for parallel provers is cheap.
 pub struct ProverKey<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
     pub verifier_key: VerifierKey<F, Comm>,
-    pub circuit: PLONKCircuit<F>,
+    pub circuit: Arc<PLONKCircuit<F>>,
+    pub preprocessed_circuit: Arc<PreprocessedCircuit<F>>,
     pub selector_c_commit_randomness: F,
+    pub prover_config: ProverConfig,
+}
+
+impl<F, Comm> ProverKey<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    /// Estimates the memory needed to fold with this key: the selectors and copy constraints of
+    /// the circuit, the witness columns and slack vectors of both witnesses, and the cross term.
+    pub fn estimated_working_set_bytes(&self) -> usize {
+        estimated_working_set_bytes::<F>(
+            self.circuit.number_of_selectors(),
+            self.circuit.single_selector(0).map_or(0, <[F]>::len),
+        )
+    }
+
+    /// Returns the strategy the prover uses with this key, see [`ProverConfig`].
+    pub fn prover_strategy(&self) -> ProverStrategy {
+        self.prover_config
+            .strategy(self.estimated_working_set_bytes())
+    }
+}
+
+impl<F, Comm> Clone for ProverKey<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            verifier_key: self.verifier_key.clone(),
+            circuit: Arc::clone(&self.circuit),
+            preprocessed_circuit: Arc::clone(&self.preprocessed_circuit),
+            selector_c_commit_randomness: self.selector_c_commit_randomness,
+            prover_config: self.prover_config,
+        }
+    }
+}
+
+const CROSS_TERM_HIDING_DOMAIN_SEPARATOR: &[u8] = b"sangria-cross-term-hiding";
+
+const COPY_CONSTRAINT_FILE: &str = "copy_constraint.bin";
+const LOOKUP_TABLES_FILE: &str = "lookup_tables.bin";
+
+fn selector_file(selector_index: usize) -> String {
+    format!("selector_{}.bin", selector_index)
+}
+
+/// A prover key whose circuit lives on disk. Each selector column and the copy constraints are
+/// stored in their own file under `directory` so that they can be streamed one at a time during
+/// cross-term computation. The full circuit is only held in memory between `load` and `unload`.
+pub struct ProverKeyHandle<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    pub verifier_key: VerifierKey<F, Comm>,
+    pub selector_c_commit_randomness: F,
+    pub prover_config: ProverConfig,
+    directory: PathBuf,
+    mode: SerializationMode,
+    number_of_selectors: usize,
+    number_of_gates: usize,
+    circuit: Option<PLONKCircuit<F>>,
+}
+
+impl<F, Comm> ProverKeyHandle<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    /// Writes the circuit of `prover_key` to `directory` and returns an unloaded handle to it.
+    /// The files are read back with the same `mode`, [`SerializationMode::Uncompressed`] is
+    /// recommended for local storage.
+    pub fn store<P: AsRef<Path>>(
+        prover_key: ProverKey<F, Comm>,
+        directory: P,
+        mode: SerializationMode,
+    ) -> Result<Self, SangriaError> {
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory).map_err(|_| SangriaError::KeyStorageError)?;
+        let tag = key_tag::<F, Comm>()?;
+
+        let selectors = prover_key.circuit.selectors();
+        for (selector_index, selector) in selectors.iter().enumerate() {
+            write_to_file(
+                &directory.join(selector_file(selector_index)),
+                selector,
+                &tag,
+                mode,
+            )?;
+        }
+        write_to_file(
+            &directory.join(COPY_CONSTRAINT_FILE),
+            &prover_key.circuit.copy_constraint(),
+            &tag,
+            mode,
+        )?;
+        write_to_file(
+            &directory.join(LOOKUP_TABLES_FILE),
+            &prover_key.circuit.lookup_tables().to_vec(),
+            &tag,
+            mode,
+        )?;
+
+        Ok(Self {
+            verifier_key: prover_key.verifier_key,
+            selector_c_commit_randomness: prover_key.selector_c_commit_randomness,
+            prover_config: prover_key.prover_config,
+            directory,
+            mode,
+            number_of_selectors: selectors.len(),
+            number_of_gates: selectors.first().map_or(0, Vec::len),
+            circuit: None,
+        })
+    }
+
+    /// Estimates the memory needed to fold with the circuit in memory, see
+    /// [`ProverKey::estimated_working_set_bytes`].
+    pub fn estimated_working_set_bytes(&self) -> usize {
+        estimated_working_set_bytes::<F>(self.number_of_selectors, self.number_of_gates)
+    }
+
+    /// Returns the strategy the prover uses with this handle, see [`ProverConfig`].
+    pub fn prover_strategy(&self) -> ProverStrategy {
+        self.prover_config
+            .strategy(self.estimated_working_set_bytes())
+    }
+
+    /// Returns true if the circuit is currently held in memory.
+    pub fn is_loaded(&self) -> bool {
+        self.circuit.is_some()
+    }
+
+    /// Reads the full circuit from disk into memory. Does nothing if it is already loaded.
+    pub fn load(&mut self) -> Result<(), SangriaError> {
+        if self.circuit.is_none() {
+            self.circuit = Some(self.read_circuit()?);
+        }
+
+        Ok(())
+    }
+
+    /// Drops the in-memory circuit. The on-disk copy is left untouched.
+    pub fn unload(&mut self) {
+        self.circuit = None;
+    }
+
+    /// Returns the in-memory circuit or an error if it has not been loaded.
+    pub fn circuit(&self) -> Result<&PLONKCircuit<F>, SangriaError> {
+        self.circuit.as_ref().ok_or(SangriaError::KeyStorageError)
+    }
+
+    /// Returns a single selector, from memory if the circuit is loaded and from disk otherwise.
+    pub fn read_selector(&self, selector_index: usize) -> Result<Vec<F>, SangriaError> {
+        if selector_index >= self.number_of_selectors {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        match &self.circuit {
+            Some(circuit) => circuit.single_selector(selector_index).map(<[F]>::to_vec),
+            None => read_from_file(
+                &self.directory.join(selector_file(selector_index)),
+                &key_tag::<F, Comm>()?,
+                self.mode,
+            ),
+        }
+    }
+
+    /// Returns an iterator that reads the selectors one at a time.
+    pub fn stream_selectors(&self) -> impl Iterator<Item = Result<Vec<F>, SangriaError>> + '_ {
+        (0..self.number_of_selectors).map(move |selector_index| self.read_selector(selector_index))
+    }
+
+    fn read_circuit(&self) -> Result<PLONKCircuit<F>, SangriaError> {
+        let selectors = self.stream_selectors().collect::<Result<Vec<_>, _>>()?;
+        let tag = key_tag::<F, Comm>()?;
+        let copy_constraint =
+            read_from_file(&self.directory.join(COPY_CONSTRAINT_FILE), &tag, self.mode)?;
+        let lookup_tables =
+            read_from_file(&self.directory.join(LOOKUP_TABLES_FILE), &tag, self.mode)?;
+
+        Ok(PLONKCircuit::new(selectors, copy_constraint).with_lookup_tables(lookup_tables))
+    }
+
+    /// Reads the circuit from disk and returns an in-memory prover key.
+    pub fn to_prover_key(&self) -> Result<ProverKey<F, Comm>, SangriaError> {
+        let circuit = match &self.circuit {
+            Some(circuit) => circuit.clone(),
+            None => self.read_circuit()?,
+        };
+
+        Ok(ProverKey {
+            verifier_key: self.verifier_key.clone(),
+            preprocessed_circuit: Arc::new(PreprocessedCircuit::new(&circuit)?),
+            circuit: Arc::new(circuit),
+            selector_c_commit_randomness: self.selector_c_commit_randomness,
+            prover_config: self.prover_config,
+        })
+    }
+}
+
+fn write_to_file<T: CanonicalSerialize>(
+    path: &Path,
+    value: &T,
+    tag: &ArtifactTag,
+    mode: SerializationMode,
+) -> Result<(), SangriaError> {
+    let file = File::create(path).map_err(|_| SangriaError::KeyStorageError)?;
+    let mut writer = BufWriter::new(file);
+    serialize_tagged(value, tag, &mut writer, mode).map_err(|_| SangriaError::KeyStorageError)?;
+
+    // Dropping a `BufWriter` flushes it but discards the error, so a failed write of the last
+    // buffered bytes would only show up when the key is loaded.
+    writer.flush().map_err(|_| SangriaError::KeyStorageError)
+}
+
+fn read_from_file<T: CanonicalDeserialize>(
+    path: &Path,
+    tag: &ArtifactTag,
+    mode: SerializationMode,
+) -> Result<T, SangriaError> {
+    let file = File::open(path).map_err(|_| SangriaError::KeyStorageError)?;
+    deserialize_tagged(tag, BufReader::new(file), mode).map_err(|error| match error {
+        SangriaError::CurveMismatch => error,
+        _ => SangriaError::KeyStorageError,
+    })
+}
+
+/// The tag of the files of a stored prover key.
+fn key_tag<F: PrimeField, Comm: FoldingCommitmentConfig<F>>() -> Result<ArtifactTag, SangriaError> {
+    ArtifactTag::new::<F>(Comm::CURVE_ID)
 }
 
-impl<F, Comm> NonInteractiveFoldingScheme for PLONKFoldingScheme<F, Comm, PoseidonSponge<F>>
+impl<F, Comm, RO> NonInteractiveFoldingScheme for PLONKFoldingScheme<F, Comm, RO>
 where
     F: PrimeField + Absorb,
     Comm: FoldingCommitmentConfig<F>,
+    RO: FoldingRandomOracle<F>,
 {
     type SetupInfo = SetupInfo<F>;
     type PublicParameters = PublicParameters<F, Comm>;
@@ -133,7 +775,10 @@ where
     type VerifierKey = VerifierKey<F, Comm>;
     type ProverMessage = <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment;
 
-    fn setup<R: Rng>(info: &SetupInfo<F>, rng: &mut R) -> Self::PublicParameters {
+    fn setup<R: Rng>(
+        info: &SetupInfo<F>,
+        rng: &mut R,
+    ) -> Result<Self::PublicParameters, SangriaError> {
         let commit_key_witness = <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::setup(
             rng,
             info.number_of_gates,
@@ -144,14 +789,23 @@ where
                 info.number_of_gates + info.number_of_public_inputs + 1,
             );
 
-        PublicParameters {
+        let mut pp = PublicParameters {
             number_of_gates: info.number_of_gates,
             number_of_public_inputs: info.number_of_public_inputs,
-            commit_key_witness,
-            commit_key_selectors_and_slack,
+            commit_key_witness: Arc::new(commit_key_witness),
+            commit_key_selectors_and_slack: Arc::new(commit_key_selectors_and_slack),
             domain_separator: info.domain_separator.clone(),
             poseidon_constants: info.poseidon_constants.clone(),
-        }
+            poseidon2_constants: info.poseidon2_constants.clone(),
+            sponge_config: info.sponge_config,
+            slack_randomization: info.slack_randomization,
+            commit_keys_digest: F::zero(),
+            digest: F::zero(),
+        };
+        pp.commit_keys_digest = pp.compute_commit_keys_digest()?;
+        pp.digest = pp.compute_digest();
+
+        Ok(pp)
     }
 
     fn encode<R: Rng>(
@@ -161,43 +815,69 @@ where
     ) -> Result<(Self::ProverKey, Self::VerifierKey), SangriaError> {
         let randomness_c = F::rand(rng);
 
+        // The constant selector and the lookup tables are committed to in one batch, so that the
+        // commitments are computed concurrently under the `parallel` feature. Lookup tables are
+        // public, so they are committed to without hiding.
         let c_selector = circuit.single_selector(CONSTANT_SELECTOR_INDEX)?;
-        let commitment_q_c = <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::commit(
-            &pp.commit_key_selectors_and_slack,
-            &c_selector,
-            randomness_c,
-        )?;
+        let vectors = ark_std::iter::once((c_selector, randomness_c))
+            .chain(
+                circuit
+                    .lookup_tables()
+                    .iter()
+                    .map(|table| (table.as_slice(), F::zero())),
+            )
+            .collect::<Vec<_>>();
+        let mut commitments =
+            <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::batch_commit(
+                &pp.commit_key_selectors_and_slack,
+                &vectors,
+            )?;
+        let lookup_table_commitments = commitments.split_off(1);
+        let commitment_q_c = commitments[0];
 
-        let mut sponge = PoseidonSponge::new(&pp.poseidon_constants);
+        let mut sponge = pp.new_oracle::<RO>()?;
 
         sponge.absorb(circuit);
         sponge.absorb(pp);
         sponge.absorb(&randomness_c);
-        let transcript_seed = sponge.squeeze_native_field_elements(1);
+        sponge.absorb(&lookup_table_commitments);
+        let transcript_seed = sponge.challenge(TRANSCRIPT_SEED);
 
         let vk: VerifierKey<F, Comm> = VerifierKey {
             selector_c_commitment: commitment_q_c,
-            transcript_seed: transcript_seed[0],
+            lookup_table_commitments,
+            transcript_seed,
         };
 
         let pk = ProverKey {
-            circuit: circuit.clone(),
+            circuit: Arc::new(circuit.clone()),
+            preprocessed_circuit: Arc::new(PreprocessedCircuit::new(circuit)?),
             verifier_key: vk.clone(),
             selector_c_commit_randomness: randomness_c,
+            prover_config: ProverConfig::default(),
         };
 
         Ok((pk, vk))
     }
 
     fn prover(
-        _public_parameters: &Self::PublicParameters,
-        _prover_key: &Self::ProverKey,
-        _left_instance: &Self::Instance,
-        _left_witness: &Self::Witness,
-        _right_instance: &Self::Instance,
-        _right_witness: &Self::Witness,
+        public_parameters: &Self::PublicParameters,
+        prover_key: &Self::ProverKey,
+        left_instance: &Self::Instance,
+        left_witness: &Self::Witness,
+        right_instance: &Self::Instance,
+        right_witness: &Self::Witness,
     ) -> Result<(Self::Instance, Self::Witness, Self::ProverMessage), SangriaError> {
-        todo!()
+        Self::prover_with_recorder(
+            public_parameters,
+            prover_key,
+            prover_key.verifier_key.transcript_seed,
+            left_instance,
+            left_witness,
+            right_instance,
+            right_witness,
+            None,
+        )
     }
 
     fn verifier(
@@ -207,19 +887,995 @@ where
         right_instance: &Self::Instance,
         prover_message: &Self::ProverMessage,
     ) -> Result<Self::Instance, SangriaError> {
-        let mut sponge = PoseidonSponge::new(&public_parameters.poseidon_constants);
+        Self::verifier_with_recorder(
+            public_parameters,
+            verifier_key,
+            verifier_key.transcript_seed,
+            left_instance,
+            right_instance,
+            prover_message,
+            None,
+        )
+    }
+}
+
+impl<F, Comm, RO> PLONKFoldingScheme<F, Comm, RO>
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+    RO: FoldingRandomOracle<F>,
+{
+    /// [`NonInteractiveFoldingScheme::prover`] for a fold seeded by `step_seed`, logging its
+    /// transcript to `recorder` if one is given, see [`TranscriptRecorder`]. The trait method
+    /// folds a single step, seeded by the `transcript_seed` of the verifier key; later steps of a
+    /// computation pass the seed chained by [`crate::transcript::next_step_seed`].
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
+    pub fn prover_with_recorder(
+        public_parameters: &PublicParameters<F, Comm>,
+        prover_key: &ProverKey<F, Comm>,
+        step_seed: F,
+        left_instance: &RelaxedPLONKInstance<F, Comm>,
+        left_witness: &RelaxedPLONKWitness<F>,
+        right_instance: &RelaxedPLONKInstance<F, Comm>,
+        right_witness: &RelaxedPLONKWitness<F>,
+        recorder: Option<&mut TranscriptRecorder<F>>,
+    ) -> Result<
+        (
+            RelaxedPLONKInstance<F, Comm>,
+            RelaxedPLONKWitness<F>,
+            <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        ),
+        SangriaError,
+    > {
+        telemetry::timed(telemetry::FOLD_SECONDS, || {
+            left_instance.check_circuit(prover_key.verifier_key.transcript_seed)?;
+            right_instance.check_circuit(prover_key.verifier_key.transcript_seed)?;
+
+            let cross_term = match prover_key.prover_strategy() {
+                ProverStrategy::InMemory => {
+                    <PLONKRelation as FoldableRelation<F, Comm>>::cross_term(
+                        prover_key,
+                        left_instance,
+                        left_witness,
+                        right_instance,
+                        right_witness,
+                    )?
+                }
+                ProverStrategy::Streaming => compute_cross_term_streaming(
+                    |selector_index| prover_key.circuit.single_selector(selector_index),
+                    &plonk_trace(left_instance, left_witness)?,
+                    &plonk_trace(right_instance, right_witness)?,
+                )?,
+            };
+
+            Self::fold_with_cross_term(
+                public_parameters,
+                &prover_key.verifier_key,
+                step_seed,
+                left_instance,
+                left_witness,
+                right_instance,
+                right_witness,
+                &cross_term,
+                recorder,
+            )
+        })
+    }
+
+    /// [`Self::prover_with_recorder`] for a prover key stored on disk. The circuit does not need
+    /// to be loaded. Under [`ProverStrategy::InMemory`] all selectors are read before the cross
+    /// term is computed; under [`ProverStrategy::Streaming`] they are read one at a time through
+    /// [`ProverKeyHandle::read_selector`], see [`compute_cross_term_streaming`].
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
+    pub fn prover_with_handle(
+        public_parameters: &PublicParameters<F, Comm>,
+        handle: &ProverKeyHandle<F, Comm>,
+        step_seed: F,
+        left_instance: &RelaxedPLONKInstance<F, Comm>,
+        left_witness: &RelaxedPLONKWitness<F>,
+        right_instance: &RelaxedPLONKInstance<F, Comm>,
+        right_witness: &RelaxedPLONKWitness<F>,
+        recorder: Option<&mut TranscriptRecorder<F>>,
+    ) -> Result<
+        (
+            RelaxedPLONKInstance<F, Comm>,
+            RelaxedPLONKWitness<F>,
+            <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        ),
+        SangriaError,
+    > {
+        telemetry::timed(telemetry::FOLD_SECONDS, || {
+            left_instance.check_circuit(handle.verifier_key.transcript_seed)?;
+            right_instance.check_circuit(handle.verifier_key.transcript_seed)?;
+
+            let left_trace = plonk_trace(left_instance, left_witness)?;
+            let right_trace = plonk_trace(right_instance, right_witness)?;
+            let cross_term = match handle.prover_strategy() {
+                ProverStrategy::InMemory => compute_cross_term(
+                    &handle.stream_selectors().collect::<Result<Vec<_>, _>>()?,
+                    &left_trace,
+                    &right_trace,
+                    CrossTermConfig::default(),
+                )?,
+                ProverStrategy::Streaming => compute_cross_term_streaming(
+                    |selector_index| handle.read_selector(selector_index),
+                    &left_trace,
+                    &right_trace,
+                )?,
+            };
+
+            Self::fold_with_cross_term(
+                public_parameters,
+                &handle.verifier_key,
+                step_seed,
+                left_instance,
+                left_witness,
+                right_instance,
+                right_witness,
+                &cross_term,
+                recorder,
+            )
+        })
+    }
+
+    /// Commits to `cross_term` and folds both pairs with the challenge of the transcript.
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
+    fn fold_with_cross_term(
+        public_parameters: &PublicParameters<F, Comm>,
+        verifier_key: &VerifierKey<F, Comm>,
+        step_seed: F,
+        left_instance: &RelaxedPLONKInstance<F, Comm>,
+        left_witness: &RelaxedPLONKWitness<F>,
+        right_instance: &RelaxedPLONKInstance<F, Comm>,
+        right_witness: &RelaxedPLONKWitness<F>,
+        cross_term: &[F],
+        recorder: Option<&mut TranscriptRecorder<F>>,
+    ) -> Result<
+        (
+            RelaxedPLONKInstance<F, Comm>,
+            RelaxedPLONKWitness<F>,
+            <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        ),
+        SangriaError,
+    > {
+        let cross_term_hiding =
+            cross_term_hiding(public_parameters, step_seed, left_witness, right_witness);
+        let cross_term_commitment =
+            <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::commit(
+                &public_parameters.commit_key_selectors_and_slack,
+                cross_term,
+                cross_term_hiding,
+            )?;
 
-        sponge.absorb(&verifier_key);
-        sponge.absorb(&left_instance);
-        sponge.absorb(&right_instance);
-        sponge.absorb(&prover_message);
-        let challenge: F = sponge.squeeze_field_elements(1)[0];
+        let challenge = derive_folding_challenge::<F, Comm, RO, _>(
+            public_parameters,
+            verifier_key,
+            step_seed,
+            left_instance,
+            right_instance,
+            &cross_term_commitment,
+            recorder,
+        )?;
+        let folded_instance = Self::fold_instances(
+            verifier_key,
+            left_instance,
+            right_instance,
+            &cross_term_commitment,
+            challenge,
+        )?;
+        let folded_witness = <PLONKRelation as FoldableRelation<F, Comm>>::fold_witnesses(
+            left_witness,
+            right_witness,
+            cross_term,
+            cross_term_hiding,
+            challenge,
+        )?;
+
+        Ok((folded_instance, folded_witness, cross_term_commitment))
+    }
+
+    /// [`NonInteractiveFoldingScheme::verifier`] for a fold seeded by `step_seed`, logging its
+    /// transcript to `recorder` if one is given, see [`Self::prover_with_recorder`].
+    pub fn verifier_with_recorder(
+        public_parameters: &PublicParameters<F, Comm>,
+        verifier_key: &VerifierKey<F, Comm>,
+        step_seed: F,
+        left_instance: &RelaxedPLONKInstance<F, Comm>,
+        right_instance: &RelaxedPLONKInstance<F, Comm>,
+        prover_message: &<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        recorder: Option<&mut TranscriptRecorder<F>>,
+    ) -> Result<RelaxedPLONKInstance<F, Comm>, SangriaError> {
+        let challenge = derive_folding_challenge::<F, Comm, RO, _>(
+            public_parameters,
+            verifier_key,
+            step_seed,
+            left_instance,
+            right_instance,
+            prover_message,
+            recorder,
+        )?;
+
+        Self::fold_instances(
+            verifier_key,
+            left_instance,
+            right_instance,
+            prover_message,
+            challenge,
+        )
+    }
 
-        let folded_instance = right_instance.clone() * challenge + left_instance;
+    /// Folds two instances of the circuit of `verifier_key` with a given `challenge`, see
+    /// [`RelaxedPLONKInstance::fold`]. This is the verifier without its transcript, for drivers
+    /// that derive challenges their own way, e.g. interactive variants or test vectors; provers
+    /// pass the verifier key of their prover key. The witnesses fold with
+    /// [`RelaxedPLONKWitness::fold_with`]. Returns an error if the instances belong to another
+    /// circuit, have different shapes or have non-zero padding rows.
+    pub fn fold_instances(
+        verifier_key: &VerifierKey<F, Comm>,
+        left_instance: &RelaxedPLONKInstance<F, Comm>,
+        right_instance: &RelaxedPLONKInstance<F, Comm>,
+        prover_message: &<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        challenge: F,
+    ) -> Result<RelaxedPLONKInstance<F, Comm>, SangriaError> {
+        left_instance.check_circuit(verifier_key.transcript_seed)?;
+        right_instance.check_circuit(verifier_key.transcript_seed)?;
+        if !left_instance.has_same_shape(right_instance) {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+        left_instance.check_padding()?;
+        right_instance.check_padding()?;
 
-        Ok(folded_instance)
+        left_instance.fold(right_instance, prover_message, challenge)
+    }
+
+    /// Runs the prover and then the verifier on the prover's message, for parties that fold and
+    /// immediately sanity-check the result. The verifier only combines the commitments already
+    /// computed by the prover, so no commitment is recomputed. Returns an error if the verifier's
+    /// folded instance differs from the prover's.
+    #[allow(clippy::type_complexity)]
+    pub fn prove_and_check(
+        public_parameters: &PublicParameters<F, Comm>,
+        prover_key: &ProverKey<F, Comm>,
+        left_instance: &RelaxedPLONKInstance<F, Comm>,
+        left_witness: &RelaxedPLONKWitness<F>,
+        right_instance: &RelaxedPLONKInstance<F, Comm>,
+        right_witness: &RelaxedPLONKWitness<F>,
+    ) -> Result<
+        (
+            RelaxedPLONKInstance<F, Comm>,
+            RelaxedPLONKWitness<F>,
+            <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        ),
+        SangriaError,
+    > {
+        let (folded_instance, folded_witness, prover_message) = Self::prover(
+            public_parameters,
+            prover_key,
+            left_instance,
+            left_witness,
+            right_instance,
+            right_witness,
+        )?;
+
+        let verifier_instance = Self::verifier(
+            public_parameters,
+            &prover_key.verifier_key,
+            left_instance,
+            right_instance,
+            &prover_message,
+        )?;
+
+        if verifier_instance != folded_instance {
+            return Err(VerificationFailure::FoldingEquation
+                .reject(|| String::from("prover and verifier folded instances differ")));
+        }
+
+        #[cfg(feature = "debug_assert_satisfied")]
+        debug_assert!(
+            commitments_open(public_parameters, &folded_instance, &folded_witness)?,
+            "folded witness does not open the folded instance's commitments"
+        );
+
+        Ok((folded_instance, folded_witness, prover_message))
     }
 }
 
+/// Returns the hiding of the commitment to the cross term of a fold seeded by `step_seed`, see
+/// [`SlackRandomization`]. The prover has no source of randomness of its own, so the hiding is
+/// derived with Poseidon from the hidings of both witnesses, which only the prover knows and which
+/// are fresh for every statement.
+fn cross_term_hiding<F, Comm>(
+    public_parameters: &PublicParameters<F, Comm>,
+    step_seed: F,
+    left_witness: &RelaxedPLONKWitness<F>,
+    right_witness: &RelaxedPLONKWitness<F>,
+) -> F
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    match public_parameters.slack_randomization {
+        SlackRandomization::EveryFold => {
+            let mut sponge = Poseidon::new(&public_parameters.poseidon_constants);
+            sponge.absorb_bytes(CROSS_TERM_HIDING_DOMAIN_SEPARATOR);
+            sponge.absorb_field_elements(&[step_seed]);
+            for witness in [left_witness, right_witness] {
+                sponge.absorb_field_elements(&witness.hiding_randomnesses());
+                sponge.absorb_field_elements(&[witness.slack_hiding()]);
+            }
+            sponge.squeeze_field_elements(1)[0]
+        }
+        SlackRandomization::Disabled => F::zero(),
+    }
+}
+
+/// Recommits to every witness column and to the slack vector and compares the results with the
+/// commitments of `instance`. This recomputes one MSM per column and is meant for development.
+pub fn commitments_open<F, Comm>(
+    public_parameters: &PublicParameters<F, Comm>,
+    instance: &RelaxedPLONKInstance<F, Comm>,
+    witness: &RelaxedPLONKWitness<F>,
+) -> Result<bool, SangriaError>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    for (column_index, commitment) in instance.witness_commitments().into_iter().enumerate() {
+        let (column, hiding) = witness.witness_column_with_rand(column_index)?;
+        let recomputed = <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::commit(
+            &public_parameters.commit_key_witness,
+            column,
+            hiding,
+        )?;
+
+        if recomputed != commitment {
+            return Ok(false);
+        }
+    }
+
+    let slack_commitment = <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::commit(
+        &public_parameters.commit_key_selectors_and_slack,
+        &witness.slack_vector(),
+        witness.slack_hiding(),
+    )?;
+
+    Ok(slack_commitment == instance.slack_commitment())
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_builder::PLONKCircuitBuilder,
+        preprocessing::cell_identities,
+        relations::{decide, relax},
+        relaxed_plonk::{PLONKInstance, PLONKWitness},
+        test_utils::seeded_rng,
+        test_vectors::{
+            poseidon2_parameters_for_test, poseidon_parameters_for_test, sponge_config_for_test,
+        },
+        transcript::{next_step_seed, Poseidon2Sponge},
+        vector_commitment::mock::FieldCommitmentConfig,
+    };
+    use ark_pallas::Fr;
+    use ark_std::{rand::seq::SliceRandom, One, UniformRand, Zero};
+
+    type FoldingScheme = PLONKFoldingScheme<Fr, FieldCommitmentConfig<Fr>, PoseidonSponge<Fr>>;
+    type Instance = RelaxedPLONKInstance<Fr, FieldCommitmentConfig<Fr>>;
+
+    const NUMBER_OF_FOLDS: usize = 100;
+
+    fn setup_info() -> SetupInfo<Fr> {
+        SetupInfo {
+            number_of_public_inputs: 2,
+            number_of_gates: 8,
+            domain_separator: b"sangria-test".to_vec(),
+            poseidon_constants: poseidon_parameters_for_test(&mut seeded_rng(
+                "folding_scheme::setup_info",
+            )),
+            poseidon2_constants: None,
+            sponge_config: sponge_config_for_test(),
+            slack_randomization: SlackRandomization::EveryFold,
+        }
+    }
+
+    #[test]
+    fn self_test() {
+        let rng = &mut seeded_rng("folding_scheme::self_test");
+        let mut pp = FoldingScheme::setup(&setup_info(), rng).unwrap();
+
+        assert_eq!(pp.self_test::<PoseidonSponge<Fr>, _>(rng), Ok(()));
+
+        // The fold runs with the oracle of the deployment, which needs its own parameters.
+        assert_eq!(
+            pp.self_test::<Poseidon2Sponge<Fr>, _>(rng),
+            Err(SangriaError::InvalidOracleParameters)
+        );
+
+        pp.number_of_gates = 4;
+        assert_eq!(
+            pp.self_test::<PoseidonSponge<Fr>, _>(rng),
+            Err(SangriaError::SelfTestFailed("parameter digest mismatch"))
+        );
+    }
+
+    #[test]
+    fn trimmed_parameters_share_commit_keys() {
+        let rng = &mut seeded_rng("folding_scheme::trimmed_parameters_share_commit_keys");
+        let pp = FoldingScheme::setup(&setup_info(), rng).unwrap();
+        let shape = CircuitShape {
+            number_of_public_inputs: 1,
+            number_of_gates: 4,
+        };
+
+        let trimmed = pp.trim_for_circuit(shape).unwrap();
+        assert_eq!(trimmed.shape(), shape);
+        assert!(Arc::ptr_eq(
+            &trimmed.commit_key_witness,
+            &pp.commit_key_witness
+        ));
+        assert!(Arc::ptr_eq(
+            &trimmed.commit_key_selectors_and_slack,
+            &pp.commit_key_selectors_and_slack
+        ));
+        assert_eq!(trimmed.commit_keys_digest, pp.commit_keys_digest);
+        assert_ne!(trimmed.digest, pp.digest);
+        assert_eq!(trimmed.digest, trimmed.compute_digest());
+        assert_eq!(trimmed.self_test::<PoseidonSponge<Fr>, _>(rng), Ok(()));
+
+        for oversized in [
+            CircuitShape {
+                number_of_public_inputs: 0,
+                number_of_gates: 9,
+            },
+            CircuitShape {
+                number_of_public_inputs: 3,
+                number_of_gates: 8,
+            },
+        ] {
+            assert_eq!(
+                pp.trim_for_circuit(oversized).err(),
+                Some(SangriaError::CircuitTooLarge)
+            );
+        }
+    }
+
+    #[test]
+    fn verifier_keys_round_trip() {
+        let rng = &mut seeded_rng("folding_scheme::verifier_keys_round_trip");
+        let pp = FoldingScheme::setup(&setup_info(), rng).unwrap();
+        let (_, vk) = FoldingScheme::encode(&pp, &random_circuit(rng), rng).unwrap();
+
+        let mode = SerializationMode::Compressed;
+        let bytes = vk.to_bytes(mode).unwrap();
+        let decoded = VerifierKey::<Fr, FieldCommitmentConfig<Fr>>::from_bytes(&bytes, mode);
+        assert_eq!(decoded.unwrap().digest(), vk.digest());
+        assert_eq!(
+            VerifierKey::<Fr, FieldCommitmentConfig<Fr>>::from_bytes(&bytes[1..], mode).err(),
+            Some(SangriaError::SerializationError)
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn verifier_keys_round_trip_through_json() {
+        let rng = &mut seeded_rng("folding_scheme::verifier_keys_round_trip_through_json");
+        let pp = FoldingScheme::setup(&setup_info(), rng).unwrap();
+        let (_, vk) = FoldingScheme::encode(&pp, &random_circuit(rng), rng).unwrap();
+
+        let json = vk.to_json().unwrap();
+        let decoded = VerifierKey::<Fr, FieldCommitmentConfig<Fr>>::from_json(&json).unwrap();
+        assert_eq!(
+            decoded.to_bytes(SerializationMode::Compressed),
+            vk.to_bytes(SerializationMode::Compressed)
+        );
+
+        let other_curve = json.replace(FieldCommitmentConfig::<Fr>::CURVE_ID, "bn254");
+        assert_eq!(
+            VerifierKey::<Fr, FieldCommitmentConfig<Fr>>::from_json(&other_curve).err(),
+            Some(SangriaError::CurveMismatch)
+        );
+    }
+
+    #[test]
+    fn stored_prover_keys_load_back() {
+        let rng = &mut seeded_rng("folding_scheme::stored_prover_keys_load_back");
+        let pp = FoldingScheme::setup(&setup_info(), rng).unwrap();
+        let (pk, vk) = FoldingScheme::encode(&pp, &random_circuit(rng), rng).unwrap();
+        let circuit = Arc::clone(&pk.circuit);
+        let directory =
+            std::env::temp_dir().join(format!("sangria-prover-key-{}", std::process::id()));
+
+        let mut handle =
+            ProverKeyHandle::store(pk, &directory, SerializationMode::Uncompressed).unwrap();
+        assert!(!handle.is_loaded());
+        handle.load().unwrap();
+        let loaded = handle.circuit().unwrap();
+        assert_eq!(loaded.selectors(), circuit.selectors());
+        assert_eq!(loaded.copy_constraint(), circuit.copy_constraint());
+        assert_eq!(loaded.lookup_tables(), circuit.lookup_tables());
+        assert_eq!(
+            handle.to_prover_key().unwrap().verifier_key.digest(),
+            vk.digest()
+        );
+
+        // A truncated selector file is rejected instead of being read as a shorter column.
+        handle.unload();
+        let selector_path = directory.join(selector_file(0));
+        let bytes = fs::read(&selector_path).unwrap();
+        fs::write(&selector_path, &bytes[..bytes.len() - 1]).unwrap();
+        assert_eq!(
+            handle.read_selector(0).err(),
+            Some(SangriaError::KeyStorageError)
+        );
+        assert_eq!(handle.load().err(), Some(SangriaError::KeyStorageError));
+        assert!(!handle.is_loaded());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    fn random_vector(rng: &mut impl Rng, length: usize) -> Vec<Fr> {
+        (0..length).map(|_| Fr::rand(rng)).collect()
+    }
+
+    fn random_circuit(rng: &mut impl Rng) -> PLONKCircuit<Fr> {
+        let info = setup_info();
+        let selectors = (0..5)
+            .map(|_| random_vector(rng, info.number_of_gates))
+            .collect();
+
+        let mut copy_constraint = cell_identities(info.number_of_gates).unwrap();
+        copy_constraint.shuffle(rng);
+
+        PLONKCircuit::new(selectors, copy_constraint)
+    }
+
+    fn random_instance(rng: &mut impl Rng, circuit_digest: Fr) -> Instance {
+        let public_inputs = (0..3)
+            .map(|_| random_vector(rng, setup_info().number_of_public_inputs + 1))
+            .collect();
+
+        Instance::new(
+            circuit_digest,
+            PLONKInstance::new(public_inputs),
+            Fr::rand(rng),
+            Fr::rand(rng),
+            random_vector(rng, 3),
+        )
+    }
+
+    #[test]
+    fn folding_chain_combines_instances_linearly() {
+        let rng = &mut seeded_rng("folding_scheme::folding_chain_combines_instances_linearly");
+        let (pp, pk, vk) = satisfiable_circuit(rng);
+
+        let (mut running_instance, mut running_witness) = satisfied_pair(&pp, &vk, rng);
+        let mut step_seed = vk.transcript_seed;
+        for step_index in 0..NUMBER_OF_FOLDS {
+            let (fresh_instance, fresh_witness) = satisfied_pair(&pp, &vk, rng);
+            let (mut prover_log, mut verifier_log) =
+                (TranscriptRecorder::new(), TranscriptRecorder::new());
+            let (folded_instance, folded_witness, prover_message) =
+                FoldingScheme::prover_with_recorder(
+                    &pp,
+                    &pk,
+                    step_seed,
+                    &running_instance,
+                    &running_witness,
+                    &fresh_instance,
+                    &fresh_witness,
+                    Some(&mut prover_log),
+                )
+                .unwrap();
+
+            let verifier_instance = FoldingScheme::verifier_with_recorder(
+                &pp,
+                &vk,
+                step_seed,
+                &running_instance,
+                &fresh_instance,
+                &prover_message,
+                Some(&mut verifier_log),
+            )
+            .unwrap();
+            assert_eq!(verifier_log.first_divergence(&prover_log), None);
+            assert_eq!(verifier_log.entries().len(), 6);
+            assert!(verifier_instance == folded_instance);
+
+            let challenge = derive_folding_challenge::<_, _, PoseidonSponge<Fr>, _>(
+                &pp,
+                &vk,
+                step_seed,
+                &running_instance,
+                &fresh_instance,
+                &prover_message,
+                None,
+            )
+            .unwrap();
+            let instance_without_transcript = FoldingScheme::fold_instances(
+                &vk,
+                &running_instance,
+                &fresh_instance,
+                &prover_message,
+                challenge,
+            );
+            assert!(instance_without_transcript.unwrap() == folded_instance);
+            assert_eq!(
+                folded_instance.scaling_factor(),
+                running_instance.scaling_factor() + challenge * fresh_instance.scaling_factor()
+            );
+            let commitments = folded_instance
+                .witness_commitments()
+                .into_iter()
+                .zip(running_instance.witness_commitments())
+                .zip(fresh_instance.witness_commitments());
+            for ((folded, running), fresh) in commitments {
+                assert_eq!(folded, running + challenge * fresh);
+            }
+            assert_eq!(decide(&pp, &pk, &folded_instance, &folded_witness), Ok(()));
+
+            step_seed = next_step_seed::<_, _, PoseidonSponge<Fr>, _>(
+                &pp,
+                step_seed,
+                &folded_instance,
+                step_index + 1,
+            )
+            .unwrap();
+            (running_instance, running_witness) = (folded_instance, folded_witness);
+        }
+    }
+
+    #[test]
+    fn steps_never_reuse_challenges() {
+        let rng = &mut seeded_rng("folding_scheme::steps_never_reuse_challenges");
+        let pp = FoldingScheme::setup(&setup_info(), rng).unwrap();
+        let (_, vk) = FoldingScheme::encode(&pp, &random_circuit(rng), rng).unwrap();
+        let (running_instance, fresh_instance) = (
+            random_instance(rng, vk.digest()),
+            random_instance(rng, vk.digest()),
+        );
+        let prover_message = Fr::rand(rng);
+
+        // Every step folds the same instances, so only the seeds tell the steps apart.
+        let mut step_seed = vk.transcript_seed;
+        let mut challenges = Vec::new();
+        for step_index in 0..NUMBER_OF_FOLDS {
+            challenges.push(
+                derive_folding_challenge::<_, _, PoseidonSponge<Fr>, _>(
+                    &pp,
+                    &vk,
+                    step_seed,
+                    &running_instance,
+                    &fresh_instance,
+                    &prover_message,
+                    None,
+                )
+                .unwrap(),
+            );
+            step_seed = next_step_seed::<_, _, PoseidonSponge<Fr>, _>(
+                &pp,
+                step_seed,
+                &running_instance,
+                step_index + 1,
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            next_step_seed::<_, _, PoseidonSponge<Fr>, _>(&pp, step_seed, &running_instance, 0),
+            Err(SangriaError::IndexOutOfBounds)
+        );
+
+        challenges.sort();
+        challenges.dedup();
+        assert_eq!(challenges.len(), NUMBER_OF_FOLDS);
+    }
+
+    #[test]
+    fn mutated_prover_messages_are_rejected() {
+        let rng = &mut seeded_rng("folding_scheme::mutated_prover_messages_are_rejected");
+        let (pp, pk, vk) = satisfiable_circuit(rng);
+
+        for _ in 0..NUMBER_OF_FOLDS {
+            let (left_instance, left_witness) = satisfied_pair(&pp, &vk, rng);
+            let (right_instance, right_witness) = satisfied_pair(&pp, &vk, rng);
+            let (instance, witness, prover_message) = FoldingScheme::prover(
+                &pp,
+                &pk,
+                &left_instance,
+                &left_witness,
+                &right_instance,
+                &right_witness,
+            )
+            .unwrap();
+
+            let fold = |message| {
+                FoldingScheme::verifier(&pp, &vk, &left_instance, &right_instance, &message)
+                    .unwrap()
+            };
+            assert!(fold(prover_message) == instance);
+            assert_eq!(decide(&pp, &pk, &fold(prover_message), &witness), Ok(()));
+
+            // The prover's folded witness does not open an instance folded with another message.
+            let mutated_message = prover_message + Fr::rand(rng);
+            assert_eq!(
+                decide(&pp, &pk, &fold(mutated_message), &witness),
+                Err(SangriaError::VerificationFailed(
+                    VerificationFailure::CommitmentOpening
+                ))
+            );
+        }
+    }
+
+    // Parameters without public inputs and the keys of a circuit with a - c = 0 at every gate.
+    fn satisfiable_circuit(
+        rng: &mut impl Rng,
+    ) -> (
+        PublicParameters<Fr, FieldCommitmentConfig<Fr>>,
+        ProverKey<Fr, FieldCommitmentConfig<Fr>>,
+        VerifierKey<Fr, FieldCommitmentConfig<Fr>>,
+    ) {
+        let info = SetupInfo {
+            number_of_public_inputs: 0,
+            ..setup_info()
+        };
+        let pp = FoldingScheme::setup(&info, rng).unwrap();
+
+        let mut builder = PLONKCircuitBuilder::new();
+        for _ in 0..info.number_of_gates {
+            builder.add_gate([Fr::one(), Fr::zero(), -Fr::one(), Fr::zero(), Fr::zero()]);
+        }
+        let (pk, vk) = FoldingScheme::encode(&pp, &builder.build().unwrap(), rng).unwrap();
+
+        (pp, pk, vk)
+    }
+
+    // A fresh relaxed pair that satisfies the circuit of `satisfiable_circuit`.
+    fn satisfied_pair(
+        pp: &PublicParameters<Fr, FieldCommitmentConfig<Fr>>,
+        vk: &VerifierKey<Fr, FieldCommitmentConfig<Fr>>,
+        rng: &mut impl Rng,
+    ) -> (Instance, RelaxedPLONKWitness<Fr>) {
+        let a = random_vector(rng, pp.number_of_gates);
+        let b = random_vector(rng, pp.number_of_gates);
+        let witness = PLONKWitness::new(vec![a.clone(), b, a]);
+
+        relax(pp, vk, PLONKInstance::new(vec![]), witness, rng).unwrap()
+    }
+
+    #[test]
+    fn prover_folds_satisfied_pairs() {
+        let rng = &mut seeded_rng("folding_scheme::prover_folds_satisfied_pairs");
+        let (pp, pk, vk) = satisfiable_circuit(rng);
+        let (left_instance, left_witness) = satisfied_pair(&pp, &vk, rng);
+        let (right_instance, right_witness) = satisfied_pair(&pp, &vk, rng);
+
+        let (instance, witness, _) = FoldingScheme::prove_and_check(
+            &pp,
+            &pk,
+            &left_instance,
+            &left_witness,
+            &right_instance,
+            &right_witness,
+        )
+        .unwrap();
+        assert_eq!(decide(&pp, &pk, &instance, &witness), Ok(()));
+        assert_eq!(commitments_open(&pp, &instance, &witness), Ok(true));
+
+        // The folded pair is running, so it can be folded again.
+        let (instance, witness, _) =
+            FoldingScheme::prover(&pp, &pk, &instance, &witness, &left_instance, &left_witness)
+                .unwrap();
+        assert_eq!(decide(&pp, &pk, &instance, &witness), Ok(()));
+
+        // Folding with the witness of another statement does not give a satisfied pair.
+        let (instance, witness, _) = FoldingScheme::prover(
+            &pp,
+            &pk,
+            &left_instance,
+            &right_witness,
+            &right_instance,
+            &right_witness,
+        )
+        .unwrap();
+        assert!(decide(&pp, &pk, &instance, &witness).is_err());
+    }
+
+    #[test]
+    fn unloaded_prover_keys_fold_from_disk() {
+        let rng = &mut seeded_rng("folding_scheme::unloaded_prover_keys_fold_from_disk");
+        let (pp, pk, vk) = satisfiable_circuit(rng);
+        let (left_instance, left_witness) = satisfied_pair(&pp, &vk, rng);
+        let (right_instance, right_witness) = satisfied_pair(&pp, &vk, rng);
+        let directory =
+            std::env::temp_dir().join(format!("sangria-unloaded-key-{}", std::process::id()));
+        let handle =
+            ProverKeyHandle::store(pk.clone(), &directory, SerializationMode::Uncompressed)
+                .unwrap();
+        assert!(!handle.is_loaded());
+
+        let fold = |handle: Option<&ProverKeyHandle<_, _>>| {
+            let mut recorder = TranscriptRecorder::new();
+            let folded = match handle {
+                Some(handle) => FoldingScheme::prover_with_handle(
+                    &pp,
+                    handle,
+                    vk.transcript_seed,
+                    &left_instance,
+                    &left_witness,
+                    &right_instance,
+                    &right_witness,
+                    Some(&mut recorder),
+                ),
+                None => FoldingScheme::prover_with_recorder(
+                    &pp,
+                    &pk,
+                    vk.transcript_seed,
+                    &left_instance,
+                    &left_witness,
+                    &right_instance,
+                    &right_witness,
+                    Some(&mut recorder),
+                ),
+            };
+            (folded.unwrap(), recorder)
+        };
+        let ((instance, witness, message), recorder) = fold(Some(&handle));
+        let ((expected_instance, expected_witness, expected_message), expected_recorder) =
+            fold(None);
+        assert!(instance == expected_instance);
+        assert_eq!(witness.slack_vector(), expected_witness.slack_vector());
+        assert_eq!(message, expected_message);
+        assert_eq!(recorder.first_divergence(&expected_recorder), None);
+        assert!(!handle.is_loaded());
+        assert_eq!(decide(&pp, &pk, &instance, &witness), Ok(()));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn prover_streams_when_the_working_set_exceeds_the_budget() {
+        let rng = &mut seeded_rng(
+            "folding_scheme::prover_streams_when_the_working_set_exceeds_the_budget",
+        );
+        let (pp, mut pk, vk) = satisfiable_circuit(rng);
+        let (left_instance, left_witness) = satisfied_pair(&pp, &vk, rng);
+        let (right_instance, right_witness) = satisfied_pair(&pp, &vk, rng);
+        let (expected_instance, expected_witness, expected_message) = FoldingScheme::prover(
+            &pp,
+            &pk,
+            &left_instance,
+            &left_witness,
+            &right_instance,
+            &right_witness,
+        )
+        .unwrap();
+
+        let working_set_bytes = pk.estimated_working_set_bytes();
+        assert!(working_set_bytes > 0);
+        assert_eq!(pk.prover_strategy(), ProverStrategy::InMemory);
+
+        for (max_memory_bytes, strategy) in [
+            (working_set_bytes, ProverStrategy::InMemory),
+            (working_set_bytes - 1, ProverStrategy::Streaming),
+        ] {
+            pk.prover_config = ProverConfig { max_memory_bytes };
+            assert_eq!(pk.prover_strategy(), strategy);
+        }
+
+        let directory =
+            std::env::temp_dir().join(format!("sangria-streaming-key-{}", std::process::id()));
+        let handle =
+            ProverKeyHandle::store(pk.clone(), &directory, SerializationMode::Uncompressed)
+                .unwrap();
+        assert_eq!(handle.estimated_working_set_bytes(), working_set_bytes);
+        assert_eq!(handle.prover_strategy(), ProverStrategy::Streaming);
+
+        let streamed_folds = [
+            FoldingScheme::prover(
+                &pp,
+                &pk,
+                &left_instance,
+                &left_witness,
+                &right_instance,
+                &right_witness,
+            ),
+            FoldingScheme::prover_with_handle(
+                &pp,
+                &handle,
+                vk.transcript_seed,
+                &left_instance,
+                &left_witness,
+                &right_instance,
+                &right_witness,
+                None,
+            ),
+        ];
+        for folded in streamed_folds {
+            let (instance, witness, message) = folded.unwrap();
+            assert!(instance == expected_instance);
+            assert_eq!(witness.slack_vector(), expected_witness.slack_vector());
+            assert_eq!(message, expected_message);
+            assert_eq!(decide(&pp, &pk, &instance, &witness), Ok(()));
+        }
+        assert!(!handle.is_loaded());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn poseidon2_oracle_requires_its_parameters() {
+        type Poseidon2FoldingScheme =
+            PLONKFoldingScheme<Fr, FieldCommitmentConfig<Fr>, Poseidon2Sponge<Fr>>;
+
+        let rng = &mut seeded_rng("folding_scheme::poseidon2_oracle_requires_its_parameters");
+        let circuit = random_circuit(rng);
+        let mut info = setup_info();
+
+        let pp = Poseidon2FoldingScheme::setup(&info, rng).unwrap();
+        assert_eq!(
+            Poseidon2FoldingScheme::encode(&pp, &circuit, rng).err(),
+            Some(SangriaError::InvalidOracleParameters)
+        );
+
+        info.poseidon2_constants = Some(poseidon2_parameters_for_test(rng));
+        let pp = Poseidon2FoldingScheme::setup(&info, rng).unwrap();
+        assert!(Poseidon2FoldingScheme::encode(&pp, &circuit, rng).is_ok());
+    }
+
+    #[test]
+    fn oracle_parameters_must_match_the_sponge_config() {
+        let rng = &mut seeded_rng("folding_scheme::oracle_parameters_must_match_the_sponge_config");
+        let circuit = random_circuit(rng);
+        let mut info = setup_info();
+        info.sponge_config.rate = 4;
+
+        let pp = FoldingScheme::setup(&info, rng).unwrap();
+        assert_eq!(
+            FoldingScheme::encode(&pp, &circuit, rng).err(),
+            Some(SangriaError::InvalidOracleParameters)
+        );
+
+        let mut info = setup_info();
+        info.poseidon_constants.ark.pop();
+        let pp = FoldingScheme::setup(&info, rng).unwrap();
+        assert_eq!(
+            FoldingScheme::encode(&pp, &circuit, rng).err(),
+            Some(SangriaError::InvalidOracleParameters)
+        );
+    }
+
+    #[test]
+    fn verifier_rejects_malformed_instances() {
+        let rng = &mut seeded_rng("folding_scheme::verifier_rejects_malformed_instances");
+        let pp = FoldingScheme::setup(&setup_info(), rng).unwrap();
+        let (_, vk) = FoldingScheme::encode(&pp, &random_circuit(rng), rng).unwrap();
+        let (_, other_vk) = FoldingScheme::encode(&pp, &random_circuit(rng), rng).unwrap();
+
+        let instance = random_instance(rng, vk.digest());
+        let other_circuit_instance = random_instance(rng, other_vk.digest());
+        let short_instance = Instance::new(
+            vk.digest(),
+            PLONKInstance::new(vec![]),
+            Fr::rand(rng),
+            Fr::rand(rng),
+            vec![],
+        );
+        let prover_message = Fr::rand(rng);
+
+        assert_eq!(
+            FoldingScheme::verifier(
+                &pp,
+                &vk,
+                &instance,
+                &other_circuit_instance,
+                &prover_message
+            )
+            .err(),
+            Some(SangriaError::MismatchedCircuit)
+        );
+        assert_eq!(
+            FoldingScheme::verifier(&pp, &vk, &instance, &short_instance, &prover_message).err(),
+            Some(SangriaError::IndexOutOfBounds)
+        );
+    }
+}
@@ -1,14 +1,31 @@
 use ark_ff::PrimeField;
+use ark_sponge::{
+    poseidon::{PoseidonParameters, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
 use ark_std::{marker::PhantomData, rand::Rng};
+use std::time::Instant;
 
 use crate::{
+    accumulator::{hash_leaf, MerkleMountainRange, MountainRangeProof},
     folding_scheme::{self, FoldingCommitmentConfig},
-    RelaxedPLONKInstance, RelaxedPLONKWitness, StepCircuit, IVC,
+    relations::decide,
+    security::SecurityLevel,
+    serialization::{
+        deserialize_with_mode, serialize_with_mode, serialized_size, SerializationMode,
+    },
+    telemetry,
+    transcript::FoldingRandomOracle,
+    IVCWithDeadline, IVCWithPublicOutputs, IVCWithVerifierKeyDigest, RelaxedPLONKInstance,
+    RelaxedPLONKWitness, SangriaError, StepCircuit, StepProgress, VerificationFailure, IVC,
 };
 
 /// A "pre-sangria" scheme. Implements IVC from a NIFS as described in Construction3 of Nova.
 /// WARNING: this scheme is neither succinct nor zero-knowledge.
 ///
+/// Only the base case is implemented. Setup, encoding, proving and verifying past step 0 need
+/// the augmented circuit and return [`SangriaError::NotImplemented`], see `TODO.md`.
+///
 /// This scheme makes use of a main field and a help field. The trace resulting of running one step of the computation
 /// is no longer in the MainField F_p, we have moved to some helper field F_q. Using cycles of curves, we can define a helper circuit
 /// in F_q that allows us to cycle back to F_p in order to compute our next step in F_p again.
@@ -22,14 +39,99 @@ pub(crate) struct SangriaNoCompression<
 /// A `SangriaIVCConfig` is a trait that allows to bundle types related to an IVC instantiation.
 /// By combining all the types here we avoid passing them as generics in structs such as `VerifierKey`, `ProverKey`, etc
 pub trait SangriaIVCConfig<MainField: PrimeField, HelperField: PrimeField> {
+    /// The commitment schemes used to fold the step circuit in the main field.
     type MainCommitmentSchemes: FoldingCommitmentConfig<MainField>;
 
+    /// The commitment schemes used to fold the helper circuit in the helper field.
     type HelperCommitmentSchemes: FoldingCommitmentConfig<HelperField>;
+
+    /// The random oracle used to fold the step circuit and to bind its instances to the state.
+    /// Proofs are typed by the configuration, so they declare which hash they were made with.
+    type MainRandomOracle: FoldingRandomOracle<MainField>;
+
+    /// The random oracle used to fold the helper circuit.
+    type HelperRandomOracle: FoldingRandomOracle<HelperField>;
+
+    /// The largest number of steps a computation may have. Soundness degrades with the number of
+    /// folds, so this bounds the field size required by [`Self::SECURITY_LEVEL`]. The step
+    /// counter is hashed into the instances as a main field element, so this must also be smaller
+    /// than the modulus, see [`check_max_steps`].
+    const MAX_NUMBER_OF_STEPS: usize = 1 << 32;
+
+    /// The security the fields and the random oracles must provide.
+    const SECURITY_LEVEL: SecurityLevel = SecurityLevel::Bits128;
+
+    /// How the augmented circuit folds the commitments of the instances.
+    const VERIFIER_ARITHMETIC: VerifierArithmetic = VerifierArithmetic::Cycle;
+}
+
+/// How the folding verifier in the augmented circuit performs the group operations on the
+/// commitments of the folded instances.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VerifierArithmetic {
+    /// The group operations are native to the helper circuit, over a 2-cycle of curves whose
+    /// scalar fields are the main and helper fields.
+    #[default]
+    Cycle,
+    /// The group operations are emulated with non-native arithmetic in the main field, so a
+    /// single curve suffices, e.g. BN254 alone. There is no helper circuit, and the augmented
+    /// circuit spends most of its gates on the emulation.
+    NonNative,
+}
+
+/// The fewest bits of soundness the folding challenges may provide over a whole computation, at
+/// any [`SecurityLevel`].
+pub const MIN_SOUNDNESS_BITS: u32 = SecurityLevel::Fast.soundness_bits();
+
+/// The degree of the folded relation in the folding challenge. Relaxed PLONK gates are quadratic.
+pub const FOLDING_DEGREE: usize = 2;
+
+/// Returns the bits of soundness of `number_of_steps` folds of a relation of degree
+/// `folding_degree` with challenges from a field of `field_bits` bits. By the Schwartz-Zippel
+/// lemma a single fold is unsound with probability at most `folding_degree / |F|`, and the union
+/// bound over the steps multiplies this by `number_of_steps`.
+pub fn soundness_bits(field_bits: u32, folding_degree: usize, number_of_steps: usize) -> u32 {
+    // The modulus is at least 2^(field_bits - 1).
+    field_bits
+        .saturating_sub(1)
+        .saturating_sub(ark_std::log2(folding_degree))
+        .saturating_sub(ark_std::log2(number_of_steps))
+}
+
+/// Checks that challenges from `F` provide the soundness of `security_level` over
+/// `number_of_steps` folds.
+pub fn check_challenge_soundness<F: PrimeField>(
+    security_level: SecurityLevel,
+    number_of_steps: usize,
+) -> Result<(), SangriaError> {
+    let bits = soundness_bits(F::size_in_bits() as u32, FOLDING_DEGREE, number_of_steps);
+    if bits < security_level.soundness_bits() {
+        return Err(SangriaError::InsufficientSoundness {
+            bits,
+            required: security_level.soundness_bits(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that a step counter up to `max_steps` is represented by a single element of `F`, so
+/// that it cannot wrap around the modulus and collide with a smaller counter.
+pub fn check_max_steps<F: PrimeField>(max_steps: usize) -> Result<(), SangriaError> {
+    if usize::BITS - max_steps.leading_zeros() >= F::size_in_bits() as u32 {
+        return Err(SangriaError::TooManySteps);
+    }
+
+    Ok(())
 }
 
+/// The number of public inputs of the augmented circuits: the hash of the verifier key digest,
+/// the step counter, the origin and current states and the running instance.
+pub const AUGMENTED_CIRCUIT_PUBLIC_INPUTS: usize = 1;
+
 /// Public parameters for the SangriaIVC scheme (no compression) contains commit parameters for the step circuit
 /// in the main field, and commit parameters for the helper circuit in the helper field.
-pub(crate) struct PublicParameters<
+pub struct PublicParameters<
     MainField: PrimeField,
     HelperField: PrimeField,
     Config: SangriaIVCConfig<MainField, HelperField>,
@@ -40,8 +142,9 @@ pub(crate) struct PublicParameters<
 }
 
 /// The SangriaIVC VerifierKey contains verifier keys for the foldings of the main and helper
-/// circuits. It also contains a description of the step circuit.
-pub(crate) struct VerifierKey<
+/// circuits. It also contains a description of the step circuit, the hash parameters of the
+/// step outputs and step instance accumulators and the maximum number of steps.
+pub struct VerifierKey<
     MainField: PrimeField,
     HelperField: PrimeField,
     Config: SangriaIVCConfig<MainField, HelperField>,
@@ -50,11 +153,106 @@ pub(crate) struct VerifierKey<
     pub _main_nifs_vk: folding_scheme::VerifierKey<MainField, Config::MainCommitmentSchemes>,
     pub _helper_nifs_vk: folding_scheme::VerifierKey<HelperField, Config::HelperCommitmentSchemes>,
     pub _step_circuit: SC,
+    pub _accumulator_parameters: PoseidonParameters<MainField>,
+    pub _max_steps: usize,
+}
+
+/// The digest of a SangriaIVC verifier key: the digests of the verifier keys of both foldings.
+/// The instance hashes and folding challenges only depend on these, not on the rest of the key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VerifierKeyDigest<MainField: PrimeField, HelperField: PrimeField> {
+    /// The digest of the verifier key for the folding of the main circuit.
+    pub main: MainField,
+    /// The digest of the verifier key for the folding of the helper circuit.
+    pub helper: HelperField,
+}
+
+impl<MainField, HelperField, Config, SC> VerifierKey<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    /// Returns the digest of the verifier key.
+    pub fn digest(&self) -> VerifierKeyDigest<MainField, HelperField> {
+        VerifierKeyDigest {
+            main: self._main_nifs_vk.digest(),
+            helper: self._helper_nifs_vk.digest(),
+        }
+    }
+}
+
+impl<MainField, HelperField, Config, SC> VerifierKey<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField + Absorb,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    /// Checks that `step_instance` is the instance of the `step_index`-th step of the computation
+    /// attested by `current_proof`, without re-running the chain. The proof itself must have been
+    /// accepted by [`IVC::verify`].
+    pub fn verify_step_inclusion(
+        &self,
+        current_proof: &IVCProof<MainField, HelperField, Config>,
+        step_index: usize,
+        step_instance: &RelaxedPLONKInstance<MainField, Config::MainCommitmentSchemes>,
+        inclusion_proof: &MountainRangeProof<MainField>,
+    ) -> Result<(), SangriaError> {
+        let parameters = &self._accumulator_parameters;
+
+        inclusion_proof.verify(
+            parameters,
+            current_proof._step_accumulator.root(parameters),
+            current_proof._number_of_steps,
+            step_index,
+            step_leaf(parameters, step_instance),
+        )
+    }
+}
+
+/// Hashes the instance of a step into a leaf of the step accumulator.
+fn step_leaf<F: PrimeField + Absorb, Comm: FoldingCommitmentConfig<F>>(
+    parameters: &PoseidonParameters<F>,
+    step_instance: &RelaxedPLONKInstance<F, Comm>,
+) -> F {
+    let mut sponge = PoseidonSponge::new(parameters);
+    sponge.absorb(step_instance);
+    sponge.squeeze_field_elements(1)[0]
+}
+
+/// The constraint breakdown of the augmented circuit, i.e. the step circuit extended with the
+/// verifier of the folding scheme. Everything but `step_circuit_gates` is recursion overhead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AugmentedCircuitCost {
+    /// Gates spent hashing the running instance and squeezing the folding challenge.
+    pub hash_gates: usize,
+    /// Gates spent on arithmetic over the helper field, e.g. folding the public inputs.
+    pub non_native_arithmetic_gates: usize,
+    /// Gates spent folding the commitments of the instances.
+    pub scalar_multiplication_gates: usize,
+    /// Gates of the user's step circuit.
+    pub step_circuit_gates: usize,
+}
+
+impl AugmentedCircuitCost {
+    /// Returns the total number of gates of the augmented circuit.
+    pub fn total(&self) -> usize {
+        self.recursion_overhead() + self.step_circuit_gates
+    }
+
+    /// Returns the number of gates added to the step circuit to make it recursive.
+    pub fn recursion_overhead(&self) -> usize {
+        self.hash_gates + self.non_native_arithmetic_gates + self.scalar_multiplication_gates
+    }
 }
 
 /// The SangriaIVC ProverKey contains prover keys for the foldings of the main and helper
-/// circuits. It also contains a description of the step circuit.
-pub(crate) struct ProverKey<
+/// circuits. It also contains a description of the step circuit, the cost of the augmented
+/// circuit it was compiled to, the hash parameters of the step outputs and step instance
+/// accumulators and the maximum number of steps.
+pub struct ProverKey<
     MainField: PrimeField,
     HelperField: PrimeField,
     Config: SangriaIVCConfig<MainField, HelperField>,
@@ -63,27 +261,511 @@ pub(crate) struct ProverKey<
     pub _main_nifs_pk: folding_scheme::ProverKey<MainField, Config::MainCommitmentSchemes>,
     pub _helper_nifs_pk: folding_scheme::ProverKey<HelperField, Config::HelperCommitmentSchemes>,
     pub _step_circuit: SC,
+    pub _augmented_circuit_cost: AugmentedCircuitCost,
+    pub _accumulator_parameters: PoseidonParameters<MainField>,
+    pub _max_steps: usize,
+}
+
+/// A running instance-witness pair, which accumulates the steps folded so far. Folding relaxes
+/// it, so unlike [`FreshInstance`] it may have any scaling factor and slack vector.
+pub struct RunningAccumulator<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    instance: RelaxedPLONKInstance<F, Comm>,
+    witness: RelaxedPLONKWitness<F>,
+}
+
+impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> RunningAccumulator<F, Comm> {
+    /// Creates a running pair from a relaxed instance and its witness.
+    pub fn new(instance: RelaxedPLONKInstance<F, Comm>, witness: RelaxedPLONKWitness<F>) -> Self {
+        Self { instance, witness }
+    }
+
+    /// Returns the running pair of the base case: the trivial instance-witness pair (see
+    /// [`RelaxedPLONKInstance::trivial`]) of the circuit of `prover_key`.
+    pub fn trivial(prover_key: &folding_scheme::ProverKey<F, Comm>) -> Self {
+        Self::new(
+            RelaxedPLONKInstance::trivial(
+                prover_key.verifier_key.digest(),
+                AUGMENTED_CIRCUIT_PUBLIC_INPUTS,
+            ),
+            RelaxedPLONKWitness::trivial(prover_key.preprocessed_circuit.number_of_gates()),
+        )
+    }
+
+    /// Returns true if this is the running pair of the base case, see [`Self::trivial`].
+    pub fn is_trivial(&self) -> bool {
+        self.instance.is_trivial()
+    }
+
+    /// Returns the running instance.
+    pub fn instance(&self) -> &RelaxedPLONKInstance<F, Comm> {
+        &self.instance
+    }
+
+    /// Returns the running witness.
+    pub fn witness(&self) -> &RelaxedPLONKWitness<F> {
+        &self.witness
+    }
+
+    /// Returns the running instance and witness.
+    pub fn into_parts(self) -> (RelaxedPLONKInstance<F, Comm>, RelaxedPLONKWitness<F>) {
+        (self.instance, self.witness)
+    }
+
+    /// Serializes the running pair.
+    pub fn to_bytes(&self, mode: SerializationMode) -> Result<Vec<u8>, SangriaError> {
+        let mut bytes = Vec::new();
+        self.instance.write(&mut bytes, mode)?;
+        self.witness.write(&mut bytes, mode)?;
+
+        Ok(bytes)
+    }
+
+    /// Re-blinds the commitments of the running pair, including the slack commitment, so that it
+    /// cannot be linked to the pair it was before. It satisfies the relation if and only if it
+    /// did before.
+    pub fn rerandomize<R: Rng>(
+        &mut self,
+        public_parameters: &folding_scheme::PublicParameters<F, Comm>,
+        rng: &mut R,
+    ) -> Result<(), SangriaError> {
+        self.instance.rerandomize(
+            &mut self.witness,
+            &public_parameters.commit_key_witness,
+            &public_parameters.commit_key_selectors_and_slack,
+            true,
+            rng,
+        )
+    }
+
+    /// Returns the length of [`Self::to_bytes`] without serializing the pair. It only depends on
+    /// the circuit, see [`HalfCycleProof::max_serialized_size`].
+    pub fn serialized_size(&self, mode: SerializationMode) -> usize {
+        self.instance.serialized_size(mode) + self.witness.serialized_size(mode)
+    }
+
+    /// Deserializes a running pair serialized with the same `mode`.
+    pub fn from_bytes(bytes: &[u8], mode: SerializationMode) -> Result<Self, SangriaError> {
+        let mut reader = bytes;
+        let instance = RelaxedPLONKInstance::read(&mut reader, mode)?;
+        let witness = RelaxedPLONKWitness::read(&mut reader, mode)?;
+        if !reader.is_empty() {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(Self::new(instance, witness))
+    }
 }
 
-/// A half cycle proof is composed of two instance-witness pairs: one running instance-witness
-/// that captures steps 0 to i-1 (via folding) and one instance-witness for the i-th step (the latest).
-pub(crate) struct HalfCycleProof<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
-    pub _latest_step_instance: RelaxedPLONKInstance<F, Comm>,
-    pub _latest_step_witness: RelaxedPLONKWitness<F>,
-    pub _running_instance: RelaxedPLONKInstance<F, Comm>,
-    pub _running_witness: RelaxedPLONKWitness<F>,
+/// A fresh instance-witness pair: the relaxation of the latest step, before it is folded into
+/// the running pair. It is strict (see [`RelaxedPLONKInstance::is_strict`]) by construction, so
+/// the prover cannot fold a relaxed pair in its place.
+pub struct FreshInstance<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    instance: RelaxedPLONKInstance<F, Comm>,
+    witness: RelaxedPLONKWitness<F>,
+}
+
+impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> FreshInstance<F, Comm> {
+    /// Creates a fresh pair, or returns [`SangriaError::NonStrictInstance`] if the instance or the
+    /// witness is not strict.
+    pub fn new(
+        instance: RelaxedPLONKInstance<F, Comm>,
+        witness: RelaxedPLONKWitness<F>,
+    ) -> Result<Self, SangriaError> {
+        if !instance.is_strict() || !witness.is_strict() {
+            return Err(SangriaError::NonStrictInstance);
+        }
+
+        Ok(Self { instance, witness })
+    }
+
+    /// Returns the fresh instance.
+    pub fn instance(&self) -> &RelaxedPLONKInstance<F, Comm> {
+        &self.instance
+    }
+
+    /// Returns the fresh witness.
+    pub fn witness(&self) -> &RelaxedPLONKWitness<F> {
+        &self.witness
+    }
+
+    /// Returns the fresh instance and witness.
+    pub fn into_parts(self) -> (RelaxedPLONKInstance<F, Comm>, RelaxedPLONKWitness<F>) {
+        (self.instance, self.witness)
+    }
+
+    /// Serializes the fresh pair.
+    pub fn to_bytes(&self, mode: SerializationMode) -> Result<Vec<u8>, SangriaError> {
+        let mut bytes = Vec::new();
+        self.instance.write(&mut bytes, mode)?;
+        self.witness.write(&mut bytes, mode)?;
+
+        Ok(bytes)
+    }
+
+    /// Re-blinds the witness commitments of the fresh pair, see [`RunningAccumulator::rerandomize`].
+    /// The slack commitment is left alone, so the pair stays strict.
+    pub fn rerandomize<R: Rng>(
+        &mut self,
+        public_parameters: &folding_scheme::PublicParameters<F, Comm>,
+        rng: &mut R,
+    ) -> Result<(), SangriaError> {
+        self.instance.rerandomize(
+            &mut self.witness,
+            &public_parameters.commit_key_witness,
+            &public_parameters.commit_key_selectors_and_slack,
+            false,
+            rng,
+        )
+    }
+
+    /// Returns the length of [`Self::to_bytes`] without serializing the pair.
+    pub fn serialized_size(&self, mode: SerializationMode) -> usize {
+        self.instance.serialized_size(mode) + self.witness.serialized_size(mode)
+    }
+
+    /// Deserializes a fresh pair serialized with the same `mode`, and checks that it is strict.
+    pub fn from_bytes(bytes: &[u8], mode: SerializationMode) -> Result<Self, SangriaError> {
+        let (instance, witness) = RunningAccumulator::from_bytes(bytes, mode)?.into_parts();
+        Self::new(instance, witness)
+    }
+}
+
+/// A fresh pair is a valid running pair, e.g. the running pair after the first step.
+impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> From<FreshInstance<F, Comm>>
+    for RunningAccumulator<F, Comm>
+{
+    fn from(fresh: FreshInstance<F, Comm>) -> Self {
+        Self::new(fresh.instance, fresh.witness)
+    }
+}
+
+/// A half cycle proof is composed of a running instance-witness pair that captures steps 0 to
+/// i-1 (via folding) and a fresh instance-witness pair for the i-th step (the latest).
+pub struct HalfCycleProof<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    /// The latest step, or `None` in the base case, which has no step.
+    pub _latest_step: Option<FreshInstance<F, Comm>>,
+    /// The running pair, which folds every step before the latest.
+    pub _running: RunningAccumulator<F, Comm>,
+}
+
+impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> HalfCycleProof<F, Comm> {
+    /// Returns the half cycle proof of the base case, without a latest step and with the trivial
+    /// running pair of the circuit of `prover_key`, see [`RunningAccumulator::trivial`].
+    pub fn base(prover_key: &folding_scheme::ProverKey<F, Comm>) -> Self {
+        Self {
+            _latest_step: None,
+            _running: RunningAccumulator::trivial(prover_key),
+        }
+    }
+
+    /// Returns true if this is the half cycle proof of the base case, see [`Self::base`].
+    pub fn is_base(&self) -> bool {
+        self._latest_step.is_none() && self._running.is_trivial()
+    }
+
+    /// Re-blinds the commitments of both pairs, see [`RunningAccumulator::rerandomize`] and
+    /// [`FreshInstance::rerandomize`].
+    ///
+    /// This does not make IVC proofs unlinkable on its own: the latest step hashes the running
+    /// instance into its public inputs, so the running instance must stay what was hashed.
+    pub fn rerandomize<R: Rng>(
+        &mut self,
+        public_parameters: &folding_scheme::PublicParameters<F, Comm>,
+        rng: &mut R,
+    ) -> Result<(), SangriaError> {
+        if let Some(latest_step) = &mut self._latest_step {
+            latest_step.rerandomize(public_parameters, rng)?;
+        }
+        self._running.rerandomize(public_parameters, rng)
+    }
+
+    /// Decides both pairs of the proof, see [`decide`]. This is linear in the size of the
+    /// circuit and reveals nothing a verifier could check, it is meant for tests and for provers
+    /// auditing their own state.
+    pub fn audit(
+        &self,
+        public_parameters: &folding_scheme::PublicParameters<F, Comm>,
+        prover_key: &folding_scheme::ProverKey<F, Comm>,
+    ) -> Result<(), SangriaError> {
+        if let Some(latest_step) = &self._latest_step {
+            decide(
+                public_parameters,
+                prover_key,
+                latest_step.instance(),
+                latest_step.witness(),
+            )?;
+        }
+        decide(
+            public_parameters,
+            prover_key,
+            self._running.instance(),
+            self._running.witness(),
+        )
+    }
+
+    /// Serializes the proof: whether it has a latest step, the latest step if any and the running
+    /// pair.
+    pub fn to_bytes(&self, mode: SerializationMode) -> Result<Vec<u8>, SangriaError> {
+        let mut bytes = Vec::with_capacity(self.serialized_size(mode));
+        serialize_with_mode(&self._latest_step.is_some(), &mut bytes, mode)?;
+        if let Some(latest_step) = &self._latest_step {
+            latest_step.instance.write(&mut bytes, mode)?;
+            latest_step.witness.write(&mut bytes, mode)?;
+        }
+        self._running.instance.write(&mut bytes, mode)?;
+        self._running.witness.write(&mut bytes, mode)?;
+
+        Ok(bytes)
+    }
+
+    /// Deserializes a proof serialized with the same `mode`, and checks that its latest step is
+    /// strict.
+    pub fn from_bytes(bytes: &[u8], mode: SerializationMode) -> Result<Self, SangriaError> {
+        let mut reader = bytes;
+        let has_latest_step: bool = deserialize_with_mode(&mut reader, mode)?;
+        let latest_step = if has_latest_step {
+            let instance = RelaxedPLONKInstance::read(&mut reader, mode)?;
+            let witness = RelaxedPLONKWitness::read(&mut reader, mode)?;
+            Some(FreshInstance::new(instance, witness)?)
+        } else {
+            None
+        };
+        let instance = RelaxedPLONKInstance::read(&mut reader, mode)?;
+        let witness = RelaxedPLONKWitness::read(&mut reader, mode)?;
+        if !reader.is_empty() {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(Self {
+            _latest_step: latest_step,
+            _running: RunningAccumulator::new(instance, witness),
+        })
+    }
+
+    /// Returns the length of [`Self::to_bytes`] without serializing the proof.
+    pub fn serialized_size(&self, mode: SerializationMode) -> usize {
+        serialized_size(&true, mode)
+            + self
+                ._latest_step
+                .as_ref()
+                .map_or(0, |latest_step| latest_step.serialized_size(mode))
+            + self._running.serialized_size(mode)
+    }
+
+    /// Returns the length of the encoding of any half cycle proof of the circuit of
+    /// `prover_key`, e.g. to check it against the message size limit of a blockchain. Every pair
+    /// of a circuit has the same dimensions, so this is exact for proofs with a latest step; the
+    /// base case is shorter.
+    pub fn max_serialized_size(
+        prover_key: &folding_scheme::ProverKey<F, Comm>,
+        mode: SerializationMode,
+    ) -> usize {
+        let pair_size = RunningAccumulator::trivial(prover_key).serialized_size(mode);
+        serialized_size(&true, mode) + 2 * pair_size
+    }
 }
 
 /// An IVC proof is composed of two half-cycle proofs. Each half cycle proof is composed
 /// of two instance-witness pairs: one running instance-witness that captures steps 0 to i-1 (via folding)
-/// and one instance-witness for the i-th step (the latest).
-pub(crate) struct IVCProof<
+/// and one instance-witness for the i-th step (the latest). It also records the number of steps `i`
+/// so that the next step can be proven without the state history, and accumulates the public
+/// outputs and the instance of every step. The roots of the accumulators are part of the state of
+/// the augmented circuit, so they are bound to the running instance.
+pub struct IVCProof<
     MainField: PrimeField,
     HelperField: PrimeField,
     Config: SangriaIVCConfig<MainField, HelperField>,
 > {
+    /// The half cycle proof of the step circuit, folded in the main field.
     pub _main_half_proof: HalfCycleProof<MainField, Config::MainCommitmentSchemes>,
+    /// The half cycle proof of the helper circuit, folded in the helper field.
     pub _helper_half_proof: HalfCycleProof<HelperField, Config::HelperCommitmentSchemes>,
+    /// The number of steps proven.
+    pub _number_of_steps: usize,
+    /// The accumulator of the public outputs of every step.
+    pub _outputs_accumulator: MerkleMountainRange<MainField>,
+    /// The accumulator of the instance of every step.
+    pub _step_accumulator: MerkleMountainRange<MainField>,
+}
+
+impl<MainField, HelperField, Config> IVCProof<MainField, HelperField, Config>
+where
+    MainField: PrimeField + Absorb,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+{
+    /// Returns the proof of the base case, i.e. of zero steps from any origin state: the half
+    /// cycle proofs are trivial (see [`HalfCycleProof::base`]) and the accumulators are empty.
+    /// [`IVC::verify`] accepts it at step 0 like a missing proof.
+    pub fn base<SC: StepCircuit<MainField>>(
+        prover_key: &ProverKey<MainField, HelperField, Config, SC>,
+    ) -> Self {
+        Self {
+            _main_half_proof: HalfCycleProof::base(&prover_key._main_nifs_pk),
+            _helper_half_proof: HalfCycleProof::base(&prover_key._helper_nifs_pk),
+            _number_of_steps: 0,
+            _outputs_accumulator: MerkleMountainRange::new(),
+            _step_accumulator: MerkleMountainRange::new(),
+        }
+    }
+
+    /// Returns true if this is the proof of the base case, see [`Self::base`].
+    pub fn is_base(&self) -> bool {
+        self._number_of_steps == 0
+            && self._main_half_proof.is_base()
+            && self._helper_half_proof.is_base()
+            && self._outputs_accumulator.is_empty()
+            && self._step_accumulator.is_empty()
+    }
+
+    /// Checks natively that every pair retained by the proof satisfies its instance, see
+    /// [`HalfCycleProof::audit`], e.g. before continuing a long computation from a stored proof.
+    pub fn audit<SC: StepCircuit<MainField>>(
+        &self,
+        public_parameters: &PublicParameters<MainField, HelperField, Config>,
+        prover_key: &ProverKey<MainField, HelperField, Config, SC>,
+    ) -> Result<(), SangriaError> {
+        self._main_half_proof
+            .audit(&public_parameters._main_nifs_pp, &prover_key._main_nifs_pk)?;
+        self._helper_half_proof.audit(
+            &public_parameters._helper_nifs_pp,
+            &prover_key._helper_nifs_pk,
+        )
+    }
+
+    /// Re-blinds every pair retained by the proof, see [`HalfCycleProof::rerandomize`], so that
+    /// the same computation can be presented twice with unrelated commitments. The step count
+    /// and the accumulators are left as they are, and the audit of the proof, see
+    /// [`Self::audit`], is unchanged. The latest step of each half still hashes the running
+    /// instance it was proven against, so [`IVC::verify`] would link or reject a re-blinded proof
+    /// once it is implemented, see `TODO.md`.
+    pub fn rerandomize<R: Rng>(
+        &mut self,
+        public_parameters: &PublicParameters<MainField, HelperField, Config>,
+        rng: &mut R,
+    ) -> Result<(), SangriaError> {
+        self._main_half_proof
+            .rerandomize(&public_parameters._main_nifs_pp, rng)?;
+        self._helper_half_proof
+            .rerandomize(&public_parameters._helper_nifs_pp, rng)
+    }
+
+    /// Returns the running instances of the main and helper half cycles, for deciders built
+    /// outside of this crate. The latest step of each half is a fresh instance that is only
+    /// folded into the running instance by the next step, so a decider must check it too, see
+    /// [`HalfCycleProof`].
+    #[allow(clippy::type_complexity)]
+    pub fn final_instances(
+        &self,
+    ) -> (
+        &RelaxedPLONKInstance<MainField, Config::MainCommitmentSchemes>,
+        &RelaxedPLONKInstance<HelperField, Config::HelperCommitmentSchemes>,
+    ) {
+        (
+            self._main_half_proof._running.instance(),
+            self._helper_half_proof._running.instance(),
+        )
+    }
+
+    /// Absorbs the final instances of the proof, see [`Self::final_instances`], so that a proof
+    /// about the final state of the computation, e.g. a state equivalence proof (see
+    /// [`crate::equivalence`]), cannot be replayed for another computation.
+    pub fn absorb_final_instances<S: CryptographicSponge>(&self, sponge: &mut S) {
+        let (main, helper) = self.final_instances();
+        sponge.absorb(main);
+        sponge.absorb(helper);
+    }
+
+    /// Returns the running pairs of the main and helper half cycles, witnesses included, see
+    /// [`Self::final_instances`]. Only the prover of the computation may read the witnesses, so
+    /// this takes its prover key and returns an error if the running instances belong to other
+    /// circuits.
+    #[allow(clippy::type_complexity)]
+    pub fn final_pairs<SC: StepCircuit<MainField>>(
+        &self,
+        prover_key: &ProverKey<MainField, HelperField, Config, SC>,
+    ) -> Result<
+        (
+            &RunningAccumulator<MainField, Config::MainCommitmentSchemes>,
+            &RunningAccumulator<HelperField, Config::HelperCommitmentSchemes>,
+        ),
+        SangriaError,
+    > {
+        self.check_circuits(&VerifierKeyDigest {
+            main: prover_key._main_nifs_pk.verifier_key.digest(),
+            helper: prover_key._helper_nifs_pk.verifier_key.digest(),
+        })?;
+
+        Ok((
+            &self._main_half_proof._running,
+            &self._helper_half_proof._running,
+        ))
+    }
+
+    /// Returns [`SangriaError::MismatchedCircuit`] if the final instances of the proof (see
+    /// [`Self::final_instances`]) do not belong to the circuits of the verifier key with
+    /// `digest`.
+    pub fn check_circuits(
+        &self,
+        digest: &VerifierKeyDigest<MainField, HelperField>,
+    ) -> Result<(), SangriaError> {
+        let (main, helper) = self.final_instances();
+        main.check_circuit(digest.main)?;
+        helper.check_circuit(digest.helper)
+    }
+
+    /// Proves that the instance of the `step_index`-th step is part of the computation attested
+    /// by this proof, see [`VerifierKey::verify_step_inclusion`].
+    pub fn prove_step_inclusion(
+        &self,
+        step_index: usize,
+    ) -> Result<MountainRangeProof<MainField>, SangriaError> {
+        self._step_accumulator.prove(step_index)
+    }
+}
+
+/// The phases of proving a step, in order. A time-boxed prover checks its deadline between them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProvingPhase {
+    /// Checking the step witness against the step circuit.
+    CheckWitness,
+    /// Folding the latest step into the running instance of the main circuit.
+    FoldMain,
+    /// Folding the latest step into the running instance of the helper circuit.
+    FoldHelper,
+    /// Appending the outputs and the instance of the step to the accumulators.
+    Accumulate,
+}
+
+/// A step whose proving was interrupted by its deadline, see [`IVCWithDeadline`]. It owns the
+/// inputs of the step and records the next phase to run.
+pub struct PartialFold<
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+> {
+    next_phase: ProvingPhase,
+    current_state: SC::State,
+    current_proof: Option<IVCProof<MainField, HelperField, Config>>,
+}
+
+impl<MainField, HelperField, Config, SC> PartialFold<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    /// Returns the phase proving resumes from.
+    pub fn next_phase(&self) -> ProvingPhase {
+        self.next_phase
+    }
+
+    /// Abandons the step and returns its state and proof, e.g. to prove it again from scratch.
+    pub fn into_inputs(self) -> (SC::State, Option<IVCProof<MainField, HelperField, Config>>) {
+        (self.current_state, self.current_proof)
+    }
 }
 
 impl<MainField, HelperField, Config, SC> IVC<MainField, SC>
@@ -99,8 +781,24 @@ where
     type VerifierKey = VerifierKey<MainField, HelperField, Config, SC>;
     type Proof = IVCProof<MainField, HelperField, Config>;
 
-    fn setup<R: Rng>(_rng: &mut R) -> Self::PublicParameters {
-        todo!()
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::PublicParameters, SangriaError> {
+        check_challenge_soundness::<MainField>(
+            Config::SECURITY_LEVEL,
+            Config::MAX_NUMBER_OF_STEPS,
+        )?;
+        check_max_steps::<MainField>(Config::MAX_NUMBER_OF_STEPS)?;
+        // A single curve has no helper field to check, and no non-native gadget to fold with.
+        match Config::VERIFIER_ARITHMETIC {
+            VerifierArithmetic::Cycle => check_challenge_soundness::<HelperField>(
+                Config::SECURITY_LEVEL,
+                Config::MAX_NUMBER_OF_STEPS,
+            )?,
+            VerifierArithmetic::NonNative => {
+                return Err(SangriaError::NotImplemented("non-native folding verifier"))
+            }
+        }
+
+        Err(SangriaError::NotImplemented("IVC setup"))
     }
 
     fn encode<R: Rng>(
@@ -108,25 +806,894 @@ where
         _step_circuit: &SC,
         _rng: &mut R,
     ) -> Result<(Self::ProverKey, Self::VerifierKey), crate::SangriaError> {
-        todo!()
+        Err(SangriaError::NotImplemented("IVC encoder"))
     }
 
     fn prove_step(
-        _prover_key: &Self::ProverKey,
+        prover_key: &Self::ProverKey,
         _origin_state: &SC::State,
         _current_state: SC::State,
-        _current_proof: Option<Self::Proof>,
-        _current_witness: &SC::Witness,
+        current_proof: Option<Self::Proof>,
+        current_witness: &SC::Witness,
     ) -> Result<(SC::State, Self::Proof), crate::SangriaError> {
-        todo!()
+        let (next_state, next_proof) = telemetry::timed(telemetry::STEP_SECONDS, || {
+            check_next_step(prover_key._max_steps, current_proof.as_ref())?;
+            check_step_witness::<MainField, SC>(&prover_key._step_circuit, current_witness)?;
+
+            Err(SangriaError::NotImplemented("IVC prover"))
+        })?;
+        telemetry::step_proven();
+
+        Ok((next_state, next_proof))
     }
 
     fn verify(
-        _verifier_key: &Self::VerifierKey,
+        verifier_key: &Self::VerifierKey,
+        number_of_steps: usize,
+        origin_state: &SC::State,
+        current_state: SC::State,
+        current_proof: Option<Self::Proof>,
+    ) -> Result<(), crate::SangriaError> {
+        check_claim(
+            verifier_key._max_steps,
+            number_of_steps,
+            current_proof.as_ref(),
+        )?;
+        if number_of_steps == 0 {
+            return check_origin::<MainField, SC>(origin_state, &current_state);
+        }
+
+        Err(SangriaError::NotImplemented("IVC verifier"))
+    }
+}
+
+impl<MainField, HelperField, Config, SC> IVCWithVerifierKeyDigest<MainField, SC>
+    for SangriaNoCompression<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    type VerifierKeyDigest = VerifierKeyDigest<MainField, HelperField>;
+
+    fn verifier_key_digest(verifier_key: &Self::VerifierKey) -> Self::VerifierKeyDigest {
+        verifier_key.digest()
+    }
+
+    fn verify_with_verifier_key_digest(
+        _verifier_key_digest: &Self::VerifierKeyDigest,
+        number_of_steps: usize,
+        origin_state: &SC::State,
+        current_state: SC::State,
+        current_proof: Option<Self::Proof>,
+    ) -> Result<(), crate::SangriaError> {
+        // The digest does not carry the bound of the verifier key, so the claim is checked
+        // against the bound of the configuration.
+        check_claim(
+            Config::MAX_NUMBER_OF_STEPS,
+            number_of_steps,
+            current_proof.as_ref(),
+        )?;
+        if number_of_steps == 0 {
+            return check_origin::<MainField, SC>(origin_state, &current_state);
+        }
+
+        Err(SangriaError::NotImplemented("IVC verifier"))
+    }
+}
+
+impl<MainField, HelperField, Config, SC> IVCWithDeadline<MainField, SC>
+    for SangriaNoCompression<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    type PartialFold = PartialFold<MainField, HelperField, Config, SC>;
+
+    fn prove_step_with_deadline(
+        prover_key: &Self::ProverKey,
+        origin_state: &SC::State,
+        current_state: SC::State,
+        current_proof: Option<Self::Proof>,
+        current_witness: &SC::Witness,
+        deadline: Instant,
+    ) -> Result<StepProgress<SC::State, Self::Proof, Self::PartialFold>, SangriaError> {
+        let partial_fold = PartialFold {
+            next_phase: ProvingPhase::CheckWitness,
+            current_state,
+            current_proof,
+        };
+
+        Self::resume_step(
+            prover_key,
+            origin_state,
+            current_witness,
+            partial_fold,
+            deadline,
+        )
+    }
+
+    fn resume_step(
+        prover_key: &Self::ProverKey,
+        _origin_state: &SC::State,
+        current_witness: &SC::Witness,
+        mut partial_fold: Self::PartialFold,
+        deadline: Instant,
+    ) -> Result<StepProgress<SC::State, Self::Proof, Self::PartialFold>, SangriaError> {
+        loop {
+            partial_fold.next_phase = match partial_fold.next_phase {
+                ProvingPhase::CheckWitness => {
+                    check_next_step(prover_key._max_steps, partial_fold.current_proof.as_ref())?;
+                    check_step_witness::<MainField, SC>(
+                        &prover_key._step_circuit,
+                        current_witness,
+                    )?;
+                    ProvingPhase::FoldMain
+                }
+                ProvingPhase::FoldMain | ProvingPhase::FoldHelper | ProvingPhase::Accumulate => {
+                    return Err(SangriaError::NotImplemented("IVC prover"));
+                }
+            };
+
+            if Instant::now() >= deadline {
+                return Ok(StepProgress::Interrupted(partial_fold));
+            }
+        }
+    }
+}
+
+impl<MainField, HelperField, Config, SC> IVCWithPublicOutputs<MainField, SC>
+    for SangriaNoCompression<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField + Absorb,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    /// The outputs of a step are hashed into a leaf of the outputs accumulator of the proof.
+    type OutputsOpening = MountainRangeProof<MainField>;
+
+    fn prove_step_with_outputs(
+        prover_key: &Self::ProverKey,
         _origin_state: &SC::State,
         _current_state: SC::State,
-        _current_proof: Option<Self::Proof>,
-    ) -> Result<(), crate::SangriaError> {
-        todo!()
+        current_proof: Option<Self::Proof>,
+        current_witness: &SC::Witness,
+        _current_outputs: &SC::Outputs,
+    ) -> Result<(SC::State, Self::Proof), SangriaError> {
+        check_next_step(prover_key._max_steps, current_proof.as_ref())?;
+        check_step_witness::<MainField, SC>(&prover_key._step_circuit, current_witness)?;
+
+        Err(SangriaError::NotImplemented("IVC prover"))
+    }
+
+    fn open_step_outputs(
+        current_proof: &Self::Proof,
+        step_index: usize,
+    ) -> Result<Self::OutputsOpening, SangriaError> {
+        current_proof._outputs_accumulator.prove(step_index)
+    }
+
+    fn verify_step_outputs(
+        verifier_key: &Self::VerifierKey,
+        current_proof: &Self::Proof,
+        step_index: usize,
+        outputs: &SC::Outputs,
+        opening: &Self::OutputsOpening,
+    ) -> Result<(), SangriaError> {
+        let parameters = &verifier_key._accumulator_parameters;
+        let leaf = hash_leaf(parameters, &SC::output_elements(outputs));
+
+        opening.verify(
+            parameters,
+            current_proof._outputs_accumulator.root(parameters),
+            current_proof._number_of_steps,
+            step_index,
+            leaf,
+        )
+    }
+}
+
+/// Checks that a claim of `number_of_steps` steps does not exceed `max_steps`.
+fn check_number_of_steps(max_steps: usize, number_of_steps: usize) -> Result<(), SangriaError> {
+    if number_of_steps > max_steps {
+        return Err(SangriaError::TooManySteps);
+    }
+
+    Ok(())
+}
+
+/// Checks that the step after the one attested by `current_proof` does not exceed `max_steps`.
+fn check_next_step<MainField, HelperField, Config>(
+    max_steps: usize,
+    current_proof: Option<&IVCProof<MainField, HelperField, Config>>,
+) -> Result<(), SangriaError>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+{
+    let number_of_steps = current_proof.map_or(0, |proof| proof._number_of_steps);
+    check_number_of_steps(max_steps, number_of_steps.saturating_add(1))
+}
+
+/// Checks a claim of `number_of_steps` steps before verifying it: the claim must not exceed
+/// `max_steps` and the proof must have the shape of the claim, see [`check_proof_shape`]. Every
+/// verifier goes through this check, whichever form of the verifier key it is given.
+fn check_claim<MainField, HelperField, Config>(
+    max_steps: usize,
+    number_of_steps: usize,
+    current_proof: Option<&IVCProof<MainField, HelperField, Config>>,
+) -> Result<(), SangriaError>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+{
+    check_number_of_steps(max_steps, number_of_steps)?;
+    check_proof_shape(number_of_steps, current_proof)
+}
+
+/// Checks that a proof may attest `number_of_steps` steps: at step 0 it must be absent or the
+/// proof of the base case, and from step 1 on it must be present and count the same steps.
+fn check_proof_shape<MainField, HelperField, Config>(
+    number_of_steps: usize,
+    current_proof: Option<&IVCProof<MainField, HelperField, Config>>,
+) -> Result<(), SangriaError>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+{
+    match current_proof {
+        None if number_of_steps == 0 => Ok(()),
+        None => Err(VerificationFailure::BaseCase
+            .reject(|| format!("no proof for {} steps", number_of_steps))),
+        Some(proof) if number_of_steps == 0 && !proof.is_base() => {
+            Err(VerificationFailure::BaseCase
+                .reject(|| String::from("step-0 proof is not the proof of the base case")))
+        }
+        Some(proof) if proof._number_of_steps != number_of_steps => {
+            Err(VerificationFailure::StepCount.reject(|| {
+                format!(
+                    "proof attests {} steps, not {}",
+                    proof._number_of_steps, number_of_steps
+                )
+            }))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// Checks the claim of the base case: after zero steps the current state is the origin state.
+fn check_origin<F: PrimeField, SC: StepCircuit<F>>(
+    origin_state: &SC::State,
+    current_state: &SC::State,
+) -> Result<(), SangriaError> {
+    if SC::state_elements(origin_state) != SC::state_elements(current_state) {
+        return Err(VerificationFailure::BaseCase
+            .reject(|| String::from("the state changed without any step")));
+    }
+
+    Ok(())
+}
+
+/// Checks that a step witness has the arity declared by the step circuit and that all of its
+/// elements are canonical, i.e. strictly smaller than the field modulus. The check runs on the
+/// integers of [`StepCircuit::witness_reprs`], before the reduction that would hide them.
+pub(crate) fn check_step_witness<F: PrimeField, SC: StepCircuit<F>>(
+    step_circuit: &SC,
+    witness: &SC::Witness,
+) -> Result<(), SangriaError> {
+    let reprs = SC::witness_reprs(witness);
+    let expected_arity = step_circuit.witness_arity();
+
+    let non_canonical_element = reprs.iter().position(|repr| F::from_repr(*repr).is_none());
+
+    if reprs.len() != expected_arity || non_canonical_element.is_some() {
+        return Err(SangriaError::InvalidWitness {
+            expected_arity,
+            actual_arity: reprs.len(),
+            non_canonical_element,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_builder::PLONKCircuitBuilder,
+        relations::{
+            decide, relax, NonInteractiveFoldingScheme, PLONKFoldingScheme, PLONKInstance,
+            PLONKWitness, SetupInfo,
+        },
+        test_utils::seeded_rng,
+        test_vectors::{poseidon_parameters_for_test, sponge_config_for_test},
+        transcript::next_step_seed,
+        vector_commitment::mock::FieldCommitmentConfig,
+    };
+    use ark_ff::{BigInteger, FpParameters};
+    use ark_pallas::{Fq, Fr};
+    use ark_std::{One, UniformRand, Zero};
+
+    struct MockConfig;
+
+    impl SangriaIVCConfig<Fr, Fq> for MockConfig {
+        type MainCommitmentSchemes = FieldCommitmentConfig<Fr>;
+        type HelperCommitmentSchemes = FieldCommitmentConfig<Fq>;
+        type MainRandomOracle = PoseidonSponge<Fr>;
+        type HelperRandomOracle = PoseidonSponge<Fq>;
+    }
+
+    struct SingleCurveConfig;
+
+    impl SangriaIVCConfig<Fr, Fq> for SingleCurveConfig {
+        type MainCommitmentSchemes = FieldCommitmentConfig<Fr>;
+        type HelperCommitmentSchemes = FieldCommitmentConfig<Fq>;
+        type MainRandomOracle = PoseidonSponge<Fr>;
+        type HelperRandomOracle = PoseidonSponge<Fq>;
+
+        const VERIFIER_ARITHMETIC: VerifierArithmetic = VerifierArithmetic::NonNative;
+    }
+
+    /// The step circuit `z_{i+1} = z_i + 1`.
+    struct Increment;
+
+    impl StepCircuit<Fr> for Increment {
+        type State = Fr;
+        type Witness = ();
+        type Outputs = ();
+
+        fn witness_arity(&self) -> usize {
+            0
+        }
+
+        fn witness_elements(_witness: &()) -> Vec<Fr> {
+            Vec::new()
+        }
+
+        fn output_elements(_outputs: &()) -> Vec<Fr> {
+            Vec::new()
+        }
+
+        fn state_elements(state: &Fr) -> Vec<Fr> {
+            vec![*state]
+        }
+    }
+
+    /// A step circuit whose witness is an integer, reduced into a single field element.
+    struct Reduce;
+
+    impl StepCircuit<Fr> for Reduce {
+        type State = Fr;
+        type Witness = <Fr as PrimeField>::BigInt;
+        type Outputs = ();
+
+        fn witness_arity(&self) -> usize {
+            1
+        }
+
+        fn witness_elements(witness: &Self::Witness) -> Vec<Fr> {
+            vec![Fr::from_le_bytes_mod_order(&witness.to_bytes_le())]
+        }
+
+        fn witness_reprs(witness: &Self::Witness) -> Vec<<Fr as PrimeField>::BigInt> {
+            vec![*witness]
+        }
+
+        fn output_elements(_outputs: &()) -> Vec<Fr> {
+            Vec::new()
+        }
+
+        fn state_elements(state: &Fr) -> Vec<Fr> {
+            vec![*state]
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn nifs_keys<F: PrimeField + Absorb, R: Rng>(
+        rng: &mut R,
+    ) -> (
+        folding_scheme::PublicParameters<F, FieldCommitmentConfig<F>>,
+        folding_scheme::ProverKey<F, FieldCommitmentConfig<F>>,
+    ) {
+        type FoldingScheme<F> = PLONKFoldingScheme<F, FieldCommitmentConfig<F>, PoseidonSponge<F>>;
+
+        let number_of_gates = 4;
+        let info = SetupInfo {
+            number_of_public_inputs: AUGMENTED_CIRCUIT_PUBLIC_INPUTS,
+            number_of_gates,
+            domain_separator: b"sangria-base-case-test".to_vec(),
+            poseidon_constants: poseidon_parameters_for_test(rng),
+            poseidon2_constants: None,
+            sponge_config: sponge_config_for_test(),
+            slack_randomization: folding_scheme::SlackRandomization::EveryFold,
+        };
+        let pp = FoldingScheme::<F>::setup(&info, rng).unwrap();
+
+        let mut builder = PLONKCircuitBuilder::new();
+        for _ in 0..number_of_gates {
+            builder.add_gate([F::one(), F::zero(), -F::one(), F::zero(), F::zero()]);
+        }
+        let (pk, _) = FoldingScheme::<F>::encode(&pp, &builder.build().unwrap(), rng).unwrap();
+
+        (pp, pk)
+    }
+
+    #[test]
+    fn pasta_fields_are_sound_and_small_fields_lose_bits() {
+        assert_eq!(
+            check_challenge_soundness::<Fr>(SecurityLevel::Bits128, 1 << 32),
+            Ok(())
+        );
+        assert_eq!(
+            check_challenge_soundness::<Fq>(SecurityLevel::Bits128, usize::MAX),
+            Ok(())
+        );
+
+        // A 64-bit field is sound for a single fold only up to 62 bits.
+        assert_eq!(soundness_bits(64, FOLDING_DEGREE, 1), 62);
+        assert_eq!(soundness_bits(64, FOLDING_DEGREE, 1 << 20), 42);
+        assert_eq!(soundness_bits(8, FOLDING_DEGREE, 1 << 20), 0);
+    }
+
+    #[test]
+    fn step_counter_is_bounded() {
+        assert_eq!(
+            check_max_steps::<Fr>(MockConfig::MAX_NUMBER_OF_STEPS),
+            Ok(())
+        );
+        assert_eq!(check_max_steps::<Fr>(usize::MAX), Ok(()));
+        assert_eq!(check_number_of_steps(3, 3), Ok(()));
+        assert_eq!(check_number_of_steps(3, 4), Err(SangriaError::TooManySteps));
+        assert_eq!(
+            check_next_step(0, None::<&IVCProof<Fr, Fq, MockConfig>>),
+            Err(SangriaError::TooManySteps)
+        );
+    }
+
+    #[test]
+    fn setup_rejects_single_curve_configurations() {
+        let rng = &mut seeded_rng("ivc::setup_rejects_single_curve_configurations");
+        assert_eq!(
+            SangriaNoCompression::<Fr, Fq, MockConfig, Increment>::setup(rng).err(),
+            Some(SangriaError::NotImplemented("IVC setup"))
+        );
+        assert_eq!(
+            SangriaNoCompression::<Fr, Fq, SingleCurveConfig, Increment>::setup(rng).err(),
+            Some(SangriaError::NotImplemented("non-native folding verifier"))
+        );
+    }
+
+    #[test]
+    fn verifier_key_digests_bound_the_steps() {
+        type Sangria = SangriaNoCompression<Fr, Fq, MockConfig, Increment>;
+        let digest = VerifierKeyDigest {
+            main: Fr::zero(),
+            helper: Fq::zero(),
+        };
+
+        assert_eq!(
+            Sangria::verify_with_verifier_key_digest(
+                &digest,
+                MockConfig::MAX_NUMBER_OF_STEPS + 1,
+                &Fr::zero(),
+                Fr::zero(),
+                None
+            ),
+            Err(SangriaError::TooManySteps)
+        );
+        assert_eq!(
+            Sangria::verify_with_verifier_key_digest(&digest, 0, &Fr::zero(), Fr::zero(), None),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn witnesses_must_be_canonical_before_reduction() {
+        let modulus = <Fr as PrimeField>::Params::MODULUS;
+        assert_eq!(
+            check_step_witness(&Reduce, &Fr::from(5u64).into_repr()),
+            Ok(())
+        );
+
+        // The modulus reduces to zero, so only its integer shows that it is not canonical.
+        assert_eq!(
+            <Reduce as StepCircuit<Fr>>::witness_elements(&modulus),
+            vec![Fr::zero()]
+        );
+        assert_eq!(
+            check_step_witness(&Reduce, &modulus),
+            Err(SangriaError::InvalidWitness {
+                expected_arity: 1,
+                actual_arity: 1,
+                non_canonical_element: Some(0),
+            })
+        );
+    }
+
+    #[test]
+    fn step_zero_accepts_only_the_base_case() {
+        let rng = &mut seeded_rng("ivc::step_zero_accepts_only_the_base_case");
+        let (main_pp, main_pk) = nifs_keys::<Fr, _>(rng);
+        let (helper_pp, helper_pk) = nifs_keys::<Fq, _>(rng);
+        let prover_key = ProverKey::<Fr, Fq, MockConfig, Increment> {
+            _main_nifs_pk: main_pk,
+            _helper_nifs_pk: helper_pk,
+            _step_circuit: Increment,
+            _augmented_circuit_cost: AugmentedCircuitCost::default(),
+            _accumulator_parameters: poseidon_parameters_for_test(rng),
+            _max_steps: MockConfig::MAX_NUMBER_OF_STEPS,
+        };
+
+        let mut proof = IVCProof::base(&prover_key);
+        assert!(proof.is_base());
+        let main = &proof._main_half_proof._running;
+        let helper = &proof._helper_half_proof._running;
+        assert_eq!(
+            decide(
+                &main_pp,
+                &prover_key._main_nifs_pk,
+                main.instance(),
+                main.witness()
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            decide(
+                &helper_pp,
+                &prover_key._helper_nifs_pk,
+                helper.instance(),
+                helper.witness()
+            ),
+            Ok(())
+        );
+        let public_parameters = PublicParameters::<Fr, Fq, MockConfig> {
+            _main_nifs_pp: main_pp,
+            _helper_nifs_pp: helper_pp,
+        };
+        assert_eq!(proof.audit(&public_parameters, &prover_key), Ok(()));
+        let (main_pair, helper_pair) = proof.final_pairs(&prover_key).unwrap();
+        assert!(main_pair.instance() == proof.final_instances().0);
+        assert!(helper_pair.instance() == proof.final_instances().1);
+
+        assert_eq!(
+            check_proof_shape(0, None::<&IVCProof<Fr, Fq, MockConfig>>),
+            Ok(())
+        );
+        assert_eq!(check_proof_shape(0, Some(&proof)), Ok(()));
+        assert_eq!(
+            check_proof_shape(1, None::<&IVCProof<Fr, Fq, MockConfig>>),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::BaseCase
+            ))
+        );
+        assert_eq!(
+            check_proof_shape(1, Some(&proof)),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::StepCount
+            ))
+        );
+        proof._number_of_steps = 1;
+        assert_eq!(
+            check_proof_shape(0, Some(&proof)),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::BaseCase
+            ))
+        );
+
+        let origin_state = Fr::from(7u64);
+        assert_eq!(
+            check_origin::<Fr, Increment>(&origin_state, &origin_state),
+            Ok(())
+        );
+        assert_eq!(
+            check_origin::<Fr, Increment>(&origin_state, &(origin_state + Fr::one())),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::BaseCase
+            ))
+        );
+    }
+
+    // Folds a long chain of steps into the main running pair and checks what its final artifacts
+    // support without the IVC verifier, which is descoped with the augmented circuit: the step
+    // count and the shape of the proof, step inclusion against the verifier key, and the audit of
+    // the final pair, which takes the prover key. Debug builds fold fewer steps.
+    #[test]
+    fn long_chains_audit_from_the_final_pairs() {
+        type FoldingScheme = PLONKFoldingScheme<Fr, FieldCommitmentConfig<Fr>, PoseidonSponge<Fr>>;
+        const NUMBER_OF_STEPS: usize = if cfg!(debug_assertions) {
+            1_000
+        } else {
+            10_000
+        };
+
+        let rng = &mut seeded_rng("ivc::long_chains_audit_from_the_final_pairs");
+        let (main_pp, main_pk) = nifs_keys::<Fr, _>(rng);
+        let (helper_pp, helper_pk) = nifs_keys::<Fq, _>(rng);
+        let prover_key = ProverKey::<Fr, Fq, MockConfig, Increment> {
+            _main_nifs_pk: main_pk,
+            _helper_nifs_pk: helper_pk,
+            _step_circuit: Increment,
+            _augmented_circuit_cost: AugmentedCircuitCost::default(),
+            _accumulator_parameters: poseidon_parameters_for_test(rng),
+            _max_steps: MockConfig::MAX_NUMBER_OF_STEPS,
+        };
+        let main_pk = &prover_key._main_nifs_pk;
+        let parameters = &prover_key._accumulator_parameters;
+        let number_of_gates = main_pk.preprocessed_circuit.number_of_gates();
+
+        let mut proof = IVCProof::base(&prover_key);
+        let mut step_seed = main_pk.verifier_key.transcript_seed;
+        let mut step_instances = Vec::new();
+        for step_index in 0..NUMBER_OF_STEPS {
+            // a - c = 0 at every gate.
+            let a = (0..number_of_gates)
+                .map(|_| Fr::rand(rng))
+                .collect::<Vec<_>>();
+            let b = (0..number_of_gates).map(|_| Fr::rand(rng)).collect();
+            let (instance, witness) = relax(
+                &main_pp,
+                &main_pk.verifier_key,
+                PLONKInstance::new(vec![
+                    vec![Fr::zero(); AUGMENTED_CIRCUIT_PUBLIC_INPUTS + 1];
+                    3
+                ]),
+                PLONKWitness::new(vec![a.clone(), b, a]),
+                rng,
+            )
+            .unwrap();
+
+            let (running_instance, running_witness) = std::mem::replace(
+                &mut proof._main_half_proof._running,
+                RunningAccumulator::trivial(main_pk),
+            )
+            .into_parts();
+            let (folded_instance, folded_witness, _) = FoldingScheme::prover_with_recorder(
+                &main_pp,
+                main_pk,
+                step_seed,
+                &running_instance,
+                &running_witness,
+                &instance,
+                &witness,
+                None,
+            )
+            .unwrap();
+            step_seed = next_step_seed::<Fr, _, PoseidonSponge<Fr>, _>(
+                &main_pp,
+                step_seed,
+                &folded_instance,
+                step_index + 1,
+            )
+            .unwrap();
+
+            proof._main_half_proof._running =
+                RunningAccumulator::new(folded_instance, folded_witness);
+            proof
+                ._step_accumulator
+                .append(parameters, step_leaf(parameters, &instance));
+            proof._number_of_steps += 1;
+            if step_index % (NUMBER_OF_STEPS / 4) == 0 || step_index == NUMBER_OF_STEPS - 1 {
+                step_instances.push((step_index, instance));
+            }
+        }
+
+        let verifier_key = VerifierKey::<Fr, Fq, MockConfig, Increment> {
+            _main_nifs_vk: prover_key._main_nifs_pk.verifier_key.clone(),
+            _helper_nifs_vk: prover_key._helper_nifs_pk.verifier_key.clone(),
+            _step_circuit: Increment,
+            _accumulator_parameters: prover_key._accumulator_parameters.clone(),
+            _max_steps: prover_key._max_steps,
+        };
+        assert_eq!(
+            check_number_of_steps(verifier_key._max_steps, NUMBER_OF_STEPS),
+            Ok(())
+        );
+        assert_eq!(check_proof_shape(NUMBER_OF_STEPS, Some(&proof)), Ok(()));
+        let public_parameters = PublicParameters::<Fr, Fq, MockConfig> {
+            _main_nifs_pp: main_pp,
+            _helper_nifs_pp: helper_pp,
+        };
+        assert_eq!(proof.audit(&public_parameters, &prover_key), Ok(()));
+
+        for (step_index, step_instance) in &step_instances {
+            let inclusion_proof = proof.prove_step_inclusion(*step_index).unwrap();
+            assert_eq!(
+                verifier_key.verify_step_inclusion(
+                    &proof,
+                    *step_index,
+                    step_instance,
+                    &inclusion_proof
+                ),
+                Ok(())
+            );
+        }
+        let (step_index, step_instance) = &step_instances[0];
+        let inclusion_proof = proof.prove_step_inclusion(*step_index).unwrap();
+        assert!(verifier_key
+            .verify_step_inclusion(&proof, step_index + 1, step_instance, &inclusion_proof)
+            .is_err());
+    }
+
+    #[test]
+    fn rerandomized_proofs_share_no_commitment() {
+        let rng = &mut seeded_rng("ivc::rerandomized_proofs_share_no_commitment");
+        let (main_pp, main_pk) = nifs_keys::<Fr, _>(rng);
+        let (helper_pp, helper_pk) = nifs_keys::<Fq, _>(rng);
+        let prover_key = ProverKey::<Fr, Fq, MockConfig, Increment> {
+            _main_nifs_pk: main_pk,
+            _helper_nifs_pk: helper_pk,
+            _step_circuit: Increment,
+            _augmented_circuit_cost: AugmentedCircuitCost::default(),
+            _accumulator_parameters: poseidon_parameters_for_test(rng),
+            _max_steps: MockConfig::MAX_NUMBER_OF_STEPS,
+        };
+        let public_parameters = PublicParameters::<Fr, Fq, MockConfig> {
+            _main_nifs_pp: main_pp,
+            _helper_nifs_pp: helper_pp,
+        };
+
+        // a - c = 0 at every gate.
+        let main_pk = &prover_key._main_nifs_pk;
+        let number_of_gates = main_pk.preprocessed_circuit.number_of_gates();
+        let a = (0..number_of_gates)
+            .map(|_| Fr::rand(rng))
+            .collect::<Vec<_>>();
+        let (instance, witness) = relax(
+            &public_parameters._main_nifs_pp,
+            &main_pk.verifier_key,
+            PLONKInstance::new(vec![
+                vec![Fr::zero(); AUGMENTED_CIRCUIT_PUBLIC_INPUTS + 1];
+                3
+            ]),
+            PLONKWitness::new(vec![a.clone(), vec![Fr::zero(); number_of_gates], a]),
+            rng,
+        )
+        .unwrap();
+        let mut proof = IVCProof::base(&prover_key);
+        proof._main_half_proof._latest_step = Some(FreshInstance::new(instance, witness).unwrap());
+        proof._number_of_steps = 1;
+
+        let latest_step = |proof: &IVCProof<Fr, Fq, MockConfig>| {
+            let latest_step = proof._main_half_proof._latest_step.as_ref().unwrap();
+            latest_step.instance().clone()
+        };
+        let (main_before, helper_before) = (
+            proof.final_instances().0.clone(),
+            proof.final_instances().1.clone(),
+        );
+        let latest_step_before = latest_step(&proof);
+
+        proof.rerandomize(&public_parameters, rng).unwrap();
+        let (main_after, helper_after) = proof.final_instances();
+        let commitments_before = main_before
+            .witness_commitments()
+            .into_iter()
+            .chain(latest_step_before.witness_commitments())
+            .chain([main_before.slack_commitment()]);
+        let commitments_after = main_after
+            .witness_commitments()
+            .into_iter()
+            .chain(latest_step(&proof).witness_commitments())
+            .chain([main_after.slack_commitment()]);
+        for (before, after) in commitments_before.zip(commitments_after) {
+            assert_ne!(before, after);
+        }
+        for (before, after) in helper_before
+            .witness_commitments()
+            .into_iter()
+            .zip(helper_after.witness_commitments())
+        {
+            assert_ne!(before, after);
+        }
+        assert_ne!(
+            helper_before.slack_commitment(),
+            helper_after.slack_commitment()
+        );
+        assert!(latest_step(&proof).is_strict());
+        assert_eq!(proof._number_of_steps, 1);
+        assert_eq!(proof.audit(&public_parameters, &prover_key), Ok(()));
+    }
+
+    #[test]
+    fn fresh_pairs_are_strict_and_round_trip() {
+        let rng = &mut seeded_rng("ivc::fresh_pairs_are_strict_and_round_trip");
+        let (pp, pk) = nifs_keys::<Fr, _>(rng);
+        let mode = SerializationMode::Compressed;
+
+        // The trivial pair has a zero scaling factor, so it is running but not fresh.
+        let trivial = RunningAccumulator::trivial(&pk);
+        let bytes = trivial.to_bytes(mode).unwrap();
+        let (instance, witness) = RunningAccumulator::from_bytes(&bytes, mode)
+            .unwrap()
+            .into_parts();
+        assert!(instance == *trivial.instance());
+        assert_eq!(
+            FreshInstance::new(instance, witness).err(),
+            Some(SangriaError::NonStrictInstance)
+        );
+        assert_eq!(
+            FreshInstance::<Fr, FieldCommitmentConfig<Fr>>::from_bytes(&bytes, mode).err(),
+            Some(SangriaError::NonStrictInstance)
+        );
+
+        let number_of_gates = pk.preprocessed_circuit.number_of_gates();
+        let (instance, witness) = relax(
+            &pp,
+            &pk.verifier_key,
+            PLONKInstance::new(vec![
+                vec![Fr::zero(); AUGMENTED_CIRCUIT_PUBLIC_INPUTS + 1];
+                3
+            ]),
+            PLONKWitness::new(vec![vec![Fr::one(); number_of_gates]; 3]),
+            rng,
+        )
+        .unwrap();
+        let mut fresh = FreshInstance::new(instance, witness).unwrap();
+        let before = fresh.instance().clone();
+        fresh.rerandomize(&pp, rng).unwrap();
+        assert!(*fresh.instance() != before);
+        assert!(fresh.instance().is_strict() && fresh.witness().is_strict());
+        let bytes = fresh.to_bytes(mode).unwrap();
+        let decoded = FreshInstance::<Fr, FieldCommitmentConfig<Fr>>::from_bytes(&bytes, mode);
+        assert!(decoded.unwrap().instance() == fresh.instance());
+        assert_eq!(
+            FreshInstance::<Fr, FieldCommitmentConfig<Fr>>::from_bytes(&bytes[1..], mode).err(),
+            Some(SangriaError::SerializationError)
+        );
+
+        assert_eq!(fresh.serialized_size(mode), bytes.len());
+
+        let proof = HalfCycleProof {
+            _latest_step: Some(fresh),
+            _running: RunningAccumulator::trivial(&pk),
+        };
+        assert_eq!(proof.audit(&pp, &pk), Ok(()));
+        let bytes = proof.to_bytes(mode).unwrap();
+        assert_eq!(proof.serialized_size(mode), bytes.len());
+        assert_eq!(HalfCycleProof::max_serialized_size(&pk, mode), bytes.len());
+
+        // a = 1 and c = 0 violate the gates a - c = 0.
+        let (instance, witness) = relax(
+            &pp,
+            &pk.verifier_key,
+            PLONKInstance::new(vec![
+                vec![Fr::zero(); AUGMENTED_CIRCUIT_PUBLIC_INPUTS + 1];
+                3
+            ]),
+            PLONKWitness::new(vec![
+                vec![Fr::one(); number_of_gates],
+                vec![Fr::one(); number_of_gates],
+                vec![Fr::zero(); number_of_gates],
+            ]),
+            rng,
+        )
+        .unwrap();
+        let corrupted = HalfCycleProof {
+            _latest_step: Some(FreshInstance::new(instance, witness).unwrap()),
+            _running: RunningAccumulator::trivial(&pk),
+        };
+        assert_eq!(
+            corrupted.audit(&pp, &pk),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::RelaxedRelation
+            ))
+        );
+        let decoded = HalfCycleProof::<Fr, FieldCommitmentConfig<Fr>>::from_bytes(&bytes, mode);
+        assert!(decoded.unwrap()._running.is_trivial());
+
+        let mut running = RunningAccumulator::from(proof._latest_step.unwrap());
+        running.rerandomize(&pp, rng).unwrap();
+        assert_eq!(
+            decide(&pp, &pk, running.instance(), running.witness()),
+            Ok(())
+        );
     }
 }
@@ -0,0 +1,69 @@
+//! Helpers for the JSON export of keys, for tooling that does not link the crate. Values are
+//! hex strings of their compressed canonical serialization (see [`crate::serialization`]):
+//! field elements are little-endian and points follow the arkworks compressed layout. Every key
+//! carries the `curve` of its configuration, checked on import.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde_json::Value;
+
+use crate::{
+    serialization::{deserialize_with_mode, to_bytes, usize_from_u64, SerializationMode},
+    SangriaError,
+};
+
+/// Encodes a value as a hex string.
+pub(crate) fn to_hex<T: CanonicalSerialize>(value: &T) -> Result<Value, SangriaError> {
+    Ok(Value::String(hex::encode(to_bytes(
+        value,
+        SerializationMode::Compressed,
+    )?)))
+}
+
+/// Decodes a hex string encoded with [`to_hex`]. Trailing bytes are rejected.
+pub(crate) fn from_hex<T: CanonicalDeserialize>(value: &Value) -> Result<T, SangriaError> {
+    let bytes = value
+        .as_str()
+        .and_then(|hex_string| hex::decode(hex_string).ok())
+        .ok_or(SangriaError::SerializationError)?;
+    let mut reader = bytes.as_slice();
+    let decoded = deserialize_with_mode(&mut reader, SerializationMode::Compressed)?;
+    if !reader.is_empty() {
+        return Err(SangriaError::SerializationError);
+    }
+
+    Ok(decoded)
+}
+
+/// Returns the member `name` of a JSON object.
+pub(crate) fn member<'a>(object: &'a Value, name: &str) -> Result<&'a Value, SangriaError> {
+    object.get(name).ok_or(SangriaError::SerializationError)
+}
+
+/// Returns the member `name` of a JSON object as an array.
+pub(crate) fn array<'a>(object: &'a Value, name: &str) -> Result<&'a [Value], SangriaError> {
+    member(object, name)?
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or(SangriaError::SerializationError)
+}
+
+/// Returns a JSON value as a non-negative integer that fits in a `usize`.
+pub(crate) fn as_index(value: &Value) -> Result<usize, SangriaError> {
+    usize_from_u64(value.as_u64().ok_or(SangriaError::SerializationError)?)
+}
+
+/// Returns the member `name` of a JSON object as a non-negative integer.
+pub(crate) fn index(object: &Value, name: &str) -> Result<usize, SangriaError> {
+    as_index(member(object, name)?)
+}
+
+/// Parses a key and checks that it was exported for `curve`.
+pub(crate) fn parse(json: &str, curve: &str) -> Result<Value, SangriaError> {
+    let object =
+        serde_json::from_str::<Value>(json).map_err(|_| SangriaError::SerializationError)?;
+    if member(&object, "curve")?.as_str() != Some(curve) {
+        return Err(SangriaError::CurveMismatch);
+    }
+
+    Ok(object)
+}
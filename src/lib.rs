@@ -5,6 +5,7 @@
 
 use ark_ff::PrimeField;
 use ark_std::rand::Rng;
+use std::time::Instant;
 
 /// Interface for an IVC scheme.
 pub trait IVC<F: PrimeField, SC: StepCircuit<F>> {
@@ -21,7 +22,7 @@ pub trait IVC<F: PrimeField, SC: StepCircuit<F>> {
     type Proof;
 
     /// Run the IVC setup to produce public parameters.
-    fn setup<R: Rng>(rng: &mut R) -> Self::PublicParameters;
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::PublicParameters, SangriaError>;
 
     /// Run the IVC encoder to produce a proving key and a verifying key.
     fn encode<R: Rng>(
@@ -39,15 +40,117 @@ pub trait IVC<F: PrimeField, SC: StepCircuit<F>> {
         current_witness: &SC::Witness,
     ) -> Result<(SC::State, Self::Proof), SangriaError>;
 
-    /// Verify a step of the IVC computation.
+    /// Verify that `current_state` is the result of applying the step circuit `number_of_steps`
+    /// times to `origin_state`. Only the final artifacts are needed: the proof has constant size
+    /// and none of the intermediate states are required, so a verifier can check step `i`
+    /// directly without having followed the computation.
+    ///
+    /// At step 0 nothing has been computed: `current_proof` may be `None` (or the scheme's proof
+    /// of the base case) and verification succeeds if and only if `current_state` is
+    /// `origin_state`. From step 1 on, `None` is rejected with
+    /// [`VerificationFailure::BaseCase`].
     fn verify(
         verifier_key: &Self::VerifierKey,
+        number_of_steps: usize,
         origin_state: &SC::State,
         current_state: SC::State,
         current_proof: Option<Self::Proof>,
     ) -> Result<(), SangriaError>;
 }
 
+/// An IVC scheme whose verifier can be given a digest of the verifier key instead of the full key,
+/// for constrained (e.g. on-chain or embedded) verifiers that cannot keep the key resident.
+pub trait IVCWithVerifierKeyDigest<F: PrimeField, SC: StepCircuit<F>>: IVC<F, SC> {
+    /// The few field elements of the verifier key that verification depends on.
+    type VerifierKeyDigest;
+
+    /// Computes the digest of a verifier key.
+    fn verifier_key_digest(verifier_key: &Self::VerifierKey) -> Self::VerifierKeyDigest;
+
+    /// Verify a step of the IVC computation against a verifier key digest.
+    fn verify_with_verifier_key_digest(
+        verifier_key_digest: &Self::VerifierKeyDigest,
+        number_of_steps: usize,
+        origin_state: &SC::State,
+        current_state: SC::State,
+        current_proof: Option<Self::Proof>,
+    ) -> Result<(), SangriaError>;
+}
+
+/// An IVC scheme that commits to the public outputs of every step in the proof, so that the
+/// outputs of any past step can be opened from the final proof.
+pub trait IVCWithPublicOutputs<F: PrimeField, SC: StepCircuit<F>>: IVC<F, SC> {
+    /// A proof that some outputs are the ones committed to at a given step.
+    type OutputsOpening;
+
+    /// Prove a step of the IVC computation, like [`IVC::prove_step`], and commit to the outputs
+    /// it emitted.
+    fn prove_step_with_outputs(
+        prover_key: &Self::ProverKey,
+        origin_state: &SC::State,
+        current_state: SC::State,
+        current_proof: Option<Self::Proof>,
+        current_witness: &SC::Witness,
+        current_outputs: &SC::Outputs,
+    ) -> Result<(SC::State, Self::Proof), SangriaError>;
+
+    /// Opens the outputs of the `step_index`-th step from a proof.
+    fn open_step_outputs(
+        current_proof: &Self::Proof,
+        step_index: usize,
+    ) -> Result<Self::OutputsOpening, SangriaError>;
+
+    /// Verify that `outputs` were emitted by the `step_index`-th step of the computation attested
+    /// by `current_proof`. The proof itself must have been accepted by [`IVC::verify`].
+    fn verify_step_outputs(
+        verifier_key: &Self::VerifierKey,
+        current_proof: &Self::Proof,
+        step_index: usize,
+        outputs: &SC::Outputs,
+        opening: &Self::OutputsOpening,
+    ) -> Result<(), SangriaError>;
+}
+
+/// The outcome of a time-boxed call to an IVC prover.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StepProgress<State, Proof, PartialFold> {
+    /// The step was proven before the deadline.
+    Done(State, Proof),
+    /// The deadline passed before the step was proven. Proving can be resumed from the partial
+    /// fold.
+    Interrupted(PartialFold),
+}
+
+/// An IVC scheme whose prover can be time-boxed, so that interactive applications (e.g. wallets)
+/// stay responsive and continue proving later. The prover checks the clock between its phases.
+pub trait IVCWithDeadline<F: PrimeField, SC: StepCircuit<F>>: IVC<F, SC> {
+    /// A step whose proving was interrupted, together with the work done so far.
+    type PartialFold;
+
+    /// Prove a step of the IVC computation like [`IVC::prove_step`], but stop at the first phase
+    /// boundary after `deadline`. At least one phase is run, so that resuming always progresses.
+    #[allow(clippy::type_complexity)]
+    fn prove_step_with_deadline(
+        prover_key: &Self::ProverKey,
+        origin_state: &SC::State,
+        current_state: SC::State,
+        current_proof: Option<Self::Proof>,
+        current_witness: &SC::Witness,
+        deadline: Instant,
+    ) -> Result<StepProgress<SC::State, Self::Proof, Self::PartialFold>, SangriaError>;
+
+    /// Resume proving an interrupted step until it is proven or `deadline` passes again.
+    /// `origin_state` and `current_witness` must be the ones the step was started with.
+    #[allow(clippy::type_complexity)]
+    fn resume_step(
+        prover_key: &Self::ProverKey,
+        origin_state: &SC::State,
+        current_witness: &SC::Witness,
+        partial_fold: Self::PartialFold,
+        deadline: Instant,
+    ) -> Result<StepProgress<SC::State, Self::Proof, Self::PartialFold>, SangriaError>;
+}
+
 /// A marker trait for an IVC scheme which implements proof compression.
 pub trait IVCWithProofCompression<F: PrimeField, SC: StepCircuit<F>>: IVC<F, SC> {}
 
@@ -58,6 +161,34 @@ pub trait StepCircuit<F: PrimeField> {
 
     /// The non-deterministic input for a step of the computation
     type Witness;
+
+    /// The public outputs of a step, e.g. emitted events. They are committed to in the proof but,
+    /// unlike the state, not carried over to the next step.
+    type Outputs;
+
+    /// The number of field elements a step witness is expected to contain.
+    fn witness_arity(&self) -> usize;
+
+    /// Returns the field elements of a step witness.
+    fn witness_elements(witness: &Self::Witness) -> Vec<F>;
+
+    /// Returns the integers the elements of a step witness are read from, before they are reduced
+    /// modulo the field size, so that non-canonical encodings can be rejected. Circuits whose
+    /// witness is decoded from untrusted integers or bytes must return them here; by default the
+    /// witness is made of field elements, which are always canonical.
+    fn witness_reprs(witness: &Self::Witness) -> Vec<F::BigInt> {
+        Self::witness_elements(witness)
+            .iter()
+            .map(PrimeField::into_repr)
+            .collect()
+    }
+
+    /// Returns the field elements of the outputs of a step.
+    fn output_elements(outputs: &Self::Outputs) -> Vec<F>;
+
+    /// Returns the field elements of a state. Two states are the same if and only if their
+    /// elements are.
+    fn state_elements(state: &Self::State) -> Vec<F>;
 }
 
 /// Interface for a non-interactive folding scheme (NIFS).
@@ -87,7 +218,10 @@ pub trait NonInteractiveFoldingScheme {
     type ProverMessage;
 
     /// Run the randomised setup for the folding scheme to produce public parameters.
-    fn setup<R: Rng>(info: &Self::SetupInfo, rng: &mut R) -> Self::PublicParameters;
+    fn setup<R: Rng>(
+        info: &Self::SetupInfo,
+        rng: &mut R,
+    ) -> Result<Self::PublicParameters, SangriaError>;
 
     /// Using the public parameters, run the randomised encoder that produces a prover key and verifier key.
     fn encode<R: Rng>(
@@ -120,19 +254,108 @@ pub trait NonInteractiveFoldingScheme {
 mod folding_scheme;
 pub use folding_scheme::PLONKFoldingScheme;
 
-// mod ivc;
+mod ivc;
+pub use ivc::{
+    check_challenge_soundness, soundness_bits, AugmentedCircuitCost, FreshInstance, HalfCycleProof,
+    IVCProof, PartialFold, ProvingPhase, RunningAccumulator, SangriaIVCConfig, VerifierArithmetic,
+    VerifierKeyDigest, AUGMENTED_CIRCUIT_PUBLIC_INPUTS, FOLDING_DEGREE, MIN_SOUNDNESS_BITS,
+};
 
 mod relaxed_plonk;
 pub use relaxed_plonk::{
-    PLONKCircuit, RelaxedPLONKInstance, RelaxedPLONKWitness, CONSTANT_SELECTOR_INDEX,
-    LEFT_SELECTOR_INDEX, MULTIPLICATION_SELECTOR_INDEX, OUTPUT_SELECTOR_INDEX,
-    RIGHT_SELECTOR_INDEX,
+    CircuitLabels, OutputRow, PLONKCircuit, PublicInputRow, RelaxedPLONKInstance,
+    RelaxedPLONKWitness, UnsatisfiedRow, CONSTANT_SELECTOR_INDEX, LEFT_SELECTOR_INDEX,
+    MULTIPLICATION_SELECTOR_INDEX, OUTPUT_SELECTOR_INDEX, RIGHT_SELECTOR_INDEX,
 };
 
 mod sangria;
-pub use sangria::Sangria;
+pub use sangria::{Sangria, SangriaProof, SangriaProverKey, SangriaVerifierKey};
 
 mod errors;
-pub use errors::SangriaError;
+pub use errors::{SangriaError, VerificationFailure};
+
+mod dyn_verifier;
+pub use dyn_verifier::{DynIVCVerifier, ErasedIVCVerifier};
+
+mod registry;
+pub use registry::VerifierRegistry;
+
+mod fixed_steps;
+pub use fixed_steps::FixedStepsVerifierKey;
+
+mod security;
+pub use security::SecurityLevel;
+
+pub mod transcript;
+
+pub mod sponge;
+
+pub mod domain;
+
+pub mod blinding;
+
+pub mod lookup;
+
+pub mod serialization;
+
+pub mod cross_term;
+
+pub mod trace;
+
+pub mod accumulator;
+
+pub mod telemetry;
+
+pub mod preprocessing;
+
+pub mod circuit_builder;
+
+pub mod combinators;
+
+pub mod relations;
+
+pub mod foldable_relation;
+
+pub mod r1cs;
+
+pub mod commitment_switch;
+
+pub mod equivalence;
+
+pub mod compression;
+
+pub mod distributed;
+
+pub mod driver;
+
+pub mod delegation;
+
+#[cfg(feature = "wire")]
+pub mod wire;
+
+#[cfg(feature = "zkvm")]
+pub mod zkvm;
+
+#[cfg(feature = "json")]
+mod json;
+
+pub mod field_encoding;
+
+#[cfg(test)]
+mod test_utils;
+
+#[cfg(test)]
+mod test_vectors;
+
+#[cfg(test)]
+mod test_matrix;
 
 mod vector_commitment;
+
+/// Concrete types for the fuzz targets in `fuzz/`. They are neither hiding nor binding and must
+/// not be used outside of fuzzing.
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing {
+    pub use crate::vector_commitment::mock::{FieldCommitment, FieldCommitmentConfig};
+}
@@ -0,0 +1,151 @@
+//! Folding of lookups into global tables. The tables themselves are part of the circuit and are
+//! committed to once in `encode`. Each step only contributes usage columns: for every table, the
+//! number of times each entry was looked up. Usage columns are linear in the step's lookups, so
+//! they are folded like witness columns and the prover never holds more than one set of them,
+//! regardless of how many steps have been folded.
+
+use ark_ff::PrimeField;
+
+use crate::{vector_commitment::HomomorphicCommitmentScheme, SangriaError};
+
+/// The usage of the global lookup tables by a (possibly folded) step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupUsage<F: PrimeField> {
+    multiplicities: Vec<Vec<F>>,
+    hidings: Vec<F>,
+}
+
+impl<F: PrimeField> LookupUsage<F> {
+    /// Creates the usage of a step: one multiplicity column per table, and the randomness used to
+    /// commit to each of them.
+    pub fn new(multiplicities: Vec<Vec<F>>, hidings: Vec<F>) -> Result<Self, SangriaError> {
+        if multiplicities.len() != hidings.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        Ok(Self {
+            multiplicities,
+            hidings,
+        })
+    }
+
+    /// Returns the multiplicity column of the i-th table or an error if index is out of bounds.
+    pub fn multiplicities(&self, table_index: usize) -> Result<&[F], SangriaError> {
+        self.multiplicities
+            .get(table_index)
+            .map(Vec::as_slice)
+            .ok_or(SangriaError::IndexOutOfBounds)
+    }
+
+    /// Commits to every multiplicity column.
+    pub fn commit<CS: HomomorphicCommitmentScheme<F>>(
+        &self,
+        commit_key: &CS::CommitKey,
+    ) -> Result<Vec<CS::Commitment>, SangriaError> {
+        self.multiplicities
+            .iter()
+            .zip(&self.hidings)
+            .map(|(column, hiding)| CS::commit(commit_key, column, *hiding))
+            .collect()
+    }
+
+    /// Folds two usages as `m = m_1 + r * m_2`.
+    pub fn fold(&self, other: &Self, challenge: F) -> Result<Self, SangriaError> {
+        if self.multiplicities.len() != other.multiplicities.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let multiplicities = self
+            .multiplicities
+            .iter()
+            .zip(&other.multiplicities)
+            .map(|(left, right)| {
+                if left.len() != right.len() {
+                    return Err(SangriaError::IndexOutOfBounds);
+                }
+
+                Ok(left
+                    .iter()
+                    .zip(right)
+                    .map(|(l, r)| *l + challenge * r)
+                    .collect())
+            })
+            .collect::<Result<Vec<_>, SangriaError>>()?;
+        let hidings = self
+            .hidings
+            .iter()
+            .zip(&other.hidings)
+            .map(|(l, r)| *l + challenge * r)
+            .collect();
+
+        Ok(Self {
+            multiplicities,
+            hidings,
+        })
+    }
+}
+
+/// Folds the commitments to two usages as `C = C_1 + r * C_2`, the verifier side of
+/// [`LookupUsage::fold`].
+pub fn fold_usage_commitments<F, CS>(
+    left: &[CS::Commitment],
+    right: &[CS::Commitment],
+    challenge: F,
+) -> Result<Vec<CS::Commitment>, SangriaError>
+where
+    F: PrimeField,
+    CS: HomomorphicCommitmentScheme<F>,
+{
+    if left.len() != right.len() {
+        return Err(SangriaError::IndexOutOfBounds);
+    }
+
+    Ok(left
+        .iter()
+        .zip(right)
+        .map(|(l, r)| [*l, *r * challenge].into_iter().sum())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::seeded_rng, vector_commitment::mock::FieldCommitment};
+    use ark_pallas::Fr;
+    use ark_std::UniformRand;
+
+    type Commitment = FieldCommitment<Fr>;
+
+    #[test]
+    fn folded_usage_matches_folded_commitments() {
+        let rng = &mut seeded_rng("lookup::folded_usage_matches_folded_commitments");
+        let table_size = 256;
+        let commit_key = Commitment::setup(rng, table_size);
+
+        let mut usage = || {
+            LookupUsage::new(
+                (0..2)
+                    .map(|_| (0..table_size).map(|_| Fr::rand(rng)).collect())
+                    .collect(),
+                vec![Fr::rand(rng), Fr::rand(rng)],
+            )
+            .unwrap()
+        };
+        let left = usage();
+        let right = usage();
+        let challenge = Fr::rand(rng);
+
+        let folded = left.fold(&right, challenge).unwrap();
+        let folded_commitments = fold_usage_commitments::<Fr, Commitment>(
+            &left.commit::<Commitment>(&commit_key).unwrap(),
+            &right.commit::<Commitment>(&commit_key).unwrap(),
+            challenge,
+        )
+        .unwrap();
+
+        assert_eq!(
+            folded.commit::<Commitment>(&commit_key).unwrap(),
+            folded_commitments
+        );
+    }
+}
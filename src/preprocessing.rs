@@ -0,0 +1,353 @@
+//! Circuit preprocessing. Everything the per-step prover needs about a [`PLONKCircuit`] is
+//! computed once when the circuit is encoded, instead of being derived again from its raw vectors
+//! at every step.
+//!
+//! The cell in wire `j` and row `i` of a circuit with `n` gates is identified by the field element
+//! `k_j * w^i`, where `w` generates the smallest radix-2 domain with at least `n` elements and
+//! `k_j = g^j` for the multiplicative generator `g` of the field. The copy constraints of a circuit
+//! list, for every cell in wire-major order, the identity of the cell it is copied to.
+//!
+//! [`CopyConstraints`] converts between this sigma encoding and the cycle notation, in which the
+//! cells holding the same value are listed together, and checks that either describes a
+//! permutation of the cells.
+
+use ark_ff::FftField;
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain,
+};
+use std::collections::HashMap;
+
+use crate::{domain::domain_for_size, PLONKCircuit, SangriaError};
+
+/// The number of wires (witness columns) of a PLONK gate.
+pub const NUMBER_OF_WIRES: usize = 3;
+
+/// Returns the coset shifts `k_j` that separate the cell identities of the different wires.
+pub fn wire_shifts<F: FftField>() -> [F; NUMBER_OF_WIRES] {
+    let g = F::multiplicative_generator();
+    [F::one(), g, g * g]
+}
+
+/// Returns the identities of the cells of a circuit with `number_of_gates` gates, in wire-major
+/// order. Copy constraints are permutations of this vector; the identity permutation has no copies.
+pub fn cell_identities<F: FftField>(number_of_gates: usize) -> Result<Vec<F>, SangriaError> {
+    let domain = domain_for_size::<F>(number_of_gates)?;
+
+    Ok(wire_shifts::<F>()
+        .iter()
+        .flat_map(|shift| {
+            domain
+                .elements()
+                .take(number_of_gates)
+                .map(move |element| *shift * element)
+        })
+        .collect())
+}
+
+/// The copy constraints of a circuit, as a permutation of its cells. Cell `wire * number_of_gates
+/// + row` is the cell of wire `wire` in row `row`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CopyConstraints {
+    number_of_gates: usize,
+    permutation: Vec<usize>,
+}
+
+impl CopyConstraints {
+    /// Returns the copy constraints of a circuit with `number_of_gates` gates and no copies.
+    pub fn identity(number_of_gates: usize) -> Self {
+        Self {
+            number_of_gates,
+            permutation: (0..NUMBER_OF_WIRES * number_of_gates).collect(),
+        }
+    }
+
+    /// Creates copy constraints from cycle notation: the cells of every cycle hold the same value
+    /// and each is mapped to the next one. Cells in no cycle are not copied. Returns
+    /// [`SangriaError::IndexOutOfBounds`] if a cell does not exist and
+    /// [`SangriaError::InvalidPermutation`] if a cell is in several cycles.
+    pub fn from_cycles(
+        number_of_gates: usize,
+        cycles: &[Vec<usize>],
+    ) -> Result<Self, SangriaError> {
+        let mut copy_constraints = Self::identity(number_of_gates);
+        let mut in_cycle = vec![false; copy_constraints.permutation.len()];
+        for cycle in cycles {
+            for (position, cell) in cycle.iter().enumerate() {
+                let seen = in_cycle
+                    .get_mut(*cell)
+                    .ok_or(SangriaError::IndexOutOfBounds)?;
+                if *seen {
+                    return Err(SangriaError::InvalidPermutation);
+                }
+                *seen = true;
+                copy_constraints.permutation[*cell] = cycle[(position + 1) % cycle.len()];
+            }
+        }
+
+        Ok(copy_constraints)
+    }
+
+    /// Decodes copy constraints from their sigma encoding, see the module documentation. Returns
+    /// [`SangriaError::InvalidPermutation`] if it is not a permutation of the cell identities.
+    pub fn from_sigma<F: FftField>(
+        number_of_gates: usize,
+        sigma: &[F],
+    ) -> Result<Self, SangriaError> {
+        let cells = cell_identities::<F>(number_of_gates)?
+            .into_iter()
+            .enumerate()
+            .map(|(cell, identity)| (identity, cell))
+            .collect::<HashMap<_, _>>();
+        if sigma.len() != cells.len() {
+            return Err(SangriaError::InvalidPermutation);
+        }
+
+        let permutation = sigma
+            .iter()
+            .map(|identity| cells.get(identity).copied())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(SangriaError::InvalidPermutation)?;
+        let copy_constraints = Self {
+            number_of_gates,
+            permutation,
+        };
+        copy_constraints.inverse()?;
+
+        Ok(copy_constraints)
+    }
+
+    /// Returns the sigma encoding of the copy constraints, see the module documentation.
+    pub fn to_sigma<F: FftField>(&self) -> Result<Vec<F>, SangriaError> {
+        let identities = cell_identities::<F>(self.number_of_gates)?;
+
+        Ok(self
+            .permutation
+            .iter()
+            .map(|target| identities[*target])
+            .collect())
+    }
+
+    /// Returns the copy constraints in cycle notation, omitting the cells that are not copied.
+    /// Cycles are ordered by their smallest cell, which comes first.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.permutation.len()];
+        let mut cycles = Vec::new();
+        for start in 0..self.permutation.len() {
+            if visited[start] || self.permutation[start] == start {
+                continue;
+            }
+
+            let mut cycle = Vec::new();
+            let mut cell = start;
+            while !visited[cell] {
+                visited[cell] = true;
+                cycle.push(cell);
+                cell = self.permutation[cell];
+            }
+            cycles.push(cycle);
+        }
+
+        cycles
+    }
+
+    /// Returns the number of gates of the circuit.
+    pub fn number_of_gates(&self) -> usize {
+        self.number_of_gates
+    }
+
+    /// Returns the map from every cell to the cell it is copied to.
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// Returns the inverse of [`Self::permutation`], or [`SangriaError::InvalidPermutation`] if
+    /// two cells are mapped to the same one.
+    fn inverse(&self) -> Result<Vec<usize>, SangriaError> {
+        let mut inverse_permutation = vec![usize::MAX; self.permutation.len()];
+        for (cell, target) in self.permutation.iter().enumerate() {
+            if inverse_permutation[*target] != usize::MAX {
+                return Err(SangriaError::InvalidPermutation);
+            }
+            inverse_permutation[*target] = cell;
+        }
+
+        Ok(inverse_permutation)
+    }
+}
+
+/// A circuit in evaluation-ready form, see the module documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreprocessedCircuit<F: FftField> {
+    domain: Radix2EvaluationDomain<F>,
+    number_of_gates: usize,
+    selector_evaluations: Vec<Evaluations<F, Radix2EvaluationDomain<F>>>,
+    permutation: Vec<usize>,
+    inverse_permutation: Vec<usize>,
+    identity_polynomials: Vec<DensePolynomial<F>>,
+}
+
+impl<F: FftField> PreprocessedCircuit<F> {
+    /// Preprocesses a circuit. Fails if its selectors do not all have the same length or if its
+    /// copy constraints are not a permutation of its cells.
+    pub fn new(circuit: &PLONKCircuit<F>) -> Result<Self, SangriaError> {
+        let number_of_gates = circuit.single_selector(0).map_or(0, <[F]>::len);
+        let domain = domain_for_size::<F>(number_of_gates)?;
+
+        let selector_evaluations = (0..circuit.number_of_selectors())
+            .map(|selector_index| {
+                let selector = circuit.single_selector(selector_index)?;
+                if selector.len() != number_of_gates {
+                    return Err(SangriaError::IndexOutOfBounds);
+                }
+
+                let mut evaluations = selector.to_vec();
+                evaluations.resize(domain.size(), F::zero());
+                Ok(Evaluations::from_vec_and_domain(evaluations, domain))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let copy_constraints =
+            CopyConstraints::from_sigma(number_of_gates, &circuit.copy_constraint())?;
+        let inverse_permutation = copy_constraints.inverse()?;
+        let permutation = copy_constraints.permutation;
+
+        let identity_polynomials = wire_shifts::<F>()
+            .iter()
+            .map(|shift| DensePolynomial {
+                coeffs: vec![F::zero(), *shift],
+            })
+            .collect();
+
+        Ok(Self {
+            domain,
+            number_of_gates,
+            selector_evaluations,
+            permutation,
+            inverse_permutation,
+            identity_polynomials,
+        })
+    }
+
+    /// Returns the evaluation domain of the circuit.
+    pub fn domain(&self) -> Radix2EvaluationDomain<F> {
+        self.domain
+    }
+
+    /// Returns the number of gates of the circuit.
+    pub fn number_of_gates(&self) -> usize {
+        self.number_of_gates
+    }
+
+    /// Returns the evaluations of a selector over the domain, padded with zeros, or an error if
+    /// index is out of bounds.
+    pub fn selector_evaluations(
+        &self,
+        selector_index: usize,
+    ) -> Result<&Evaluations<F, Radix2EvaluationDomain<F>>, SangriaError> {
+        self.selector_evaluations
+            .get(selector_index)
+            .ok_or(SangriaError::IndexOutOfBounds)
+    }
+
+    /// Returns the copy constraints as a map from every cell index (`wire * number_of_gates +
+    /// row`) to the index of the cell it is copied to.
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// Returns the inverse of [`Self::permutation`].
+    pub fn inverse_permutation(&self) -> &[usize] {
+        &self.inverse_permutation
+    }
+
+    /// Returns the identity polynomials `k_j * X`, one per wire.
+    pub fn identity_polynomials(&self) -> &[DensePolynomial<F>] {
+        &self.identity_polynomials
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seeded_rng;
+    use ark_pallas::Fr;
+    use ark_poly::Polynomial;
+    use ark_std::{rand::seq::SliceRandom, UniformRand};
+
+    #[test]
+    fn copy_constraints_are_mapped_to_cell_indices() {
+        let rng = &mut seeded_rng("preprocessing::copy_constraints_are_mapped_to_cell_indices");
+        let number_of_gates = 6;
+        let selectors = (0..5)
+            .map(|_| (0..number_of_gates).map(|_| Fr::rand(rng)).collect())
+            .collect::<Vec<Vec<Fr>>>();
+
+        let identities = cell_identities::<Fr>(number_of_gates).unwrap();
+        let mut permutation = (0..identities.len()).collect::<Vec<_>>();
+        permutation.shuffle(rng);
+        let copy_constraint = permutation.iter().map(|cell| identities[*cell]).collect();
+
+        let circuit = PLONKCircuit::new(selectors.clone(), copy_constraint);
+        let preprocessed = PreprocessedCircuit::new(&circuit).unwrap();
+
+        assert_eq!(preprocessed.domain().size(), 8);
+        assert_eq!(preprocessed.permutation(), permutation);
+        for (cell, target) in permutation.iter().enumerate() {
+            assert_eq!(preprocessed.inverse_permutation()[*target], cell);
+        }
+        for (selector_index, selector) in selectors.iter().enumerate() {
+            let evaluations = &preprocessed
+                .selector_evaluations(selector_index)
+                .unwrap()
+                .evals;
+            assert_eq!(&evaluations[..number_of_gates], selector.as_slice());
+        }
+        for (wire, identity_polynomial) in preprocessed.identity_polynomials().iter().enumerate() {
+            for (row, element) in preprocessed.domain().elements().take(3).enumerate() {
+                assert_eq!(
+                    identity_polynomial.evaluate(&element),
+                    identities[wire * number_of_gates + row]
+                );
+            }
+        }
+
+        let mut duplicated = identities.clone();
+        duplicated[1] = duplicated[0];
+        let circuit = PLONKCircuit::new(selectors.clone(), duplicated);
+        assert_eq!(
+            PreprocessedCircuit::new(&circuit),
+            Err(SangriaError::InvalidPermutation)
+        );
+
+        let circuit = PLONKCircuit::new(selectors, identities[1..].to_vec());
+        assert_eq!(
+            PreprocessedCircuit::new(&circuit),
+            Err(SangriaError::InvalidPermutation)
+        );
+    }
+
+    #[test]
+    fn cycle_notation_round_trips_through_sigma() {
+        let number_of_gates = 4;
+        let cycles = vec![vec![0, 5, 9], vec![2, 11]];
+        let copy_constraints = CopyConstraints::from_cycles(number_of_gates, &cycles).unwrap();
+        assert_eq!(copy_constraints.cycles(), cycles);
+        assert_eq!(copy_constraints.permutation()[9], 0);
+        assert_eq!(copy_constraints.permutation()[1], 1);
+
+        let sigma = copy_constraints.to_sigma::<Fr>().unwrap();
+        assert_eq!(
+            CopyConstraints::from_sigma(number_of_gates, &sigma),
+            Ok(copy_constraints)
+        );
+
+        assert_eq!(
+            CopyConstraints::from_cycles(number_of_gates, &[vec![0, 5], vec![5, 6]]),
+            Err(SangriaError::InvalidPermutation)
+        );
+        assert_eq!(
+            CopyConstraints::from_cycles(number_of_gates, &[vec![0, 12]]),
+            Err(SangriaError::IndexOutOfBounds)
+        );
+    }
+}
@@ -0,0 +1,689 @@
+//! The relaxed R1CS relation of Nova, as a [`FoldableRelation`], for comparing folding costs
+//! with relaxed PLONK and for migrating R1CS circuits. An R1CS with matrices `A, B, C` over the
+//! vector `z = (W, u, x)` of witness `W`, scaling factor `u` and public inputs `x` is satisfied
+//! with slack vector `E` if
+//!
+//! `(A * z) o (B * z) = u * (C * z) + E`,
+//!
+//! where `o` is the entry-wise product. Folding two pairs with challenge `r` yields the cross term
+//!
+//! `T = (A * z_1) o (B * z_2) + (A * z_2) o (B * z_1) - u_1 * (C * z_2) - u_2 * (C * z_1)`.
+//!
+//! [`R1CSShape::from_plonk`] bridges relaxed PLONK to relaxed R1CS, e.g. for deciders that
+//! prove R1CS: the gate `u * (q_L * a + q_R * b + q_O * c) + q_M * a * b + u^2 * q_C = E` is the
+//! constraint `(q_M * a) * b = u * (-(q_L * a + q_R * b + q_O * c) - q_C * u) + E`, and the copy
+//! of a cell `s` to a cell `t` is the constraint `(s - t) * u = 0`. The scaling factor and the
+//! gate slack carry over unchanged, so a folded PLONK pair is bridged without unfolding it.
+
+use ark_ff::{One, PrimeField, Zero};
+use ark_serialize::CanonicalSerialize;
+use ark_sponge::Absorb;
+
+use crate::{
+    foldable_relation::FoldableRelation,
+    preprocessing::{PreprocessedCircuit, NUMBER_OF_WIRES},
+    relations::{FoldingCommitmentConfig, HomomorphicCommitmentScheme},
+    PLONKCircuit, RelaxedPLONKWitness, SangriaError, VerificationFailure, CONSTANT_SELECTOR_INDEX,
+    LEFT_SELECTOR_INDEX, MULTIPLICATION_SELECTOR_INDEX, OUTPUT_SELECTOR_INDEX,
+    RIGHT_SELECTOR_INDEX,
+};
+
+/// A sparse matrix, as its non-zero entries `(row, column, value)`.
+pub type SparseMatrix<F> = Vec<(usize, usize, F)>;
+
+/// The matrices of an R1CS and its dimensions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1CSShape<F: PrimeField> {
+    number_of_constraints: usize,
+    number_of_witnesses: usize,
+    number_of_public_inputs: usize,
+    a: SparseMatrix<F>,
+    b: SparseMatrix<F>,
+    c: SparseMatrix<F>,
+}
+
+impl<F: PrimeField> R1CSShape<F> {
+    /// Creates an R1CS with `number_of_constraints` rows and columns indexed by
+    /// `z = (W, u, x)`, i.e. `number_of_witnesses + 1 + number_of_public_inputs` columns. Returns
+    /// an error if an entry of a matrix is out of bounds.
+    pub fn new(
+        number_of_constraints: usize,
+        number_of_witnesses: usize,
+        number_of_public_inputs: usize,
+        a: SparseMatrix<F>,
+        b: SparseMatrix<F>,
+        c: SparseMatrix<F>,
+    ) -> Result<Self, SangriaError> {
+        let number_of_columns = number_of_witnesses + 1 + number_of_public_inputs;
+        if [&a, &b, &c].iter().any(|matrix| {
+            matrix.iter().any(|(row, column, _)| {
+                *row >= number_of_constraints || *column >= number_of_columns
+            })
+        }) {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        Ok(Self {
+            number_of_constraints,
+            number_of_witnesses,
+            number_of_public_inputs,
+            a,
+            b,
+            c,
+        })
+    }
+
+    /// Creates the R1CS of the relaxed PLONK relation of `circuit`, see the module
+    /// documentation. The witness is the concatenation of the wire columns, see
+    /// [`RelaxedR1CSWitness::from_plonk`], and there are no public inputs. The first constraints
+    /// are the gates, in order, followed by one constraint per cell that is copied to another
+    /// one. Fails if the circuit cannot be preprocessed.
+    pub fn from_plonk(circuit: &PLONKCircuit<F>) -> Result<Self, SangriaError> {
+        let preprocessed_circuit = PreprocessedCircuit::new(circuit)?;
+        let number_of_gates = preprocessed_circuit.number_of_gates();
+        let scaling_factor = NUMBER_OF_WIRES * number_of_gates;
+        let selector = |selector_index| circuit.single_selector(selector_index);
+        let (q_l, q_r, q_o) = (
+            selector(LEFT_SELECTOR_INDEX)?,
+            selector(RIGHT_SELECTOR_INDEX)?,
+            selector(OUTPUT_SELECTOR_INDEX)?,
+        );
+        let (q_m, q_c) = (
+            selector(MULTIPLICATION_SELECTOR_INDEX)?,
+            selector(CONSTANT_SELECTOR_INDEX)?,
+        );
+
+        let (mut a, mut b, mut c) = (Vec::new(), Vec::new(), Vec::new());
+        for row in 0..number_of_gates {
+            let [left, right, output] = [0, 1, 2].map(|wire| wire * number_of_gates + row);
+            a.push((row, left, q_m[row]));
+            b.push((row, right, F::one()));
+            c.extend([
+                (row, left, -q_l[row]),
+                (row, right, -q_r[row]),
+                (row, output, -q_o[row]),
+                (row, scaling_factor, -q_c[row]),
+            ]);
+        }
+
+        let mut number_of_constraints = number_of_gates;
+        for (cell, target) in preprocessed_circuit.permutation().iter().enumerate() {
+            if cell != *target {
+                a.extend([
+                    (number_of_constraints, cell, F::one()),
+                    (number_of_constraints, *target, -F::one()),
+                ]);
+                b.push((number_of_constraints, scaling_factor, F::one()));
+                number_of_constraints += 1;
+            }
+        }
+        // Selectors are mostly zero.
+        for matrix in [&mut a, &mut b, &mut c] {
+            matrix.retain(|(_, _, value)| !value.is_zero());
+        }
+
+        Self::new(number_of_constraints, scaling_factor, 0, a, b, c)
+    }
+
+    /// The number of constraints, i.e. the length of the slack vector.
+    pub fn number_of_constraints(&self) -> usize {
+        self.number_of_constraints
+    }
+
+    /// The length of the witness `W`.
+    pub fn number_of_witnesses(&self) -> usize {
+        self.number_of_witnesses
+    }
+
+    /// The number of public inputs `x`.
+    pub fn number_of_public_inputs(&self) -> usize {
+        self.number_of_public_inputs
+    }
+
+    /// Returns the matrices `A, B, C`.
+    pub fn matrices(&self) -> [&SparseMatrix<F>; 3] {
+        [&self.a, &self.b, &self.c]
+    }
+
+    /// Returns `(A * z, B * z, C * z)` for `z = (W, u, x)`, or an error if the witness or the
+    /// public inputs do not have the dimensions of the R1CS.
+    pub(crate) fn products(
+        &self,
+        witness: &[F],
+        scaling_factor: F,
+        public_inputs: &[F],
+    ) -> Result<[Vec<F>; 3], SangriaError> {
+        if witness.len() != self.number_of_witnesses
+            || public_inputs.len() != self.number_of_public_inputs
+        {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let z = witness
+            .iter()
+            .copied()
+            .chain(ark_std::iter::once(scaling_factor))
+            .chain(public_inputs.iter().copied())
+            .collect::<Vec<_>>();
+        let product = |matrix: &SparseMatrix<F>| {
+            let mut result = vec![F::zero(); self.number_of_constraints];
+            for (row, column, value) in matrix {
+                result[*row] += *value * z[*column];
+            }
+            result
+        };
+
+        Ok([product(&self.a), product(&self.b), product(&self.c)])
+    }
+}
+
+/// A committed relaxed R1CS instance: the public inputs, the scaling factor and the commitments
+/// to the witness and to the slack vector.
+pub struct RelaxedR1CSInstance<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    public_inputs: Vec<F>,
+    scaling_factor: F,
+    witness_commitment: <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::Commitment,
+    slack_commitment: <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+}
+
+impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> RelaxedR1CSInstance<F, Comm> {
+    /// Creates a relaxed R1CS instance.
+    pub fn new(
+        public_inputs: Vec<F>,
+        scaling_factor: F,
+        witness_commitment: <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::Commitment,
+        slack_commitment: <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+    ) -> Self {
+        Self {
+            public_inputs,
+            scaling_factor,
+            witness_commitment,
+            slack_commitment,
+        }
+    }
+
+    /// Returns the public inputs.
+    pub fn public_inputs(&self) -> &[F] {
+        &self.public_inputs
+    }
+
+    /// Returns the scaling factor.
+    pub fn scaling_factor(&self) -> F {
+        self.scaling_factor
+    }
+
+    /// Returns the commitment to the witness.
+    pub fn witness_commitment(
+        &self,
+    ) -> <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::Commitment {
+        self.witness_commitment
+    }
+
+    /// Returns the commitment to the slack vector.
+    pub fn slack_commitment(
+        &self,
+    ) -> <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment {
+        self.slack_commitment
+    }
+}
+
+impl<F, Comm> Clone for RelaxedR1CSInstance<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            public_inputs: self.public_inputs.clone(),
+            scaling_factor: self.scaling_factor,
+            witness_commitment: self.witness_commitment,
+            slack_commitment: self.slack_commitment,
+        }
+    }
+}
+
+/// Commitments are compared by their canonical representation, as for
+/// [`crate::RelaxedPLONKInstance`].
+impl<F, Comm> PartialEq for RelaxedR1CSInstance<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.public_inputs == other.public_inputs
+            && self.scaling_factor == other.scaling_factor
+            && Comm::CommitmentWitness::canonicalize(self.witness_commitment)
+                == Comm::CommitmentWitness::canonicalize(other.witness_commitment)
+            && Comm::CommitmentSlack::canonicalize(self.slack_commitment)
+                == Comm::CommitmentSlack::canonicalize(other.slack_commitment)
+    }
+}
+
+impl<F, Comm> Absorb for RelaxedR1CSInstance<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        dest.extend(self.public_scalars_bytes());
+        Comm::CommitmentWitness::canonicalize(self.witness_commitment).to_sponge_bytes(dest);
+        Comm::CommitmentSlack::canonicalize(self.slack_commitment).to_sponge_bytes(dest);
+    }
+
+    fn to_sponge_field_elements<SpongeF: PrimeField>(&self, dest: &mut Vec<SpongeF>) {
+        self.public_scalars_bytes().to_sponge_field_elements(dest);
+        Comm::CommitmentWitness::canonicalize(self.witness_commitment)
+            .to_sponge_field_elements(dest);
+        Comm::CommitmentSlack::canonicalize(self.slack_commitment).to_sponge_field_elements(dest);
+    }
+}
+
+impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> RelaxedR1CSInstance<F, Comm> {
+    /// Serializes the public inputs and the scaling factor, whose field is not necessarily the
+    /// sponge's field.
+    fn public_scalars_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.public_inputs
+            .serialize(&mut bytes)
+            .and_then(|_| self.scaling_factor.serialize(&mut bytes))
+            .expect("serialization into a vector cannot fail");
+        bytes
+    }
+}
+
+/// A relaxed R1CS witness: the witness `W`, the slack vector `E` and the hiding randomness of
+/// their commitments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelaxedR1CSWitness<F: PrimeField> {
+    witness: Vec<F>,
+    slack_vector: Vec<F>,
+    witness_hiding: F,
+    slack_hiding: F,
+}
+
+impl<F: PrimeField> RelaxedR1CSWitness<F> {
+    /// Creates a relaxed R1CS witness.
+    pub fn new(witness: Vec<F>, slack_vector: Vec<F>, witness_hiding: F, slack_hiding: F) -> Self {
+        Self {
+            witness,
+            slack_vector,
+            witness_hiding,
+            slack_hiding,
+        }
+    }
+
+    /// Bridges a relaxed PLONK witness to the R1CS of [`R1CSShape::from_plonk`]: the witness is
+    /// the concatenation of the wire columns and the slack vector is the gate slack, padded with
+    /// zeros for the copy constraints. The PLONK commitments commit to the columns separately,
+    /// so the witness is committed to again, with zero randomness here. Fails if the witness
+    /// does not have the dimensions of `shape`.
+    pub fn from_plonk(
+        shape: &R1CSShape<F>,
+        witness: &RelaxedPLONKWitness<F>,
+    ) -> Result<Self, SangriaError> {
+        let columns = (0..NUMBER_OF_WIRES)
+            .map(|column_index| witness.witness_column(column_index))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut slack_vector = witness.slack_vector();
+        let number_of_gates = shape.number_of_witnesses / NUMBER_OF_WIRES;
+        if columns.iter().any(|column| column.len() != number_of_gates)
+            || slack_vector.len() != number_of_gates
+            || number_of_gates * NUMBER_OF_WIRES != shape.number_of_witnesses
+        {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+        slack_vector.resize(shape.number_of_constraints, F::zero());
+
+        Ok(Self::new(
+            columns.concat(),
+            slack_vector,
+            F::zero(),
+            F::zero(),
+        ))
+    }
+
+    /// Returns the witness `W`.
+    pub fn witness(&self) -> &[F] {
+        &self.witness
+    }
+
+    /// Returns the slack vector.
+    pub fn slack_vector(&self) -> &[F] {
+        &self.slack_vector
+    }
+
+    /// Returns the hiding randomness of the witness commitment.
+    pub fn witness_hiding(&self) -> F {
+        self.witness_hiding
+    }
+
+    /// Returns the hiding randomness of the slack commitment.
+    pub fn slack_hiding(&self) -> F {
+        self.slack_hiding
+    }
+}
+
+/// The relaxed R1CS relation, see the module documentation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct R1CSRelation;
+
+impl<F, Comm> FoldableRelation<F, Comm> for R1CSRelation
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    type Structure = R1CSShape<F>;
+    type Instance = RelaxedR1CSInstance<F, Comm>;
+    type Witness = RelaxedR1CSWitness<F>;
+
+    fn is_strict(instance: &Self::Instance, witness: &Self::Witness) -> bool {
+        instance.scaling_factor.is_one()
+            && Comm::CommitmentSlack::canonicalize(instance.slack_commitment).is_zero()
+            && witness.slack_hiding.is_zero()
+            && witness.slack_vector.iter().all(Zero::is_zero)
+    }
+
+    fn cross_term(
+        structure: &Self::Structure,
+        left_instance: &Self::Instance,
+        left_witness: &Self::Witness,
+        right_instance: &Self::Instance,
+        right_witness: &Self::Witness,
+    ) -> Result<Vec<F>, SangriaError> {
+        let (u_1, u_2) = (left_instance.scaling_factor, right_instance.scaling_factor);
+        let [a_1, b_1, c_1] =
+            structure.products(&left_witness.witness, u_1, &left_instance.public_inputs)?;
+        let [a_2, b_2, c_2] =
+            structure.products(&right_witness.witness, u_2, &right_instance.public_inputs)?;
+
+        Ok((0..structure.number_of_constraints)
+            .map(|i| a_1[i] * b_2[i] + a_2[i] * b_1[i] - u_1 * c_2[i] - u_2 * c_1[i])
+            .collect())
+    }
+
+    fn fold_instances(
+        left: &Self::Instance,
+        right: &Self::Instance,
+        cross_term_commitment: &<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        challenge: F,
+    ) -> Result<Self::Instance, SangriaError> {
+        if left.public_inputs.len() != right.public_inputs.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        Ok(RelaxedR1CSInstance::new(
+            fold_vectors(&left.public_inputs, &right.public_inputs, challenge),
+            left.scaling_factor + challenge * right.scaling_factor,
+            [
+                left.witness_commitment,
+                right.witness_commitment * challenge,
+            ]
+            .into_iter()
+            .sum(),
+            [
+                left.slack_commitment,
+                *cross_term_commitment * challenge,
+                right.slack_commitment * challenge.square(),
+            ]
+            .into_iter()
+            .sum(),
+        ))
+    }
+
+    fn fold_witnesses(
+        left: &Self::Witness,
+        right: &Self::Witness,
+        cross_term: &[F],
+        cross_term_hiding: F,
+        challenge: F,
+    ) -> Result<Self::Witness, SangriaError> {
+        if left.witness.len() != right.witness.len()
+            || left.slack_vector.len() != right.slack_vector.len()
+            || left.slack_vector.len() != cross_term.len()
+        {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let challenge_squared = challenge.square();
+        let slack_vector = left
+            .slack_vector
+            .iter()
+            .zip(cross_term)
+            .zip(&right.slack_vector)
+            .map(|((e_1, t), e_2)| *e_1 + challenge * t + challenge_squared * e_2)
+            .collect();
+
+        Ok(RelaxedR1CSWitness::new(
+            fold_vectors(&left.witness, &right.witness, challenge),
+            slack_vector,
+            left.witness_hiding + challenge * right.witness_hiding,
+            left.slack_hiding
+                + challenge * cross_term_hiding
+                + challenge_squared * right.slack_hiding,
+        ))
+    }
+
+    fn check(
+        structure: &Self::Structure,
+        instance: &Self::Instance,
+        witness: &Self::Witness,
+    ) -> Result<(), SangriaError> {
+        if witness.slack_vector.len() != structure.number_of_constraints {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let [a, b, c] = structure.products(
+            &witness.witness,
+            instance.scaling_factor,
+            &instance.public_inputs,
+        )?;
+        if let Some(row) = (0..structure.number_of_constraints)
+            .find(|i| a[*i] * b[*i] != instance.scaling_factor * c[*i] + witness.slack_vector[*i])
+        {
+            return Err(VerificationFailure::RelaxedRelation
+                .reject(|| format!("constraint {} does not hold", row)));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `left + r * right`.
+fn fold_vectors<F: PrimeField>(left: &[F], right: &[F], challenge: F) -> Vec<F> {
+    left.iter()
+        .zip(right)
+        .map(|(l, r)| *l + challenge * r)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        preprocessing::cell_identities,
+        test_utils::seeded_rng,
+        vector_commitment::mock::{FieldCommitment, FieldCommitmentConfig},
+        PLONKWitness,
+    };
+    use ark_pallas::Fr;
+    use ark_std::UniformRand;
+
+    type Comm = FieldCommitmentConfig<Fr>;
+
+    #[test]
+    fn folded_r1cs_pairs_satisfy_the_relation_and_open_their_commitments() {
+        let rng = &mut seeded_rng(
+            "r1cs::folded_r1cs_pairs_satisfy_the_relation_and_open_their_commitments",
+        );
+
+        // w * w = x and (w + 1) * w = y, over z = (w, u, x, y).
+        let one = Fr::one();
+        let shape = R1CSShape::new(
+            2,
+            1,
+            2,
+            vec![(0, 0, one), (1, 0, one), (1, 1, one)],
+            vec![(0, 0, one), (1, 0, one)],
+            vec![(0, 2, one), (1, 3, one)],
+        )
+        .unwrap();
+        assert_eq!(
+            R1CSShape::new(2, 1, 2, vec![(2, 0, one)], vec![], vec![]),
+            Err(SangriaError::IndexOutOfBounds)
+        );
+
+        let witness_key = FieldCommitment::<Fr>::setup(rng, 1);
+        let slack_key = FieldCommitment::<Fr>::setup(rng, 2);
+        let strict = |w: Fr, rng: &mut _| {
+            let hiding = Fr::rand(rng);
+            let instance = RelaxedR1CSInstance::<Fr, Comm>::new(
+                vec![w * w, (w + one) * w],
+                one,
+                FieldCommitment::commit(&witness_key, &[w], hiding).unwrap(),
+                Fr::zero(),
+            );
+            (
+                instance,
+                RelaxedR1CSWitness::new(vec![w], vec![Fr::zero(); 2], hiding, Fr::zero()),
+            )
+        };
+
+        let (left_instance, left_witness) = strict(Fr::from(3u64), rng);
+        let (right_instance, right_witness) = strict(Fr::from(5u64), rng);
+        assert!(<R1CSRelation as FoldableRelation<Fr, Comm>>::is_strict(
+            &left_instance,
+            &left_witness
+        ));
+        assert_eq!(
+            <R1CSRelation as FoldableRelation<Fr, Comm>>::check(
+                &shape,
+                &left_instance,
+                &left_witness
+            ),
+            Ok(())
+        );
+
+        let cross_term = <R1CSRelation as FoldableRelation<Fr, Comm>>::cross_term(
+            &shape,
+            &left_instance,
+            &left_witness,
+            &right_instance,
+            &right_witness,
+        )
+        .unwrap();
+        // w_1 * w_2 + w_2 * w_1 - x_2 - x_1 = 30 - 25 - 9 for the first constraint.
+        assert_eq!(cross_term[0], Fr::from(4u64) - Fr::from(8u64));
+
+        let (cross_term_hiding, challenge) = (Fr::rand(rng), Fr::rand(rng));
+        let cross_term_commitment =
+            FieldCommitment::commit(&slack_key, &cross_term, cross_term_hiding).unwrap();
+        let instance = <R1CSRelation as FoldableRelation<Fr, Comm>>::fold_instances(
+            &left_instance,
+            &right_instance,
+            &cross_term_commitment,
+            challenge,
+        )
+        .unwrap();
+        let witness = <R1CSRelation as FoldableRelation<Fr, Comm>>::fold_witnesses(
+            &left_witness,
+            &right_witness,
+            &cross_term,
+            cross_term_hiding,
+            challenge,
+        )
+        .unwrap();
+
+        assert!(!<R1CSRelation as FoldableRelation<Fr, Comm>>::is_strict(
+            &instance, &witness
+        ));
+        assert_eq!(
+            <R1CSRelation as FoldableRelation<Fr, Comm>>::check(&shape, &instance, &witness),
+            Ok(())
+        );
+        assert_eq!(
+            FieldCommitment::commit(&witness_key, witness.witness(), witness.witness_hiding()),
+            Ok(instance.witness_commitment())
+        );
+        assert_eq!(
+            FieldCommitment::commit(&slack_key, witness.slack_vector(), witness.slack_hiding()),
+            Ok(instance.slack_commitment())
+        );
+
+        let mut tampered = instance.clone();
+        tampered.scaling_factor += one;
+        assert_eq!(
+            <R1CSRelation as FoldableRelation<Fr, Comm>>::check(&shape, &tampered, &witness),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::RelaxedRelation
+            ))
+        );
+    }
+
+    #[test]
+    fn relaxed_plonk_pairs_are_bridged_to_relaxed_r1cs() {
+        let rng = &mut seeded_rng("r1cs::relaxed_plonk_pairs_are_bridged_to_relaxed_r1cs");
+        let number_of_gates = 4;
+
+        // c = a + b at every gate, and the output of each gate is the left input of the next.
+        let (zero, one) = (Fr::zero(), Fr::one());
+        let constant = |value: Fr| vec![value; number_of_gates];
+        let selectors = vec![
+            constant(one),
+            constant(one),
+            constant(-one),
+            constant(zero),
+            constant(zero),
+        ];
+        let mut copy_constraint = cell_identities::<Fr>(number_of_gates).unwrap();
+        for row in 0..number_of_gates - 1 {
+            copy_constraint.swap(2 * number_of_gates + row, row + 1);
+        }
+        let circuit = PLONKCircuit::new(selectors, copy_constraint);
+        let shape = R1CSShape::from_plonk(&circuit).unwrap();
+        assert_eq!(shape.number_of_witnesses(), 3 * number_of_gates);
+        // Every copy is a cycle of two cells.
+        assert_eq!(
+            shape.number_of_constraints(),
+            number_of_gates + 2 * (number_of_gates - 1)
+        );
+
+        // A relaxed pair: the wires are chained, but the gates only hold up to the slack.
+        let scaling_factor = Fr::rand(rng);
+        let c = (0..number_of_gates)
+            .map(|_| Fr::rand(rng))
+            .collect::<Vec<_>>();
+        let a = [vec![Fr::rand(rng)], c[..number_of_gates - 1].to_vec()].concat();
+        let b = constant(one);
+        let slack_vector = (0..number_of_gates)
+            .map(|i| scaling_factor * (a[i] + b[i] - c[i]))
+            .collect::<Vec<_>>();
+        let bridge = |a: &[Fr]| {
+            let witness = RelaxedPLONKWitness::new(
+                PLONKWitness::new(vec![a.to_vec(), b.clone(), c.clone()]),
+                slack_vector.clone(),
+                vec![zero; 3],
+                zero,
+            );
+            RelaxedR1CSWitness::from_plonk(&shape, &witness).unwrap()
+        };
+        let instance = RelaxedR1CSInstance::<Fr, Comm>::new(vec![], scaling_factor, zero, zero);
+
+        assert_eq!(
+            <R1CSRelation as FoldableRelation<Fr, Comm>>::check(&shape, &instance, &bridge(&a)),
+            Ok(())
+        );
+
+        let mut unlinked_a = a.clone();
+        unlinked_a[number_of_gates - 1] += one;
+        assert_eq!(
+            <R1CSRelation as FoldableRelation<Fr, Comm>>::check(
+                &shape,
+                &instance,
+                &bridge(&unlinked_a)
+            ),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::RelaxedRelation
+            ))
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! A registry of verifier keys indexed by their digest, for services that verify proofs of many
+//! circuits. Proofs are routed to their key by the digest they claim, and the registry can be
+//! stored and loaded as a whole. Digests are recomputed when keys are loaded, never read from
+//! the serialized registry.
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{
+    serialization::{deserialize_with_mode, serialize_with_mode, SerializationMode},
+    IVCWithVerifierKeyDigest, SangriaError, StepCircuit,
+};
+
+/// Verifier keys of an IVC scheme, indexed by [`IVCWithVerifierKeyDigest::verifier_key_digest`].
+pub struct VerifierRegistry<F, SC, I>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    I: IVCWithVerifierKeyDigest<F, SC>,
+{
+    verifier_keys: HashMap<I::VerifierKeyDigest, I::VerifierKey>,
+    _marker: PhantomData<fn() -> (F, SC)>,
+}
+
+impl<F, SC, I> Default for VerifierRegistry<F, SC, I>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    I: IVCWithVerifierKeyDigest<F, SC>,
+{
+    fn default() -> Self {
+        Self {
+            verifier_keys: HashMap::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F, SC, I> VerifierRegistry<F, SC, I>
+where
+    F: PrimeField,
+    SC: StepCircuit<F>,
+    I: IVCWithVerifierKeyDigest<F, SC>,
+    I::VerifierKeyDigest: Clone + Hash + Eq,
+{
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of registered verifier keys.
+    pub fn len(&self) -> usize {
+        self.verifier_keys.len()
+    }
+
+    /// Returns true if no verifier key is registered.
+    pub fn is_empty(&self) -> bool {
+        self.verifier_keys.is_empty()
+    }
+
+    /// Registers a verifier key and returns its digest. A key with the same digest is replaced.
+    pub fn register(&mut self, verifier_key: I::VerifierKey) -> I::VerifierKeyDigest {
+        let digest = I::verifier_key_digest(&verifier_key);
+        self.verifier_keys.insert(digest.clone(), verifier_key);
+        digest
+    }
+
+    /// Returns the verifier key with the given digest, if it is registered.
+    pub fn get(&self, digest: &I::VerifierKeyDigest) -> Option<&I::VerifierKey> {
+        self.verifier_keys.get(digest)
+    }
+
+    /// Unregisters and returns the verifier key with the given digest, if it is registered.
+    pub fn remove(&mut self, digest: &I::VerifierKeyDigest) -> Option<I::VerifierKey> {
+        self.verifier_keys.remove(digest)
+    }
+
+    /// Verifies a proof with the verifier key registered under `digest`, see [`crate::IVC::verify`].
+    pub fn verify_by_digest(
+        &self,
+        digest: &I::VerifierKeyDigest,
+        number_of_steps: usize,
+        origin_state: &SC::State,
+        current_state: SC::State,
+        current_proof: Option<I::Proof>,
+    ) -> Result<(), SangriaError> {
+        let verifier_key = self.get(digest).ok_or(SangriaError::UnknownVerifierKey)?;
+
+        I::verify(
+            verifier_key,
+            number_of_steps,
+            origin_state,
+            current_state,
+            current_proof,
+        )
+    }
+
+    /// Serializes the registered verifier keys, in no particular order.
+    pub fn to_bytes(&self, mode: SerializationMode) -> Result<Vec<u8>, SangriaError>
+    where
+        I::VerifierKey: CanonicalSerialize,
+    {
+        let mut bytes = Vec::new();
+        serialize_with_mode(&(self.len() as u64), &mut bytes, mode)?;
+        for verifier_key in self.verifier_keys.values() {
+            serialize_with_mode(verifier_key, &mut bytes, mode)?;
+        }
+
+        Ok(bytes)
+    }
+
+    /// Deserializes a registry serialized with the same `mode` and re-derives the digests.
+    pub fn from_bytes(bytes: &[u8], mode: SerializationMode) -> Result<Self, SangriaError>
+    where
+        I::VerifierKey: CanonicalDeserialize,
+    {
+        let mut reader = bytes;
+        let number_of_keys: u64 = deserialize_with_mode(&mut reader, mode)?;
+
+        let mut registry = Self::new();
+        for _ in 0..number_of_keys {
+            registry.register(deserialize_with_mode(&mut reader, mode)?);
+        }
+        if !reader.is_empty() {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(registry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::seeded_rng, VerificationFailure, IVC};
+    use ark_pallas::Fr;
+    use ark_std::{rand::Rng, UniformRand};
+
+    /// The step circuit `z_{i+1} = z_i + k`, where `k` is the verifier key.
+    struct AddKey;
+
+    impl StepCircuit<Fr> for AddKey {
+        type State = Fr;
+        type Witness = ();
+        type Outputs = ();
+
+        fn witness_arity(&self) -> usize {
+            0
+        }
+
+        fn witness_elements(_witness: &()) -> Vec<Fr> {
+            Vec::new()
+        }
+
+        fn output_elements(_outputs: &()) -> Vec<Fr> {
+            Vec::new()
+        }
+
+        fn state_elements(state: &Fr) -> Vec<Fr> {
+            vec![*state]
+        }
+    }
+
+    /// A stand-in IVC scheme without proofs whose verifier recomputes the state.
+    struct AddKeyIVC;
+
+    impl IVC<Fr, AddKey> for AddKeyIVC {
+        type PublicParameters = ();
+        type ProverKey = Fr;
+        type VerifierKey = Fr;
+        type Proof = ();
+
+        fn setup<R: Rng>(_rng: &mut R) -> Result<(), SangriaError> {
+            Ok(())
+        }
+
+        fn encode<R: Rng>(
+            _public_parameters: &(),
+            _step_circuit: &AddKey,
+            rng: &mut R,
+        ) -> Result<(Fr, Fr), SangriaError> {
+            let key = Fr::rand(rng);
+            Ok((key, key))
+        }
+
+        fn prove_step(
+            prover_key: &Fr,
+            _origin_state: &Fr,
+            current_state: Fr,
+            _current_proof: Option<()>,
+            _current_witness: &(),
+        ) -> Result<(Fr, ()), SangriaError> {
+            Ok((current_state + prover_key, ()))
+        }
+
+        fn verify(
+            verifier_key: &Fr,
+            number_of_steps: usize,
+            origin_state: &Fr,
+            current_state: Fr,
+            _current_proof: Option<()>,
+        ) -> Result<(), SangriaError> {
+            if *origin_state + Fr::from(number_of_steps as u64) * verifier_key != current_state {
+                return Err(SangriaError::VerificationFailed(
+                    VerificationFailure::RelaxedRelation,
+                ));
+            }
+
+            Ok(())
+        }
+    }
+
+    impl IVCWithVerifierKeyDigest<Fr, AddKey> for AddKeyIVC {
+        type VerifierKeyDigest = Fr;
+
+        fn verifier_key_digest(verifier_key: &Fr) -> Fr {
+            *verifier_key * verifier_key
+        }
+
+        fn verify_with_verifier_key_digest(
+            _verifier_key_digest: &Fr,
+            _number_of_steps: usize,
+            _origin_state: &Fr,
+            _current_state: Fr,
+            _current_proof: Option<()>,
+        ) -> Result<(), SangriaError> {
+            Err(SangriaError::NotImplemented("IVC verifier"))
+        }
+    }
+
+    #[test]
+    fn proofs_are_verified_with_the_key_of_their_digest() {
+        let rng = &mut seeded_rng("registry::proofs_are_verified_with_the_key_of_their_digest");
+        let mut registry = VerifierRegistry::<Fr, AddKey, AddKeyIVC>::new();
+        let keys = (0..4)
+            .map(|_| AddKeyIVC::encode(&(), &AddKey, rng).unwrap())
+            .collect::<Vec<_>>();
+        let digests = keys
+            .iter()
+            .map(|(_, verifier_key)| registry.register(*verifier_key))
+            .collect::<Vec<_>>();
+
+        let registry = VerifierRegistry::<Fr, AddKey, AddKeyIVC>::from_bytes(
+            &registry.to_bytes(SerializationMode::Compressed).unwrap(),
+            SerializationMode::Compressed,
+        )
+        .unwrap();
+        assert_eq!(registry.len(), keys.len());
+
+        let origin_state = Fr::rand(rng);
+        for ((prover_key, _), digest) in keys.iter().zip(&digests) {
+            let (state, proof) =
+                AddKeyIVC::prove_step(prover_key, &origin_state, origin_state, None, &()).unwrap();
+            registry
+                .verify_by_digest(digest, 1, &origin_state, state, Some(proof))
+                .unwrap();
+            assert!(registry
+                .verify_by_digest(digest, 1, &origin_state, state + Fr::from(1u64), None)
+                .is_err());
+        }
+
+        assert_eq!(
+            registry.verify_by_digest(&Fr::from(0u64), 0, &origin_state, origin_state, None),
+            Err(SangriaError::UnknownVerifierKey)
+        );
+    }
+}
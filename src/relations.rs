@@ -0,0 +1,417 @@
+//! The relaxed PLONK relation and its folding scheme, usable without IVC, e.g. to aggregate
+//! proofs of independent statements incrementally. Unlike [`crate::StepCircuit`], nothing here
+//! assumes a state that is carried from step to step.
+//!
+//! A circuit `C` with `n` gates has selectors `q_L, q_R, q_O, q_M, q_C` and copy constraints
+//! (see [`crate::preprocessing`]). A relaxed instance-witness pair with wire columns `a, b, c`,
+//! scaling factor `u` and slack vector `E` satisfies the relation if its commitments open to the
+//! witness, the copy constraints hold and, for every gate `i`,
+//!
+//! `u * (q_L[i] * a[i] + q_R[i] * b[i] + q_O[i] * c[i]) + q_M[i] * a[i] * b[i] + u^2 * q_C[i] = E[i]`.
+//!
+//! A PLONK witness satisfies the (unrelaxed) relation if its relaxation, with `u = 1` and `E = 0`,
+//! does. The flow is:
+//! 1. [`PLONKFoldingScheme::setup`] and [`PLONKFoldingScheme::encode`] the circuit,
+//! 2. [`relax`] every statement into a relaxed instance-witness pair,
+//! 3. fold pairs two at a time with [`PLONKFoldingScheme::prover`], the verifier folding the
+//!    instances with [`PLONKFoldingScheme::verifier`],
+//! 4. [`decide`] the final pair once, which vouches for every statement that was folded into it.
+//!
+//! Statements that share static wire columns can be relaxed with a [`WitnessCommitmentCache`]
+//! instead of [`relax`], which commits to each distinct static column once.
+
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+use blake2::{Blake2s256, Digest};
+use std::collections::BTreeMap;
+
+pub use crate::{
+    folding_scheme::{
+        commitments_open, CircuitShape, FoldingCommitmentConfig, ProverKey, ProverKeyHandle,
+        PublicParameters, SetupInfo, SlackRandomization, VerifierKey,
+    },
+    relaxed_plonk::{PLONKInstance, PLONKWitness},
+    vector_commitment::{
+        opening::{LinearFormOpening, OpenableCommitmentScheme, PositionOpening},
+        pedersen::{Commitment, PedersenCommitment},
+        HomomorphicCommitmentScheme,
+    },
+    NonInteractiveFoldingScheme, PLONKCircuit, PLONKFoldingScheme, RelaxedPLONKInstance,
+    RelaxedPLONKWitness, UnsatisfiedRow,
+};
+use crate::{preprocessing::NUMBER_OF_WIRES, SangriaError, VerificationFailure};
+
+/// Commits to the wire columns of `witness` with fresh hiding randomness and returns the relaxed
+/// instance-witness pair with scaling factor one and a zero slack vector.
+pub fn relax<F, Comm, R>(
+    public_parameters: &PublicParameters<F, Comm>,
+    verifier_key: &VerifierKey<F, Comm>,
+    instance: PLONKInstance<F>,
+    witness: PLONKWitness<F>,
+    rng: &mut R,
+) -> Result<(RelaxedPLONKInstance<F, Comm>, RelaxedPLONKWitness<F>), SangriaError>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+    R: Rng,
+{
+    let hidings = (0..NUMBER_OF_WIRES)
+        .map(|_| F::rand(rng))
+        .collect::<Vec<_>>();
+    let witness_commitments = hidings
+        .iter()
+        .enumerate()
+        .map(|(column_index, hiding)| {
+            <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::commit(
+                &public_parameters.commit_key_witness,
+                witness.column(column_index)?,
+                *hiding,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    relaxed_pair(
+        public_parameters,
+        verifier_key,
+        instance,
+        witness,
+        hidings,
+        witness_commitments,
+    )
+}
+
+/// The commitment to a static wire column, with the digest of the column it opens to.
+struct CachedCommitment<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    column_digest: [u8; 32],
+    commitment: <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::Commitment,
+    hiding: F,
+}
+
+/// Remembers the commitments to the static wire columns of a circuit (see
+/// [`PLONKCircuit::with_static_columns`]), so that [`Self::relax`] reuses them instead of
+/// committing again when a column repeats. A reused commitment keeps its hiding randomness, so
+/// instances that share a static column share its commitment. A cache must only be used with
+/// one prover key.
+pub struct WitnessCommitmentCache<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    entries: BTreeMap<usize, CachedCommitment<F, Comm>>,
+}
+
+impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> Default for WitnessCommitmentCache<F, Comm> {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> WitnessCommitmentCache<F, Comm> {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Like [`relax`], but reuses the cached commitment to every static column whose values did
+    /// not change, and caches the commitments to the static columns that did.
+    pub fn relax<R: Rng>(
+        &mut self,
+        public_parameters: &PublicParameters<F, Comm>,
+        prover_key: &ProverKey<F, Comm>,
+        instance: PLONKInstance<F>,
+        witness: PLONKWitness<F>,
+        rng: &mut R,
+    ) -> Result<(RelaxedPLONKInstance<F, Comm>, RelaxedPLONKWitness<F>), SangriaError> {
+        let static_columns = prover_key.circuit.static_columns();
+        let mut hidings = Vec::with_capacity(NUMBER_OF_WIRES);
+        let mut witness_commitments = Vec::with_capacity(NUMBER_OF_WIRES);
+        for column_index in 0..NUMBER_OF_WIRES {
+            let column = witness.column(column_index)?;
+            let column_digest = if static_columns.contains(&column_index) {
+                Some(column_digest(column)?)
+            } else {
+                None
+            };
+
+            let cached = column_digest.and_then(|column_digest| {
+                self.entries
+                    .get(&column_index)
+                    .filter(|entry| entry.column_digest == column_digest)
+                    .map(|entry| (entry.commitment, entry.hiding))
+            });
+            let (commitment, hiding) = match cached {
+                Some(cached) => cached,
+                None => {
+                    let hiding = F::rand(rng);
+                    let commitment =
+                        <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::commit(
+                            &public_parameters.commit_key_witness,
+                            column,
+                            hiding,
+                        )?;
+                    if let Some(column_digest) = column_digest {
+                        self.entries.insert(
+                            column_index,
+                            CachedCommitment {
+                                column_digest,
+                                commitment,
+                                hiding,
+                            },
+                        );
+                    }
+                    (commitment, hiding)
+                }
+            };
+            hidings.push(hiding);
+            witness_commitments.push(commitment);
+        }
+
+        relaxed_pair(
+            public_parameters,
+            &prover_key.verifier_key,
+            instance,
+            witness,
+            hidings,
+            witness_commitments,
+        )
+    }
+}
+
+fn column_digest<F: PrimeField>(column: &[F]) -> Result<[u8; 32], SangriaError> {
+    let mut bytes = Vec::new();
+    column
+        .to_vec()
+        .serialize(&mut bytes)
+        .map_err(|_| SangriaError::SerializationError)?;
+
+    Ok(Blake2s256::digest(bytes).into())
+}
+
+/// Returns the relaxed pair of `witness` with the given witness commitments, scaling factor one
+/// and a zero slack vector.
+fn relaxed_pair<F, Comm>(
+    public_parameters: &PublicParameters<F, Comm>,
+    verifier_key: &VerifierKey<F, Comm>,
+    instance: PLONKInstance<F>,
+    witness: PLONKWitness<F>,
+    hidings: Vec<F>,
+    witness_commitments: Vec<
+        <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::Commitment,
+    >,
+) -> Result<(RelaxedPLONKInstance<F, Comm>, RelaxedPLONKWitness<F>), SangriaError>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    let slack_vector = vec![F::zero(); public_parameters.number_of_gates];
+    let slack_commitment = <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::commit(
+        &public_parameters.commit_key_selectors_and_slack,
+        &slack_vector,
+        F::zero(),
+    )?;
+
+    Ok((
+        RelaxedPLONKInstance::new(
+            verifier_key.digest(),
+            instance,
+            F::one(),
+            slack_commitment,
+            witness_commitments,
+        ),
+        RelaxedPLONKWitness::new(witness, slack_vector, hidings, F::zero()),
+    ))
+}
+
+/// Decides whether a relaxed instance-witness pair satisfies the relation of the circuit of
+/// `prover_key`, see the module documentation. This is linear in the size of the circuit.
+pub fn decide<F, Comm>(
+    public_parameters: &PublicParameters<F, Comm>,
+    prover_key: &ProverKey<F, Comm>,
+    instance: &RelaxedPLONKInstance<F, Comm>,
+    witness: &RelaxedPLONKWitness<F>,
+) -> Result<(), SangriaError>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    instance.check_circuit(prover_key.verifier_key.digest())?;
+    instance.check_padding()?;
+
+    if !commitments_open(public_parameters, instance, witness)? {
+        return Err(VerificationFailure::CommitmentOpening
+            .reject(|| String::from("witness does not open the instance commitments")));
+    }
+
+    check_constraints(prover_key, instance, witness)
+}
+
+/// Checks the gates and the copy constraints of the circuit of `prover_key` on `witness`, with
+/// the scaling factor of `instance`. Unlike [`decide`], it does not check that the witness opens
+/// the commitments of the instance.
+pub(crate) fn check_constraints<F, Comm>(
+    prover_key: &ProverKey<F, Comm>,
+    instance: &RelaxedPLONKInstance<F, Comm>,
+    witness: &RelaxedPLONKWitness<F>,
+) -> Result<(), SangriaError>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    let labels = prover_key.circuit.labels();
+    if let Some(row) = witness
+        .dump_unsatisfied_rows(&prover_key.circuit, instance)?
+        .first()
+    {
+        return Err(VerificationFailure::RelaxedRelation
+            .reject(|| format!("{} does not hold", labels.describe_gate(row.row_index))));
+    }
+
+    let number_of_gates = prover_key.preprocessed_circuit.number_of_gates();
+    let columns = (0..NUMBER_OF_WIRES)
+        .map(|column_index| witness.witness_column(column_index))
+        .collect::<Result<Vec<_>, _>>()?;
+    if columns.iter().any(|column| column.len() != number_of_gates) {
+        return Err(SangriaError::IndexOutOfBounds);
+    }
+
+    let cell = |index: usize| columns[index / number_of_gates][index % number_of_gates];
+    for (index, target) in prover_key
+        .preprocessed_circuit
+        .permutation()
+        .iter()
+        .enumerate()
+    {
+        if cell(index) != cell(*target) {
+            return Err(VerificationFailure::RelaxedRelation.reject(|| {
+                format!(
+                    "copy constraint {} -> {} does not hold",
+                    labels.describe_cell(index),
+                    labels.describe_cell(*target)
+                )
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        preprocessing::cell_identities,
+        test_utils::seeded_rng,
+        test_vectors::{poseidon_parameters_for_test, sponge_config_for_test},
+        vector_commitment::mock::FieldCommitmentConfig,
+    };
+    use ark_pallas::Fr;
+    use ark_sponge::poseidon::PoseidonSponge;
+    use ark_std::UniformRand;
+
+    type FoldingScheme = PLONKFoldingScheme<Fr, FieldCommitmentConfig<Fr>, PoseidonSponge<Fr>>;
+
+    #[test]
+    fn relaxed_addition_circuit_is_decided() {
+        let rng = &mut seeded_rng("relations::relaxed_addition_circuit_is_decided");
+        let number_of_gates = 4;
+        let info = SetupInfo {
+            number_of_public_inputs: 0,
+            number_of_gates,
+            domain_separator: b"sangria-relations-test".to_vec(),
+            poseidon_constants: poseidon_parameters_for_test(rng),
+            poseidon2_constants: None,
+            sponge_config: sponge_config_for_test(),
+            slack_randomization: SlackRandomization::EveryFold,
+        };
+        let pp = FoldingScheme::setup(&info, rng).unwrap();
+
+        // c = a + b at every gate, and the output of each gate is the left input of the next.
+        let (zero, one) = (Fr::from(0u64), Fr::from(1u64));
+        let constant = |value: Fr| vec![value; number_of_gates];
+        let selectors = vec![
+            constant(one),
+            constant(one),
+            constant(-one),
+            constant(zero),
+            constant(zero),
+        ];
+        let mut copy_constraint = cell_identities::<Fr>(number_of_gates).unwrap();
+        for row in 0..number_of_gates - 1 {
+            copy_constraint.swap(2 * number_of_gates + row, row + 1);
+        }
+        let circuit = PLONKCircuit::new(selectors, copy_constraint);
+        let (pk, vk) = FoldingScheme::encode(&pp, &circuit, rng).unwrap();
+
+        let (mut a, b, mut c) = (vec![Fr::rand(rng)], constant(one), Vec::new());
+        for b_i in &b {
+            let c_i = a[a.len() - 1] + b_i;
+            c.push(c_i);
+            a.push(c_i);
+        }
+        a.truncate(number_of_gates);
+
+        let relaxed = |a: &[Fr], c: &[Fr], rng: &mut _| {
+            relax(
+                &pp,
+                &vk,
+                PLONKInstance::new(vec![]),
+                PLONKWitness::new(vec![a.to_vec(), b.clone(), c.to_vec()]),
+                rng,
+            )
+            .unwrap()
+        };
+
+        let (instance, witness) = relaxed(&a, &c, rng);
+        assert!(instance.is_strict() && witness.is_strict());
+        assert_eq!(decide(&pp, &pk, &instance, &witness), Ok(()));
+
+        // Every gate still holds, but the output of the third gate is no longer the left input
+        // of the last one.
+        let (mut unlinked_a, mut unlinked_c) = (a.clone(), c.clone());
+        unlinked_a[number_of_gates - 1] += one;
+        unlinked_c[number_of_gates - 1] += one;
+        let (instance, witness) = relaxed(&unlinked_a, &unlinked_c, rng);
+        assert_eq!(
+            decide(&pp, &pk, &instance, &witness),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::RelaxedRelation
+            ))
+        );
+
+        let (instance, _) = relaxed(&a, &c, rng);
+        let (_, other_witness) = relaxed(&a, &c, rng);
+        assert_eq!(
+            decide(&pp, &pk, &instance, &other_witness),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::CommitmentOpening
+            ))
+        );
+
+        // The column `b` is the same in every statement, so its commitment is reused.
+        let circuit = circuit.with_static_columns(vec![1]);
+        let (pk, _) = FoldingScheme::encode(&pp, &circuit, rng).unwrap();
+        let mut cache = WitnessCommitmentCache::new();
+        let mut cached = |a: &[Fr], c: &[Fr], rng: &mut _| {
+            cache
+                .relax(
+                    &pp,
+                    &pk,
+                    PLONKInstance::new(vec![]),
+                    PLONKWitness::new(vec![a.to_vec(), b.clone(), c.to_vec()]),
+                    rng,
+                )
+                .unwrap()
+        };
+        let (first, first_witness) = cached(&a, &c, rng);
+        let (second, second_witness) = cached(&unlinked_a, &unlinked_c, rng);
+        assert_eq!(
+            first.witness_commitments()[1],
+            second.witness_commitments()[1]
+        );
+        assert_ne!(
+            first.witness_commitments()[0],
+            second.witness_commitments()[0]
+        );
+        assert_eq!(decide(&pp, &pk, &first, &first_witness), Ok(()));
+        assert!(commitments_open(&pp, &second, &second_witness).unwrap());
+    }
+}
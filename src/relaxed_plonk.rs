@@ -1,9 +1,27 @@
-use ark_ff::{Field, PrimeField};
+use ark_ff::{FftField, Field, One, PrimeField, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain,
+};
+use ark_serialize::CanonicalSerialize;
 use ark_sponge::Absorb;
-use std::ops::{Add, Mul};
+use ark_std::{
+    io::{Read, Write},
+    rand::Rng,
+};
+use std::{
+    collections::BTreeMap,
+    ops::{Add, Mul},
+};
 
-use crate::vector_commitment::HomomorphicCommitmentScheme;
-use crate::{folding_scheme::FoldingCommitmentConfig, SangriaError};
+use crate::vector_commitment::{HomomorphicCommitmentScheme, VectorCommitmentScheme};
+use crate::{
+    folding_scheme::FoldingCommitmentConfig,
+    preprocessing::NUMBER_OF_WIRES,
+    serialization::{
+        deserialize_with_mode, serialize_with_mode, serialized_size, SerializationMode,
+    },
+    SangriaError, VerificationFailure,
+};
 
 type ColumnVector<F> = Vec<F>;
 type Permutation<F> = Vec<F>;
@@ -23,8 +41,10 @@ pub const MULTIPLICATION_SELECTOR_INDEX: usize = 3;
 /// A constant variable for the q_C selector's index
 pub const CONSTANT_SELECTOR_INDEX: usize = 4;
 
-/// A committed relaxed PLONK instance
+/// A committed relaxed PLONK instance. It records the digest of the circuit it belongs to, so
+/// that instances of different circuits are never folded together.
 pub struct RelaxedPLONKInstance<F: PrimeField, Comm: FoldingCommitmentConfig<F>> {
+    circuit_digest: F,
     plonk_instance: PLONKInstance<F>,
     scaling_factor: F,
     slack_commitment: <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
@@ -33,8 +53,94 @@ pub struct RelaxedPLONKInstance<F: PrimeField, Comm: FoldingCommitmentConfig<F>>
 }
 
 impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> RelaxedPLONKInstance<F, Comm> {
+    /// Creates a relaxed PLONK instance of the circuit with digest `circuit_digest` (the
+    /// `transcript_seed` of its verifier key) from a PLONK instance, a scaling factor and the
+    /// commitments to the slack vector and to each witness column.
+    pub fn new(
+        circuit_digest: F,
+        plonk_instance: PLONKInstance<F>,
+        scaling_factor: F,
+        slack_commitment: <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        witness_commitments: Vec<
+            <Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::Commitment,
+        >,
+    ) -> Self {
+        Self {
+            circuit_digest,
+            plonk_instance,
+            scaling_factor,
+            slack_commitment,
+            witness_commitments,
+        }
+    }
+
+    /// Returns the trivial instance of the circuit with digest `circuit_digest`: a zero scaling
+    /// factor, `number_of_public_inputs` zero public inputs and commitments to zero vectors.
+    /// Together with [`RelaxedPLONKWitness::trivial`] it satisfies the relaxed relation of every
+    /// circuit, which makes it the running instance of the base case of IVC.
+    pub fn trivial(circuit_digest: F, number_of_public_inputs: usize) -> Self {
+        Self::new(
+            circuit_digest,
+            PLONKInstance::from_rows(
+                vec![
+                    PublicInputRow::new(vec![F::zero(); NUMBER_OF_WIRES]);
+                    number_of_public_inputs
+                ],
+                OutputRow::new(vec![F::zero(); NUMBER_OF_WIRES]),
+            )
+            .expect("rows have the same width"),
+            F::zero(),
+            Zero::zero(),
+            vec![Zero::zero(); NUMBER_OF_WIRES],
+        )
+    }
+
+    /// Returns true if this is a trivial instance, see [`Self::trivial`].
+    pub fn is_trivial(&self) -> bool {
+        self.scaling_factor.is_zero()
+            && self.slack_commitment.is_zero()
+            && self.witness_commitments.len() == NUMBER_OF_WIRES
+            && self.witness_commitments.iter().all(Zero::is_zero)
+            && self.plonk_instance.matrix.len() == NUMBER_OF_WIRES
+            && self
+                .plonk_instance
+                .matrix
+                .iter()
+                .flatten()
+                .all(Zero::is_zero)
+    }
+
+    /// Returns true if the scaling factor is one and the slack commitment is the commitment to a
+    /// zero slack vector with zero hiding, as for the relaxation of a PLONK instance (see
+    /// [`crate::relations::relax`]). Folding only relaxes these invariants, so instances of fresh
+    /// steps must be strict while running instances generally are not.
+    pub fn is_strict(&self) -> bool {
+        self.scaling_factor.is_one() && self.canonical_slack_commitment().is_zero()
+    }
+
+    /// Returns the digest of the circuit this instance belongs to.
+    pub fn circuit_digest(&self) -> F {
+        self.circuit_digest
+    }
+
+    /// Returns an error if this instance does not belong to the circuit with digest
+    /// `circuit_digest`.
+    pub(crate) fn check_circuit(&self, circuit_digest: F) -> Result<(), SangriaError> {
+        if self.circuit_digest != circuit_digest {
+            return Err(SangriaError::MismatchedCircuit);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the PLONK instance, with its padding rows.
+    #[cfg(feature = "wire")]
+    pub(crate) fn plonk_instance(&self) -> &PLONKInstance<F> {
+        &self.plonk_instance
+    }
+
     /// Returns the i-th column of the PLONK instance or an error if index is out of bounds.
-    pub fn instance_column(&self, column_index: usize) -> Result<ColumnVector<F>, SangriaError> {
+    pub fn instance_column(&self, column_index: usize) -> Result<&[F], SangriaError> {
         self.plonk_instance.column(column_index)
     }
 
@@ -43,6 +149,17 @@ impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> RelaxedPLONKInstance<F, Co
         self.plonk_instance.row(row_index)
     }
 
+    /// Returns the i-th public input row of the PLONK instance, see
+    /// [`PLONKInstance::public_input_row`].
+    pub fn public_input_row(&self, row_index: usize) -> Result<PublicInputRow<F>, SangriaError> {
+        self.plonk_instance.public_input_row(row_index)
+    }
+
+    /// Returns the output row of the PLONK instance, see [`PLONKInstance::output_row`].
+    pub fn output_row(&self) -> Result<OutputRow<F>, SangriaError> {
+        self.plonk_instance.output_row()
+    }
+
     /// Returns the scaling factor of the relaxed PLONK instance.
     pub fn scaling_factor(&self) -> F {
         self.scaling_factor
@@ -62,34 +179,243 @@ impl<F: PrimeField, Comm: FoldingCommitmentConfig<F>> RelaxedPLONKInstance<F, Co
         self.witness_commitments.clone()
     }
 
+    /// Returns true if both instances have the same number of public input columns and rows and
+    /// the same number of witness commitments, i.e. if they can be folded together.
+    pub(crate) fn has_same_shape(&self, other: &Self) -> bool {
+        self.plonk_instance.padding.len() == other.plonk_instance.padding.len()
+            && self.plonk_instance.matrix.len() == other.plonk_instance.matrix.len()
+            && self
+                .plonk_instance
+                .matrix
+                .iter()
+                .zip(&other.plonk_instance.matrix)
+                .all(|(left, right)| left.len() == right.len())
+            && self.witness_commitments.len() == other.witness_commitments.len()
+    }
+
+    /// Checks that the padding rows of the public inputs are zero, see [`PLONKInstance::pad`].
+    pub(crate) fn check_padding(&self) -> Result<(), SangriaError> {
+        self.plonk_instance.check_padding()
+    }
+
+    /// Returns the instance with its commitments in canonical representation, see
+    /// [`HomomorphicCommitmentScheme::canonicalize`]. Equality and absorption already ignore the
+    /// representation, this is for callers that e.g. serialize instances as cache keys.
+    pub fn canonicalize(self) -> Self {
+        Self {
+            slack_commitment: self.canonical_slack_commitment(),
+            witness_commitments: self.canonical_witness_commitments(),
+            ..self
+        }
+    }
+
+    fn canonical_slack_commitment(
+        &self,
+    ) -> <Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment {
+        Comm::CommitmentSlack::canonicalize(self.slack_commitment)
+    }
+
+    fn canonical_witness_commitments(
+        &self,
+    ) -> Vec<<Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::Commitment> {
+        self.witness_commitments
+            .iter()
+            .map(|commitment| Comm::CommitmentWitness::canonicalize(*commitment))
+            .collect()
+    }
+
+    /// Folds `other` into `self` with challenge `r`: the public inputs, scaling factors and
+    /// witness commitments as `self + r * other`, and the slack commitments together with the
+    /// commitment to the cross term `T` as `E_1 + r * T + r^2 * E_2`, mirroring
+    /// [`RelaxedPLONKWitness::fold_with`]. Returns an error if the instances belong to different
+    /// circuits or have different shapes.
+    pub fn fold(
+        &self,
+        other: &Self,
+        cross_term_commitment: &<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+        challenge: F,
+    ) -> Result<Self, SangriaError> {
+        other.check_circuit(self.circuit_digest)?;
+        if !self.has_same_shape(other) {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let folded = other.clone() * challenge + self;
+        Ok(Self {
+            slack_commitment: [
+                self.slack_commitment,
+                *cross_term_commitment * challenge,
+                other.slack_commitment * challenge.square(),
+            ]
+            .into_iter()
+            .sum(),
+            ..folded
+        })
+    }
+
+    /// Re-blinds the commitments of the pair of this instance and `witness`: fresh randomness is
+    /// added to the hiding of every witness commitment and, if `rerandomize_slack`, of the slack
+    /// commitment. The new commitments are independent of the old ones, and the pair satisfies
+    /// the relation if and only if it did before. Returns an error if the witness does not have a
+    /// hiding for every witness commitment.
+    pub(crate) fn rerandomize<R: Rng>(
+        &mut self,
+        witness: &mut RelaxedPLONKWitness<F>,
+        commit_key_witness: &<Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::CommitKey,
+        commit_key_slack: &<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::CommitKey,
+        rerandomize_slack: bool,
+        rng: &mut R,
+    ) -> Result<(), SangriaError> {
+        if witness.commitment_hidings.len() != self.witness_commitments.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        for (commitment, hiding) in self
+            .witness_commitments
+            .iter_mut()
+            .zip(&mut witness.commitment_hidings)
+        {
+            let blinding = F::rand(rng);
+            let blinder = Comm::CommitmentWitness::commit(commit_key_witness, &[], blinding)?;
+            *commitment = [*commitment, blinder].into_iter().sum();
+            *hiding += blinding;
+        }
+
+        if rerandomize_slack {
+            let blinding = F::rand(rng);
+            let blinder = Comm::CommitmentSlack::commit(commit_key_slack, &[], blinding)?;
+            self.slack_commitment = [self.slack_commitment, blinder].into_iter().sum();
+            witness.slack_hiding += blinding;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the instance into `writer`, see [`crate::serialization`].
+    pub(crate) fn write<W: Write>(
+        &self,
+        mut writer: W,
+        mode: SerializationMode,
+    ) -> Result<(), SangriaError> {
+        serialize_with_mode(&self.circuit_digest, &mut writer, mode)?;
+        serialize_with_mode(&self.plonk_instance.matrix, &mut writer, mode)?;
+        serialize_with_mode(&self.plonk_instance.padding, &mut writer, mode)?;
+        serialize_with_mode(&self.scaling_factor, &mut writer, mode)?;
+        serialize_with_mode(&self.slack_commitment, &mut writer, mode)?;
+        serialize_with_mode(&self.witness_commitments, &mut writer, mode)
+    }
+
+    /// Returns the number of bytes written by [`Self::write`] with `mode`.
+    pub(crate) fn serialized_size(&self, mode: SerializationMode) -> usize {
+        serialized_size(&self.circuit_digest, mode)
+            + serialized_size(&self.plonk_instance.matrix, mode)
+            + serialized_size(&self.plonk_instance.padding, mode)
+            + serialized_size(&self.scaling_factor, mode)
+            + serialized_size(&self.slack_commitment, mode)
+            + serialized_size(&self.witness_commitments, mode)
+    }
+
+    /// Reads an instance written by [`Self::write`] with the same `mode`.
+    pub(crate) fn read<R: Read>(
+        mut reader: R,
+        mode: SerializationMode,
+    ) -> Result<Self, SangriaError> {
+        let circuit_digest = deserialize_with_mode(&mut reader, mode)?;
+        let matrix: Vec<ColumnVector<F>> = deserialize_with_mode(&mut reader, mode)?;
+        let padding: Vec<bool> = deserialize_with_mode(&mut reader, mode)?;
+        if matrix.iter().any(|column| column.len() != padding.len()) {
+            return Err(SangriaError::SerializationError);
+        }
+
+        let scaling_factor = deserialize_with_mode(&mut reader, mode)?;
+        let slack_commitment = deserialize_with_mode(&mut reader, mode)?;
+        let witness_commitments: Vec<_> = deserialize_with_mode(&mut reader, mode)?;
+        if witness_commitments.len() != matrix.len() {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(Self {
+            circuit_digest,
+            plonk_instance: PLONKInstance { matrix, padding },
+            scaling_factor,
+            slack_commitment,
+            witness_commitments,
+        })
+    }
+
     /// Returns a commitment to the i-th row of the witness or an error if index is out of bounds.
     pub fn single_witness_commitment(
         &self,
         column_index: usize,
     ) -> Result<<Comm::CommitmentWitness as HomomorphicCommitmentScheme<F>>::Commitment, SangriaError>
     {
-        if column_index > self.witness_commitments.len() {
-            return Err(SangriaError::IndexOutOfBounds);
-        }
+        self.witness_commitments
+            .get(column_index)
+            .copied()
+            .ok_or(SangriaError::IndexOutOfBounds)
+    }
+}
 
-        Ok(self.witness_commitments[column_index])
+/// Two instances are equal if they have the same public inputs and scaling factor and their
+/// commitments are the same group elements, whatever their representation.
+impl<F, Comm> PartialEq for RelaxedPLONKInstance<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.circuit_digest == other.circuit_digest
+            && self.plonk_instance.matrix == other.plonk_instance.matrix
+            && self.plonk_instance.padding == other.plonk_instance.padding
+            && self.scaling_factor == other.scaling_factor
+            && self.canonical_slack_commitment() == other.canonical_slack_commitment()
+            && self.canonical_witness_commitments() == other.canonical_witness_commitments()
     }
 }
 
+impl<F, Comm> Eq for RelaxedPLONKInstance<F, Comm>
+where
+    F: PrimeField,
+    Comm: FoldingCommitmentConfig<F>,
+{
+}
+
 impl<F, Comm> Absorb for RelaxedPLONKInstance<F, Comm>
 where
     F: PrimeField,
     Comm: FoldingCommitmentConfig<F>,
 {
-    fn to_sponge_bytes(&self, _dest: &mut Vec<u8>) {
-        todo!()
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        serialize_into(&self.circuit_digest, dest);
+        serialize_into(&self.plonk_instance.matrix, dest);
+        serialize_into(&self.plonk_instance.padding, dest);
+        serialize_into(&self.scaling_factor, dest);
+        self.canonical_slack_commitment().to_sponge_bytes(dest);
+        self.canonical_witness_commitments().to_sponge_bytes(dest);
     }
 
-    fn to_sponge_field_elements<SpongeF: PrimeField>(&self, _dest: &mut Vec<SpongeF>) {
-        todo!()
+    fn to_sponge_field_elements<SpongeF: PrimeField>(&self, dest: &mut Vec<SpongeF>) {
+        let mut bytes = Vec::new();
+        serialize_into(&self.circuit_digest, &mut bytes);
+        serialize_into(&self.plonk_instance.matrix, &mut bytes);
+        serialize_into(&self.plonk_instance.padding, &mut bytes);
+        serialize_into(&self.scaling_factor, &mut bytes);
+        bytes.to_sponge_field_elements(dest);
+        self.canonical_slack_commitment()
+            .to_sponge_field_elements(dest);
+        self.canonical_witness_commitments()
+            .to_sponge_field_elements(dest);
     }
 }
 
+/// Serializes public data whose field is not necessarily the sponge's field. Serializing into a
+/// vector cannot fail.
+fn serialize_into<T: CanonicalSerialize>(value: &T, dest: &mut Vec<u8>) {
+    value
+        .serialize(dest)
+        .expect("serialization into a vector cannot fail");
+}
+
 impl<F, Comm> Add<&Self> for RelaxedPLONKInstance<F, Comm>
 where
     F: PrimeField,
@@ -97,8 +423,39 @@ where
 {
     type Output = Self;
 
-    fn add(self, _rhs: &Self) -> Self::Output {
-        todo!()
+    /// Adds two instances entry-wise. Callers must check that both belong to the same circuit and
+    /// have the same shape, see `check_circuit` and `has_same_shape`.
+    fn add(self, rhs: &Self) -> Self::Output {
+        let matrix = self
+            .plonk_instance
+            .matrix
+            .iter()
+            .zip(&rhs.plonk_instance.matrix)
+            .map(|(left, right)| left.iter().zip(right).map(|(l, r)| *l + r).collect())
+            .collect();
+        let padding = self
+            .plonk_instance
+            .padding
+            .iter()
+            .zip(&rhs.plonk_instance.padding)
+            .map(|(left, right)| *left && *right)
+            .collect();
+        let witness_commitments = self
+            .witness_commitments
+            .iter()
+            .zip(&rhs.witness_commitments)
+            .map(|(left, right)| [*left, *right].into_iter().sum())
+            .collect();
+
+        Self {
+            circuit_digest: self.circuit_digest,
+            plonk_instance: PLONKInstance { matrix, padding },
+            scaling_factor: self.scaling_factor + rhs.scaling_factor,
+            slack_commitment: [self.slack_commitment, rhs.slack_commitment]
+                .into_iter()
+                .sum(),
+            witness_commitments,
+        }
     }
 }
 
@@ -109,8 +466,29 @@ where
 {
     type Output = Self;
 
-    fn mul(self, _rhs: F) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: F) -> Self::Output {
+        let matrix = self
+            .plonk_instance
+            .matrix
+            .iter()
+            .map(|column| column.iter().map(|value| *value * rhs).collect())
+            .collect();
+        let witness_commitments = self
+            .witness_commitments
+            .iter()
+            .map(|commitment| *commitment * rhs)
+            .collect();
+
+        Self {
+            circuit_digest: self.circuit_digest,
+            plonk_instance: PLONKInstance {
+                matrix,
+                padding: self.plonk_instance.padding,
+            },
+            scaling_factor: self.scaling_factor * rhs,
+            slack_commitment: self.slack_commitment * rhs,
+            witness_commitments,
+        }
     }
 }
 
@@ -120,20 +498,74 @@ where
     Comm: FoldingCommitmentConfig<F>,
 {
     fn clone(&self) -> Self {
-        todo!()
+        Self {
+            circuit_digest: self.circuit_digest,
+            plonk_instance: self.plonk_instance.clone(),
+            scaling_factor: self.scaling_factor,
+            slack_commitment: self.slack_commitment,
+            witness_commitments: self.witness_commitments.clone(),
+        }
     }
 }
 
+/// A row of a relaxed witness that violates the relaxed gate identity, see
+/// [`RelaxedPLONKWitness::dump_unsatisfied_rows`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsatisfiedRow<F> {
+    /// The index of the row (gate) in the circuit.
+    pub row_index: usize,
+    /// The label of the gate, see [`CircuitLabels`].
+    pub label: Option<String>,
+    /// The left-hand side of the gate identity, evaluated on the row.
+    pub gate_value: F,
+    /// The entry of the slack vector the gate value should equal.
+    pub slack: F,
+}
+
 /// A committed relaxed PLONK witness.
 pub struct RelaxedPLONKWitness<F: PrimeField> {
     plonk_witness: PLONKWitness<F>,
     slack_vector: ColumnVector<F>,
     commitment_hidings: Vec<F>,
+    slack_hiding: F,
 }
 
 impl<F: PrimeField> RelaxedPLONKWitness<F> {
+    /// Creates a relaxed PLONK witness. `commitment_hidings` holds the randomness used to commit
+    /// to each witness column and `slack_hiding` the randomness used to commit to the slack vector.
+    pub fn new(
+        plonk_witness: PLONKWitness<F>,
+        slack_vector: ColumnVector<F>,
+        commitment_hidings: Vec<F>,
+        slack_hiding: F,
+    ) -> Self {
+        Self {
+            plonk_witness,
+            slack_vector,
+            commitment_hidings,
+            slack_hiding,
+        }
+    }
+
+    /// Returns the all-zero witness of a circuit with `number_of_gates` gates, committed to with
+    /// zero randomness, see [`RelaxedPLONKInstance::trivial`].
+    pub fn trivial(number_of_gates: usize) -> Self {
+        Self::new(
+            PLONKWitness::new(vec![vec![F::zero(); number_of_gates]; NUMBER_OF_WIRES]),
+            vec![F::zero(); number_of_gates],
+            vec![F::zero(); NUMBER_OF_WIRES],
+            F::zero(),
+        )
+    }
+
+    /// Returns true if the slack vector and its hiding are zero, see
+    /// [`RelaxedPLONKInstance::is_strict`].
+    pub fn is_strict(&self) -> bool {
+        self.slack_hiding.is_zero() && self.slack_vector.iter().all(Zero::is_zero)
+    }
+
     /// Returns the i-th column of the PLONK witness or an error if index is out of bounds.
-    pub fn witness_column(&self, column_index: usize) -> Result<ColumnVector<F>, SangriaError> {
+    pub fn witness_column(&self, column_index: usize) -> Result<&[F], SangriaError> {
         self.plonk_witness.column(column_index)
     }
 
@@ -152,13 +584,176 @@ impl<F: PrimeField> RelaxedPLONKWitness<F> {
         self.commitment_hidings.clone()
     }
 
-    /// Returns a column from the witness as well as the randomness used to commit to it or an error if index is out of bounds.
-    pub fn witness_column_with_rand(
+    /// Returns the random value used when committing to the slack vector.
+    pub fn slack_hiding(&self) -> F {
+        self.slack_hiding
+    }
+
+    /// Folds the slack vectors of `self` and `other` together with the cross term `T` as
+    /// `E = E_1 + r * T + r^2 * E_2`, and their hiding randomness the same way, so that the folded
+    /// slack commitment opens to the folded slack vector.
+    pub fn fold_slack(
         &self,
-        column_index: usize,
+        other: &Self,
+        cross_term: &[F],
+        cross_term_hiding: F,
+        challenge: F,
     ) -> Result<(ColumnVector<F>, F), SangriaError> {
+        if self.slack_vector.len() != other.slack_vector.len()
+            || self.slack_vector.len() != cross_term.len()
+        {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let challenge_squared = challenge.square();
+        let slack_vector = self
+            .slack_vector
+            .iter()
+            .zip(cross_term)
+            .zip(&other.slack_vector)
+            .map(|((e_1, t), e_2)| *e_1 + challenge * t + challenge_squared * e_2)
+            .collect();
+        let slack_hiding = self.slack_hiding
+            + challenge * cross_term_hiding
+            + challenge_squared * other.slack_hiding;
+
+        Ok((slack_vector, slack_hiding))
+    }
+
+    /// Folds `other` into `self` as `W = W_1 + r * W_2` column by column and hiding by hiding, and
+    /// folds the slack vectors with the cross term `T`, see [`Self::fold_slack`]. This is the
+    /// witness side of [`RelaxedPLONKInstance`]'s `right * r + left`. Returns an error if the
+    /// witnesses, or the cross term, have different lengths.
+    pub fn fold_with(
+        &self,
+        other: &Self,
+        cross_term: &[F],
+        cross_term_hiding: F,
+        challenge: F,
+    ) -> Result<Self, SangriaError> {
+        let (left, right) = (&self.plonk_witness.matrix, &other.plonk_witness.matrix);
+        if left.len() != right.len()
+            || left.iter().zip(right).any(|(l, r)| l.len() != r.len())
+            || self.commitment_hidings.len() != other.commitment_hidings.len()
+        {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let fold = |left: &[F], right: &[F]| {
+            left.iter()
+                .zip(right)
+                .map(|(l, r)| *l + challenge * r)
+                .collect::<Vec<_>>()
+        };
+        let (slack_vector, slack_hiding) =
+            self.fold_slack(other, cross_term, cross_term_hiding, challenge)?;
+
+        Ok(Self::new(
+            PLONKWitness::new(left.iter().zip(right).map(|(l, r)| fold(l, r)).collect()),
+            slack_vector,
+            fold(&self.commitment_hidings, &other.commitment_hidings),
+            slack_hiding,
+        ))
+    }
+
+    /// Writes the witness into `writer`, see [`crate::serialization`].
+    pub(crate) fn write<W: Write>(
+        &self,
+        mut writer: W,
+        mode: SerializationMode,
+    ) -> Result<(), SangriaError> {
+        serialize_with_mode(&self.plonk_witness.matrix, &mut writer, mode)?;
+        serialize_with_mode(&self.slack_vector, &mut writer, mode)?;
+        serialize_with_mode(&self.commitment_hidings, &mut writer, mode)?;
+        serialize_with_mode(&self.slack_hiding, &mut writer, mode)
+    }
+
+    /// Returns the number of bytes written by [`Self::write`] with `mode`.
+    pub(crate) fn serialized_size(&self, mode: SerializationMode) -> usize {
+        serialized_size(&self.plonk_witness.matrix, mode)
+            + serialized_size(&self.slack_vector, mode)
+            + serialized_size(&self.commitment_hidings, mode)
+            + serialized_size(&self.slack_hiding, mode)
+    }
+
+    /// Reads a witness written by [`Self::write`] with the same `mode`.
+    pub(crate) fn read<R: Read>(
+        mut reader: R,
+        mode: SerializationMode,
+    ) -> Result<Self, SangriaError> {
+        let matrix: Vec<ColumnVector<F>> = deserialize_with_mode(&mut reader, mode)?;
+        let slack_vector: ColumnVector<F> = deserialize_with_mode(&mut reader, mode)?;
+        let commitment_hidings: Vec<F> = deserialize_with_mode(&mut reader, mode)?;
+        if commitment_hidings.len() != matrix.len()
+            || matrix
+                .iter()
+                .any(|column| column.len() != slack_vector.len())
+        {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(Self::new(
+            PLONKWitness::new(matrix),
+            slack_vector,
+            commitment_hidings,
+            deserialize_with_mode(&mut reader, mode)?,
+        ))
+    }
+
+    /// Returns a column from the witness as well as the randomness used to commit to it or an error if index is out of bounds.
+    pub fn witness_column_with_rand(&self, column_index: usize) -> Result<(&[F], F), SangriaError> {
         let column = self.plonk_witness.column(column_index)?;
-        Ok((column, self.commitment_hidings[column_index]))
+        let hiding = self
+            .commitment_hidings
+            .get(column_index)
+            .ok_or(SangriaError::IndexOutOfBounds)?;
+        Ok((column, *hiding))
+    }
+
+    /// Evaluates the relaxed gate identity
+    /// `u * (q_L * a + q_R * b + q_O * c) + q_M * a * b + u^2 * q_C` on every row, with the scaling
+    /// factor `u` of `instance`, and returns the rows where it differs from the slack vector, in
+    /// order. This is meant for debugging circuits; it neither checks the copy constraints nor
+    /// that the witness opens the commitments of `instance`.
+    pub fn dump_unsatisfied_rows<Comm: FoldingCommitmentConfig<F>>(
+        &self,
+        circuit: &PLONKCircuit<F>,
+        instance: &RelaxedPLONKInstance<F, Comm>,
+    ) -> Result<Vec<UnsatisfiedRow<F>>, SangriaError> {
+        let q_l = circuit.single_selector(LEFT_SELECTOR_INDEX)?;
+        let q_r = circuit.single_selector(RIGHT_SELECTOR_INDEX)?;
+        let q_o = circuit.single_selector(OUTPUT_SELECTOR_INDEX)?;
+        let q_m = circuit.single_selector(MULTIPLICATION_SELECTOR_INDEX)?;
+        let q_c = circuit.single_selector(CONSTANT_SELECTOR_INDEX)?;
+        let a = self.witness_column(0)?;
+        let b = self.witness_column(1)?;
+        let c = self.witness_column(2)?;
+
+        let number_of_gates = self.slack_vector.len();
+        if [q_l, q_r, q_o, q_m, q_c, a, b, c]
+            .iter()
+            .any(|column| column.len() != number_of_gates)
+        {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let u = instance.scaling_factor;
+        let u_squared = u.square();
+
+        Ok((0..number_of_gates)
+            .filter_map(|i| {
+                let gate_value = u * (q_l[i] * a[i] + q_r[i] * b[i] + q_o[i] * c[i])
+                    + q_m[i] * a[i] * b[i]
+                    + u_squared * q_c[i];
+                let slack = self.slack_vector[i];
+                (gate_value != slack).then(|| UnsatisfiedRow {
+                    row_index: i,
+                    label: circuit.labels.gate_label(i).map(String::from),
+                    gate_value,
+                    slack,
+                })
+            })
+            .collect())
     }
 }
 
@@ -168,56 +763,367 @@ pub struct PLONKWitness<F: PrimeField> {
 }
 
 impl<F: PrimeField> PLONKWitness<F> {
-    pub fn column(&self, column_index: usize) -> Result<ColumnVector<F>, SangriaError> {
-        if column_index > self.matrix.len() {
-            return Err(SangriaError::IndexOutOfBounds);
-        }
+    /// Creates a PLONK witness from its columns.
+    pub fn new(matrix: Vec<ColumnVector<F>>) -> Self {
+        Self { matrix }
+    }
 
-        Ok(self.matrix[column_index].clone())
+    /// Returns the i-th column or an error if index is out of bounds.
+    pub fn column(&self, column_index: usize) -> Result<&[F], SangriaError> {
+        self.matrix
+            .get(column_index)
+            .map(Vec::as_slice)
+            .ok_or(SangriaError::IndexOutOfBounds)
     }
 
+    /// Returns the i-th row or an error if index is out of bounds. The matrix is stored by
+    /// column, so the row is copied.
     pub fn row(&self, row_index: usize) -> Result<Vec<F>, SangriaError> {
         self.matrix
             .iter()
-            .map(|column| -> Result<F, SangriaError> {
-                if row_index > column.len() {
-                    return Err(SangriaError::IndexOutOfBounds);
-                }
-
-                Ok(column[row_index])
+            .map(|column| {
+                column
+                    .get(row_index)
+                    .copied()
+                    .ok_or(SangriaError::IndexOutOfBounds)
             })
             .collect::<Result<Vec<_>, SangriaError>>()
     }
+
+    /// Interpolates every column over `domain`, see [`crate::domain::domain_for_size`]. Columns shorter than the domain are padded with zeros.
+    pub fn to_polynomials(
+        &self,
+        domain: Radix2EvaluationDomain<F>,
+    ) -> Result<Vec<DensePolynomial<F>>, SangriaError> {
+        columns_to_polynomials(&self.matrix, domain)
+    }
+
+    /// Builds a PLONKWitness from the evaluations of its columns.
+    pub fn from_evaluations(columns: Vec<Evaluations<F, Radix2EvaluationDomain<F>>>) -> Self {
+        Self {
+            matrix: columns.into_iter().map(|column| column.evals).collect(),
+        }
+    }
+}
+
+/// A public input row of a [`PLONKInstance`], with one value per column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicInputRow<F: PrimeField> {
+    values: Vec<F>,
+    is_padding: bool,
+}
+
+impl<F: PrimeField> PublicInputRow<F> {
+    /// Creates a public input row that is not padding.
+    pub fn new(values: Vec<F>) -> Self {
+        Self {
+            values,
+            is_padding: false,
+        }
+    }
+
+    /// Returns the values of the row.
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    /// Returns true if the row was inserted by [`PLONKInstance::pad`].
+    pub fn is_padding(&self) -> bool {
+        self.is_padding
+    }
+}
+
+/// The last row of a [`PLONKInstance`], used to check the final output of the circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputRow<F: PrimeField>(Vec<F>);
+
+impl<F: PrimeField> OutputRow<F> {
+    /// Creates an output row.
+    pub fn new(values: Vec<F>) -> Self {
+        Self(values)
+    }
+
+    /// Returns the values of the row.
+    pub fn values(&self) -> &[F] {
+        &self.0
+    }
 }
 
 /// A PLONK instance, this is a sub-table of the Trace with one row per public input plus
-/// one extra row to check the final output.
+/// one extra row to check the final output, see [`PublicInputRow`] and [`OutputRow`].
+///
+/// Circuits whose number of public inputs varies up to a maximum are padded to that maximum with
+/// zero rows, inserted before the output row so that the output row of every instance is at the
+/// same position. Padding rows are marked explicitly; a padding row must be zero in every column
+/// and stays marked after folding only if it is marked in both folded instances.
 #[derive(Clone)]
 pub struct PLONKInstance<F: PrimeField> {
     matrix: Vec<ColumnVector<F>>,
+    padding: Vec<bool>,
 }
 
 impl<F: PrimeField> PLONKInstance<F> {
-    pub fn column(&self, column_index: usize) -> Result<ColumnVector<F>, SangriaError> {
-        if column_index > self.matrix.len() {
+    /// Creates a PLONK instance from its columns.
+    pub fn new(matrix: Vec<ColumnVector<F>>) -> Self {
+        let number_of_rows = matrix.first().map_or(0, Vec::len);
+
+        Self {
+            matrix,
+            padding: vec![false; number_of_rows],
+        }
+    }
+
+    /// Creates a PLONK instance from its public input rows and its output row. Returns an error
+    /// if the rows do not all have the same number of columns.
+    pub fn from_rows(
+        public_inputs: Vec<PublicInputRow<F>>,
+        output: OutputRow<F>,
+    ) -> Result<Self, SangriaError> {
+        let number_of_columns = output.values().len();
+        if public_inputs
+            .iter()
+            .any(|row| row.values().len() != number_of_columns)
+        {
             return Err(SangriaError::IndexOutOfBounds);
         }
 
-        Ok(self.matrix[column_index].clone())
+        let matrix = (0..number_of_columns)
+            .map(|column_index| {
+                public_inputs
+                    .iter()
+                    .map(PublicInputRow::values)
+                    .chain(ark_std::iter::once(output.values()))
+                    .map(|values| values[column_index])
+                    .collect()
+            })
+            .collect();
+        let padding = public_inputs
+            .iter()
+            .map(PublicInputRow::is_padding)
+            .chain(ark_std::iter::once(false))
+            .collect();
+
+        Ok(Self { matrix, padding })
+    }
+
+    /// Returns the index of the output row, i.e. the number of public input rows including
+    /// padding, or `None` if the instance has no rows.
+    fn output_row_index(&self) -> Option<usize> {
+        self.padding.len().checked_sub(1)
+    }
+
+    /// Returns the i-th public input row, padding included, or an error if there is no such row.
+    pub fn public_input_row(&self, row_index: usize) -> Result<PublicInputRow<F>, SangriaError> {
+        if Some(row_index) >= self.output_row_index() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        Ok(PublicInputRow {
+            values: self.row(row_index)?,
+            is_padding: self.padding[row_index],
+        })
+    }
+
+    /// Returns the output row, or an error if the instance has no rows.
+    pub fn output_row(&self) -> Result<OutputRow<F>, SangriaError> {
+        let row_index = self
+            .output_row_index()
+            .ok_or(SangriaError::IndexOutOfBounds)?;
+
+        self.row(row_index).map(OutputRow)
+    }
+
+    /// Pads the public input rows with zero rows up to `max_public_inputs`. Returns an error if
+    /// the instance already has more public inputs than that.
+    pub fn pad(mut self, max_public_inputs: usize) -> Result<Self, SangriaError> {
+        let number_of_public_inputs = self.output_row_index().unwrap_or(0);
+        if number_of_public_inputs > max_public_inputs {
+            return Err(SangriaError::UnsupportedSize {
+                requested: number_of_public_inputs,
+                max: max_public_inputs,
+            });
+        }
+
+        let number_of_padding_rows = max_public_inputs - number_of_public_inputs;
+        let padding_rows = number_of_public_inputs..number_of_public_inputs;
+        for column in self.matrix.iter_mut() {
+            column.splice(
+                padding_rows.clone(),
+                ark_std::iter::repeat(F::zero()).take(number_of_padding_rows),
+            );
+        }
+        self.padding.splice(
+            padding_rows,
+            ark_std::iter::repeat(true).take(number_of_padding_rows),
+        );
+
+        Ok(self)
+    }
+
+    /// Creates a PLONK instance from its columns and the rows that are padding. Returns an error
+    /// if a column does not have one entry per row.
+    #[cfg(feature = "wire")]
+    pub(crate) fn from_parts(
+        matrix: Vec<ColumnVector<F>>,
+        padding: Vec<bool>,
+    ) -> Result<Self, SangriaError> {
+        if matrix.iter().any(|column| column.len() != padding.len()) {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        Ok(Self { matrix, padding })
+    }
+
+    /// Returns the columns of the instance.
+    #[cfg(feature = "wire")]
+    pub(crate) fn columns(&self) -> &[ColumnVector<F>] {
+        &self.matrix
+    }
+
+    /// Returns which rows are padding.
+    #[cfg(feature = "wire")]
+    pub(crate) fn padding(&self) -> &[bool] {
+        &self.padding
+    }
+
+    /// Returns true if the i-th row is a padding row, or an error if index is out of bounds.
+    pub fn is_padding_row(&self, row_index: usize) -> Result<bool, SangriaError> {
+        self.padding
+            .get(row_index)
+            .copied()
+            .ok_or(SangriaError::IndexOutOfBounds)
+    }
+
+    /// Returns the number of public inputs that are not padding.
+    pub fn number_of_public_inputs(&self) -> usize {
+        self.padding
+            .iter()
+            .take(self.output_row_index().unwrap_or(0))
+            .filter(|is_padding| !**is_padding)
+            .count()
+    }
+
+    /// Checks that every column has one entry per row and that padding rows are zero.
+    pub(crate) fn check_padding(&self) -> Result<(), SangriaError> {
+        for column in &self.matrix {
+            if column.len() != self.padding.len() {
+                return Err(SangriaError::IndexOutOfBounds);
+            }
+
+            let non_zero_padding = column
+                .iter()
+                .zip(&self.padding)
+                .position(|(value, is_padding)| *is_padding && !value.is_zero());
+            if let Some(row_index) = non_zero_padding {
+                return Err(VerificationFailure::RelaxedRelation
+                    .reject(|| format!("padding row {} of the instance is not zero", row_index)));
+            }
+        }
+
+        Ok(())
     }
 
+    /// Returns the i-th column or an error if index is out of bounds.
+    pub fn column(&self, column_index: usize) -> Result<&[F], SangriaError> {
+        self.matrix
+            .get(column_index)
+            .map(Vec::as_slice)
+            .ok_or(SangriaError::IndexOutOfBounds)
+    }
+
+    /// Returns the i-th row or an error if index is out of bounds. The matrix is stored by
+    /// column, so the row is copied.
     pub fn row(&self, row_index: usize) -> Result<Vec<F>, SangriaError> {
         self.matrix
             .iter()
-            .map(|column| -> Result<F, SangriaError> {
-                if row_index > column.len() {
-                    return Err(SangriaError::IndexOutOfBounds);
-                }
-
-                Ok(column[row_index])
+            .map(|column| {
+                column
+                    .get(row_index)
+                    .copied()
+                    .ok_or(SangriaError::IndexOutOfBounds)
             })
             .collect::<Result<Vec<_>, SangriaError>>()
     }
+
+    /// Interpolates every column over `domain`, see [`crate::domain::domain_for_size`]. Columns shorter than the domain are padded with zeros.
+    pub fn to_polynomials(
+        &self,
+        domain: Radix2EvaluationDomain<F>,
+    ) -> Result<Vec<DensePolynomial<F>>, SangriaError> {
+        columns_to_polynomials(&self.matrix, domain)
+    }
+
+    /// Builds a PLONKInstance from the evaluations of its columns. No row is marked as padding.
+    pub fn from_evaluations(columns: Vec<Evaluations<F, Radix2EvaluationDomain<F>>>) -> Self {
+        Self::new(columns.into_iter().map(|column| column.evals).collect())
+    }
+}
+
+fn columns_to_polynomials<F: FftField>(
+    matrix: &[ColumnVector<F>],
+    domain: Radix2EvaluationDomain<F>,
+) -> Result<Vec<DensePolynomial<F>>, SangriaError> {
+    matrix
+        .iter()
+        .map(|column| {
+            if column.len() > domain.size() {
+                return Err(SangriaError::DomainTooSmall);
+            }
+
+            let mut evaluations = column.clone();
+            evaluations.resize(domain.size(), F::zero());
+
+            Ok(Evaluations::from_vec_and_domain(evaluations, domain).interpolate())
+        })
+        .collect()
+}
+
+/// Human-readable labels of the gates and wire cells of a circuit, e.g.
+/// `poseidon_round_3/after_sbox`, used to report failures. Cells are indexed in wire-major order
+/// (`wire * number_of_gates + row`), like [`crate::preprocessing::PreprocessedCircuit::permutation`].
+/// Labels are diagnostics only: they are neither committed to nor absorbed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CircuitLabels {
+    gate_labels: BTreeMap<usize, String>,
+    cell_labels: BTreeMap<usize, String>,
+}
+
+impl CircuitLabels {
+    /// Labels a gate, replacing its previous label.
+    pub fn label_gate(&mut self, row_index: usize, label: impl Into<String>) {
+        self.gate_labels.insert(row_index, label.into());
+    }
+
+    /// Labels a wire cell, replacing its previous label.
+    pub fn label_cell(&mut self, cell_index: usize, label: impl Into<String>) {
+        self.cell_labels.insert(cell_index, label.into());
+    }
+
+    /// Returns the label of a gate, if it has one.
+    pub fn gate_label(&self, row_index: usize) -> Option<&str> {
+        self.gate_labels.get(&row_index).map(String::as_str)
+    }
+
+    /// Returns the label of a wire cell, if it has one.
+    pub fn cell_label(&self, cell_index: usize) -> Option<&str> {
+        self.cell_labels.get(&cell_index).map(String::as_str)
+    }
+
+    /// Describes a gate by its label, falling back to its index.
+    pub fn describe_gate(&self, row_index: usize) -> String {
+        match self.gate_label(row_index) {
+            Some(label) => format!("gate {} ({})", row_index, label),
+            None => format!("gate {}", row_index),
+        }
+    }
+
+    /// Describes a wire cell by its label, falling back to its index.
+    pub fn describe_cell(&self, cell_index: usize) -> String {
+        match self.cell_label(cell_index) {
+            Some(label) => format!("cell {} ({})", cell_index, label),
+            None => format!("cell {}", cell_index),
+        }
+    }
 }
 
 /// A structure that hold the defining elements of a PLONK circuit
@@ -225,21 +1131,91 @@ impl<F: PrimeField> PLONKInstance<F> {
 pub struct PLONKCircuit<F: Field> {
     selectors: Vec<ColumnVector<F>>,
     copy_constraint: Permutation<F>,
+    lookup_tables: Vec<ColumnVector<F>>,
+    labels: CircuitLabels,
+    static_columns: Vec<usize>,
+    version: u32,
 }
 
 impl<F: Field> PLONKCircuit<F> {
+    /// Creates a circuit from its selector columns and copy constraints.
+    pub fn new(selectors: Vec<ColumnVector<F>>, copy_constraint: Permutation<F>) -> Self {
+        Self {
+            selectors,
+            copy_constraint,
+            lookup_tables: vec![],
+            labels: CircuitLabels::default(),
+            static_columns: vec![],
+            version: 0,
+        }
+    }
+
+    /// Sets the version of the circuit, 0 by default. The version is absorbed with the circuit,
+    /// so two versions of a step circuit have different keys even if their gates are the same.
+    /// A computation is moved to a later version with [`crate::equivalence::prove_migration`].
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Returns the version of the circuit, see [`Self::with_version`].
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Attaches labels to the gates and wires of the circuit, see [`CircuitLabels`].
+    pub fn with_labels(mut self, labels: CircuitLabels) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Returns the labels of the gates and wires.
+    pub fn labels(&self) -> &CircuitLabels {
+        &self.labels
+    }
+
+    /// Marks wire columns whose values are the same at every step, e.g. constants fed into the
+    /// step circuit. Their commitments can then be reused, see
+    /// [`crate::relations::WitnessCommitmentCache`]. Like labels, this is a hint to the prover: it
+    /// is neither committed to nor absorbed.
+    pub fn with_static_columns(mut self, static_columns: Vec<usize>) -> Self {
+        self.static_columns = static_columns;
+        self
+    }
+
+    /// Returns the wire columns marked static, see [`Self::with_static_columns`].
+    pub fn static_columns(&self) -> &[usize] {
+        &self.static_columns
+    }
+
+    /// Attaches global lookup tables (e.g. an 8-bit range or a XOR table) to the circuit. They
+    /// are committed to once in `encode` and shared by every step.
+    pub fn with_lookup_tables(mut self, lookup_tables: Vec<ColumnVector<F>>) -> Self {
+        self.lookup_tables = lookup_tables;
+        self
+    }
+
+    /// Returns the global lookup tables.
+    pub fn lookup_tables(&self) -> &[ColumnVector<F>] {
+        &self.lookup_tables
+    }
+
+    /// Returns the number of selector columns.
+    pub fn number_of_selectors(&self) -> usize {
+        self.selectors.len()
+    }
+
     /// Returns the selectors matrix.
     pub fn selectors(&self) -> Vec<ColumnVector<F>> {
         self.selectors.clone()
     }
 
     /// Returns a single selector or an error if index is out of bounds.
-    pub fn single_selector(&self, selector_index: usize) -> Result<ColumnVector<F>, SangriaError> {
-        if selector_index > self.selectors.len() {
-            return Err(SangriaError::IndexOutOfBounds);
-        }
-
-        Ok(self.selectors[selector_index].clone())
+    pub fn single_selector(&self, selector_index: usize) -> Result<&[F], SangriaError> {
+        self.selectors
+            .get(selector_index)
+            .map(Vec::as_slice)
+            .ok_or(SangriaError::IndexOutOfBounds)
     }
 
     /// Returns the copy constraints.
@@ -248,12 +1224,405 @@ impl<F: Field> PLONKCircuit<F> {
     }
 }
 
+impl<F: PrimeField> PLONKCircuit<F> {
+    /// Commits to every selector and to the copy constraints using a (non-homomorphic) vector
+    /// commitment scheme. The result identifies the circuit and is cheap to recompute in-circuit.
+    pub fn digest<VC: VectorCommitmentScheme<F>>(
+        &self,
+        parameters: &VC::Parameters,
+    ) -> Result<Vec<VC::Commitment>, SangriaError> {
+        self.selectors
+            .iter()
+            .chain(ark_std::iter::once(&self.copy_constraint))
+            .map(|column| VC::commit(parameters, column))
+            .collect()
+    }
+}
+
 impl<CircuitField: PrimeField> Absorb for PLONKCircuit<CircuitField> {
-    fn to_sponge_bytes(&self, _dest: &mut Vec<u8>) {
-        todo!()
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        serialize_into(&self.selectors, dest);
+        serialize_into(&self.copy_constraint, dest);
+        serialize_into(&self.lookup_tables, dest);
+        serialize_into(&self.version, dest);
+    }
+
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        let mut bytes = Vec::new();
+        self.to_sponge_bytes(&mut bytes);
+        bytes.to_sponge_field_elements(dest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::seeded_rng,
+        vector_commitment::mock::{FieldCommitment, FieldCommitmentConfig},
+    };
+    use ark_ff::{One, Zero};
+    use ark_pallas::Fr;
+    use ark_std::{rand::Rng, UniformRand};
+
+    type Commitment = FieldCommitment<Fr>;
+
+    #[test]
+    fn accessors_reject_index_equal_to_length() {
+        let rng = &mut seeded_rng("relaxed_plonk::accessors_reject_index_equal_to_length");
+        let (columns, rows) = (3, 5);
+        let matrix = (0..columns)
+            .map(|_| (0..rows).map(|_| Fr::rand(rng)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let hidings = (0..columns).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        let witness = RelaxedPLONKWitness::new(
+            PLONKWitness::new(matrix.clone()),
+            vec![],
+            hidings,
+            Fr::one(),
+        );
+        let instance = RelaxedPLONKInstance::<Fr, FieldCommitmentConfig<Fr>>::new(
+            Fr::one(),
+            PLONKInstance::new(matrix.clone()),
+            Fr::one(),
+            Fr::zero(),
+            matrix.iter().map(|column| column[0]).collect(),
+        );
+        let circuit = PLONKCircuit::new(matrix.clone(), vec![]);
+
+        for index in [0, columns - 1] {
+            assert_eq!(witness.witness_column(index).unwrap(), &matrix[index][..]);
+            assert_eq!(
+                witness.witness_column_with_rand(index).unwrap().0,
+                &matrix[index][..]
+            );
+            assert_eq!(instance.instance_column(index).unwrap(), &matrix[index][..]);
+            assert_eq!(
+                instance.single_witness_commitment(index).unwrap(),
+                matrix[index][0]
+            );
+            assert_eq!(circuit.single_selector(index).unwrap(), &matrix[index][..]);
+        }
+        for index in [0, rows - 1] {
+            let row = matrix
+                .iter()
+                .map(|column| column[index])
+                .collect::<Vec<_>>();
+            assert_eq!(witness.witness_row(index).unwrap(), row);
+            assert_eq!(instance.instance_row(index).unwrap(), row);
+        }
+
+        for index in [columns, columns + 1, usize::MAX] {
+            assert_eq!(
+                witness.witness_column(index),
+                Err(SangriaError::IndexOutOfBounds)
+            );
+            assert_eq!(
+                witness.witness_column_with_rand(index),
+                Err(SangriaError::IndexOutOfBounds)
+            );
+            assert_eq!(
+                instance.instance_column(index),
+                Err(SangriaError::IndexOutOfBounds)
+            );
+            assert_eq!(
+                instance.single_witness_commitment(index),
+                Err(SangriaError::IndexOutOfBounds)
+            );
+            assert_eq!(
+                circuit.single_selector(index),
+                Err(SangriaError::IndexOutOfBounds)
+            );
+        }
+        for index in [rows, rows + 1, usize::MAX] {
+            assert_eq!(
+                witness.witness_row(index),
+                Err(SangriaError::IndexOutOfBounds)
+            );
+            assert_eq!(
+                instance.instance_row(index),
+                Err(SangriaError::IndexOutOfBounds)
+            );
+        }
+    }
+
+    #[test]
+    fn the_output_row_is_the_row_after_the_public_inputs() {
+        let public_inputs = vec![
+            PublicInputRow::new(vec![Fr::from(1u64), Fr::from(2u64)]),
+            PublicInputRow::new(vec![Fr::from(3u64), Fr::from(4u64)]),
+        ];
+        let output = OutputRow::new(vec![Fr::from(5u64), Fr::from(6u64)]);
+        let instance = PLONKInstance::from_rows(public_inputs.clone(), output.clone())
+            .unwrap()
+            .pad(3)
+            .unwrap();
+
+        assert_eq!(instance.number_of_public_inputs(), 2);
+        assert_eq!(instance.public_input_row(1), Ok(public_inputs[1].clone()));
+        assert!(instance.public_input_row(2).unwrap().is_padding());
+        assert_eq!(instance.output_row(), Ok(output));
+        assert_eq!(
+            instance.public_input_row(3),
+            Err(SangriaError::IndexOutOfBounds)
+        );
+
+        assert_eq!(
+            PLONKInstance::from_rows(public_inputs, OutputRow::new(vec![Fr::zero()])).err(),
+            Some(SangriaError::IndexOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn padded_instances_fold_with_the_intersection_of_their_padding() {
+        let rng = &mut seeded_rng(
+            "relaxed_plonk::padded_instances_fold_with_the_intersection_of_their_padding",
+        );
+        let max_public_inputs = 2;
+        let padded_instance = |rng: &mut _, number_of_public_inputs: usize| {
+            let matrix = (0..3)
+                .map(|_| {
+                    (0..number_of_public_inputs + 1)
+                        .map(|_| Fr::rand(rng))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            RelaxedPLONKInstance::<Fr, FieldCommitmentConfig<Fr>>::new(
+                Fr::one(),
+                PLONKInstance::new(matrix).pad(max_public_inputs).unwrap(),
+                Fr::rand(rng),
+                Fr::rand(rng),
+                vec![Fr::rand(rng); 3],
+            )
+        };
+
+        let left = padded_instance(rng, 0);
+        let right = padded_instance(rng, 1);
+        assert!(left.has_same_shape(&right));
+        assert_eq!(left.plonk_instance.number_of_public_inputs(), 0);
+        assert_eq!(right.plonk_instance.number_of_public_inputs(), 1);
+
+        let folded = right.clone() * Fr::rand(rng) + &left;
+        assert_eq!(folded.check_padding(), Ok(()));
+        assert_eq!(folded.plonk_instance.number_of_public_inputs(), 1);
+        for (row_index, is_padding) in [false, true, false].into_iter().enumerate() {
+            assert_eq!(
+                folded.plonk_instance.is_padding_row(row_index),
+                Ok(is_padding)
+            );
+        }
+
+        assert_eq!(
+            PLONKInstance::new(vec![vec![Fr::one(); 4]])
+                .pad(max_public_inputs)
+                .err(),
+            Some(SangriaError::UnsupportedSize {
+                requested: 3,
+                max: max_public_inputs
+            })
+        );
+        let non_zero_padding = PLONKInstance {
+            matrix: vec![vec![Fr::one(); 3]],
+            padding: vec![false, true, false],
+        };
+        assert_eq!(
+            non_zero_padding.check_padding(),
+            Err(SangriaError::VerificationFailed(
+                VerificationFailure::RelaxedRelation
+            ))
+        );
     }
 
-    fn to_sponge_field_elements<F: PrimeField>(&self, _dest: &mut Vec<F>) {
-        todo!()
+    #[test]
+    fn unsatisfied_rows_are_reported_with_their_gate_values() {
+        let rng =
+            &mut seeded_rng("relaxed_plonk::unsatisfied_rows_are_reported_with_their_gate_values");
+        let rows = 6;
+        let random_column = |rng: &mut _| (0..rows).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let selectors = (0..5).map(|_| random_column(rng)).collect::<Vec<_>>();
+        let columns = (0..3).map(|_| random_column(rng)).collect::<Vec<_>>();
+        let mut labels = CircuitLabels::default();
+        labels.label_gate(4, "range_check/decompose");
+        let circuit = PLONKCircuit::new(selectors.clone(), vec![]).with_labels(labels);
+
+        let u = Fr::rand(rng);
+        let mut slack_vector = (0..rows)
+            .map(|i| {
+                u * (selectors[0][i] * columns[0][i]
+                    + selectors[1][i] * columns[1][i]
+                    + selectors[2][i] * columns[2][i])
+                    + selectors[3][i] * columns[0][i] * columns[1][i]
+                    + u * u * selectors[4][i]
+            })
+            .collect::<Vec<_>>();
+        let gate_values = slack_vector.clone();
+        slack_vector[1] += Fr::one();
+        slack_vector[4] = Fr::zero();
+
+        let instance = RelaxedPLONKInstance::<Fr, FieldCommitmentConfig<Fr>>::new(
+            Fr::one(),
+            PLONKInstance::new(vec![]),
+            u,
+            Fr::zero(),
+            vec![],
+        );
+        let witness = RelaxedPLONKWitness::new(
+            PLONKWitness::new(columns.clone()),
+            slack_vector.clone(),
+            vec![],
+            Fr::zero(),
+        );
+        assert_eq!(
+            witness.dump_unsatisfied_rows(&circuit, &instance),
+            Ok(vec![
+                UnsatisfiedRow {
+                    row_index: 1,
+                    label: None,
+                    gate_value: gate_values[1],
+                    slack: slack_vector[1],
+                },
+                UnsatisfiedRow {
+                    row_index: 4,
+                    label: Some(String::from("range_check/decompose")),
+                    gate_value: gate_values[4],
+                    slack: Fr::zero(),
+                },
+            ])
+        );
+
+        let witness = RelaxedPLONKWitness::new(
+            PLONKWitness::new(columns),
+            slack_vector[1..].to_vec(),
+            vec![],
+            Fr::zero(),
+        );
+        assert_eq!(
+            witness.dump_unsatisfied_rows(&circuit, &instance),
+            Err(SangriaError::IndexOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn instances_of_other_circuits_are_rejected() {
+        let instance = RelaxedPLONKInstance::<Fr, FieldCommitmentConfig<Fr>>::new(
+            Fr::one(),
+            PLONKInstance::new(vec![]),
+            Fr::one(),
+            Fr::zero(),
+            vec![],
+        );
+
+        assert_eq!(instance.check_circuit(Fr::one()), Ok(()));
+        assert_eq!(
+            instance.check_circuit(Fr::zero()),
+            Err(SangriaError::MismatchedCircuit)
+        );
+        assert_eq!((instance * Fr::from(2u64)).circuit_digest(), Fr::one());
+    }
+
+    #[test]
+    fn versions_of_a_circuit_are_absorbed_differently() {
+        let v1 = PLONKCircuit::<Fr>::new(vec![], vec![]).with_version(1);
+        let v2 = PLONKCircuit::<Fr>::new(vec![], vec![]).with_version(2);
+
+        assert_eq!(v2.version(), 2);
+        assert_ne!(v1.to_sponge_bytes_as_vec(), v2.to_sponge_bytes_as_vec());
+    }
+
+    #[test]
+    fn malformed_inputs_return_errors() {
+        let rng = &mut seeded_rng("relaxed_plonk::malformed_inputs_return_errors");
+        let random_vector =
+            |rng: &mut _, length| (0..length).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        for _ in 0..256 {
+            let (columns, rows) = (rng.gen_range(0..4), rng.gen_range(1..4));
+            let matrix = (0..columns)
+                .map(|_| random_vector(rng, rows))
+                .collect::<Vec<_>>();
+            let lengths: [usize; 4] = rng.gen();
+            let [hidings_length, slack_length, other_slack_length, cross_term_length] =
+                lengths.map(|length| length % 4);
+            let hidings = random_vector(rng, hidings_length);
+            let witness = RelaxedPLONKWitness::new(
+                PLONKWitness::new(matrix.clone()),
+                random_vector(rng, slack_length),
+                hidings.clone(),
+                Fr::rand(rng),
+            );
+            let instance = PLONKInstance::new(matrix);
+            let circuit = PLONKCircuit::new(instance.matrix.clone(), vec![]);
+            let index = rng.gen_range(0..8);
+
+            assert_eq!(witness.witness_column(index).is_ok(), index < columns);
+            assert_eq!(
+                witness.witness_column_with_rand(index).is_ok(),
+                index < columns && index < hidings.len()
+            );
+            assert_eq!(
+                witness.witness_row(index).is_ok(),
+                index < rows || columns == 0
+            );
+            assert_eq!(instance.column(index).is_ok(), index < columns);
+            assert_eq!(instance.row(index).is_ok(), index < rows || columns == 0);
+            assert_eq!(circuit.single_selector(index).is_ok(), index < columns);
+
+            let other = RelaxedPLONKWitness::new(
+                PLONKWitness::new(vec![]),
+                random_vector(rng, other_slack_length),
+                vec![],
+                Fr::rand(rng),
+            );
+            let cross_term = random_vector(rng, cross_term_length);
+            assert_eq!(
+                witness
+                    .fold_slack(&other, &cross_term, Fr::rand(rng), Fr::rand(rng))
+                    .is_ok(),
+                witness.slack_vector.len() == other.slack_vector.len()
+                    && cross_term.len() == other.slack_vector.len()
+            );
+        }
+    }
+
+    #[test]
+    fn folded_slack_commitment_matches_folded_randomness() {
+        let rng =
+            &mut seeded_rng("relaxed_plonk::folded_slack_commitment_matches_folded_randomness");
+        let n = 16;
+        let commit_key = Commitment::setup(rng, n);
+
+        let random_vector = |rng: &mut _| (0..n).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let witness = |rng: &mut _| {
+            RelaxedPLONKWitness::new(
+                PLONKWitness::new(vec![]),
+                random_vector(rng),
+                vec![],
+                Fr::rand(rng),
+            )
+        };
+        let left = witness(rng);
+        let right = witness(rng);
+        let cross_term = random_vector(rng);
+        let cross_term_hiding = Fr::rand(rng);
+        let challenge = Fr::rand(rng);
+
+        let commit =
+            |vector: &[Fr], hiding| Commitment::commit(&commit_key, vector, hiding).unwrap();
+        let left_commitment = commit(&left.slack_vector(), left.slack_hiding());
+        let right_commitment = commit(&right.slack_vector(), right.slack_hiding());
+        let cross_term_commitment = commit(&cross_term, cross_term_hiding);
+
+        let (slack_vector, slack_hiding) = left
+            .fold_slack(&right, &cross_term, cross_term_hiding, challenge)
+            .unwrap();
+
+        assert_eq!(
+            commit(&slack_vector, slack_hiding),
+            left_commitment
+                + cross_term_commitment * challenge
+                + right_commitment * challenge.square()
+        );
     }
 }
@@ -1,2 +1,181 @@
+use ark_ff::PrimeField;
+use ark_sponge::Absorb;
+
+use crate::{
+    ivc::{
+        AugmentedCircuitCost, IVCProof, ProverKey, PublicParameters, SangriaIVCConfig, VerifierKey,
+        VerifierKeyDigest,
+    },
+    SangriaError, StepCircuit,
+};
+
 /// The Sangria IVC scheme with proof compression and zero-knowledge
 pub struct Sangria {}
+
+impl Sangria {
+    /// Checks natively, and slowly, that every instance-witness pair retained by `proof`
+    /// satisfies its instance, see [`IVCProof::audit`]. Meant for tests and for provers to detect
+    /// a corrupted proof before spending hours continuing the computation from it.
+    pub fn audit<MainField, HelperField, Config, SC>(
+        public_parameters: &PublicParameters<MainField, HelperField, Config>,
+        prover_key: &SangriaProverKey<MainField, HelperField, Config, SC>,
+        proof: &SangriaProof<MainField, HelperField, Config>,
+    ) -> Result<(), SangriaError>
+    where
+        MainField: PrimeField + Absorb,
+        HelperField: PrimeField,
+        Config: SangriaIVCConfig<MainField, HelperField>,
+        SC: StepCircuit<MainField>,
+    {
+        proof
+            .ivc_proof
+            .audit(public_parameters, &prover_key.ivc_prover_key)
+    }
+}
+
+/// A Sangria prover key. Wraps the prover key of the underlying (uncompressed) IVC scheme.
+pub struct SangriaProverKey<
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+> {
+    ivc_prover_key: ProverKey<MainField, HelperField, Config, SC>,
+}
+
+impl<MainField, HelperField, Config, SC> SangriaProverKey<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    /// Creates a Sangria prover key from an IVC prover key.
+    pub fn new(ivc_prover_key: ProverKey<MainField, HelperField, Config, SC>) -> Self {
+        Self { ivc_prover_key }
+    }
+
+    /// Returns the step circuit this key was encoded for.
+    pub fn step_circuit(&self) -> &SC {
+        &self.ivc_prover_key._step_circuit
+    }
+
+    /// Returns the constraint breakdown of the augmented circuit, to measure the recursion
+    /// overhead relative to the size of the step circuit.
+    pub fn augmented_circuit_cost(&self) -> AugmentedCircuitCost {
+        self.ivc_prover_key._augmented_circuit_cost
+    }
+
+    /// Returns the underlying IVC prover key.
+    pub fn ivc_prover_key(&self) -> &ProverKey<MainField, HelperField, Config, SC> {
+        &self.ivc_prover_key
+    }
+}
+
+impl<MainField, HelperField, Config, SC> From<ProverKey<MainField, HelperField, Config, SC>>
+    for SangriaProverKey<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    fn from(ivc_prover_key: ProverKey<MainField, HelperField, Config, SC>) -> Self {
+        Self::new(ivc_prover_key)
+    }
+}
+
+/// A Sangria verifier key. Wraps the verifier key of the underlying (uncompressed) IVC scheme.
+pub struct SangriaVerifierKey<
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+> {
+    ivc_verifier_key: VerifierKey<MainField, HelperField, Config, SC>,
+}
+
+impl<MainField, HelperField, Config, SC> SangriaVerifierKey<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    /// Creates a Sangria verifier key from an IVC verifier key.
+    pub fn new(ivc_verifier_key: VerifierKey<MainField, HelperField, Config, SC>) -> Self {
+        Self { ivc_verifier_key }
+    }
+
+    /// Returns the step circuit this key was encoded for.
+    pub fn step_circuit(&self) -> &SC {
+        &self.ivc_verifier_key._step_circuit
+    }
+
+    /// Returns the digest of the verifier key, which is all a constrained verifier needs.
+    pub fn digest(&self) -> VerifierKeyDigest<MainField, HelperField> {
+        self.ivc_verifier_key.digest()
+    }
+
+    /// Returns the underlying IVC verifier key.
+    pub fn ivc_verifier_key(&self) -> &VerifierKey<MainField, HelperField, Config, SC> {
+        &self.ivc_verifier_key
+    }
+}
+
+impl<MainField, HelperField, Config, SC> From<VerifierKey<MainField, HelperField, Config, SC>>
+    for SangriaVerifierKey<MainField, HelperField, Config, SC>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+    SC: StepCircuit<MainField>,
+{
+    fn from(ivc_verifier_key: VerifierKey<MainField, HelperField, Config, SC>) -> Self {
+        Self::new(ivc_verifier_key)
+    }
+}
+
+/// A Sangria proof. Until compression is implemented this is the uncompressed IVC proof, i.e.
+/// the running and latest instance-witness pairs of both half cycles.
+pub struct SangriaProof<
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+> {
+    ivc_proof: IVCProof<MainField, HelperField, Config>,
+}
+
+impl<MainField, HelperField, Config> SangriaProof<MainField, HelperField, Config>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+{
+    /// Creates a Sangria proof from an uncompressed IVC proof.
+    pub fn new(ivc_proof: IVCProof<MainField, HelperField, Config>) -> Self {
+        Self { ivc_proof }
+    }
+
+    /// Returns the underlying IVC proof.
+    pub fn ivc_proof(&self) -> &IVCProof<MainField, HelperField, Config> {
+        &self.ivc_proof
+    }
+
+    /// Consumes the Sangria proof and returns the underlying IVC proof.
+    pub fn into_ivc_proof(self) -> IVCProof<MainField, HelperField, Config> {
+        self.ivc_proof
+    }
+}
+
+impl<MainField, HelperField, Config> From<IVCProof<MainField, HelperField, Config>>
+    for SangriaProof<MainField, HelperField, Config>
+where
+    MainField: PrimeField,
+    HelperField: PrimeField,
+    Config: SangriaIVCConfig<MainField, HelperField>,
+{
+    fn from(ivc_proof: IVCProof<MainField, HelperField, Config>) -> Self {
+        Self::new(ivc_proof)
+    }
+}
@@ -0,0 +1,89 @@
+//! The security level of a deployment. It is chosen once, in
+//! [`crate::SangriaIVCConfig::SECURITY_LEVEL`], and the checks of the challenge field and of the
+//! sponge configuration derive their thresholds from it.
+
+use crate::{transcript::SpongeConfig, SangriaError, FOLDING_DEGREE};
+
+/// The bits of security a deployment targets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SecurityLevel {
+    /// 128 bits of security.
+    #[default]
+    Bits128,
+    /// About 100 bits of security, for deployments that accept less security for smaller fields
+    /// and cheaper hashing.
+    Fast,
+}
+
+impl SecurityLevel {
+    /// The bits of soundness the folding challenges must provide over a whole computation.
+    pub const fn soundness_bits(self) -> u32 {
+        match self {
+            Self::Bits128 => 128,
+            Self::Fast => 100,
+        }
+    }
+
+    /// The bits a folding challenge must have so that `number_of_steps` folds keep the soundness
+    /// of this level, see [`crate::soundness_bits`]. Challenges longer than this can be truncated to it,
+    /// which shortens the scalar multiplications of the in-circuit verifier.
+    pub fn challenge_bits(self, number_of_steps: usize) -> u32 {
+        self.soundness_bits() + 1 + ark_std::log2(FOLDING_DEGREE) + ark_std::log2(number_of_steps)
+    }
+
+    /// The fewest full rounds of a Poseidon permutation. Six full rounds resist statistical
+    /// attacks; the 128-bit level adds the security margin of two rounds recommended by the
+    /// Poseidon paper.
+    pub const fn min_full_rounds(self) -> usize {
+        match self {
+            Self::Bits128 => 8,
+            Self::Fast => 6,
+        }
+    }
+
+    /// Checks that a sponge has at least [`Self::min_full_rounds`] full rounds.
+    pub fn check_sponge_config(self, sponge_config: &SpongeConfig) -> Result<(), SangriaError> {
+        if sponge_config.full_rounds < self.min_full_rounds() {
+            return Err(SangriaError::InvalidOracleParameters);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{check_challenge_soundness, soundness_bits, test_vectors::sponge_config_for_test};
+    use ark_pallas::Fr;
+
+    #[test]
+    fn levels_derive_consistent_thresholds() {
+        let number_of_steps = 1 << 32;
+        for level in [SecurityLevel::Bits128, SecurityLevel::Fast] {
+            assert_eq!(
+                check_challenge_soundness::<Fr>(level, number_of_steps),
+                Ok(())
+            );
+            assert_eq!(
+                soundness_bits(
+                    level.challenge_bits(number_of_steps),
+                    FOLDING_DEGREE,
+                    number_of_steps
+                ),
+                level.soundness_bits()
+            );
+        }
+
+        let mut sponge_config = sponge_config_for_test();
+        sponge_config.full_rounds = 6;
+        assert_eq!(
+            SecurityLevel::Fast.check_sponge_config(&sponge_config),
+            Ok(())
+        );
+        assert_eq!(
+            SecurityLevel::Bits128.check_sponge_config(&sponge_config),
+            Err(SangriaError::InvalidOracleParameters)
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Byte encodings of keys, instances and proofs. Compressed encodings are smaller and should be
+//! used on the wire. Uncompressed encodings skip point decompression, which dominates the time
+//! to deserialize multi-megabyte proving keys, and should be used for local storage.
+//!
+//! Artifacts that outlive a process can be prefixed with an [`ArtifactTag`] naming the curve and
+//! field they were produced over, so that loading them in the wrong configuration fails with
+//! [`SangriaError::CurveMismatch`] instead of yielding unrelated field elements.
+//!
+//! Encodings do not depend on the platform: integers are little-endian and lengths, counters and
+//! indices are encoded as `u64`, never as `usize`, so keys and proofs produced on a 64-bit machine
+//! load on 32-bit targets such as wasm32 and vice versa. Decoding a length that does not fit in a
+//! `usize` fails instead of truncating it, see [`usize_from_u64`].
+
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::io::{Read, Write};
+use blake2::{Blake2s256, Digest};
+
+use crate::SangriaError;
+
+/// How group elements are encoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SerializationMode {
+    /// Points are stored by their x-coordinate and a sign bit. Use this on the wire.
+    #[default]
+    Compressed,
+    /// Points are stored with both coordinates. Use this for disk storage.
+    Uncompressed,
+}
+
+/// Serializes `value` into a byte vector.
+pub fn to_bytes<T: CanonicalSerialize>(
+    value: &T,
+    mode: SerializationMode,
+) -> Result<Vec<u8>, SangriaError> {
+    let mut bytes = Vec::new();
+    serialize_with_mode(value, &mut bytes, mode)?;
+    Ok(bytes)
+}
+
+/// Deserializes a value that was serialized with the same `mode`.
+pub fn from_bytes<T: CanonicalDeserialize>(
+    bytes: &[u8],
+    mode: SerializationMode,
+) -> Result<T, SangriaError> {
+    deserialize_with_mode(bytes, mode)
+}
+
+/// Serializes `value` into `writer`, e.g. to append several values to one message.
+pub fn serialize_with_mode<T: CanonicalSerialize, W: Write>(
+    value: &T,
+    writer: W,
+    mode: SerializationMode,
+) -> Result<(), SangriaError> {
+    match mode {
+        SerializationMode::Compressed => value.serialize(writer),
+        SerializationMode::Uncompressed => value.serialize_uncompressed(writer),
+    }
+    .map_err(|_| SangriaError::SerializationError)
+}
+
+/// Deserializes a value from `reader`, consuming only the bytes that encode it.
+pub fn deserialize_with_mode<T: CanonicalDeserialize, R: Read>(
+    reader: R,
+    mode: SerializationMode,
+) -> Result<T, SangriaError> {
+    match mode {
+        SerializationMode::Compressed => T::deserialize(reader),
+        SerializationMode::Uncompressed => T::deserialize_uncompressed(reader),
+    }
+    .map_err(|_| SangriaError::SerializationError)
+}
+
+/// Converts a decoded length or index to a `usize`. Returns
+/// [`SangriaError::SerializationError`] if it does not fit on this platform, e.g. above `u32::MAX`
+/// on a 32-bit target.
+pub fn usize_from_u64(value: u64) -> Result<usize, SangriaError> {
+    usize::try_from(value).map_err(|_| SangriaError::SerializationError)
+}
+
+/// Returns the length of the encoding of `value` with `mode`, without serializing it.
+pub fn serialized_size<T: CanonicalSerialize>(value: &T, mode: SerializationMode) -> usize {
+    match mode {
+        SerializationMode::Compressed => value.serialized_size(),
+        SerializationMode::Uncompressed => value.uncompressed_size(),
+    }
+}
+
+/// Identifies the curve and the field of a serialized artifact. It is encoded as the length of
+/// the curve identifier (one byte), the identifier itself and a 32-byte digest of the modulus.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArtifactTag {
+    curve_id: Vec<u8>,
+    modulus_digest: [u8; 32],
+}
+
+impl ArtifactTag {
+    /// Creates the tag of artifacts over the field `F` of the curve named `curve_id`, e.g.
+    /// `"pallas"`. Identifiers longer than 255 bytes are rejected.
+    pub fn new<F: PrimeField>(curve_id: &str) -> Result<Self, SangriaError> {
+        if curve_id.len() > u8::MAX as usize {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(Self {
+            curve_id: curve_id.as_bytes().to_vec(),
+            modulus_digest: Blake2s256::digest(F::Params::MODULUS.to_bytes_le()).into(),
+        })
+    }
+
+    /// Writes the tag into `writer`.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), SangriaError> {
+        writer
+            .write_all(&[self.curve_id.len() as u8])
+            .and_then(|_| writer.write_all(&self.curve_id))
+            .and_then(|_| writer.write_all(&self.modulus_digest))
+            .map_err(|_| SangriaError::SerializationError)
+    }
+
+    /// Reads a tag from `reader` and checks that it is this one.
+    pub fn check<R: Read>(&self, mut reader: R) -> Result<(), SangriaError> {
+        let mut length = [0u8];
+        reader
+            .read_exact(&mut length)
+            .map_err(|_| SangriaError::SerializationError)?;
+        let mut curve_id = vec![0u8; length[0] as usize];
+        let mut modulus_digest = [0u8; 32];
+        reader
+            .read_exact(&mut curve_id)
+            .and_then(|_| reader.read_exact(&mut modulus_digest))
+            .map_err(|_| SangriaError::SerializationError)?;
+
+        if curve_id != self.curve_id || modulus_digest != self.modulus_digest {
+            return Err(SangriaError::CurveMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+/// Serializes `value` into `writer`, prefixed with `tag`.
+pub fn serialize_tagged<T: CanonicalSerialize, W: Write>(
+    value: &T,
+    tag: &ArtifactTag,
+    mut writer: W,
+    mode: SerializationMode,
+) -> Result<(), SangriaError> {
+    tag.write(&mut writer)?;
+    serialize_with_mode(value, writer, mode)
+}
+
+/// Deserializes a value that was serialized with [`serialize_tagged`], after checking that it
+/// was produced with the same `tag`.
+pub fn deserialize_tagged<T: CanonicalDeserialize, R: Read>(
+    tag: &ArtifactTag,
+    mut reader: R,
+    mode: SerializationMode,
+) -> Result<T, SangriaError> {
+    tag.check(&mut reader)?;
+    deserialize_with_mode(reader, mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seeded_rng;
+    use ark_pallas::Projective;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn round_trip_in_both_modes() {
+        let rng = &mut seeded_rng("serialization::round_trip_in_both_modes");
+        let points = (0..4)
+            .map(|_| Projective::rand(rng).into())
+            .collect::<Vec<ark_pallas::Affine>>();
+
+        let compressed = to_bytes(&points, SerializationMode::Compressed).unwrap();
+        let uncompressed = to_bytes(&points, SerializationMode::Uncompressed).unwrap();
+        assert!(compressed.len() < uncompressed.len());
+
+        for (bytes, mode) in [
+            (compressed, SerializationMode::Compressed),
+            (uncompressed, SerializationMode::Uncompressed),
+        ] {
+            assert_eq!(
+                from_bytes::<Vec<ark_pallas::Affine>>(&bytes, mode),
+                Ok(points.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn encodings_do_not_depend_on_the_platform() {
+        let mode = SerializationMode::Compressed;
+        let values = vec![ark_pallas::Fr::from(1u64); 3];
+        let bytes = to_bytes(&values, mode).unwrap();
+        assert_eq!(bytes[..8], 3u64.to_le_bytes());
+        assert_eq!(bytes[8], 1);
+        assert_eq!(bytes.len(), 8 + 3 * 32);
+        assert_eq!(to_bytes(&3usize, mode).unwrap(), 3u64.to_le_bytes());
+
+        let large = u64::from(u32::MAX) + 1;
+        assert_eq!(usize_from_u64(large).is_ok(), usize::BITS == 64);
+        assert_eq!(usize_from_u64(7), Ok(7));
+    }
+
+    #[test]
+    fn artifacts_from_another_configuration_are_rejected() {
+        let value = ark_pallas::Fr::from(42u64);
+        let pallas = ArtifactTag::new::<ark_pallas::Fr>("pallas").unwrap();
+        let mode = SerializationMode::Compressed;
+
+        let mut bytes = Vec::new();
+        serialize_tagged(&value, &pallas, &mut bytes, mode).unwrap();
+        assert_eq!(
+            deserialize_tagged::<ark_pallas::Fr, _>(&pallas, &bytes[..], mode),
+            Ok(value)
+        );
+
+        let other_curve = ArtifactTag::new::<ark_pallas::Fr>("bn254").unwrap();
+        let other_field = ArtifactTag::new::<ark_vesta::Fr>("pallas").unwrap();
+        for tag in [other_curve, other_field] {
+            assert_eq!(
+                deserialize_tagged::<ark_pallas::Fr, _>(&tag, &bytes[..], mode),
+                Err(SangriaError::CurveMismatch)
+            );
+        }
+    }
+}
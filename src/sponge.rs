@@ -0,0 +1,227 @@
+//! A minimal sponge interface, so that hashing does not tie the public API to `ark_sponge`: a
+//! [`Sponge`] absorbs field elements and bytes and squeezes field elements, which is all the
+//! folding scheme needs. [`Poseidon`] implements it natively and agrees with `PoseidonSponge` on
+//! the same parameters, and every `ark_sponge` field sponge is a [`Sponge`] too while the
+//! remaining APIs are migrated.
+
+use ark_ff::{FpParameters, PrimeField};
+use ark_sponge::{poseidon::PoseidonParameters, Absorb, FieldBasedCryptographicSponge};
+
+/// A duplex sponge over a prime field.
+pub trait Sponge<F: PrimeField> {
+    /// Absorbs field elements.
+    fn absorb_field_elements(&mut self, elements: &[F]);
+
+    /// Absorbs bytes, prefixed with their number as a little-endian `u64` and packed
+    /// little-endian into as many field elements as needed.
+    fn absorb_bytes(&mut self, bytes: &[u8]);
+
+    /// Squeezes `number_of_elements` field elements.
+    fn squeeze_field_elements(&mut self, number_of_elements: usize) -> Vec<F>;
+}
+
+impl<F, S> Sponge<F> for S
+where
+    F: PrimeField + Absorb,
+    S: FieldBasedCryptographicSponge<F>,
+{
+    fn absorb_field_elements(&mut self, elements: &[F]) {
+        self.absorb(&elements);
+    }
+
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        self.absorb(&bytes);
+    }
+
+    fn squeeze_field_elements(&mut self, number_of_elements: usize) -> Vec<F> {
+        self.squeeze_native_field_elements(number_of_elements)
+    }
+}
+
+/// Packs bytes into field elements, `(MODULUS_BITS - 1) / 8` little-endian bytes per element.
+pub(crate) fn bytes_to_field_elements<F: PrimeField>(bytes: &[u8]) -> Vec<F> {
+    let bytes_per_element = (F::Params::MODULUS_BITS as usize - 1) / 8;
+    bytes
+        .chunks(bytes_per_element)
+        .map(F::from_le_bytes_mod_order)
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum DuplexMode {
+    Absorbing { next_index: usize },
+    Squeezing { next_index: usize },
+}
+
+/// A duplex sponge over the Poseidon permutation.
+#[derive(Clone)]
+pub struct Poseidon<F: PrimeField> {
+    parameters: PoseidonParameters<F>,
+    state: Vec<F>,
+    mode: DuplexMode,
+}
+
+impl<F: PrimeField> Poseidon<F> {
+    /// Creates a sponge with an all-zero state.
+    pub fn new(parameters: &PoseidonParameters<F>) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            state: vec![F::zero(); parameters.rate + parameters.capacity],
+            mode: DuplexMode::Absorbing { next_index: 0 },
+        }
+    }
+
+    fn round(&mut self, round: usize, full: bool) {
+        for (x, c) in self.state.iter_mut().zip(&self.parameters.ark[round]) {
+            *x += c;
+        }
+        if full {
+            for x in self.state.iter_mut() {
+                *x = x.pow([self.parameters.alpha]);
+            }
+        } else {
+            self.state[0] = self.state[0].pow([self.parameters.alpha]);
+        }
+        self.state = self
+            .parameters
+            .mds
+            .iter()
+            .map(|row| row.iter().zip(&self.state).map(|(m, x)| *m * x).sum())
+            .collect();
+    }
+
+    fn permute(&mut self) {
+        let half_full_rounds = self.parameters.full_rounds as usize / 2;
+        let partial_rounds = self.parameters.partial_rounds as usize;
+
+        for round in 0..half_full_rounds {
+            self.round(round, true);
+        }
+        for round in half_full_rounds..half_full_rounds + partial_rounds {
+            self.round(round, false);
+        }
+        for round in half_full_rounds + partial_rounds..2 * half_full_rounds + partial_rounds {
+            self.round(round, true);
+        }
+    }
+
+    fn absorb_internal(&mut self, mut rate_start_index: usize, mut elements: &[F]) {
+        let (rate, capacity) = (self.parameters.rate, self.parameters.capacity);
+        loop {
+            if rate_start_index + elements.len() <= rate {
+                for (i, element) in elements.iter().enumerate() {
+                    self.state[capacity + rate_start_index + i] += element;
+                }
+                self.mode = DuplexMode::Absorbing {
+                    next_index: rate_start_index + elements.len(),
+                };
+                return;
+            }
+
+            let absorbed = rate - rate_start_index;
+            for (i, element) in elements.iter().take(absorbed).enumerate() {
+                self.state[capacity + rate_start_index + i] += element;
+            }
+            self.permute();
+            elements = &elements[absorbed..];
+            rate_start_index = 0;
+        }
+    }
+
+    fn squeeze_internal(&mut self, mut rate_start_index: usize, mut output: &mut [F]) {
+        let (rate, capacity) = (self.parameters.rate, self.parameters.capacity);
+        loop {
+            let start = capacity + rate_start_index;
+            if rate_start_index + output.len() <= rate {
+                output.copy_from_slice(&self.state[start..start + output.len()]);
+                self.mode = DuplexMode::Squeezing {
+                    next_index: rate_start_index + output.len(),
+                };
+                return;
+            }
+
+            let squeezed = rate - rate_start_index;
+            output[..squeezed].copy_from_slice(&self.state[start..start + squeezed]);
+            if output.len() != rate {
+                self.permute();
+            }
+            output = &mut output[squeezed..];
+            rate_start_index = 0;
+        }
+    }
+}
+
+impl<F: PrimeField> Sponge<F> for Poseidon<F> {
+    fn absorb_field_elements(&mut self, elements: &[F]) {
+        if elements.is_empty() {
+            return;
+        }
+
+        match self.mode {
+            DuplexMode::Absorbing { next_index } if next_index == self.parameters.rate => {
+                self.permute();
+                self.absorb_internal(0, elements);
+            }
+            DuplexMode::Absorbing { next_index } => self.absorb_internal(next_index, elements),
+            DuplexMode::Squeezing { .. } => self.absorb_internal(0, elements),
+        }
+    }
+
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        let mut prefixed = (bytes.len() as u64).to_le_bytes().to_vec();
+        prefixed.extend_from_slice(bytes);
+        self.absorb_field_elements(&bytes_to_field_elements(&prefixed));
+    }
+
+    fn squeeze_field_elements(&mut self, number_of_elements: usize) -> Vec<F> {
+        let mut output = vec![F::zero(); number_of_elements];
+        match self.mode {
+            DuplexMode::Absorbing { .. } => {
+                self.permute();
+                self.squeeze_internal(0, &mut output);
+            }
+            DuplexMode::Squeezing { next_index } if next_index == self.parameters.rate => {
+                self.permute();
+                self.squeeze_internal(0, &mut output);
+            }
+            DuplexMode::Squeezing { next_index } => self.squeeze_internal(next_index, &mut output),
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::seeded_rng, test_vectors::poseidon_parameters_for_test};
+    use ark_pallas::Fr;
+    use ark_sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn poseidon_agrees_with_ark_sponge() {
+        let rng = &mut seeded_rng("sponge::poseidon_agrees_with_ark_sponge");
+        let parameters = poseidon_parameters_for_test::<Fr, _>(rng);
+        let elements = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let bytes = (0..70).map(|i| i as u8).collect::<Vec<_>>();
+
+        let mut native = Poseidon::new(&parameters);
+        let mut reference = PoseidonSponge::new(&parameters);
+        native.absorb_bytes(&bytes);
+        native.absorb_field_elements(&elements);
+        Sponge::absorb_bytes(&mut reference, &bytes);
+        Sponge::absorb_field_elements(&mut reference, &elements);
+        assert_eq!(
+            native.squeeze_field_elements(3),
+            Sponge::squeeze_field_elements(&mut reference, 3)
+        );
+
+        native.absorb_field_elements(&elements[..1]);
+        Sponge::absorb_field_elements(&mut reference, &elements[..1]);
+        assert_eq!(
+            native.squeeze_field_elements(1),
+            Sponge::squeeze_field_elements(&mut reference, 1)
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Operational metrics of the prover. Under the `metrics` feature they are emitted through the
+//! [`metrics`](https://docs.rs/metrics) facade, so whichever exporter the application installs
+//! (e.g. a Prometheus endpoint) picks them up. Without the feature every function is a no-op.
+
+/// Counter of the IVC steps proven successfully.
+pub const STEPS_PROVEN: &str = "sangria_steps_proven_total";
+
+/// Histogram of the time spent proving an IVC step, in seconds.
+pub const STEP_SECONDS: &str = "sangria_step_seconds";
+
+/// Histogram of the time spent by the folding prover, in seconds.
+pub const FOLD_SECONDS: &str = "sangria_fold_seconds";
+
+/// Histogram of the time spent in multi-scalar multiplications, in seconds.
+pub const MSM_SECONDS: &str = "sangria_msm_seconds";
+
+/// Histogram of the size of the serialized proofs, in bytes.
+pub const PROOF_BYTES: &str = "sangria_proof_bytes";
+
+/// Runs `f` and records its duration in the histogram `name`.
+pub(crate) fn timed<T>(_name: &'static str, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "metrics")]
+    let start = std::time::Instant::now();
+
+    let result = f();
+
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(_name, start.elapsed());
+
+    result
+}
+
+/// Records that an IVC step was proven.
+pub(crate) fn step_proven() {
+    #[cfg(feature = "metrics")]
+    metrics::increment_counter!(STEPS_PROVEN);
+}
+
+/// Records the size of a serialized proof.
+pub(crate) fn proof_bytes(_length: usize) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(PROOF_BYTES, _length as f64);
+}
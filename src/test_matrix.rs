@@ -0,0 +1,302 @@
+//! Field-agnostic tests instantiated over the scalar fields of every supported curve, so that
+//! bugs depending on the field size (absorption packing, challenge truncation, two-adicity)
+//! surface in all configurations. The commitment and folding tests run both with the mock
+//! commitment over each field and with Pedersen commitments over each curve.
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{One, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Evaluations};
+use ark_sponge::{
+    poseidon::PoseidonSponge, Absorb, CryptographicSponge, FieldBasedCryptographicSponge,
+};
+use ark_std::{marker::PhantomData, UniformRand};
+
+use crate::{
+    circuit_builder::PLONKCircuitBuilder,
+    domain::domain_for_size,
+    relations::{
+        decide, relax, FoldingCommitmentConfig, HomomorphicCommitmentScheme,
+        NonInteractiveFoldingScheme, OpenableCommitmentScheme, PLONKFoldingScheme, PLONKInstance,
+        PLONKWitness, SetupInfo, SlackRandomization,
+    },
+    test_utils::seeded_rng,
+    test_vectors::{poseidon_parameters_for_test, sponge_config_for_test},
+    transcript::{Blake2bSponge, LabeledChallenges, FOLDING_CHALLENGE, TRANSCRIPT_SEED},
+    vector_commitment::{
+        mock::{FieldCommitment, FieldCommitmentConfig},
+        pedersen::PedersenCommitment,
+        poseidon_merkle::PoseidonMerkleCommitment,
+        VectorCommitmentScheme,
+    },
+};
+
+/// Commits to both the witness and the slack vector with [`PedersenCommitment`] on `C`.
+struct PedersenConfig<C: ProjectiveCurve>(PhantomData<C>);
+
+impl<C: ProjectiveCurve> FoldingCommitmentConfig<C::ScalarField> for PedersenConfig<C>
+where
+    C::ScalarField: Absorb,
+{
+    type CommitmentSlack = PedersenCommitment<C>;
+    type CommitmentWitness = PedersenCommitment<C>;
+
+    const CURVE_ID: &'static str = "test-matrix";
+}
+
+fn squeeze_is_deterministic<F: PrimeField + Absorb>() {
+    let rng = &mut seeded_rng("test_matrix::squeeze_is_deterministic");
+    let parameters = poseidon_parameters_for_test::<F, _>(rng);
+    let absorbed = (0..7).map(|_| F::rand(rng)).collect::<Vec<_>>();
+
+    let squeeze = || {
+        let mut sponge = PoseidonSponge::new(&parameters);
+        sponge.absorb(&absorbed);
+        sponge.squeeze_field_elements::<F>(2)
+    };
+
+    let first = squeeze();
+    assert_eq!(first, squeeze());
+    assert_ne!(first[0], first[1]);
+}
+
+fn squeezed_bytes_have_requested_length<F: PrimeField + Absorb>() {
+    let rng = &mut seeded_rng("test_matrix::squeezed_bytes_have_requested_length");
+    let parameters = poseidon_parameters_for_test::<F, _>(rng);
+
+    let mut sponge = PoseidonSponge::new(&parameters);
+    sponge.absorb(&F::rand(rng));
+
+    for length in [1, 31, 32, 33, 64] {
+        assert_eq!(sponge.squeeze_bytes(length).len(), length);
+    }
+}
+
+fn blake2b_native_squeeze_is_deterministic<F: PrimeField + Absorb>() {
+    let rng = &mut seeded_rng("test_matrix::blake2b_native_squeeze_is_deterministic");
+    let absorbed = (0..7).map(|_| F::rand(rng)).collect::<Vec<_>>();
+
+    let squeeze = || {
+        let mut sponge = Blake2bSponge::new(&());
+        sponge.absorb(&absorbed);
+        let first: Vec<F> = sponge.squeeze_native_field_elements(2);
+        let second: Vec<F> = sponge.squeeze_native_field_elements(1);
+        (first, second)
+    };
+
+    let (first, second) = squeeze();
+    assert_eq!((first.clone(), second.clone()), squeeze());
+    assert_ne!(first[0], first[1]);
+    assert_ne!(first[0], second[0]);
+}
+
+fn labeled_challenges_are_domain_separated<F: PrimeField + Absorb>() {
+    let rng = &mut seeded_rng("test_matrix::labeled_challenges_are_domain_separated");
+    let parameters = poseidon_parameters_for_test::<F, _>(rng);
+    let absorbed = F::rand(rng);
+
+    let challenge = |label| {
+        let mut sponge = PoseidonSponge::new(&parameters);
+        sponge.absorb(&absorbed);
+        LabeledChallenges::<F>::challenge(&mut sponge, label)
+    };
+
+    assert_eq!(challenge(FOLDING_CHALLENGE), challenge(FOLDING_CHALLENGE));
+    assert_ne!(challenge(FOLDING_CHALLENGE), challenge(TRANSCRIPT_SEED));
+}
+
+fn merkle_commitment_binds_length<F: PrimeField + Absorb>() {
+    let rng = &mut seeded_rng("test_matrix::merkle_commitment_binds_length");
+    let parameters = poseidon_parameters_for_test::<F, _>(rng);
+    let value = F::rand(rng);
+
+    let short = PoseidonMerkleCommitment::commit(&parameters, &[value]).unwrap();
+    let padded = PoseidonMerkleCommitment::commit(&parameters, &[value, F::zero()]).unwrap();
+
+    assert_ne!(short, padded);
+}
+
+fn witness_polynomials_round_trip<F: PrimeField>() {
+    let rng = &mut seeded_rng("test_matrix::witness_polynomials_round_trip");
+    let domain = domain_for_size::<F>(16).unwrap();
+
+    let columns = (0..3)
+        .map(|_| {
+            let evaluations = (0..domain.size()).map(|_| F::rand(rng)).collect();
+            Evaluations::from_vec_and_domain(evaluations, domain)
+        })
+        .collect::<Vec<_>>();
+    let witness = PLONKWitness::from_evaluations(columns.clone());
+
+    let polynomials = witness.to_polynomials(domain).unwrap();
+    for (polynomial, column) in polynomials.iter().zip(columns) {
+        assert_eq!(polynomial.evaluate_over_domain_by_ref(domain), column);
+    }
+}
+
+fn commitments_are_homomorphic<F, Comm>()
+where
+    F: PrimeField,
+    Comm: OpenableCommitmentScheme<F>,
+{
+    let rng = &mut seeded_rng("test_matrix::commitments_are_homomorphic");
+    let commit_key = Comm::setup(rng, 8);
+    let sample = |rng: &mut _| (0..8).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let (x, y) = (sample(rng), sample(rng));
+    let (r, s, challenge) = (F::rand(rng), F::rand(rng), F::rand(rng));
+
+    let folded = x
+        .iter()
+        .zip(&y)
+        .map(|(x_i, y_i)| *x_i + challenge * y_i)
+        .collect::<Vec<_>>();
+    let commitment_x = Comm::commit(&commit_key, &x, r).unwrap();
+    let commitment_y = Comm::commit(&commit_key, &y, s).unwrap();
+    assert!(
+        Comm::commit(&commit_key, &folded, r + challenge * s).unwrap()
+            == commitment_x + commitment_y * challenge
+    );
+    assert!(
+        Comm::batch_commit(&commit_key, &[(x.as_slice(), r), (y.as_slice(), s)]).unwrap()
+            == [commitment_x, commitment_y]
+    );
+
+    let opening = Comm::open(&commit_key, &x, r, 3, rng).unwrap();
+    assert!(Comm::verify_opening(&commit_key, &commitment_x, 3, x[3], &opening).is_ok());
+    assert!(Comm::verify_opening(&commit_key, &commitment_x, 3, y[3], &opening).is_err());
+}
+
+fn folded_pairs_are_decided<F, Comm>()
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+{
+    type FoldingScheme<F, Comm> = PLONKFoldingScheme<F, Comm, PoseidonSponge<F>>;
+
+    let rng = &mut seeded_rng("test_matrix::folded_pairs_are_decided");
+    let number_of_gates = 4;
+    let info = SetupInfo {
+        number_of_public_inputs: 0,
+        number_of_gates,
+        domain_separator: b"sangria-test-matrix".to_vec(),
+        poseidon_constants: poseidon_parameters_for_test(rng),
+        poseidon2_constants: None,
+        sponge_config: sponge_config_for_test(),
+        slack_randomization: SlackRandomization::EveryFold,
+    };
+    let pp = FoldingScheme::<F, Comm>::setup(&info, rng).unwrap();
+
+    // c = a * b + 1 at every gate, so that folding produces a non-zero cross term.
+    let mut builder = PLONKCircuitBuilder::new();
+    for _ in 0..number_of_gates {
+        builder.add_gate([F::zero(), F::zero(), -F::one(), F::one(), F::one()]);
+    }
+    let (pk, vk) = FoldingScheme::<F, Comm>::encode(&pp, &builder.build().unwrap(), rng).unwrap();
+    let mut relaxed = || {
+        let a = (0..number_of_gates)
+            .map(|_| F::rand(rng))
+            .collect::<Vec<_>>();
+        let b = (0..number_of_gates)
+            .map(|_| F::rand(rng))
+            .collect::<Vec<_>>();
+        let c = a.iter().zip(&b).map(|(a, b)| *a * b + F::one()).collect();
+        let witness = PLONKWitness::new(vec![a, b, c]);
+        relax(&pp, &vk, PLONKInstance::new(vec![]), witness, rng).unwrap()
+    };
+    let (left_instance, left_witness) = relaxed();
+    let (right_instance, right_witness) = relaxed();
+
+    let (instance, witness, _) = FoldingScheme::<F, Comm>::prove_and_check(
+        &pp,
+        &pk,
+        &left_instance,
+        &left_witness,
+        &right_instance,
+        &right_witness,
+    )
+    .unwrap();
+    assert!(!instance.is_strict());
+    assert!(decide(&pp, &pk, &instance, &witness).is_ok());
+    assert!(decide(&pp, &pk, &instance, &left_witness).is_err());
+}
+
+macro_rules! field_test_matrix {
+    ($($name:ident => $field:ty),* $(,)?) => {
+        $(
+            mod $name {
+                #[test]
+                fn squeeze_is_deterministic() {
+                    super::squeeze_is_deterministic::<$field>();
+                }
+
+                #[test]
+                fn squeezed_bytes_have_requested_length() {
+                    super::squeezed_bytes_have_requested_length::<$field>();
+                }
+
+                #[test]
+                fn blake2b_native_squeeze_is_deterministic() {
+                    super::blake2b_native_squeeze_is_deterministic::<$field>();
+                }
+
+                #[test]
+                fn labeled_challenges_are_domain_separated() {
+                    super::labeled_challenges_are_domain_separated::<$field>();
+                }
+
+                #[test]
+                fn merkle_commitment_binds_length() {
+                    super::merkle_commitment_binds_length::<$field>();
+                }
+
+                #[test]
+                fn witness_polynomials_round_trip() {
+                    super::witness_polynomials_round_trip::<$field>();
+                }
+
+                #[test]
+                fn commitments_are_homomorphic() {
+                    super::commitments_are_homomorphic::<$field, super::FieldCommitment<$field>>();
+                }
+
+                #[test]
+                fn folded_pairs_are_decided() {
+                    super::folded_pairs_are_decided::<$field, super::FieldCommitmentConfig<$field>>();
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! curve_test_matrix {
+    ($($name:ident => $curve:ty),* $(,)?) => {
+        $(
+            mod $name {
+                type Scalar = <$curve as ark_ec::ProjectiveCurve>::ScalarField;
+
+                #[test]
+                fn commitments_are_homomorphic() {
+                    super::commitments_are_homomorphic::<Scalar, super::PedersenCommitment<$curve>>();
+                }
+
+                #[test]
+                fn folded_pairs_are_decided() {
+                    super::folded_pairs_are_decided::<Scalar, super::PedersenConfig<$curve>>();
+                }
+            }
+        )*
+    };
+}
+
+field_test_matrix!(
+    bls12_377 => ark_bls12_377::Fr,
+    bn254 => ark_bn254::Fr,
+    pallas => ark_pallas::Fr,
+    vesta => ark_vesta::Fr,
+);
+
+curve_test_matrix!(
+    pedersen_bls12_377 => ark_bls12_377::G1Projective,
+    pedersen_bn254 => ark_bn254::G1Projective,
+    pedersen_pallas => ark_pallas::Projective,
+    pedersen_vesta => ark_vesta::Projective,
+);
@@ -0,0 +1,88 @@
+//! Deterministic randomness for tests. Every test draws from [`seeded_rng`] with its own label,
+//! so tests do not share a random stream and a failure can be reproduced on its own.
+//!
+//! The seed of a label is fixed unless `SANGRIA_TEST_SEED` is set, which mixes its value into
+//! every seed, e.g. to run the randomized tests on other inputs in CI. A test that fails reports
+//! its label, seed and `SANGRIA_TEST_SEED` after the failure message; rerunning with the same
+//! `SANGRIA_TEST_SEED` reproduces the failure. Golden test vectors use [`fixed_rng`] instead,
+//! which ignores `SANGRIA_TEST_SEED`.
+
+use ark_std::rand::{rngs::StdRng, CryptoRng, Error, RngCore, SeedableRng};
+use blake2::{Blake2s256, Digest};
+
+const SEED_ENV_VAR: &str = "SANGRIA_TEST_SEED";
+
+/// A generator returned by [`seeded_rng`]. If the test panics while it is alive, e.g. on a
+/// failed assertion, its label and seed are reported after the panic message.
+pub(crate) struct TestRng {
+    label: String,
+    salt: String,
+    seed: [u8; 32],
+    rng: StdRng,
+}
+
+impl RngCore for TestRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for TestRng {}
+
+impl Drop for TestRng {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!(
+                "{} failed with seed {} ({}={:?})",
+                self.label,
+                self.seed
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<String>(),
+                SEED_ENV_VAR,
+                self.salt
+            );
+        }
+    }
+}
+
+/// Returns a generator seeded with the hash of `label` and of `SANGRIA_TEST_SEED`, if set.
+/// Labels are conventionally the path of the test, e.g. `folding_scheme::self_test`.
+pub(crate) fn seeded_rng(label: &str) -> TestRng {
+    let salt = std::env::var(SEED_ENV_VAR).unwrap_or_default();
+    let seed = seed(label, &salt);
+
+    TestRng {
+        label: label.to_string(),
+        salt,
+        seed,
+        rng: StdRng::from_seed(seed),
+    }
+}
+
+/// Returns the generator that [`seeded_rng`] returns for `label` when `SANGRIA_TEST_SEED` is not
+/// set, regardless of the variable.
+pub(crate) fn fixed_rng(label: &str) -> StdRng {
+    StdRng::from_seed(seed(label, ""))
+}
+
+fn seed(label: &str, salt: &str) -> [u8; 32] {
+    Blake2s256::new()
+        .chain_update((label.len() as u64).to_le_bytes())
+        .chain_update(label)
+        .chain_update(salt)
+        .finalize()
+        .into()
+}
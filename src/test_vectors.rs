@@ -0,0 +1,279 @@
+//! Golden test vectors. Each test serializes a value computed from a fixed seed (see
+//! [`fixed_rng`]) and compares it byte for byte against a fixture committed under
+//! `test_vectors/`. A mismatch means the transcript or serialization format changed, which breaks
+//! compatibility with existing proofs.
+//!
+//! A missing fixture fails its test like a mismatch, so that a fixture that was never committed
+//! cannot pass silently. To generate or regenerate the fixtures after an intended change, run the
+//! tests with `SANGRIA_UPDATE_TEST_VECTORS=1` and commit the result.
+
+use ark_ff::PrimeField;
+use ark_pallas::Fr;
+use ark_serialize::CanonicalSerialize;
+use ark_sponge::{
+    poseidon::{PoseidonParameters, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_std::{rand::Rng, UniformRand};
+use std::{fs, path::PathBuf};
+
+use crate::{
+    test_utils::fixed_rng,
+    transcript::{Poseidon2Parameters, SpongeConfig},
+    PLONKCircuit,
+};
+
+const UPDATE_ENV_VAR: &str = "SANGRIA_UPDATE_TEST_VECTORS";
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test_vectors")
+        .join(format!("{}.bin", name))
+}
+
+/// Compares the serialization of `value` against the fixture `name`, or writes the fixture if an
+/// update was requested. Panics if the fixture is missing.
+fn assert_matches_fixture<T: CanonicalSerialize>(name: &str, value: &T) {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).unwrap();
+
+    let path = fixture_path(name);
+    if std::env::var_os(UPDATE_ENV_VAR).is_some() {
+        fs::write(&path, &bytes).unwrap();
+        return;
+    }
+
+    let expected = fs::read(&path).unwrap_or_else(|_| {
+        panic!(
+            "test vector `{}` is missing, run with {}=1 to generate it",
+            name, UPDATE_ENV_VAR
+        )
+    });
+    assert_eq!(
+        expected, bytes,
+        "test vector `{}` changed, rerun with {}=1 if this is intended",
+        name, UPDATE_ENV_VAR
+    );
+}
+
+/// Poseidon parameters sampled from `rng`. Only meant to give tests a deterministic sponge.
+/// The sponge configuration of [`poseidon_parameters_for_test`] and
+/// [`poseidon2_parameters_for_test`].
+pub(crate) fn sponge_config_for_test() -> SpongeConfig {
+    SpongeConfig {
+        rate: 2,
+        capacity: 1,
+        full_rounds: 8,
+        partial_rounds: 31,
+        alpha: 5,
+    }
+}
+
+pub(crate) fn poseidon_parameters_for_test<F: PrimeField, R: Rng>(
+    rng: &mut R,
+) -> PoseidonParameters<F> {
+    let full_rounds = 8;
+    let partial_rounds = 31;
+    let rate = 2;
+    let capacity = 1;
+    let width = rate + capacity;
+
+    let mds = (0..width)
+        .map(|_| (0..width).map(|_| F::rand(rng)).collect())
+        .collect();
+    let ark = (0..full_rounds + partial_rounds)
+        .map(|_| (0..width).map(|_| F::rand(rng)).collect())
+        .collect();
+
+    PoseidonParameters::new(
+        full_rounds as u32,
+        partial_rounds as u32,
+        5,
+        mds,
+        ark,
+        rate,
+        capacity,
+    )
+}
+
+/// Poseidon2 parameters sampled from `rng`, with the same round numbers as
+/// `poseidon_parameters_for_test`.
+pub(crate) fn poseidon2_parameters_for_test<F: PrimeField, R: Rng>(
+    rng: &mut R,
+) -> Poseidon2Parameters<F> {
+    let full_rounds = 8;
+    let partial_rounds = 31;
+    let rate = 2;
+    let capacity = 1;
+    let width = rate + capacity;
+
+    let external_round_constants = (0..full_rounds)
+        .map(|_| (0..width).map(|_| F::rand(rng)).collect())
+        .collect();
+    let internal_round_constants = (0..partial_rounds).map(|_| F::rand(rng)).collect();
+    let internal_diagonal = (0..width).map(|_| F::rand(rng)).collect();
+
+    Poseidon2Parameters::new(
+        full_rounds,
+        partial_rounds,
+        5,
+        external_round_constants,
+        internal_round_constants,
+        internal_diagonal,
+        rate,
+        capacity,
+    )
+    .unwrap()
+}
+
+#[test]
+fn poseidon_transcript() {
+    let rng = &mut fixed_rng("test_vectors::poseidon_transcript");
+    let parameters = poseidon_parameters_for_test::<Fr, _>(rng);
+
+    let mut sponge = PoseidonSponge::new(&parameters);
+    let absorbed = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    sponge.absorb(&absorbed);
+    let challenges: Vec<Fr> = sponge.squeeze_field_elements(3);
+
+    assert_matches_fixture("poseidon_transcript", &challenges);
+}
+
+#[test]
+fn circuit_serialization() {
+    let rng = &mut fixed_rng("test_vectors::circuit_serialization");
+    let number_of_rows = 8;
+
+    let selectors = (0..5)
+        .map(|_| (0..number_of_rows).map(|_| Fr::rand(rng)).collect())
+        .collect::<Vec<Vec<Fr>>>();
+    let copy_constraint = (0..3 * number_of_rows)
+        .map(|_| Fr::rand(rng))
+        .collect::<Vec<_>>();
+    let circuit = PLONKCircuit::new(selectors, copy_constraint);
+
+    assert_matches_fixture("circuit_selectors", &circuit.selectors());
+    assert_matches_fixture("circuit_copy_constraint", &circuit.copy_constraint());
+}
+
+/// Converts a small signed integer, e.g. from a worked example, to a field element.
+fn small_element(value: i64) -> Fr {
+    let magnitude = Fr::from(value.unsigned_abs());
+    if value < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+fn small_elements(values: &[i64]) -> Vec<Fr> {
+    values.iter().copied().map(small_element).collect()
+}
+
+/// A worked example of two folds, small enough to check by hand: the gates `a * b = c` and
+/// `a + b + 5 = c`, folded with the fixed challenges `r = 2` and then `r = 3`. Every expected
+/// value is derived by hand below, independently of the implementation, so that a sign or
+/// ordering error in the relaxation or the cross term changes at least one of them.
+#[test]
+fn worked_folding_example() {
+    use crate::{
+        cross_term::{compute_cross_term, CrossTermConfig, RelaxedTrace},
+        relaxed_plonk::{PLONKInstance, PLONKWitness},
+        vector_commitment::mock::FieldCommitmentConfig,
+        RelaxedPLONKInstance, RelaxedPLONKWitness,
+    };
+    use ark_ff::{One, Zero};
+
+    // q_L, q_R, q_O, q_M, q_C.
+    let selectors = [[0, 1], [0, 1], [-1, -1], [1, 0], [0, 5]]
+        .iter()
+        .map(|selector| small_elements(selector))
+        .collect::<Vec<_>>();
+    let circuit = PLONKCircuit::new(selectors.clone(), Vec::new());
+
+    // Each step is `(u, [a, b, c], E)`, its columns indexed by gate.
+    type Step = (i64, [[i64; 2]; 3], [i64; 2]);
+    // Two fresh traces: 2 * 3 = 6, 1 + 4 + 5 = 10 and 4 * 5 = 20, 7 + 0 + 5 = 12.
+    let first: Step = (1, [[2, 1], [3, 4], [6, 10]], [0, 0]);
+    let second: Step = (1, [[4, 7], [5, 0], [20, 12]], [0, 0]);
+    // T = u_1 * (-c_2) + u_2 * (-c_1) + a_1 * b_2 + a_2 * b_1 = -20 - 6 + 10 + 12 = -4 for the
+    // first gate, and u_1 * (a_2 + b_2 - c_2) + u_2 * (a_1 + b_1 - c_1) + 2 * u_1 * u_2 * 5
+    // = -5 - 5 + 10 = 0 for the second. Folding with r = 2 gives u = 3 and E = 2 * T.
+    let first_cross_term = [-4, 0];
+    let first_fold: Step = (3, [[10, 15], [13, 4], [46, 34]], [-8, 0]);
+    // Folding in the fresh trace 1 * 1 = 1, 0 + 0 + 5 = 5 gives T = -3 - 46 + 10 + 13 = -26 and
+    // T = -15 - 15 + 30 = 0. With r = 3, u = 6 and E = -8 + 3 * T = -86.
+    let third: Step = (1, [[1, 0], [1, 0], [1, 5]], [0, 0]);
+    let second_cross_term = [-26, 0];
+    let second_fold: Step = (6, [[13, 15], [16, 4], [49, 49]], [-86, 0]);
+
+    let instance = |step: &Step| {
+        RelaxedPLONKInstance::<Fr, FieldCommitmentConfig<Fr>>::new(
+            Fr::one(),
+            PLONKInstance::new(vec![vec![Fr::zero()]; 3]),
+            small_element(step.0),
+            Fr::zero(),
+            vec![Fr::zero(); 3],
+        )
+    };
+    let witness = |step: &Step| {
+        RelaxedPLONKWitness::new(
+            PLONKWitness::new(step.1.iter().map(|column| small_elements(column)).collect()),
+            small_elements(&step.2),
+            vec![Fr::zero(); 3],
+            Fr::zero(),
+        )
+    };
+    for step in [&first, &second, &first_fold, &third, &second_fold] {
+        assert_eq!(
+            witness(step).dump_unsatisfied_rows(&circuit, &instance(step)),
+            Ok(vec![])
+        );
+    }
+
+    for (left, right, challenge, cross_term, folded) in [
+        (&first, &second, 2, first_cross_term, &first_fold),
+        (&first_fold, &third, 3, second_cross_term, &second_fold),
+    ] {
+        let (left_witness, right_witness) = (witness(left), witness(right));
+        let trace = |witness: &RelaxedPLONKWitness<Fr>, step: &Step| RelaxedTrace {
+            columns: [0, 1, 2].map(|column| witness.witness_column(column).unwrap()),
+            scaling_factor: small_element(step.0),
+        };
+        let computed_cross_term = compute_cross_term(
+            &selectors,
+            &trace(&left_witness, left),
+            &trace(&right_witness, right),
+            CrossTermConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(computed_cross_term, small_elements(&cross_term));
+
+        let challenge = small_element(challenge);
+        let folded_witness = left_witness
+            .fold_with(&right_witness, &computed_cross_term, Fr::zero(), challenge)
+            .unwrap();
+        assert_eq!(folded_witness.slack_vector(), small_elements(&folded.2));
+        for column in 0..3 {
+            assert_eq!(
+                folded_witness.witness_column(column),
+                Ok(small_elements(&folded.1[column]).as_slice())
+            );
+        }
+
+        let folded_instance = instance(right) * challenge + &instance(left);
+        assert_eq!(
+            folded_instance.scaling_factor(),
+            instance(folded).scaling_factor()
+        );
+    }
+
+    assert!(witness(&first)
+        .fold_with(
+            &RelaxedPLONKWitness::trivial(3),
+            &[Fr::zero(); 2],
+            Fr::zero(),
+            Fr::one()
+        )
+        .is_err());
+}
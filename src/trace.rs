@@ -0,0 +1,251 @@
+//! Witness generation. A synthesizer writes the execution trace of a step into a [`TraceSink`],
+//! either row by row, in batches of rows, or by reserving a region of rows up front and filling
+//! disjoint parts of it in parallel. The trace is stored by column, like [`PLONKWitness`].
+
+use ark_ff::PrimeField;
+
+use crate::{relaxed_plonk::PLONKWitness, SangriaError};
+
+/// A destination for the rows of an execution trace.
+pub trait TraceSink<F: PrimeField> {
+    /// The number of columns of every row.
+    fn number_of_columns(&self) -> usize;
+
+    /// Appends a single row.
+    fn push_row(&mut self, row: &[F]) -> Result<(), SangriaError>;
+
+    /// Appends a batch of rows, laid out one after the other in `rows`.
+    fn push_rows(&mut self, rows: &[F]) -> Result<(), SangriaError> {
+        let number_of_columns = self.number_of_columns();
+        if number_of_columns == 0 || rows.len() % number_of_columns != 0 {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        rows.chunks_exact(number_of_columns)
+            .try_for_each(|row| self.push_row(row))
+    }
+
+    /// Appends `number_of_rows` zero rows and returns them as a region to be filled in.
+    fn reserve_rows(&mut self, number_of_rows: usize) -> Result<TraceRegion<'_, F>, SangriaError>;
+}
+
+/// A contiguous range of rows of a trace, borrowed mutably. Regions can be split into disjoint
+/// sub-regions, which can be filled from different threads.
+pub struct TraceRegion<'a, F: PrimeField> {
+    columns: Vec<&'a mut [F]>,
+    number_of_rows: usize,
+}
+
+impl<'a, F: PrimeField> TraceRegion<'a, F> {
+    /// The number of rows in the region.
+    pub fn number_of_rows(&self) -> usize {
+        self.number_of_rows
+    }
+
+    /// Sets the value of a cell, with `row_index` relative to the start of the region.
+    pub fn set(
+        &mut self,
+        row_index: usize,
+        column_index: usize,
+        value: F,
+    ) -> Result<(), SangriaError> {
+        let cell = self
+            .columns
+            .get_mut(column_index)
+            .and_then(|column| column.get_mut(row_index))
+            .ok_or(SangriaError::IndexOutOfBounds)?;
+        *cell = value;
+
+        Ok(())
+    }
+
+    /// Sets every cell of a row, with `row_index` relative to the start of the region.
+    pub fn set_row(&mut self, row_index: usize, row: &[F]) -> Result<(), SangriaError> {
+        if row.len() != self.columns.len() || row_index >= self.number_of_rows {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+        for (column, value) in self.columns.iter_mut().zip(row) {
+            column[row_index] = *value;
+        }
+
+        Ok(())
+    }
+
+    /// Splits the region into the rows before `mid` and the rows from `mid` onwards.
+    pub fn split_at(self, mid: usize) -> Result<(Self, Self), SangriaError> {
+        if mid > self.number_of_rows {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let (left, right): (Vec<_>, Vec<_>) = self
+            .columns
+            .into_iter()
+            .map(|column| column.split_at_mut(mid))
+            .unzip();
+
+        Ok((
+            Self {
+                columns: left,
+                number_of_rows: mid,
+            },
+            Self {
+                columns: right,
+                number_of_rows: self.number_of_rows - mid,
+            },
+        ))
+    }
+
+    /// Splits the region into sub-regions of `rows_per_region` rows; the last one may be shorter.
+    pub fn chunks(self, rows_per_region: usize) -> Result<Vec<Self>, SangriaError> {
+        if rows_per_region == 0 {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let mut regions = Vec::new();
+        let mut rest = self;
+        while rest.number_of_rows > rows_per_region {
+            let (region, remainder) = rest.split_at(rows_per_region)?;
+            regions.push(region);
+            rest = remainder;
+        }
+        regions.push(rest);
+
+        Ok(regions)
+    }
+}
+
+/// A [`TraceSink`] that collects the trace in memory, one vector per column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceBuilder<F: PrimeField> {
+    columns: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> TraceBuilder<F> {
+    /// Creates an empty trace with `number_of_columns` columns.
+    pub fn new(number_of_columns: usize) -> Self {
+        Self::with_capacity(number_of_columns, 0)
+    }
+
+    /// Creates an empty trace whose columns are preallocated for `number_of_rows` rows, so that
+    /// pushing up to that many rows does not reallocate.
+    pub fn with_capacity(number_of_columns: usize, number_of_rows: usize) -> Self {
+        Self {
+            columns: (0..number_of_columns)
+                .map(|_| Vec::with_capacity(number_of_rows))
+                .collect(),
+        }
+    }
+
+    /// The number of rows written so far.
+    pub fn number_of_rows(&self) -> usize {
+        self.columns.first().map_or(0, Vec::len)
+    }
+
+    /// Returns the i-th column or an error if index is out of bounds.
+    pub fn column(&self, column_index: usize) -> Result<&[F], SangriaError> {
+        self.columns
+            .get(column_index)
+            .map(Vec::as_slice)
+            .ok_or(SangriaError::IndexOutOfBounds)
+    }
+
+    /// Consumes the builder and returns the trace as a PLONK witness.
+    pub fn into_witness(self) -> PLONKWitness<F> {
+        PLONKWitness::new(self.columns)
+    }
+}
+
+impl<F: PrimeField> TraceSink<F> for TraceBuilder<F> {
+    fn number_of_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn push_row(&mut self, row: &[F]) -> Result<(), SangriaError> {
+        if row.len() != self.columns.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+        for (column, value) in self.columns.iter_mut().zip(row) {
+            column.push(*value);
+        }
+
+        Ok(())
+    }
+
+    fn push_rows(&mut self, rows: &[F]) -> Result<(), SangriaError> {
+        let number_of_columns = self.columns.len();
+        if number_of_columns == 0 || rows.len() % number_of_columns != 0 {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        for (column_index, column) in self.columns.iter_mut().enumerate() {
+            column.extend(rows.iter().skip(column_index).step_by(number_of_columns));
+        }
+
+        Ok(())
+    }
+
+    fn reserve_rows(&mut self, number_of_rows: usize) -> Result<TraceRegion<'_, F>, SangriaError> {
+        let start = self.number_of_rows();
+        let columns = self
+            .columns
+            .iter_mut()
+            .map(|column| {
+                column.resize(start + number_of_rows, F::zero());
+                &mut column[start..]
+            })
+            .collect();
+
+        Ok(TraceRegion {
+            columns,
+            number_of_rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_pallas::Fr;
+    use std::thread;
+
+    #[test]
+    fn rows_filled_in_parallel_match_rows_pushed_one_at_a_time() {
+        let number_of_columns = 3;
+        let number_of_rows = 100;
+        let row = |i: usize| {
+            (0..number_of_columns)
+                .map(|j| Fr::from((i * number_of_columns + j) as u64))
+                .collect::<Vec<_>>()
+        };
+
+        let mut sequential = TraceBuilder::new(number_of_columns);
+        for i in 0..number_of_rows {
+            sequential.push_row(&row(i)).unwrap();
+        }
+
+        let mut batched = TraceBuilder::with_capacity(number_of_columns, number_of_rows);
+        let flat = (0..number_of_rows).flat_map(row).collect::<Vec<_>>();
+        batched.push_rows(&flat).unwrap();
+        assert_eq!(batched, sequential);
+
+        let mut parallel = TraceBuilder::new(number_of_columns);
+        let regions = parallel
+            .reserve_rows(number_of_rows)
+            .unwrap()
+            .chunks(7)
+            .unwrap();
+        thread::scope(|scope| {
+            for (region_index, mut region) in regions.into_iter().enumerate() {
+                scope.spawn(move || {
+                    for i in 0..region.number_of_rows() {
+                        region.set_row(i, &row(region_index * 7 + i)).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(parallel, sequential);
+
+        assert!(parallel.push_row(&row(0)[1..]).is_err());
+        assert!(parallel.push_rows(&flat[1..]).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Fiat-Shamir transcript helpers shared by the folding prover, the folding verifier and the
+//! in-circuit verifier.
+
+use ark_ff::PrimeField;
+use ark_sponge::{
+    poseidon::{PoseidonParameters, PoseidonSponge},
+    Absorb, CryptographicSponge, FieldBasedCryptographicSponge,
+};
+
+use crate::{
+    folding_scheme::{FoldingCommitmentConfig, PublicParameters, VerifierKey},
+    vector_commitment::HomomorphicCommitmentScheme,
+    SangriaError,
+};
+
+mod blake2b;
+pub use blake2b::Blake2bSponge;
+
+mod poseidon2;
+pub use poseidon2::{Poseidon2Parameters, Poseidon2Sponge};
+
+mod recorder;
+pub use recorder::{RecordedTranscript, TranscriptEntry, TranscriptRecorder};
+
+/// The shape of an algebraic sponge: the rate and capacity of its state and the rounds and
+/// S-box exponent of its permutation. The cost of hashing in the augmented circuit, and hence the
+/// layout of the in-circuit verifier, is fixed by this configuration, so it is declared at setup
+/// and the native hash parameters are checked against it rather than trusted to imply it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpongeConfig {
+    /// The number of field elements absorbed or squeezed per permutation.
+    pub rate: usize,
+    /// The number of field elements of the state that are never output.
+    pub capacity: usize,
+    /// The number of full rounds, split evenly before and after the partial rounds.
+    pub full_rounds: usize,
+    /// The number of partial rounds.
+    pub partial_rounds: usize,
+    /// The exponent of the S-box.
+    pub alpha: u64,
+}
+
+impl SpongeConfig {
+    /// Returns the configuration of Poseidon parameters, or an error if their round constants
+    /// and MDS matrix do not match their number of rounds and width.
+    pub fn of_poseidon<F: PrimeField>(
+        poseidon_constants: &PoseidonParameters<F>,
+    ) -> Result<Self, SangriaError> {
+        let config = Self {
+            rate: poseidon_constants.rate,
+            capacity: poseidon_constants.capacity,
+            full_rounds: poseidon_constants.full_rounds as usize,
+            partial_rounds: poseidon_constants.partial_rounds as usize,
+            alpha: poseidon_constants.alpha,
+        };
+        let width = config.rate + config.capacity;
+
+        if config.rate == 0
+            || config.full_rounds % 2 != 0
+            || poseidon_constants.mds.len() != width
+            || poseidon_constants.mds.iter().any(|row| row.len() != width)
+            || poseidon_constants.ark.len() != config.full_rounds + config.partial_rounds
+            || poseidon_constants.ark.iter().any(|row| row.len() != width)
+        {
+            return Err(SangriaError::InvalidOracleParameters);
+        }
+
+        Ok(config)
+    }
+}
+
+/// A sponge that can be used as the random oracle of the folding scheme.
+///
+/// Poseidon is the default since the folding verifier has to be expressed in-circuit.
+/// [`Poseidon2Sponge`] roughly halves the cost of hashing in the augmented circuit. Native-only
+/// deployments, whose augmented circuit binds instances with a commitment-based hash instead,
+/// can use [`Blake2bSponge`] which is considerably faster outside of a circuit.
+pub trait FoldingRandomOracle<F: PrimeField>: FieldBasedCryptographicSponge<F> {
+    /// Creates a fresh oracle from the hash parameters of the public parameters. Returns an error
+    /// if the parameters this oracle needs were not provided at setup.
+    fn new_oracle(
+        poseidon_constants: &PoseidonParameters<F>,
+        poseidon2_constants: Option<&Poseidon2Parameters<F>>,
+    ) -> Result<Self, SangriaError>;
+
+    /// Returns the configuration of the sponge `new_oracle` creates from the same parameters, or
+    /// `None` if the oracle is not hashed in-circuit and has no configuration to agree on.
+    fn sponge_config(
+        poseidon_constants: &PoseidonParameters<F>,
+        poseidon2_constants: Option<&Poseidon2Parameters<F>>,
+    ) -> Result<Option<SpongeConfig>, SangriaError>;
+}
+
+impl<F: PrimeField> FoldingRandomOracle<F> for PoseidonSponge<F> {
+    fn new_oracle(
+        poseidon_constants: &PoseidonParameters<F>,
+        _poseidon2_constants: Option<&Poseidon2Parameters<F>>,
+    ) -> Result<Self, SangriaError> {
+        Ok(PoseidonSponge::new(poseidon_constants))
+    }
+
+    fn sponge_config(
+        poseidon_constants: &PoseidonParameters<F>,
+        _poseidon2_constants: Option<&Poseidon2Parameters<F>>,
+    ) -> Result<Option<SpongeConfig>, SangriaError> {
+        SpongeConfig::of_poseidon(poseidon_constants).map(Some)
+    }
+}
+
+impl<F: PrimeField> FoldingRandomOracle<F> for Poseidon2Sponge<F> {
+    fn new_oracle(
+        _poseidon_constants: &PoseidonParameters<F>,
+        poseidon2_constants: Option<&Poseidon2Parameters<F>>,
+    ) -> Result<Self, SangriaError> {
+        poseidon2_constants
+            .map(Poseidon2Sponge::new)
+            .ok_or(SangriaError::InvalidOracleParameters)
+    }
+
+    fn sponge_config(
+        _poseidon_constants: &PoseidonParameters<F>,
+        poseidon2_constants: Option<&Poseidon2Parameters<F>>,
+    ) -> Result<Option<SpongeConfig>, SangriaError> {
+        poseidon2_constants
+            .map(|constants| Some(constants.sponge_config()))
+            .ok_or(SangriaError::InvalidOracleParameters)
+    }
+}
+
+impl<F: PrimeField> FoldingRandomOracle<F> for Blake2bSponge {
+    fn new_oracle(
+        _poseidon_constants: &PoseidonParameters<F>,
+        _poseidon2_constants: Option<&Poseidon2Parameters<F>>,
+    ) -> Result<Self, SangriaError> {
+        Ok(Blake2bSponge::new(&()))
+    }
+
+    fn sponge_config(
+        _poseidon_constants: &PoseidonParameters<F>,
+        _poseidon2_constants: Option<&Poseidon2Parameters<F>>,
+    ) -> Result<Option<SpongeConfig>, SangriaError> {
+        Ok(None)
+    }
+}
+
+/// Label of the folding challenge `r`.
+pub const FOLDING_CHALLENGE: &[u8] = b"fold_r";
+
+/// Label of the seed squeezed by the encoder, which binds the circuit and the parameters.
+pub const TRANSCRIPT_SEED: &[u8] = b"transcript_seed";
+
+/// Label of the seed of every fold after the first, see [`next_step_seed`].
+pub const STEP_SEED: &[u8] = b"step_seed";
+
+/// Labeled challenges. The label and its length are absorbed before squeezing, so each challenge
+/// is domain separated by its name: inserting an absorb for one challenge cannot make it collide
+/// with another.
+pub trait LabeledChallenges<F: PrimeField>: FieldBasedCryptographicSponge<F> {
+    /// Absorbs `label` and squeezes a single challenge.
+    fn challenge(&mut self, label: &[u8]) -> F {
+        self.absorb(&(label.len() as u64));
+        self.absorb(&label.to_vec());
+        self.squeeze_native_field_elements(1)[0]
+    }
+}
+
+impl<F: PrimeField, S: FieldBasedCryptographicSponge<F>> LabeledChallenges<F> for S {}
+
+/// Derives the seed of the `step_index`-th fold of a computation from the seed of the previous
+/// fold and the running instance that fold produced.
+///
+/// Folds are counted from 0 and the fold at index 0 is seeded by the `transcript_seed` of the
+/// verifier key, so `step_index` is the index of the fold the returned seed is for: after the
+/// fold at index `i`, callers pass `i + 1`, which is also the number of folds done so far.
+/// Returns [`SangriaError::IndexOutOfBounds`] if `step_index` is 0.
+///
+/// The folding transcript does not restart from the same seed at every step: since the seeds are
+/// chained through every running instance and step index, two steps of a computation never
+/// derive their challenges from the same transcript, even if they fold equal instances.
+pub fn next_step_seed<F, Comm, RO, I>(
+    public_parameters: &PublicParameters<F, Comm>,
+    previous_seed: F,
+    running_instance: &I,
+    step_index: usize,
+) -> Result<F, SangriaError>
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+    RO: FoldingRandomOracle<F>,
+    I: Absorb,
+{
+    if step_index == 0 {
+        return Err(SangriaError::IndexOutOfBounds);
+    }
+    let mut sponge = public_parameters.new_oracle::<RO>()?;
+
+    sponge.absorb(&previous_seed);
+    sponge.absorb(running_instance);
+    sponge.absorb(&(step_index as u64));
+
+    Ok(sponge.challenge(STEP_SEED))
+}
+
+/// Derives the folding challenge from the verifier key, the seed of the step (see
+/// [`next_step_seed`]), the two instances being folded and the prover's message (the commitment
+/// to the cross terms). The instances may be of any
+/// [`crate::foldable_relation::FoldableRelation`].
+///
+/// Prover and verifier must both call this function so that they agree on the challenge. The
+/// in-circuit verifier must absorb the same values in the same order. If `recorder` is given,
+/// every absorbed value and the challenge are logged to it.
+pub fn derive_folding_challenge<F, Comm, RO, I>(
+    public_parameters: &PublicParameters<F, Comm>,
+    verifier_key: &VerifierKey<F, Comm>,
+    step_seed: F,
+    left_instance: &I,
+    right_instance: &I,
+    prover_message: &<Comm::CommitmentSlack as HomomorphicCommitmentScheme<F>>::Commitment,
+    recorder: Option<&mut TranscriptRecorder<F>>,
+) -> Result<F, SangriaError>
+where
+    F: PrimeField + Absorb,
+    Comm: FoldingCommitmentConfig<F>,
+    RO: FoldingRandomOracle<F>,
+    I: Absorb,
+{
+    let mut transcript = RecordedTranscript::new(public_parameters.new_oracle::<RO>()?, recorder);
+
+    transcript.absorb(b"verifier_key", verifier_key);
+    transcript.absorb(STEP_SEED, &step_seed);
+    transcript.absorb(b"left_instance", left_instance);
+    transcript.absorb(b"right_instance", right_instance);
+    transcript.absorb(b"prover_message", prover_message);
+
+    Ok(transcript.challenge(FOLDING_CHALLENGE))
+}
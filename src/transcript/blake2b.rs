@@ -0,0 +1,80 @@
+use ark_ff::PrimeField;
+use ark_sponge::{Absorb, CryptographicSponge, FieldBasedCryptographicSponge, FieldElementSize};
+use blake2::{Blake2b512, Digest};
+
+/// A sponge built on Blake2b for native-only deployments. Absorbed inputs are appended to a
+/// running Blake2b state; squeezing finalizes that state in counter mode and reseeds it with
+/// the output, so that later absorptions and squeezes depend on everything before them.
+#[derive(Clone)]
+pub struct Blake2bSponge {
+    hasher: Blake2b512,
+}
+
+impl Blake2bSponge {
+    const DOMAIN_SEPARATOR: &'static [u8] = b"sangria-blake2b-sponge";
+}
+
+impl CryptographicSponge for Blake2bSponge {
+    type Parameters = ();
+
+    fn new(_params: &Self::Parameters) -> Self {
+        let mut hasher = Blake2b512::new();
+        hasher.update(Self::DOMAIN_SEPARATOR);
+
+        Self { hasher }
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        self.hasher.update(input.to_sponge_bytes_as_vec());
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let seed = self.hasher.finalize_reset();
+
+        let mut output = Vec::with_capacity(num_bytes);
+        let mut counter = 0u64;
+        while output.len() < num_bytes {
+            let mut block = Blake2b512::new();
+            block.update(&seed);
+            block.update(counter.to_le_bytes());
+            output.extend_from_slice(&block.finalize());
+            counter += 1;
+        }
+        output.truncate(num_bytes);
+
+        self.hasher.update(Self::DOMAIN_SEPARATOR);
+        self.hasher.update(&seed);
+
+        output
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        let bytes = self.squeeze_bytes((num_bits + 7) / 8);
+
+        bytes
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+            .take(num_bits)
+            .collect()
+    }
+}
+
+impl<F: PrimeField> FieldBasedCryptographicSponge<F> for Blake2bSponge {
+    fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F> {
+        // Squeeze 128 bits more than the modulus so that the reduction bias is negligible.
+        let bytes_per_element = (F::size_in_bits() + 128 + 7) / 8;
+
+        self.squeeze_bytes(num_elements * bytes_per_element)
+            .chunks(bytes_per_element)
+            .map(F::from_le_bytes_mod_order)
+            .collect()
+    }
+
+    fn squeeze_native_field_elements_with_sizes(&mut self, sizes: &[FieldElementSize]) -> Vec<F> {
+        if sizes.iter().all(|size| *size == FieldElementSize::Full) {
+            return self.squeeze_native_field_elements(sizes.len());
+        }
+
+        self.squeeze_field_elements_with_sizes(sizes)
+    }
+}
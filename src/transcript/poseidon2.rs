@@ -0,0 +1,362 @@
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_sponge::{Absorb, CryptographicSponge, FieldBasedCryptographicSponge, FieldElementSize};
+
+use super::SpongeConfig;
+use crate::SangriaError;
+
+/// Parameters of the Poseidon2 permutation. Compared to Poseidon, the internal rounds use a
+/// matrix of the form `1 + diag(internal_diagonal)` instead of a dense MDS matrix, which roughly
+/// halves the number of constraints needed to hash in-circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Poseidon2Parameters<F: PrimeField> {
+    full_rounds: usize,
+    partial_rounds: usize,
+    alpha: u64,
+    external_round_constants: Vec<Vec<F>>,
+    internal_round_constants: Vec<F>,
+    internal_diagonal: Vec<F>,
+    rate: usize,
+    capacity: usize,
+}
+
+impl<F: PrimeField> Poseidon2Parameters<F> {
+    /// Creates Poseidon2 parameters. The width `rate + capacity` must be 2, 3 or a multiple of 4,
+    /// `full_rounds` must be even and the constants must match the number of rounds and the width.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        full_rounds: usize,
+        partial_rounds: usize,
+        alpha: u64,
+        external_round_constants: Vec<Vec<F>>,
+        internal_round_constants: Vec<F>,
+        internal_diagonal: Vec<F>,
+        rate: usize,
+        capacity: usize,
+    ) -> Result<Self, SangriaError> {
+        let width = rate + capacity;
+        let supported_width = width == 2 || width == 3 || (width > 0 && width % 4 == 0);
+
+        if rate == 0
+            || !supported_width
+            || full_rounds % 2 != 0
+            || external_round_constants.len() != full_rounds
+            || external_round_constants
+                .iter()
+                .any(|constants| constants.len() != width)
+            || internal_round_constants.len() != partial_rounds
+            || internal_diagonal.len() != width
+        {
+            return Err(SangriaError::InvalidOracleParameters);
+        }
+
+        Ok(Self {
+            full_rounds,
+            partial_rounds,
+            alpha,
+            external_round_constants,
+            internal_round_constants,
+            internal_diagonal,
+            rate,
+            capacity,
+        })
+    }
+
+    fn width(&self) -> usize {
+        self.rate + self.capacity
+    }
+
+    /// Returns the configuration of the sponge. The constants were checked against it in `new`.
+    pub fn sponge_config(&self) -> SpongeConfig {
+        SpongeConfig {
+            rate: self.rate,
+            capacity: self.capacity,
+            full_rounds: self.full_rounds,
+            partial_rounds: self.partial_rounds,
+            alpha: self.alpha,
+        }
+    }
+}
+
+/// Multiplies the state by the external matrix: `circ(2, 1)` and `circ(2, 1, 1)` for widths 2
+/// and 3, and `circ(2 * M4, M4, ..., M4)` for multiples of 4.
+fn external_linear_layer<F: PrimeField>(state: &mut [F]) {
+    if state.len() < 4 {
+        let sum: F = state.iter().sum();
+        state.iter_mut().for_each(|x| *x += sum);
+        return;
+    }
+
+    for chunk in state.chunks_exact_mut(4) {
+        // M4 = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]]
+        let t_0 = chunk[0] + chunk[1];
+        let t_1 = chunk[2] + chunk[3];
+        let t_2 = chunk[1].double() + t_1;
+        let t_3 = chunk[3].double() + t_0;
+        let t_4 = t_1.double().double() + t_3;
+        let t_5 = t_0.double().double() + t_2;
+        let t_6 = t_3 + t_5;
+        let t_7 = t_2 + t_4;
+        chunk.copy_from_slice(&[t_6, t_5, t_7, t_4]);
+    }
+
+    let sums = (0..4)
+        .map(|i| state.iter().skip(i).step_by(4).sum())
+        .collect::<Vec<F>>();
+    state
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, x)| *x += sums[i % 4]);
+}
+
+/// Multiplies the state by the internal matrix `1 + diag(internal_diagonal)`.
+fn internal_linear_layer<F: PrimeField>(state: &mut [F], internal_diagonal: &[F]) {
+    let sum: F = state.iter().sum();
+    state
+        .iter_mut()
+        .zip(internal_diagonal)
+        .for_each(|(x, d)| *x = sum + *d * *x);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DuplexMode {
+    Absorbing { next_index: usize },
+    Squeezing { next_index: usize },
+}
+
+/// A duplex sponge over the Poseidon2 permutation. Absorption and squeezing follow the same
+/// duplex construction as `PoseidonSponge`.
+#[derive(Clone)]
+pub struct Poseidon2Sponge<F: PrimeField> {
+    parameters: Poseidon2Parameters<F>,
+    state: Vec<F>,
+    mode: DuplexMode,
+}
+
+impl<F: PrimeField> Poseidon2Sponge<F> {
+    fn sbox(&self, x: &mut F) {
+        *x = x.pow([self.parameters.alpha]);
+    }
+
+    fn external_round(&mut self, round: usize) {
+        for (x, c) in self
+            .state
+            .iter_mut()
+            .zip(&self.parameters.external_round_constants[round])
+        {
+            *x += c;
+            *x = x.pow([self.parameters.alpha]);
+        }
+        external_linear_layer(&mut self.state);
+    }
+
+    fn permute(&mut self) {
+        let half_full_rounds = self.parameters.full_rounds / 2;
+
+        external_linear_layer(&mut self.state);
+        for round in 0..half_full_rounds {
+            self.external_round(round);
+        }
+        for round in 0..self.parameters.partial_rounds {
+            let mut first = self.state[0] + self.parameters.internal_round_constants[round];
+            self.sbox(&mut first);
+            self.state[0] = first;
+            internal_linear_layer(&mut self.state, &self.parameters.internal_diagonal);
+        }
+        for round in half_full_rounds..self.parameters.full_rounds {
+            self.external_round(round);
+        }
+    }
+
+    fn absorb_internal(&mut self, mut rate_start_index: usize, mut elements: &[F]) {
+        let (rate, capacity) = (self.parameters.rate, self.parameters.capacity);
+        loop {
+            if rate_start_index + elements.len() <= rate {
+                for (i, element) in elements.iter().enumerate() {
+                    self.state[capacity + rate_start_index + i] += element;
+                }
+                self.mode = DuplexMode::Absorbing {
+                    next_index: rate_start_index + elements.len(),
+                };
+                return;
+            }
+
+            let absorbed = rate - rate_start_index;
+            for (i, element) in elements.iter().take(absorbed).enumerate() {
+                self.state[capacity + rate_start_index + i] += element;
+            }
+            self.permute();
+            elements = &elements[absorbed..];
+            rate_start_index = 0;
+        }
+    }
+
+    fn squeeze_internal(&mut self, mut rate_start_index: usize, mut output: &mut [F]) {
+        let (rate, capacity) = (self.parameters.rate, self.parameters.capacity);
+        loop {
+            let start = capacity + rate_start_index;
+            if rate_start_index + output.len() <= rate {
+                output.copy_from_slice(&self.state[start..start + output.len()]);
+                self.mode = DuplexMode::Squeezing {
+                    next_index: rate_start_index + output.len(),
+                };
+                return;
+            }
+
+            let squeezed = rate - rate_start_index;
+            output[..squeezed].copy_from_slice(&self.state[start..start + squeezed]);
+            if output.len() != rate {
+                self.permute();
+            }
+            output = &mut output[squeezed..];
+            rate_start_index = 0;
+        }
+    }
+}
+
+impl<F: PrimeField> CryptographicSponge for Poseidon2Sponge<F> {
+    type Parameters = Poseidon2Parameters<F>;
+
+    fn new(parameters: &Self::Parameters) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            state: vec![F::zero(); parameters.width()],
+            mode: DuplexMode::Absorbing { next_index: 0 },
+        }
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        let elements = input.to_sponge_field_elements_as_vec::<F>();
+        if elements.is_empty() {
+            return;
+        }
+
+        match self.mode {
+            DuplexMode::Absorbing { next_index } if next_index == self.parameters.rate => {
+                self.permute();
+                self.absorb_internal(0, &elements);
+            }
+            DuplexMode::Absorbing { next_index } => self.absorb_internal(next_index, &elements),
+            DuplexMode::Squeezing { .. } => self.absorb_internal(0, &elements),
+        }
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let usable_bytes = (F::Params::CAPACITY / 8) as usize;
+        let num_elements = (num_bytes + usable_bytes - 1) / usable_bytes;
+
+        let mut bytes = self
+            .squeeze_native_field_elements(num_elements)
+            .iter()
+            .flat_map(|element| element.into_repr().to_bytes_le()[..usable_bytes].to_vec())
+            .collect::<Vec<_>>();
+        bytes.truncate(num_bytes);
+        bytes
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        let usable_bits = F::Params::CAPACITY as usize;
+        let num_elements = (num_bits + usable_bits - 1) / usable_bits;
+
+        let mut bits = self
+            .squeeze_native_field_elements(num_elements)
+            .iter()
+            .flat_map(|element| element.into_repr().to_bits_le()[..usable_bits].to_vec())
+            .collect::<Vec<_>>();
+        bits.truncate(num_bits);
+        bits
+    }
+}
+
+impl<F: PrimeField> FieldBasedCryptographicSponge<F> for Poseidon2Sponge<F> {
+    fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F> {
+        let mut output = vec![F::zero(); num_elements];
+        match self.mode {
+            DuplexMode::Absorbing { .. } => {
+                self.permute();
+                self.squeeze_internal(0, &mut output);
+            }
+            DuplexMode::Squeezing { next_index } if next_index == self.parameters.rate => {
+                self.permute();
+                self.squeeze_internal(0, &mut output);
+            }
+            DuplexMode::Squeezing { next_index } => self.squeeze_internal(next_index, &mut output),
+        }
+
+        output
+    }
+
+    fn squeeze_native_field_elements_with_sizes(&mut self, sizes: &[FieldElementSize]) -> Vec<F> {
+        if sizes.iter().all(|size| *size == FieldElementSize::Full) {
+            return self.squeeze_native_field_elements(sizes.len());
+        }
+
+        self.squeeze_field_elements_with_sizes(sizes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::seeded_rng, test_vectors::poseidon2_parameters_for_test};
+    use ark_ff::{One, Zero};
+    use ark_pallas::Fr;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn external_layer_matches_m4() {
+        let m4 = [[5u64, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+        let rng = &mut seeded_rng("transcript::poseidon2::external_layer_matches_m4");
+        let input = (0..8).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        // For width 8 the external matrix is circ(2 * M4, M4).
+        let expected = (0..8)
+            .map(|row| {
+                (0..8)
+                    .map(|column| {
+                        let block = if row / 4 == column / 4 { 2 } else { 1 };
+                        Fr::from(block * m4[row % 4][column % 4]) * input[column]
+                    })
+                    .sum::<Fr>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut state = input;
+        external_linear_layer(&mut state);
+        assert_eq!(state, expected);
+    }
+
+    #[test]
+    fn squeezes_depend_on_absorbed_values() {
+        let rng = &mut seeded_rng("transcript::poseidon2::squeezes_depend_on_absorbed_values");
+        let parameters = poseidon2_parameters_for_test::<Fr, _>(rng);
+
+        let squeeze = |value: Fr| {
+            let mut sponge = Poseidon2Sponge::new(&parameters);
+            sponge.absorb(&value);
+            sponge.squeeze_native_field_elements(3)
+        };
+
+        assert_eq!(squeeze(Fr::one()), squeeze(Fr::one()));
+        assert_ne!(squeeze(Fr::one()), squeeze(Fr::zero()));
+    }
+
+    #[test]
+    fn malformed_parameters_are_rejected() {
+        let rng = &mut seeded_rng("transcript::poseidon2::malformed_parameters_are_rejected");
+        let parameters = poseidon2_parameters_for_test::<Fr, _>(rng);
+
+        assert_eq!(
+            Poseidon2Parameters::new(
+                parameters.full_rounds,
+                parameters.partial_rounds,
+                parameters.alpha,
+                parameters.external_round_constants.clone(),
+                parameters.internal_round_constants,
+                parameters.internal_diagonal[1..].to_vec(),
+                parameters.rate,
+                parameters.capacity,
+            ),
+            Err(SangriaError::InvalidOracleParameters)
+        );
+    }
+}
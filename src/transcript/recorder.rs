@@ -0,0 +1,219 @@
+use ark_ff::PrimeField;
+use ark_sponge::{Absorb, CryptographicSponge, FieldBasedCryptographicSponge};
+
+use super::LabeledChallenges;
+use crate::{
+    serialization::{deserialize_with_mode, serialize_with_mode, SerializationMode},
+    SangriaError,
+};
+
+/// One operation on a Fiat-Shamir transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptEntry<F> {
+    /// A value was absorbed. It is recorded as the field elements it absorbs as, whatever the
+    /// sponge, so that logs of different oracles can be compared.
+    Absorb {
+        /// What the value is, e.g. `left_instance`.
+        label: Vec<u8>,
+        /// The value as field elements.
+        elements: Vec<F>,
+    },
+    /// A labeled challenge was squeezed, see [`LabeledChallenges`].
+    Squeeze {
+        /// The label of the challenge.
+        label: Vec<u8>,
+        /// The challenge.
+        challenge: F,
+    },
+}
+
+/// A log of every value absorbed into and every challenge squeezed from a transcript. Attach one
+/// to the folding prover and another to the folding verifier, then compare them with
+/// [`Self::first_divergence`] to find where the two sides of a Fiat-Shamir transform disagree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TranscriptRecorder<F> {
+    entries: Vec<TranscriptEntry<F>>,
+}
+
+impl<F: PrimeField> TranscriptRecorder<F> {
+    const ABSORB_TAG: u8 = 0;
+    const SQUEEZE_TAG: u8 = 1;
+
+    /// Creates an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded entries, in order.
+    pub fn entries(&self) -> &[TranscriptEntry<F>] {
+        &self.entries
+    }
+
+    /// Records an absorbed value.
+    pub fn record_absorb<A: Absorb>(&mut self, label: &[u8], value: &A) {
+        self.entries.push(TranscriptEntry::Absorb {
+            label: label.to_vec(),
+            elements: value.to_sponge_field_elements_as_vec(),
+        });
+    }
+
+    /// Records a squeezed challenge.
+    pub fn record_squeeze(&mut self, label: &[u8], challenge: F) {
+        self.entries.push(TranscriptEntry::Squeeze {
+            label: label.to_vec(),
+            challenge,
+        });
+    }
+
+    /// Returns the index of the first entry that differs between the two logs, or `None` if they
+    /// are identical. A log that is a strict prefix of the other diverges at its length.
+    pub fn first_divergence(&self, other: &Self) -> Option<usize> {
+        self.entries
+            .iter()
+            .zip(&other.entries)
+            .position(|(left, right)| left != right)
+            .or_else(|| {
+                (self.entries.len() != other.entries.len())
+                    .then(|| self.entries.len().min(other.entries.len()))
+            })
+    }
+
+    /// Serializes the log, e.g. to compare prover and verifier runs offline.
+    pub fn to_bytes(&self, mode: SerializationMode) -> Result<Vec<u8>, SangriaError> {
+        let mut bytes = Vec::new();
+        serialize_with_mode(&(self.entries.len() as u64), &mut bytes, mode)?;
+        for entry in &self.entries {
+            match entry {
+                TranscriptEntry::Absorb { label, elements } => {
+                    serialize_with_mode(&Self::ABSORB_TAG, &mut bytes, mode)?;
+                    serialize_with_mode(label, &mut bytes, mode)?;
+                    serialize_with_mode(elements, &mut bytes, mode)?;
+                }
+                TranscriptEntry::Squeeze { label, challenge } => {
+                    serialize_with_mode(&Self::SQUEEZE_TAG, &mut bytes, mode)?;
+                    serialize_with_mode(label, &mut bytes, mode)?;
+                    serialize_with_mode(challenge, &mut bytes, mode)?;
+                }
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Deserializes a log serialized with the same `mode`.
+    pub fn from_bytes(bytes: &[u8], mode: SerializationMode) -> Result<Self, SangriaError> {
+        let mut reader = bytes;
+        let number_of_entries: u64 = deserialize_with_mode(&mut reader, mode)?;
+
+        let mut recorder = Self::new();
+        for _ in 0..number_of_entries {
+            let tag: u8 = deserialize_with_mode(&mut reader, mode)?;
+            let label = deserialize_with_mode(&mut reader, mode)?;
+            let entry = match tag {
+                Self::ABSORB_TAG => TranscriptEntry::Absorb {
+                    label,
+                    elements: deserialize_with_mode(&mut reader, mode)?,
+                },
+                Self::SQUEEZE_TAG => TranscriptEntry::Squeeze {
+                    label,
+                    challenge: deserialize_with_mode(&mut reader, mode)?,
+                },
+                _ => return Err(SangriaError::SerializationError),
+            };
+            recorder.entries.push(entry);
+        }
+        if !reader.is_empty() {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(recorder)
+    }
+}
+
+/// A sponge with an optional [`TranscriptRecorder`] attached. Every absorb and challenge goes
+/// through it with a label, so that the recorded log cannot drift from what is actually hashed.
+pub struct RecordedTranscript<'a, F: PrimeField, S: FieldBasedCryptographicSponge<F>> {
+    sponge: S,
+    recorder: Option<&'a mut TranscriptRecorder<F>>,
+}
+
+impl<'a, F: PrimeField, S: FieldBasedCryptographicSponge<F>> RecordedTranscript<'a, F, S> {
+    /// Wraps `sponge`, recording into `recorder` if one is given.
+    pub fn new(sponge: S, recorder: Option<&'a mut TranscriptRecorder<F>>) -> Self {
+        Self { sponge, recorder }
+    }
+
+    /// Absorbs `value`.
+    pub fn absorb<A: Absorb>(&mut self, label: &[u8], value: &A) {
+        if let Some(recorder) = self.recorder.as_deref_mut() {
+            recorder.record_absorb(label, value);
+        }
+        self.sponge.absorb(value);
+    }
+
+    /// Squeezes the challenge labeled `label`, see [`LabeledChallenges::challenge`].
+    pub fn challenge(&mut self, label: &[u8]) -> F {
+        let challenge = self.sponge.challenge(label);
+        if let Some(recorder) = self.recorder.as_deref_mut() {
+            recorder.record_squeeze(label, challenge);
+        }
+        challenge
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::seeded_rng, test_vectors::poseidon_parameters_for_test};
+    use ark_pallas::Fr;
+    use ark_sponge::poseidon::PoseidonSponge;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn logs_round_trip_and_locate_the_first_divergence() {
+        let rng = &mut seeded_rng(
+            "transcript::recorder::logs_round_trip_and_locate_the_first_divergence",
+        );
+        let parameters = poseidon_parameters_for_test::<Fr, _>(rng);
+        let values = (0..3).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        let run = |values: &[Fr], recorder: &mut TranscriptRecorder<Fr>| {
+            let mut transcript =
+                RecordedTranscript::new(PoseidonSponge::new(&parameters), Some(recorder));
+            transcript.absorb(b"first", &values[0]);
+            transcript.absorb(b"rest", &values[1..].to_vec());
+            transcript.challenge(b"r")
+        };
+
+        let (mut prover, mut verifier) = (TranscriptRecorder::new(), TranscriptRecorder::new());
+        let challenge = run(&values, &mut prover);
+        assert_eq!(run(&values, &mut verifier), challenge);
+        assert_eq!(prover.first_divergence(&verifier), None);
+        assert_eq!(
+            prover.entries()[2],
+            TranscriptEntry::Squeeze {
+                label: b"r".to_vec(),
+                challenge,
+            }
+        );
+
+        let mut unrecorded = RecordedTranscript::new(PoseidonSponge::new(&parameters), None);
+        unrecorded.absorb(b"first", &values[0]);
+        unrecorded.absorb(b"rest", &values[1..].to_vec());
+        assert_eq!(unrecorded.challenge(b"r"), challenge);
+
+        let mut diverging = TranscriptRecorder::new();
+        let mut tampered = values.clone();
+        tampered[2] += Fr::from(1u64);
+        run(&tampered, &mut diverging);
+        assert_eq!(prover.first_divergence(&diverging), Some(1));
+
+        let mode = SerializationMode::Compressed;
+        let bytes = prover.to_bytes(mode).unwrap();
+        assert_eq!(TranscriptRecorder::from_bytes(&bytes, mode), Ok(prover));
+        assert_eq!(
+            TranscriptRecorder::<Fr>::from_bytes(&bytes[..bytes.len() - 1], mode),
+            Err(SangriaError::SerializationError)
+        );
+    }
+}
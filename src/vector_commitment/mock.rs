@@ -0,0 +1,57 @@
+//! A homomorphic commitment over the scalar field itself: `commit(x, r) = <g, x> + h * r`.
+//! It is neither hiding nor binding and only exists so that tests and fuzz targets can exercise
+//! the folding arithmetic without depending on a curve.
+
+use crate::errors::SangriaError;
+use crate::folding_scheme::FoldingCommitmentConfig;
+use crate::vector_commitment::{opening::OpenableCommitmentScheme, HomomorphicCommitmentScheme};
+
+use ark_ff::PrimeField;
+use ark_sponge::Absorb;
+use ark_std::{marker::PhantomData, rand::Rng};
+
+/// The commitment `<g, x> + h * r` over the scalar field, see the module documentation.
+pub struct FieldCommitment<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField + Absorb> HomomorphicCommitmentScheme<F> for FieldCommitment<F> {
+    type CommitKey = Vec<F>;
+    type Commitment = F;
+
+    fn setup<R: Rng>(public_randomness: &mut R, len: usize) -> Vec<F> {
+        (0..len + 1).map(|_| F::rand(public_randomness)).collect()
+    }
+
+    fn supported_size(commit_key: &Vec<F>) -> usize {
+        commit_key.len() - 1
+    }
+
+    fn commit(commit_key: &Vec<F>, x: &[F], r: F) -> Result<F, SangriaError> {
+        if x.len() > Self::supported_size(commit_key) {
+            return Err(SangriaError::UnsupportedSize {
+                requested: x.len(),
+                max: Self::supported_size(commit_key),
+            });
+        }
+
+        let h = commit_key[0];
+        Ok(h * r
+            + x.iter()
+                .zip(&commit_key[1..])
+                .map(|(x_i, g_i)| *x_i * g_i)
+                .sum::<F>())
+    }
+}
+
+impl<F: PrimeField + Absorb> OpenableCommitmentScheme<F> for FieldCommitment<F> {}
+
+/// Commits to both the witness and the slack vector with [`FieldCommitment`].
+pub struct FieldCommitmentConfig<F: PrimeField>(PhantomData<F>);
+
+impl<F: PrimeField + Absorb> FoldingCommitmentConfig<F> for FieldCommitmentConfig<F> {
+    type CommitmentSlack = FieldCommitment<F>;
+    type CommitmentWitness = FieldCommitment<F>;
+
+    const CURVE_ID: &'static str = "mock";
+}
@@ -1,16 +1,24 @@
-// pub mod pedersen;
+pub mod opening;
+pub mod pedersen;
+pub mod poseidon_merkle;
+
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) mod mock;
 
 use crate::errors::SangriaError;
-use ark_ff::{Field, ToBytes, Zero};
+use ark_ff::{Field, PrimeField, ToBytes, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_sponge::Absorb;
 use ark_std::rand::Rng;
 use std::{iter::Sum, ops};
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 /// Trait defining the types and functions needed for an additively homomorphic commitment scheme.
 /// The scheme is defined with respect to a finite field `F` for which scalar multiplication is preserved.
 pub trait HomomorphicCommitmentScheme<F: Field> {
-    type CommitKey: Clone + CanonicalSerialize + CanonicalDeserialize + ToBytes;
+    type CommitKey: Clone + CanonicalSerialize + CanonicalDeserialize + ToBytes + Sync;
 
     /// Represent a ciphertext from a generic homomorphic encryption scheme. To manifest the homomorphic
     /// property of the scheme, we require that some arithmetic operations (add and multiply by scalar) are implemented.
@@ -24,15 +32,55 @@ pub trait HomomorphicCommitmentScheme<F: Field> {
         + Zero
         + Sum
         + ToBytes
-        + Absorb;
+        + Absorb
+        + Send
+        + Sync;
 
     /// Generate a commit key using the provided length
     fn setup<R: Rng>(public_randomness: &mut R, len: usize) -> Self::CommitKey;
 
+    /// Returns the maximum length of the vectors that can be committed to with the commit key
+    fn supported_size(commit_key: &Self::CommitKey) -> usize;
+
     /// Commit to a vector of scalars using the commit key
     fn commit(
         commit_key: &Self::CommitKey,
         x: &[F],
         r: F,
     ) -> Result<Self::Commitment, SangriaError>;
+
+    /// Commits to several vectors, each with its own randomness, using the same commit key. Under
+    /// the `parallel` feature the commitments are computed concurrently. Schemes can override
+    /// this to share work between the commitments, e.g. by batching their MSMs.
+    fn batch_commit(
+        commit_key: &Self::CommitKey,
+        vectors: &[(&[F], F)],
+    ) -> Result<Vec<Self::Commitment>, SangriaError> {
+        #[cfg(feature = "parallel")]
+        let vectors = vectors.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let vectors = vectors.iter();
+
+        vectors
+            .map(|(x, r)| Self::commit(commit_key, x, *r))
+            .collect()
+    }
+
+    /// Returns the canonical representation of a commitment. Commitments that are equal as group
+    /// elements but represented differently, e.g. the point at infinity with different
+    /// placeholder coordinates, have the same canonical representation.
+    fn canonicalize(commitment: Self::Commitment) -> Self::Commitment {
+        commitment
+    }
+}
+
+/// Trait for a (non-homomorphic) vector commitment scheme. It is not used for folding, only to
+/// identify public artifacts such as the selectors of a circuit without relying on a group.
+pub trait VectorCommitmentScheme<F: PrimeField> {
+    type Parameters: Clone;
+
+    type Commitment: PartialEq + Copy + Clone + CanonicalSerialize + CanonicalDeserialize + Absorb;
+
+    /// Commit to a vector of scalars
+    fn commit(parameters: &Self::Parameters, x: &[F]) -> Result<Self::Commitment, SangriaError>;
 }
@@ -0,0 +1,309 @@
+//! Openings of single positions of a committed vector, for selective disclosure, e.g. revealing
+//! one public input of a committed instance without the others. The opening is a Schnorr-style
+//! proof of knowledge of an opening of the commitment whose `index`-th entry is the claimed value.
+//! It only uses the homomorphism of the scheme, so it works for any
+//! [`HomomorphicCommitmentScheme`], and is zero-knowledge for the other entries if the scheme is
+//! hiding, e.g. Pedersen.
+//!
+//! Linear form openings generalize these to a claimed inner product `<a, x> = y` with a public
+//! vector `a`, e.g. the evaluation of the polynomial with coefficients `x` at a point.
+
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_sponge::CryptographicSponge;
+use ark_std::{rand::Rng, UniformRand};
+
+use super::HomomorphicCommitmentScheme;
+use crate::{
+    transcript::{Blake2bSponge, LabeledChallenges},
+    SangriaError, VerificationFailure,
+};
+
+/// Label of the challenge of a position opening.
+pub const OPENING_CHALLENGE: &[u8] = b"position_opening";
+
+/// Label of the challenge of a linear form opening.
+pub const LINEAR_FORM_CHALLENGE: &[u8] = b"linear_form_opening";
+
+/// A proof that a position of a committed vector holds a given value. It reveals nothing else
+/// about the vector than its length.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PositionOpening<F: PrimeField, C: CanonicalSerialize + CanonicalDeserialize> {
+    /// The commitment to the masks.
+    announcement: C,
+    /// The responses for every position but the opened one.
+    responses: Vec<F>,
+    /// The response for the hiding randomness.
+    hiding_response: F,
+}
+
+/// A proof that the vector of a commitment has a given inner product with a public vector. It
+/// reveals nothing else about the vector than its length.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LinearFormOpening<F: PrimeField, C: CanonicalSerialize + CanonicalDeserialize> {
+    /// The commitment to the masks.
+    announcement: C,
+    /// The inner product of the masks with the public vector.
+    inner_product_announcement: F,
+    /// The responses for every position.
+    responses: Vec<F>,
+    /// The response for the hiding randomness.
+    hiding_response: F,
+}
+
+/// A homomorphic commitment scheme whose commitments can be opened at a single position, see the
+/// module documentation.
+pub trait OpenableCommitmentScheme<F: PrimeField>: HomomorphicCommitmentScheme<F> {
+    /// Proves that the `index`-th entry of the commitment to `x` with hiding `randomness` is
+    /// `x[index]`.
+    fn open<R: Rng>(
+        commit_key: &Self::CommitKey,
+        x: &[F],
+        randomness: F,
+        index: usize,
+        rng: &mut R,
+    ) -> Result<PositionOpening<F, Self::Commitment>, SangriaError> {
+        if index >= x.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let mut masks = (0..x.len()).map(|_| F::rand(rng)).collect::<Vec<_>>();
+        masks[index] = F::zero();
+        let hiding_mask = F::rand(rng);
+        let announcement = Self::commit(commit_key, &masks, hiding_mask)?;
+
+        let commitment = Self::commit(commit_key, x, randomness)?;
+        let challenge =
+            opening_challenge::<F, Self>(commit_key, &commitment, index, x[index], &announcement)?;
+
+        let mut responses = masks
+            .iter()
+            .zip(x)
+            .map(|(mask, x_i)| *mask + challenge * x_i)
+            .collect::<Vec<_>>();
+        responses.remove(index);
+
+        Ok(PositionOpening {
+            announcement,
+            responses,
+            hiding_response: hiding_mask + challenge * randomness,
+        })
+    }
+
+    /// Verifies that the `index`-th entry of the vector committed to in `commitment` is `value`.
+    fn verify_opening(
+        commit_key: &Self::CommitKey,
+        commitment: &Self::Commitment,
+        index: usize,
+        value: F,
+        opening: &PositionOpening<F, Self::Commitment>,
+    ) -> Result<(), SangriaError> {
+        if index > opening.responses.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let challenge = opening_challenge::<F, Self>(
+            commit_key,
+            commitment,
+            index,
+            value,
+            &opening.announcement,
+        )?;
+
+        // The mask of the opened position is zero, so its response is `challenge * value`.
+        let mut responses = opening.responses.clone();
+        responses.insert(index, challenge * value);
+        let opened = Self::commit(commit_key, &responses, opening.hiding_response)?;
+        let expected = [opening.announcement, *commitment * challenge]
+            .into_iter()
+            .sum();
+        if Self::canonicalize(opened) != Self::canonicalize(expected) {
+            return Err(VerificationFailure::CommitmentOpening
+                .reject(|| format!("position {} does not open to the claimed value", index)));
+        }
+
+        Ok(())
+    }
+
+    /// Proves that the inner product of `coefficients` with the vector `x` of the commitment with
+    /// hiding `randomness` is `<coefficients, x>`.
+    fn open_linear_form<R: Rng>(
+        commit_key: &Self::CommitKey,
+        x: &[F],
+        randomness: F,
+        coefficients: &[F],
+        rng: &mut R,
+    ) -> Result<LinearFormOpening<F, Self::Commitment>, SangriaError> {
+        if coefficients.len() != x.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let masks = (0..x.len()).map(|_| F::rand(rng)).collect::<Vec<_>>();
+        let hiding_mask = F::rand(rng);
+        let announcement = Self::commit(commit_key, &masks, hiding_mask)?;
+        let inner_product_announcement = inner_product(coefficients, &masks);
+
+        let commitment = Self::commit(commit_key, x, randomness)?;
+        let challenge = linear_form_challenge::<F, Self>(
+            commit_key,
+            &commitment,
+            coefficients,
+            inner_product(coefficients, x),
+            &announcement,
+            inner_product_announcement,
+        )?;
+
+        Ok(LinearFormOpening {
+            announcement,
+            inner_product_announcement,
+            responses: masks
+                .iter()
+                .zip(x)
+                .map(|(mask, x_i)| *mask + challenge * x_i)
+                .collect(),
+            hiding_response: hiding_mask + challenge * randomness,
+        })
+    }
+
+    /// Verifies that the inner product of `coefficients` with the vector committed to in
+    /// `commitment` is `value`.
+    fn verify_linear_form(
+        commit_key: &Self::CommitKey,
+        commitment: &Self::Commitment,
+        coefficients: &[F],
+        value: F,
+        opening: &LinearFormOpening<F, Self::Commitment>,
+    ) -> Result<(), SangriaError> {
+        if opening.responses.len() != coefficients.len() {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        let challenge = linear_form_challenge::<F, Self>(
+            commit_key,
+            commitment,
+            coefficients,
+            value,
+            &opening.announcement,
+            opening.inner_product_announcement,
+        )?;
+
+        let opened = Self::commit(commit_key, &opening.responses, opening.hiding_response)?;
+        let expected = [opening.announcement, *commitment * challenge]
+            .into_iter()
+            .sum();
+        if Self::canonicalize(opened) != Self::canonicalize(expected)
+            || inner_product(coefficients, &opening.responses)
+                != opening.inner_product_announcement + challenge * value
+        {
+            return Err(VerificationFailure::CommitmentOpening
+                .reject(|| "linear form does not open to the claimed value".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+fn inner_product<F: PrimeField>(left: &[F], right: &[F]) -> F {
+    left.iter().zip(right).map(|(l, r)| *l * r).sum()
+}
+
+/// Derives the challenge of a linear form opening from the commit key and the public data.
+fn linear_form_challenge<F, S>(
+    commit_key: &S::CommitKey,
+    commitment: &S::Commitment,
+    coefficients: &[F],
+    value: F,
+    announcement: &S::Commitment,
+    inner_product_announcement: F,
+) -> Result<F, SangriaError>
+where
+    F: PrimeField,
+    S: HomomorphicCommitmentScheme<F> + ?Sized,
+{
+    let mut public_data = Vec::new();
+    commit_key
+        .serialize(&mut public_data)
+        .and_then(|_| coefficients.to_vec().serialize(&mut public_data))
+        .and_then(|_| value.serialize(&mut public_data))
+        .and_then(|_| inner_product_announcement.serialize(&mut public_data))
+        .map_err(|_| SangriaError::SerializationError)?;
+
+    let mut sponge = Blake2bSponge::new(&());
+    sponge.absorb(&public_data);
+    sponge.absorb(&S::canonicalize(*commitment));
+    sponge.absorb(&S::canonicalize(*announcement));
+
+    Ok(LabeledChallenges::<F>::challenge(
+        &mut sponge,
+        LINEAR_FORM_CHALLENGE,
+    ))
+}
+
+/// Derives the challenge of a position opening from the commit key and the public data.
+fn opening_challenge<F, S>(
+    commit_key: &S::CommitKey,
+    commitment: &S::Commitment,
+    index: usize,
+    value: F,
+    announcement: &S::Commitment,
+) -> Result<F, SangriaError>
+where
+    F: PrimeField,
+    S: HomomorphicCommitmentScheme<F> + ?Sized,
+{
+    let mut public_data = Vec::new();
+    commit_key
+        .serialize(&mut public_data)
+        .and_then(|_| (index as u64).serialize(&mut public_data))
+        .and_then(|_| value.serialize(&mut public_data))
+        .map_err(|_| SangriaError::SerializationError)?;
+
+    let mut sponge = Blake2bSponge::new(&());
+    sponge.absorb(&public_data);
+    sponge.absorb(&S::canonicalize(*commitment));
+    sponge.absorb(&S::canonicalize(*announcement));
+
+    Ok(LabeledChallenges::<F>::challenge(
+        &mut sponge,
+        OPENING_CHALLENGE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::seeded_rng, vector_commitment::mock::FieldCommitment};
+    use ark_pallas::Fr;
+
+    #[test]
+    fn positions_open_to_their_entries_only() {
+        let rng =
+            &mut seeded_rng("vector_commitment::opening::positions_open_to_their_entries_only");
+        let commit_key = FieldCommitment::<Fr>::setup(rng, 4);
+        let x = (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let randomness = Fr::rand(rng);
+        let commitment = FieldCommitment::commit(&commit_key, &x, randomness).unwrap();
+
+        let opening = FieldCommitment::open(&commit_key, &x, randomness, 2, rng).unwrap();
+        assert_eq!(
+            FieldCommitment::verify_opening(&commit_key, &commitment, 2, x[2], &opening),
+            Ok(())
+        );
+
+        let failure = Err(SangriaError::VerificationFailed(
+            VerificationFailure::CommitmentOpening,
+        ));
+        assert_eq!(
+            FieldCommitment::verify_opening(&commit_key, &commitment, 2, x[3], &opening),
+            failure
+        );
+        assert_eq!(
+            FieldCommitment::verify_opening(&commit_key, &commitment, 1, x[2], &opening),
+            failure
+        );
+        assert_eq!(
+            FieldCommitment::open(&commit_key, &x, randomness, 4, rng),
+            Err(SangriaError::IndexOutOfBounds)
+        );
+    }
+}
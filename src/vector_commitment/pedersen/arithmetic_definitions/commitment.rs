@@ -1,8 +1,9 @@
 use super::super::Commitment;
 use ark_ec::{AffineCurve, ProjectiveCurve};
-use ark_ff::Zero;
-use ark_std::UniformRand;
-use rand::Rng;
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::CanonicalSerialize;
+use ark_sponge::Absorb;
+use ark_std::{rand::Rng, UniformRand};
 use std::ops::Mul;
 
 impl<C: ProjectiveCurve> Mul<C::ScalarField> for Commitment<C> {
@@ -32,7 +33,7 @@ impl<C: ProjectiveCurve> Zero for Commitment<C> {
     }
 
     fn is_zero(&self) -> bool {
-        *self == Self(C::Affine::zero())
+        self.0.is_zero()
     }
 }
 
@@ -41,3 +42,18 @@ impl<C: ProjectiveCurve> UniformRand for Commitment<C> {
         Self(C::rand(rng).into_affine())
     }
 }
+
+/// Commitments are absorbed through their compressed serialization, in which the point at
+/// infinity has a single encoding. The augmented circuit absorbs the coordinates of the other
+/// curve instead, see [`Commitment::to_base_field_elements`].
+impl<C: ProjectiveCurve> Absorb for Commitment<C> {
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        self.0
+            .serialize(dest)
+            .expect("serialization into a vector cannot fail");
+    }
+
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        self.to_sponge_bytes_as_vec().to_sponge_field_elements(dest)
+    }
+}
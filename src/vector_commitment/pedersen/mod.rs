@@ -1,43 +1,110 @@
 use crate::errors::SangriaError;
-use crate::vector_commitment::HomomorphicCommitmentScheme;
+use crate::vector_commitment::{opening::OpenableCommitmentScheme, HomomorphicCommitmentScheme};
 
-use ark_ec::{msm::VariableBaseMSM, ProjectiveCurve};
-use ark_ff::{PrimeField, ToBytes};
+use ark_ec::{short_weierstrass_jacobian::GroupProjective, ProjectiveCurve, SWModelParameters};
+use ark_ff::{One, PrimeField, ToBytes, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::rand::Rng;
 use ark_std::{
     io::{Read, Write},
     marker::PhantomData,
 };
-use ark_std::rand::Rng;
+use std::sync::Arc;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 pub mod arithmetic_definitions;
+pub mod msm;
 mod tests;
 
+use msm::{msm, MsmStrategy};
+
 pub struct PedersenCommitment<C: ProjectiveCurve> {
     _curve: PhantomData<C>,
 }
 
-#[derive(Clone, CanonicalSerialize, CanonicalDeserialize, Debug)]
+/// A Pedersen commit key. The generators are reference counted so that cloning a key, e.g. to
+/// hand it to several worker threads, does not copy them. The MSM strategy is a local tuning
+/// knob and is not serialized.
+#[derive(Clone, Debug)]
 pub struct CommitKey<C: ProjectiveCurve> {
-    g: Vec<C::Affine>,
+    g: Arc<[C::Affine]>,
     h: C::Affine,
+    msm_strategy: MsmStrategy,
 }
 
 impl<C: ProjectiveCurve> CommitKey<C> {
     pub fn new(g: Vec<C::Affine>, h: C::Affine) -> Self {
-        Self { g, h }
+        Self {
+            g: g.into(),
+            h,
+            msm_strategy: MsmStrategy::default(),
+        }
+    }
+
+    /// Returns the key with commitments computed using `msm_strategy`, see
+    /// [`msm::select_commitment_backend`].
+    pub fn with_msm_strategy(mut self, msm_strategy: MsmStrategy) -> Self {
+        self.msm_strategy = msm_strategy;
+        self
+    }
+
+    /// Returns the strategy used to compute commitments.
+    pub fn msm_strategy(&self) -> MsmStrategy {
+        self.msm_strategy
+    }
+
+    /// Returns the generators of the vector entries. A polynomial commitment scheme that commits
+    /// to coefficient vectors with the same generators, e.g. an inner-product argument, can share
+    /// this key with the folding layer.
+    pub fn generators(&self) -> &[C::Affine] {
+        &self.g
+    }
+
+    /// Returns the generator of the hiding randomness.
+    pub fn hiding_generator(&self) -> C::Affine {
+        self.h
     }
 }
 
 impl<C: ProjectiveCurve> ToBytes for CommitKey<C> {
     fn write<W: Write>(&self, mut w: W) -> ark_std::io::Result<()> {
-        self.g.write(&mut w)?;
+        for g in self.g.iter() {
+            g.write(&mut w)?;
+        }
         self.h.write(&mut w)?;
 
         Ok(())
     }
 }
 
+// Serialized as if `g` were a `Vec`, so the format does not depend on how the key is shared.
+impl<C: ProjectiveCurve> CanonicalSerialize for CommitKey<C> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        (self.g.len() as u64).serialize(&mut writer)?;
+        for g in self.g.iter() {
+            g.serialize(&mut writer)?;
+        }
+        self.h.serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        (self.g.len() as u64).serialized_size()
+            + self.g.iter().map(|g| g.serialized_size()).sum::<usize>()
+            + self.h.serialized_size()
+    }
+}
+
+impl<C: ProjectiveCurve> CanonicalDeserialize for CommitKey<C> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let g = Vec::<C::Affine>::deserialize(&mut reader)?;
+        let h = C::Affine::deserialize(&mut reader)?;
+
+        Ok(Self::new(g, h))
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Commitment<C: ProjectiveCurve>(pub C::Affine);
 
@@ -49,6 +116,108 @@ impl<C: ProjectiveCurve> ToBytes for Commitment<C> {
     }
 }
 
+/// Coordinates of commitments on short Weierstrass curves. On a cycle of curves the base field of
+/// one curve is the scalar field of the other, so these are the field elements that the other
+/// curve's transcript and augmented circuit work with.
+impl<P: SWModelParameters> Commitment<GroupProjective<P>> {
+    /// Returns the affine coordinates `(x, y)`, or `None` for the point at infinity, whose stored
+    /// coordinates are placeholders and must not be absorbed.
+    pub fn coordinates(&self) -> Option<(P::BaseField, P::BaseField)> {
+        if self.0.infinity {
+            None
+        } else {
+            Some((self.0.x, self.0.y))
+        }
+    }
+
+    /// Returns `[x, y, 0]`, or `[0, 0, 1]` for the point at infinity. This is the encoding that is
+    /// absorbed and allocated in the augmented circuit, so that the identity (e.g. the commitment
+    /// to a zero slack vector) never collides with a point on the curve.
+    pub fn to_base_field_elements(&self) -> [P::BaseField; 3] {
+        match self.coordinates() {
+            Some((x, y)) => [x, y, P::BaseField::zero()],
+            None => [
+                P::BaseField::zero(),
+                P::BaseField::zero(),
+                P::BaseField::one(),
+            ],
+        }
+    }
+}
+
+impl<C: ProjectiveCurve> PedersenCommitment<C> {
+    /// Commits like [`HomomorphicCommitmentScheme::commit`] but returns the projective result of
+    /// the MSM, so that it can be normalized together with other commitments, see
+    /// [`DeferredCommitments`].
+    pub fn commit_projective(
+        commit_key: &CommitKey<C>,
+        x: &[C::ScalarField],
+        r: C::ScalarField,
+    ) -> Result<C, SangriaError> {
+        if x.len() > commit_key.g.len() {
+            return Err(SangriaError::UnsupportedSize {
+                requested: x.len(),
+                max: commit_key.g.len(),
+            });
+        }
+
+        let scalars = [&[r], x]
+            .concat()
+            .iter()
+            .map(|x| x.into_repr())
+            .collect::<Vec<_>>();
+
+        let bases = [&[commit_key.h], &commit_key.g[..]].concat();
+
+        Ok(msm(&bases, &scalars[..], commit_key.msm_strategy))
+    }
+}
+
+/// Commitments accumulated in projective form, e.g. all the commitments of a prover step.
+/// Normalizing a point to affine costs a field inversion, so [`Self::finalize`] normalizes them
+/// all at once with a single inversion instead of one per commitment.
+#[derive(Clone, Debug)]
+pub struct DeferredCommitments<C: ProjectiveCurve> {
+    pending: Vec<C>,
+}
+
+impl<C: ProjectiveCurve> Default for DeferredCommitments<C> {
+    fn default() -> Self {
+        Self {
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<C: ProjectiveCurve> DeferredCommitments<C> {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Commits to `x` with randomness `r` and returns the position of the commitment in the
+    /// output of [`Self::finalize`].
+    pub fn commit(
+        &mut self,
+        commit_key: &CommitKey<C>,
+        x: &[C::ScalarField],
+        r: C::ScalarField,
+    ) -> Result<usize, SangriaError> {
+        self.pending
+            .push(PedersenCommitment::commit_projective(commit_key, x, r)?);
+
+        Ok(self.pending.len() - 1)
+    }
+
+    /// Normalizes the commitments, in the order they were made.
+    pub fn finalize(self) -> Vec<Commitment<C>> {
+        C::batch_normalization_into_affine(&self.pending)
+            .into_iter()
+            .map(Commitment)
+            .collect()
+    }
+}
+
 impl<C: ProjectiveCurve> HomomorphicCommitmentScheme<C::ScalarField> for PedersenCommitment<C> {
     type CommitKey = CommitKey<C>;
     type Commitment = Commitment<C>;
@@ -59,32 +228,47 @@ impl<C: ProjectiveCurve> HomomorphicCommitmentScheme<C::ScalarField> for Pederse
             g.push(C::rand(public_randomess).into_affine());
         }
         let h = C::rand(public_randomess).into_affine();
-        CommitKey::<C> { g, h }
+        CommitKey::<C>::new(g, h)
+    }
+
+    fn supported_size(commit_key: &CommitKey<C>) -> usize {
+        commit_key.g.len()
     }
 
     fn commit(
         commit_key: &CommitKey<C>,
-        x: &Vec<C::ScalarField>,
+        x: &[C::ScalarField],
         r: C::ScalarField,
-    ) -> Result<Self::Commitment, CryptoError> {
-        if x.len() > commit_key.g.len() {
-            return Err(CryptoError::CommitmentLengthError(
-                String::from("Pedersen"),
-                x.len(),
-                commit_key.g.len(),
-            ));
-        }
+    ) -> Result<Self::Commitment, SangriaError> {
+        Self::commit_projective(commit_key, x, r)
+            .map(|commitment| Commitment(commitment.into_affine()))
+    }
 
-        let scalars = [&[r], x.as_slice()]
-            .concat()
-            .iter()
-            .map(|x| x.into_repr())
-            .collect::<Vec<_>>();
+    /// Computes the MSMs in projective form, concurrently under the `parallel` feature, and
+    /// normalizes all the commitments at once, see [`DeferredCommitments`].
+    fn batch_commit(
+        commit_key: &CommitKey<C>,
+        vectors: &[(&[C::ScalarField], C::ScalarField)],
+    ) -> Result<Vec<Commitment<C>>, SangriaError> {
+        #[cfg(feature = "parallel")]
+        let vectors = vectors.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let vectors = vectors.iter();
 
-        let bases = [&[commit_key.h], &commit_key.g[..]].concat();
+        let pending = vectors
+            .map(|(x, r)| Self::commit_projective(commit_key, x, *r))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(Commitment(
-            VariableBaseMSM::multi_scalar_mul(&bases, &scalars[..]).into_affine(),
-        ))
+        Ok(DeferredCommitments { pending }.finalize())
+    }
+
+    fn canonicalize(commitment: Commitment<C>) -> Commitment<C> {
+        if commitment.is_zero() {
+            Commitment::zero()
+        } else {
+            commitment
+        }
     }
 }
+
+impl<C: ProjectiveCurve> OpenableCommitmentScheme<C::ScalarField> for PedersenCommitment<C> {}
@@ -0,0 +1,232 @@
+//! Multi-scalar multiplication strategies for Pedersen commitments. The fastest window size and
+//! the size from which splitting an MSM across threads pays off depend on the host, so long-running
+//! provers can measure them once at startup with [`select_commitment_backend`].
+
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_std::{rand::Rng, UniformRand};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::CommitKey;
+use crate::telemetry;
+
+type BigInt<G> = <<G as AffineCurve>::ScalarField as PrimeField>::BigInt;
+
+/// The window sizes tried by [`select_commitment_backend`].
+const CANDIDATE_WINDOW_SIZES: [usize; 6] = [4, 6, 8, 10, 12, 14];
+
+/// The largest MSM run by [`select_commitment_backend`], to bound the time spent at startup.
+const MAX_BENCHMARK_SIZE: usize = 1 << 12;
+
+/// How a commit key computes multi-scalar multiplications.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MsmStrategy {
+    /// The Pippenger window size in bits, or `None` for the heuristic of `ark-ec`.
+    pub window_size: Option<usize>,
+    /// MSMs with at least this many bases are split across threads. Only has an effect under the
+    /// `parallel` feature.
+    pub parallel_threshold: usize,
+}
+
+impl Default for MsmStrategy {
+    fn default() -> Self {
+        Self {
+            window_size: None,
+            parallel_threshold: usize::MAX,
+        }
+    }
+}
+
+/// Computes `sum_i scalars[i] * bases[i]` with the given strategy.
+pub fn msm<G: AffineCurve>(
+    bases: &[G],
+    scalars: &[BigInt<G>],
+    strategy: MsmStrategy,
+) -> G::Projective {
+    telemetry::timed(telemetry::MSM_SECONDS, || {
+        msm_with_strategy(bases, scalars, strategy)
+    })
+}
+
+fn msm_with_strategy<G: AffineCurve>(
+    bases: &[G],
+    scalars: &[BigInt<G>],
+    strategy: MsmStrategy,
+) -> G::Projective {
+    #[cfg(feature = "parallel")]
+    if bases.len() >= strategy.parallel_threshold {
+        let chunk_size = (bases.len() / rayon::current_num_threads()).max(1);
+        return bases
+            .par_chunks(chunk_size)
+            .zip(scalars.par_chunks(chunk_size))
+            .map(|(bases, scalars)| msm_sequential(bases, scalars, strategy.window_size))
+            .sum();
+    }
+
+    msm_sequential(bases, scalars, strategy.window_size)
+}
+
+fn msm_sequential<G: AffineCurve>(
+    bases: &[G],
+    scalars: &[BigInt<G>],
+    window_size: Option<usize>,
+) -> G::Projective {
+    match window_size {
+        Some(window_size) => pippenger(bases, scalars, window_size),
+        None => VariableBaseMSM::multi_scalar_mul(bases, scalars),
+    }
+}
+
+/// Pippenger's bucket method with a fixed window size of `c` bits.
+fn pippenger<G: AffineCurve>(bases: &[G], scalars: &[BigInt<G>], c: usize) -> G::Projective {
+    let zero = G::Projective::zero();
+    let number_of_bits = G::ScalarField::size_in_bits();
+
+    let window_sums = (0..number_of_bits)
+        .step_by(c)
+        .map(|window_start| {
+            let mut buckets = vec![zero; (1 << c) - 1];
+            for (scalar, base) in scalars.iter().zip(bases) {
+                let mut scalar = *scalar;
+                scalar.divn(window_start as u32);
+                let bucket = scalar.as_ref()[0] % (1 << c);
+                if bucket != 0 {
+                    buckets[(bucket - 1) as usize].add_assign_mixed(base);
+                }
+            }
+
+            // sum_j j * buckets[j - 1], as a running sum from the top bucket down.
+            let mut running_sum = zero;
+            let mut window_sum = zero;
+            for bucket in buckets.into_iter().rev() {
+                running_sum += &bucket;
+                window_sum += &running_sum;
+            }
+            window_sum
+        })
+        .collect::<Vec<_>>();
+
+    window_sums
+        .iter()
+        .rev()
+        .fold(zero, |mut total, window_sum| {
+            for _ in 0..c {
+                total.double_in_place();
+            }
+            total + window_sum
+        })
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+/// Returns the smallest power of two from which splitting an MSM across threads is faster.
+#[cfg(feature = "parallel")]
+fn parallel_threshold<G: AffineCurve>(
+    bases: &[G],
+    scalars: &[BigInt<G>],
+    window_size: Option<usize>,
+) -> usize {
+    let sequential = MsmStrategy {
+        window_size,
+        parallel_threshold: usize::MAX,
+    };
+    let parallel = MsmStrategy {
+        window_size,
+        parallel_threshold: 0,
+    };
+
+    for log_size in 6..=ark_std::log2(bases.len()) {
+        let n = (1 << log_size).min(bases.len());
+        let sequential_time = time(|| {
+            msm(&bases[..n], &scalars[..n], sequential);
+        });
+        let parallel_time = time(|| {
+            msm(&bases[..n], &scalars[..n], parallel);
+        });
+        if parallel_time < sequential_time {
+            return n;
+        }
+    }
+
+    usize::MAX
+}
+
+#[cfg(not(feature = "parallel"))]
+fn parallel_threshold<G: AffineCurve>(
+    _bases: &[G],
+    _scalars: &[BigInt<G>],
+    _window_size: Option<usize>,
+) -> usize {
+    usize::MAX
+}
+
+/// Micro-benchmarks MSM strategies with the generators of `commit_key` and returns the key
+/// configured with the fastest one. This takes a fraction of a second and is meant to be called
+/// once at startup by long-running prover services; keys use the `ark-ec` defaults otherwise.
+pub fn select_commitment_backend<C: ProjectiveCurve, R: Rng>(
+    commit_key: CommitKey<C>,
+    rng: &mut R,
+) -> CommitKey<C> {
+    let size = commit_key.g.len().min(MAX_BENCHMARK_SIZE);
+    let bases = &commit_key.g[..size];
+    let scalars = (0..size)
+        .map(|_| C::ScalarField::rand(rng).into_repr())
+        .collect::<Vec<_>>();
+
+    let candidates = ark_std::iter::once(None).chain(CANDIDATE_WINDOW_SIZES.map(Some));
+    let window_size = candidates
+        .min_by_key(|window_size| {
+            time(|| {
+                msm_sequential(bases, &scalars, *window_size);
+            })
+        })
+        .flatten();
+
+    let parallel_threshold = parallel_threshold(bases, &scalars, window_size);
+
+    commit_key.with_msm_strategy(MsmStrategy {
+        window_size,
+        parallel_threshold,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seeded_rng;
+
+    type Curve = ark_vesta::Projective;
+    type Scalar = ark_vesta::Fr;
+
+    #[test]
+    fn every_strategy_computes_the_same_msm() {
+        let rng = &mut seeded_rng(
+            "vector_commitment::pedersen::msm::every_strategy_computes_the_same_msm",
+        );
+        let n = 100;
+        let bases = (0..n)
+            .map(|_| Curve::rand(rng).into_affine())
+            .collect::<Vec<_>>();
+        let scalars = (0..n)
+            .map(|_| Scalar::rand(rng).into_repr())
+            .collect::<Vec<_>>();
+
+        let expected = msm(&bases, &scalars, MsmStrategy::default());
+        for window_size in CANDIDATE_WINDOW_SIZES {
+            for parallel_threshold in [0, n, usize::MAX] {
+                let strategy = MsmStrategy {
+                    window_size: Some(window_size),
+                    parallel_threshold,
+                };
+                assert_eq!(msm(&bases, &scalars, strategy), expected);
+            }
+        }
+    }
+}
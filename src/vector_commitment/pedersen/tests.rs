@@ -1,20 +1,30 @@
 #[cfg(test)]
 mod test {
-    use crate::utils::rand::sample_vector;
-    use crate::vector_commitment::{pedersen, HomomorphicCommitmentScheme};
-    use ark_ff::Zero;
-    use ark_std::{rand::thread_rng, UniformRand};
-    use starknet_curve;
+    use crate::test_utils::seeded_rng;
+    use crate::vector_commitment::{
+        pedersen::{self, Commitment},
+        HomomorphicCommitmentScheme,
+    };
+    use crate::SangriaError;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{One, Zero};
+    use ark_pallas::{Affine, Fq, Projective};
+    use ark_sponge::Absorb;
+    use ark_std::{rand::Rng, UniformRand};
     use std::ops::Mul;
 
     // Define type aliases for succinctness
-    type Curve = starknet_curve::Projective;
-    type Scalar = starknet_curve::Fr;
+    type Curve = Projective;
+    type Scalar = ark_pallas::Fr;
     type Pedersen = pedersen::PedersenCommitment<Curve>;
 
+    fn sample_vector(rng: &mut impl Rng, n: usize) -> Vec<Scalar> {
+        (0..n).map(|_| Scalar::rand(rng)).collect()
+    }
+
     #[test]
     fn additive_homomorphism() {
-        let rng = &mut thread_rng();
+        let rng = &mut seeded_rng("vector_commitment::pedersen::additive_homomorphism");
         let n = 52;
 
         let commit_key = Pedersen::setup(rng, n);
@@ -22,8 +32,8 @@ mod test {
         let r1 = Scalar::rand(rng);
         let r2 = Scalar::rand(rng);
 
-        let v1: Vec<Scalar> = sample_vector(rng, n);
-        let v2: Vec<Scalar> = sample_vector(rng, n);
+        let v1 = sample_vector(rng, n);
+        let v2 = sample_vector(rng, n);
 
         let alpha = Scalar::rand(rng);
         let beta = Scalar::rand(rng);
@@ -44,9 +54,49 @@ mod test {
         assert_eq!(expected, commit_v3)
     }
 
+    #[test]
+    fn deferred_commitments_match_eager_commitments() {
+        let rng = &mut seeded_rng(
+            "vector_commitment::pedersen::deferred_commitments_match_eager_commitments",
+        );
+        let n = 8;
+
+        let commit_key = Pedersen::setup(rng, n);
+        let mut vectors = (0..3)
+            .map(|_| {
+                let v = sample_vector(rng, n);
+                (v, Scalar::rand(rng))
+            })
+            .collect::<Vec<_>>();
+        // The identity has no affine coordinates and must survive batch normalization, as well
+        // as a vector shorter than the key.
+        vectors.insert(1, (vec![Scalar::zero(); n], Scalar::zero()));
+        vectors.push((sample_vector(rng, n / 2), Scalar::rand(rng)));
+        let eager = vectors
+            .iter()
+            .map(|(v, r)| Pedersen::commit(&commit_key, v, *r).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut deferred = pedersen::DeferredCommitments::new();
+        for (index, (v, r)) in vectors.iter().enumerate() {
+            assert_eq!(deferred.commit(&commit_key, v, *r), Ok(index));
+        }
+        assert_eq!(deferred.finalize(), eager);
+        assert!(eager[1].is_zero());
+        assert!(pedersen::DeferredCommitments::<Curve>::new()
+            .finalize()
+            .is_empty());
+
+        let borrowed = vectors
+            .iter()
+            .map(|(v, r)| (v.as_slice(), *r))
+            .collect::<Vec<_>>();
+        assert_eq!(Pedersen::batch_commit(&commit_key, &borrowed), Ok(eager));
+    }
+
     #[test]
     fn short_commitment() {
-        let rng = &mut thread_rng();
+        let rng = &mut seeded_rng("vector_commitment::pedersen::short_commitment");
         let n = 10;
 
         let commit_key = Pedersen::setup(rng, n);
@@ -60,16 +110,15 @@ mod test {
 
         let commit_v1 = Pedersen::commit(&commit_key, &v1, r).unwrap();
 
-        let commit_s1 = Pedersen::commit(&commit_key, &vec![s1], r).unwrap();
+        let commit_s1 = Pedersen::commit(&commit_key, &[s1], r).unwrap();
 
         assert_eq!(v1[0], s1);
         assert_eq!(commit_v1, commit_s1);
     }
 
     #[test]
-    #[should_panic]
     fn too_many_values() {
-        let rng = &mut thread_rng();
+        let rng = &mut seeded_rng("vector_commitment::pedersen::too_many_values");
         let n = 5;
 
         let commit_key = Pedersen::setup(rng, n);
@@ -80,6 +129,73 @@ mod test {
 
         let too_long = vec![s1; n + 2];
 
-        let _commit = Pedersen::commit(&commit_key, &too_long, r).unwrap();
+        assert_eq!(
+            Pedersen::commit(&commit_key, &too_long, r),
+            Err(SangriaError::UnsupportedSize {
+                requested: n + 2,
+                max: n
+            })
+        );
+    }
+
+    #[test]
+    fn coordinates_handle_identity_and_negation() {
+        let rng = &mut seeded_rng(
+            "vector_commitment::pedersen::coordinates_handle_identity_and_negation",
+        );
+        let point = Commitment::<Projective>(Projective::rand(rng).into_affine());
+        let (x, y) = point.coordinates().unwrap();
+        assert_eq!(point.to_base_field_elements(), [x, y, Fq::zero()]);
+
+        let negated = Commitment::<Projective>(-point.0);
+        assert_eq!(negated.coordinates(), Some((x, -y)));
+
+        let identity = point + negated;
+        assert!(identity.is_zero());
+        assert_eq!(identity.coordinates(), None);
+        assert_eq!(
+            identity.to_base_field_elements(),
+            [Fq::zero(), Fq::zero(), Fq::one()]
+        );
+        assert_eq!(
+            Commitment::<Projective>::zero().to_base_field_elements(),
+            identity.to_base_field_elements()
+        );
+
+        // The point at infinity with the placeholder coordinates of another point.
+        let placeholder = Commitment::<Projective>(Affine::new(x, y, true));
+        assert!(placeholder.is_zero());
+        assert_ne!(placeholder, identity);
+        assert_eq!(
+            pedersen::PedersenCommitment::<Projective>::canonicalize(placeholder),
+            identity
+        );
+    }
+
+    #[test]
+    fn coordinates_of_commitments_are_on_the_curve() {
+        let rng = &mut seeded_rng(
+            "vector_commitment::pedersen::coordinates_of_commitments_are_on_the_curve",
+        );
+        let commit_key = Pedersen::setup(rng, 4);
+        let commitment =
+            Pedersen::commit(&commit_key, &sample_vector(rng, 4), Scalar::rand(rng)).unwrap();
+
+        let [x, y, infinity] = commitment.to_base_field_elements();
+        assert_eq!(infinity, Fq::zero());
+        assert!(Affine::new(x, y, false).is_on_curve());
+
+        // The commitment to the zero vector with zero randomness is the identity.
+        let identity = Pedersen::commit(&commit_key, &[Scalar::zero(); 4], Scalar::zero()).unwrap();
+        assert_eq!(identity.coordinates(), None);
+        let placeholder = Commitment::<Projective>(Affine::new(x, y, true));
+        assert_eq!(
+            placeholder.to_base_field_elements(),
+            identity.to_base_field_elements()
+        );
+        assert_eq!(
+            placeholder.to_sponge_bytes_as_vec(),
+            identity.to_sponge_bytes_as_vec()
+        );
     }
 }
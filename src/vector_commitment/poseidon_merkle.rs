@@ -0,0 +1,36 @@
+use crate::errors::SangriaError;
+use crate::sponge::{Poseidon, Sponge};
+use crate::vector_commitment::VectorCommitmentScheme;
+
+use ark_ff::PrimeField;
+use ark_sponge::poseidon::PoseidonParameters;
+use ark_std::marker::PhantomData;
+
+/// A Merkle tree commitment using Poseidon as the two-to-one hash. The vector is padded with
+/// zeros to the next power of two and the root is bound to the original length.
+pub struct PoseidonMerkleCommitment<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+fn hash<F: PrimeField>(parameters: &PoseidonParameters<F>, inputs: &[F]) -> F {
+    let mut sponge = Poseidon::new(parameters);
+    sponge.absorb_field_elements(inputs);
+    sponge.squeeze_field_elements(1)[0]
+}
+
+impl<F: PrimeField> VectorCommitmentScheme<F> for PoseidonMerkleCommitment<F> {
+    type Parameters = PoseidonParameters<F>;
+    type Commitment = F;
+
+    fn commit(parameters: &PoseidonParameters<F>, x: &[F]) -> Result<F, SangriaError> {
+        let mut layer = x.to_vec();
+        layer.resize(x.len().next_power_of_two(), F::zero());
+
+        while layer.len() > 1 {
+            layer = layer.chunks(2).map(|pair| hash(parameters, pair)).collect();
+        }
+
+        let root = layer.pop().unwrap_or_else(F::zero);
+        Ok(hash(parameters, &[F::from(x.len() as u64), root]))
+    }
+}
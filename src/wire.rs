@@ -0,0 +1,353 @@
+//! Protocol buffer messages for exchanging instances, witnesses and accumulators with processes
+//! that are not written in Rust, e.g. a coordinator of [`crate::distributed`] folding. The schema
+//! is `proto/sangria.proto`; the structs below are its `prost` encoding, so that no code
+//! generation is needed at build time.
+//!
+//! Field elements and commitments are carried as bytes in their canonical encoding with the
+//! [`SerializationMode`] both ends agree on.
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{
+    distributed::{self, ProverMessage as Commitment},
+    folding_scheme::FoldingCommitmentConfig,
+    relations::{PLONKInstance, PLONKWitness},
+    serialization::{deserialize_with_mode, to_bytes, SerializationMode},
+    RelaxedPLONKInstance, RelaxedPLONKWitness, SangriaError,
+};
+
+/// A column of field elements.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Column {
+    /// The entries of the column.
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub values: Vec<Vec<u8>>,
+}
+
+/// A committed relaxed PLONK instance.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RelaxedInstance {
+    /// The digest of the circuit the instance belongs to.
+    #[prost(bytes = "vec", tag = "1")]
+    pub circuit_digest: Vec<u8>,
+    /// The public input columns.
+    #[prost(message, repeated, tag = "2")]
+    pub public_input_columns: Vec<Column>,
+    /// Whether each public input row is padding.
+    #[prost(bool, repeated, tag = "3")]
+    pub padding: Vec<bool>,
+    /// The scaling factor `u`.
+    #[prost(bytes = "vec", tag = "4")]
+    pub scaling_factor: Vec<u8>,
+    /// The commitment to the slack vector.
+    #[prost(bytes = "vec", tag = "5")]
+    pub slack_commitment: Vec<u8>,
+    /// The commitments to the wire columns.
+    #[prost(bytes = "vec", repeated, tag = "6")]
+    pub witness_commitments: Vec<Vec<u8>>,
+}
+
+/// A committed relaxed PLONK witness.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RelaxedWitness {
+    /// The wire columns.
+    #[prost(message, repeated, tag = "1")]
+    pub witness_columns: Vec<Column>,
+    /// The slack vector.
+    #[prost(message, optional, tag = "2")]
+    pub slack_vector: Option<Column>,
+    /// The hiding randomness of the commitment to each wire column.
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub commitment_hidings: Vec<Vec<u8>>,
+    /// The hiding randomness of the commitment to the slack vector.
+    #[prost(bytes = "vec", tag = "4")]
+    pub slack_hiding: Vec<u8>,
+}
+
+/// The message of the folding prover: the commitment to the cross term.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProverMessage {
+    /// The commitment to the cross term.
+    #[prost(bytes = "vec", tag = "1")]
+    pub cross_term_commitment: Vec<u8>,
+}
+
+/// The running pair of a contiguous segment of statements, see
+/// [`distributed::SegmentAccumulator`].
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SegmentAccumulator {
+    /// The index of the first statement of the segment.
+    #[prost(uint64, tag = "1")]
+    pub first_statement: u64,
+    /// The number of statements of the segment.
+    #[prost(uint64, tag = "2")]
+    pub number_of_statements: u64,
+    /// The running instance.
+    #[prost(message, optional, tag = "3")]
+    pub instance: Option<RelaxedInstance>,
+    /// The running witness.
+    #[prost(message, optional, tag = "4")]
+    pub witness: Option<RelaxedWitness>,
+    /// The prover messages of the folds of the segment, in order.
+    #[prost(message, repeated, tag = "5")]
+    pub prover_messages: Vec<ProverMessage>,
+}
+
+/// Decodes a single value and rejects trailing bytes.
+fn decode_value<T: CanonicalDeserialize>(
+    bytes: &[u8],
+    mode: SerializationMode,
+) -> Result<T, SangriaError> {
+    let mut reader = bytes;
+    let value = deserialize_with_mode(&mut reader, mode)?;
+    if !reader.is_empty() {
+        return Err(SangriaError::SerializationError);
+    }
+
+    Ok(value)
+}
+
+fn encode_values<T: CanonicalSerialize>(
+    values: &[T],
+    mode: SerializationMode,
+) -> Result<Vec<Vec<u8>>, SangriaError> {
+    values.iter().map(|value| to_bytes(value, mode)).collect()
+}
+
+fn decode_values<T: CanonicalDeserialize>(
+    values: &[Vec<u8>],
+    mode: SerializationMode,
+) -> Result<Vec<T>, SangriaError> {
+    values
+        .iter()
+        .map(|value| decode_value(value, mode))
+        .collect()
+}
+
+impl Column {
+    fn from_values<F: PrimeField>(
+        values: &[F],
+        mode: SerializationMode,
+    ) -> Result<Self, SangriaError> {
+        Ok(Self {
+            values: encode_values(values, mode)?,
+        })
+    }
+
+    fn to_values<F: PrimeField>(&self, mode: SerializationMode) -> Result<Vec<F>, SangriaError> {
+        decode_values(&self.values, mode)
+    }
+}
+
+impl RelaxedInstance {
+    /// Encodes a relaxed instance.
+    pub fn from_instance<F: PrimeField, Comm: FoldingCommitmentConfig<F>>(
+        instance: &RelaxedPLONKInstance<F, Comm>,
+        mode: SerializationMode,
+    ) -> Result<Self, SangriaError> {
+        let plonk_instance = instance.plonk_instance();
+
+        Ok(Self {
+            circuit_digest: to_bytes(&instance.circuit_digest(), mode)?,
+            public_input_columns: plonk_instance
+                .columns()
+                .iter()
+                .map(|column| Column::from_values(column, mode))
+                .collect::<Result<_, _>>()?,
+            padding: plonk_instance.padding().to_vec(),
+            scaling_factor: to_bytes(&instance.scaling_factor(), mode)?,
+            slack_commitment: to_bytes(&instance.slack_commitment(), mode)?,
+            witness_commitments: encode_values(&instance.witness_commitments(), mode)?,
+        })
+    }
+
+    /// Decodes a relaxed instance encoded with the same `mode`.
+    pub fn to_instance<F: PrimeField, Comm: FoldingCommitmentConfig<F>>(
+        &self,
+        mode: SerializationMode,
+    ) -> Result<RelaxedPLONKInstance<F, Comm>, SangriaError> {
+        let columns = self
+            .public_input_columns
+            .iter()
+            .map(|column| column.to_values(mode))
+            .collect::<Result<Vec<_>, _>>()?;
+        let witness_commitments: Vec<_> = decode_values(&self.witness_commitments, mode)?;
+        if witness_commitments.len() != columns.len() {
+            return Err(SangriaError::SerializationError);
+        }
+        let plonk_instance = PLONKInstance::from_parts(columns, self.padding.clone())
+            .map_err(|_| SangriaError::SerializationError)?;
+
+        Ok(RelaxedPLONKInstance::new(
+            decode_value(&self.circuit_digest, mode)?,
+            plonk_instance,
+            decode_value(&self.scaling_factor, mode)?,
+            decode_value(&self.slack_commitment, mode)?,
+            witness_commitments,
+        ))
+    }
+}
+
+impl RelaxedWitness {
+    /// Encodes a relaxed witness.
+    pub fn from_witness<F: PrimeField>(
+        witness: &RelaxedPLONKWitness<F>,
+        mode: SerializationMode,
+    ) -> Result<Self, SangriaError> {
+        let commitment_hidings = witness.hiding_randomnesses();
+
+        Ok(Self {
+            witness_columns: (0..commitment_hidings.len())
+                .map(|column_index| {
+                    Column::from_values(witness.witness_column(column_index)?, mode)
+                })
+                .collect::<Result<_, _>>()?,
+            slack_vector: Some(Column::from_values(&witness.slack_vector(), mode)?),
+            commitment_hidings: encode_values(&commitment_hidings, mode)?,
+            slack_hiding: to_bytes(&witness.slack_hiding(), mode)?,
+        })
+    }
+
+    /// Decodes a relaxed witness encoded with the same `mode`.
+    pub fn to_witness<F: PrimeField>(
+        &self,
+        mode: SerializationMode,
+    ) -> Result<RelaxedPLONKWitness<F>, SangriaError> {
+        let columns = self
+            .witness_columns
+            .iter()
+            .map(|column| column.to_values(mode))
+            .collect::<Result<Vec<_>, _>>()?;
+        let slack_vector: Vec<F> = self
+            .slack_vector
+            .as_ref()
+            .ok_or(SangriaError::SerializationError)?
+            .to_values(mode)?;
+        let commitment_hidings: Vec<F> = decode_values(&self.commitment_hidings, mode)?;
+        if commitment_hidings.len() != columns.len()
+            || columns
+                .iter()
+                .any(|column| column.len() != slack_vector.len())
+        {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(RelaxedPLONKWitness::new(
+            PLONKWitness::new(columns),
+            slack_vector,
+            commitment_hidings,
+            decode_value(&self.slack_hiding, mode)?,
+        ))
+    }
+}
+
+impl SegmentAccumulator {
+    /// Encodes the accumulator of a segment.
+    pub fn from_segment<F: PrimeField, Comm: FoldingCommitmentConfig<F>>(
+        segment: &distributed::SegmentAccumulator<F, Comm>,
+        mode: SerializationMode,
+    ) -> Result<Self, SangriaError> {
+        Ok(Self {
+            first_statement: segment.first_statement,
+            number_of_statements: segment.number_of_statements,
+            instance: Some(RelaxedInstance::from_instance(&segment.instance, mode)?),
+            witness: Some(RelaxedWitness::from_witness(&segment.witness, mode)?),
+            prover_messages: segment
+                .prover_messages
+                .iter()
+                .map(|prover_message| {
+                    Ok(ProverMessage {
+                        cross_term_commitment: to_bytes(prover_message, mode)?,
+                    })
+                })
+                .collect::<Result<_, SangriaError>>()?,
+        })
+    }
+
+    /// Decodes the accumulator of a segment encoded with the same `mode`.
+    pub fn to_segment<F: PrimeField, Comm: FoldingCommitmentConfig<F>>(
+        &self,
+        mode: SerializationMode,
+    ) -> Result<distributed::SegmentAccumulator<F, Comm>, SangriaError> {
+        let missing = || SangriaError::SerializationError;
+        if self.number_of_statements == 0 {
+            return Err(SangriaError::SerializationError);
+        }
+
+        Ok(distributed::SegmentAccumulator {
+            first_statement: self.first_statement,
+            number_of_statements: self.number_of_statements,
+            instance: self
+                .instance
+                .as_ref()
+                .ok_or_else(missing)?
+                .to_instance(mode)?,
+            witness: self
+                .witness
+                .as_ref()
+                .ok_or_else(missing)?
+                .to_witness(mode)?,
+            prover_messages: self
+                .prover_messages
+                .iter()
+                .map(|prover_message| {
+                    decode_value::<Commitment<F, Comm>>(&prover_message.cross_term_commitment, mode)
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::seeded_rng, vector_commitment::mock::FieldCommitmentConfig};
+    use ark_pallas::Fr;
+    use ark_std::UniformRand;
+    use prost::Message;
+
+    #[test]
+    fn relaxed_pairs_round_trip_through_protocol_buffers() {
+        let rng = &mut seeded_rng("wire::relaxed_pairs_round_trip_through_protocol_buffers");
+        let mut random_column = || (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let instance = RelaxedPLONKInstance::<Fr, FieldCommitmentConfig<Fr>>::new(
+            Fr::from(7u64),
+            PLONKInstance::new(vec![random_column()]).pad(4).unwrap(),
+            Fr::from(3u64),
+            Fr::from(5u64),
+            vec![Fr::from(11u64); 3],
+        );
+        let witness = RelaxedPLONKWitness::new(
+            PLONKWitness::new(vec![random_column(), random_column(), random_column()]),
+            random_column(),
+            random_column()[..3].to_vec(),
+            Fr::from(13u64),
+        );
+
+        let mode = SerializationMode::Compressed;
+        let segment = distributed::SegmentAccumulator {
+            first_statement: 2,
+            number_of_statements: 3,
+            instance,
+            witness,
+            prover_messages: vec![Fr::from(17u64); 2],
+        };
+        let message = SegmentAccumulator::from_segment(&segment, mode).unwrap();
+        let decoded = SegmentAccumulator::decode(message.encode_to_vec().as_slice())
+            .unwrap()
+            .to_segment::<Fr, FieldCommitmentConfig<Fr>>(mode)
+            .unwrap();
+        assert_eq!(
+            decoded.to_bytes(mode).unwrap(),
+            segment.to_bytes(mode).unwrap()
+        );
+
+        let mut truncated = message;
+        truncated.witness.as_mut().unwrap().commitment_hidings.pop();
+        assert!(matches!(
+            truncated.to_segment::<Fr, FieldCommitmentConfig<Fr>>(mode),
+            Err(SangriaError::SerializationError)
+        ));
+    }
+}
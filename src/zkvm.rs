@@ -0,0 +1,346 @@
+//! A register machine as a step circuit: a template for zkVMs built on this crate. Every step
+//! - fetches the instruction word at the program counter,
+//! - decodes its opcode into one of the machine's [`Instruction`]s,
+//! - executes it on two registers and writes the result to a third one.
+//!
+//! Instructions that are not cheap as gates, e.g. bitwise operations, are checked by a lookup into
+//! a table of the instruction (see [`crate::lookup`]): the tables are attached to the circuit with
+//! [`RegisterMachine::lookup_tables`] and every step contributes to their usage columns, see
+//! [`RegisterMachine::multiplicities`].
+//!
+//! The skeleton only runs the machine and encodes its steps; the gates of the fetch, decode and
+//! execute stages are left to the zkVM built on it.
+
+use ark_ff::PrimeField;
+
+use crate::{SangriaError, StepCircuit};
+
+/// An instruction of a [`RegisterMachine`].
+pub trait Instruction<F: PrimeField>: Send + Sync {
+    /// The name of the instruction, e.g. for disassembly.
+    fn name(&self) -> &str;
+
+    /// Computes the result of the instruction on two operands.
+    fn execute(&self, left: F, right: F) -> F;
+
+    /// The table the instruction is looked up in, if it is not checked by gates. Its entries are
+    /// the keys of [`Self::lookup_key`].
+    fn lookup_table(&self) -> Option<Vec<F>> {
+        None
+    }
+
+    /// Combines the operands and the result into a single entry of the table.
+    fn lookup_key(&self, _left: F, _right: F, _result: F) -> F {
+        F::zero()
+    }
+}
+
+/// Field addition.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Add;
+
+impl<F: PrimeField> Instruction<F> for Add {
+    fn name(&self) -> &str {
+        "add"
+    }
+
+    fn execute(&self, left: F, right: F) -> F {
+        left + right
+    }
+}
+
+/// Field multiplication.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Mul;
+
+impl<F: PrimeField> Instruction<F> for Mul {
+    fn name(&self) -> &str {
+        "mul"
+    }
+
+    fn execute(&self, left: F, right: F) -> F {
+        left * right
+    }
+}
+
+/// Exclusive or of `bits`-bit operands, checked by a lookup in a table of `2^(2 * bits)` entries
+/// keyed by `left + 2^bits * right + 2^(2 * bits) * result`.
+#[derive(Clone, Copy, Debug)]
+pub struct Xor {
+    /// The width of the operands.
+    pub bits: u32,
+}
+
+impl Xor {
+    fn key<F: PrimeField>(&self, left: u64, right: u64) -> F {
+        F::from(left + (right << self.bits) + ((left ^ right) << (2 * self.bits)))
+    }
+}
+
+impl<F: PrimeField> Instruction<F> for Xor {
+    fn name(&self) -> &str {
+        "xor"
+    }
+
+    fn execute(&self, left: F, right: F) -> F {
+        let mask = (1u64 << self.bits) - 1;
+        let low_bits = |value: F| value.into_repr().as_ref()[0] & mask;
+        F::from(low_bits(left) ^ low_bits(right))
+    }
+
+    fn lookup_table(&self) -> Option<Vec<F>> {
+        let range = 0..1u64 << self.bits;
+        Some(
+            range
+                .clone()
+                .flat_map(|right| range.clone().map(move |left| self.key(left, right)))
+                .collect(),
+        )
+    }
+
+    fn lookup_key(&self, left: F, right: F, result: F) -> F {
+        let shift = F::from(1u64 << self.bits);
+        left + shift * right + shift.square() * result
+    }
+}
+
+/// An instruction word: `registers[destination] = instruction(registers[left], registers[right])`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Word {
+    /// The index of the instruction among the instructions of the machine.
+    pub opcode: usize,
+    /// The register the result is written to.
+    pub destination: usize,
+    /// The register of the left operand.
+    pub left: usize,
+    /// The register of the right operand.
+    pub right: usize,
+}
+
+/// The state of a [`RegisterMachine`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MachineState<F: PrimeField> {
+    /// The index of the next instruction word.
+    pub program_counter: usize,
+    /// The registers.
+    pub registers: Vec<F>,
+}
+
+/// The witness of a step: the fetched word, its operands and its result.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StepWitness<F: PrimeField> {
+    /// The instruction word at the program counter.
+    pub word: Word,
+    /// The value of the left operand.
+    pub left: F,
+    /// The value of the right operand.
+    pub right: F,
+    /// The result written to the destination register.
+    pub result: F,
+}
+
+/// A register machine running a fixed program, see the module documentation.
+pub struct RegisterMachine<F: PrimeField> {
+    instructions: Vec<Box<dyn Instruction<F>>>,
+    program: Vec<Word>,
+    number_of_registers: usize,
+}
+
+impl<F: PrimeField> RegisterMachine<F> {
+    /// Creates a machine with `number_of_registers` registers that runs `program`. Returns an
+    /// error if a word of the program refers to an unknown instruction or register.
+    pub fn new(
+        instructions: Vec<Box<dyn Instruction<F>>>,
+        program: Vec<Word>,
+        number_of_registers: usize,
+    ) -> Result<Self, SangriaError> {
+        let is_valid = |word: &Word| {
+            word.opcode < instructions.len()
+                && [word.destination, word.left, word.right]
+                    .iter()
+                    .all(|register| *register < number_of_registers)
+        };
+        if !program.iter().all(is_valid) {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        Ok(Self {
+            instructions,
+            program,
+            number_of_registers,
+        })
+    }
+
+    /// Returns the initial state: the program counter at zero and the given registers. Returns an
+    /// error if there are not as many registers as the machine has.
+    pub fn initial_state(&self, registers: Vec<F>) -> Result<MachineState<F>, SangriaError> {
+        if registers.len() != self.number_of_registers {
+            return Err(SangriaError::IndexOutOfBounds);
+        }
+
+        Ok(MachineState {
+            program_counter: 0,
+            registers,
+        })
+    }
+
+    /// Runs one step: fetches, decodes and executes the word at the program counter. Returns
+    /// the next state and the witness of the step, or an error if the program has halted.
+    pub fn step(
+        &self,
+        state: &MachineState<F>,
+    ) -> Result<(MachineState<F>, StepWitness<F>), SangriaError> {
+        let word = *self
+            .program
+            .get(state.program_counter)
+            .ok_or(SangriaError::IndexOutOfBounds)?;
+        let register = |index: usize| {
+            state
+                .registers
+                .get(index)
+                .copied()
+                .ok_or(SangriaError::IndexOutOfBounds)
+        };
+        let (left, right) = (register(word.left)?, register(word.right)?);
+        let result = self.instructions[word.opcode].execute(left, right);
+
+        let mut registers = state.registers.clone();
+        *registers
+            .get_mut(word.destination)
+            .ok_or(SangriaError::IndexOutOfBounds)? = result;
+
+        Ok((
+            MachineState {
+                program_counter: state.program_counter + 1,
+                registers,
+            },
+            StepWitness {
+                word,
+                left,
+                right,
+                result,
+            },
+        ))
+    }
+
+    /// Returns the lookup tables of the instructions that have one, in the order of the
+    /// instructions. They are attached to the circuit with
+    /// [`crate::PLONKCircuit::with_lookup_tables`].
+    pub fn lookup_tables(&self) -> Vec<Vec<F>> {
+        self.instructions
+            .iter()
+            .filter_map(|instruction| instruction.lookup_table())
+            .collect()
+    }
+
+    /// Returns the multiplicity columns of [`Self::lookup_tables`] used by `steps`, to commit to
+    /// with [`crate::lookup::LookupUsage`]. Returns [`SangriaError::LookupFailed`] if a step is
+    /// not an entry of the table of its instruction.
+    pub fn multiplicities(&self, steps: &[StepWitness<F>]) -> Result<Vec<Vec<F>>, SangriaError> {
+        let tables = self
+            .instructions
+            .iter()
+            .map(|instruction| instruction.lookup_table())
+            .collect::<Vec<_>>();
+        let mut multiplicities = tables
+            .iter()
+            .map(|table| table.as_ref().map(|table| vec![F::zero(); table.len()]))
+            .collect::<Vec<_>>();
+
+        for step in steps {
+            let opcode = step.word.opcode;
+            if let (Some(table), Some(column)) = (&tables[opcode], &mut multiplicities[opcode]) {
+                let key = self.instructions[opcode].lookup_key(step.left, step.right, step.result);
+                let position = table
+                    .iter()
+                    .position(|entry| *entry == key)
+                    .ok_or(SangriaError::LookupFailed)?;
+                column[position] += F::one();
+            }
+        }
+
+        Ok(multiplicities.into_iter().flatten().collect())
+    }
+}
+
+impl<F: PrimeField> StepCircuit<F> for RegisterMachine<F> {
+    type State = MachineState<F>;
+    type Witness = StepWitness<F>;
+    type Outputs = ();
+
+    /// The word (opcode and three registers), both operands and the result.
+    fn witness_arity(&self) -> usize {
+        7
+    }
+
+    fn witness_elements(witness: &StepWitness<F>) -> Vec<F> {
+        let word = witness.word;
+        [word.opcode, word.destination, word.left, word.right]
+            .into_iter()
+            .map(|index| F::from(index as u64))
+            .chain([witness.left, witness.right, witness.result])
+            .collect()
+    }
+
+    fn output_elements(_outputs: &()) -> Vec<F> {
+        Vec::new()
+    }
+
+    fn state_elements(state: &MachineState<F>) -> Vec<F> {
+        ark_std::iter::once(F::from(state.program_counter as u64))
+            .chain(state.registers.iter().copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ivc::check_step_witness;
+    use ark_pallas::Fr;
+
+    #[test]
+    fn program_runs_and_looks_up_its_bitwise_steps() {
+        let word = |opcode, destination, left, right| Word {
+            opcode,
+            destination,
+            left,
+            right,
+        };
+        // r2 = r0 + r1, r2 = r2 ^ r1, r0 = r2 * r2
+        let program = vec![word(0, 2, 0, 1), word(2, 2, 2, 1), word(1, 0, 2, 2)];
+        let machine = RegisterMachine::<Fr>::new(
+            vec![Box::new(Add), Box::new(Mul), Box::new(Xor { bits: 4 })],
+            program,
+            3,
+        )
+        .unwrap();
+
+        let mut state = machine
+            .initial_state(vec![Fr::from(3u64), Fr::from(5u64), Fr::from(0u64)])
+            .unwrap();
+        let mut steps = Vec::new();
+        for _ in 0..3 {
+            let (next_state, witness) = machine.step(&state).unwrap();
+            assert_eq!(check_step_witness(&machine, &witness), Ok(()));
+            state = next_state;
+            steps.push(witness);
+        }
+        // 3 + 5 = 8, 8 ^ 5 = 13, 13 * 13 = 169
+        assert_eq!(state.registers[0], Fr::from(169u64));
+        assert_eq!(
+            machine.step(&state).map(|_| ()),
+            Err(SangriaError::IndexOutOfBounds)
+        );
+
+        let multiplicities = machine.multiplicities(&steps).unwrap();
+        assert_eq!(multiplicities.len(), machine.lookup_tables().len());
+        assert_eq!(multiplicities[0].iter().sum::<Fr>(), Fr::from(1u64));
+
+        steps[1].result += Fr::from(1u64);
+        assert_eq!(
+            machine.multiplicities(&steps),
+            Err(SangriaError::LookupFailed)
+        );
+    }
+}